@@ -0,0 +1,78 @@
+//! Loads and stores in allocations of different sizes, and copies of homogeneous arrays.
+
+use minibench::bench;
+use miniutil::build::*;
+use miniutil::*;
+
+/// How many stores the loop performs.
+const ROUNDS: u32 = 4_096;
+
+/// Builds a program that writes `ROUNDS` bytes into a `[u8; len]` at spread-out indices,
+/// copying the whole array into a second one after every store, and prints the last byte written.
+/// If stores or copies take time proportional to `len`, the runtime grows with `len`.
+fn store_and_copy(len: u32) -> Program {
+    let arr = array_ty(<u8>::get_type(), len);
+    let locals = [arr, arr, <u32>::get_type()];
+    let i = || load(local(2));
+    let idx = || int_cast::<usize>(rem(mul(i(), const_int(97u32)), const_int(len)));
+
+    let b0 = block!(
+        storage_live(0),
+        storage_live(1),
+        storage_live(2),
+        assign(local(0), array(&vec![const_int(0u8); len as usize], <u8>::get_type())),
+        assign(local(2), const_int(0u32)),
+        goto(1)
+    );
+    let b1 = block!(if_(lt(i(), const_int(ROUNDS)), 2, 3));
+    let b2 = block!(
+        assign(index(local(0), idx()), int_cast::<u8>(i())),
+        assign(local(1), load(local(0))),
+        assign(local(2), add(i(), const_int(1u32))),
+        goto(1)
+    );
+    let b3 = block!(print(load(index(local(1), const_int(0usize))), 4));
+    let b4 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1, b2, b3, b4]);
+    program(&[f])
+}
+
+/// How many copies `zeroed_copies` makes.
+const COPIES: u32 = 16;
+
+/// Builds a program that zeroes a `[u8; len]` and copies it into a second one `COPIES` times.
+/// The memory stores chunks of equal bytes compactly, so this should stay cheap even for huge `len`.
+fn zeroed_copies(len: u32) -> Program {
+    let arr = array_ty(<u8>::get_type(), len);
+    let locals = [arr, arr, <u32>::get_type()];
+    let i = || load(local(2));
+
+    let b0 = block!(
+        storage_live(0),
+        storage_live(1),
+        storage_live(2),
+        assign(local(0), array(&vec![const_int(0u8); len as usize], <u8>::get_type())),
+        assign(local(2), const_int(0u32)),
+        goto(1)
+    );
+    let b1 = block!(if_(lt(i(), const_int(COPIES)), 2, 3));
+    let b2 = block!(
+        assign(local(1), load(local(0))),
+        assign(local(2), add(i(), const_int(1u32))),
+        goto(1)
+    );
+    let b3 = block!(print(load(index(local(1), const_int(len as usize - 1))), 4));
+    let b4 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1, b2, b3, b4]);
+    program(&[f])
+}
+
+fn main() {
+    bench("stores and copies of 64 bytes", store_and_copy(64));
+    bench("stores and copies of 4 KiB", store_and_copy(4 * 1024));
+    bench("stores and copies of 64 KiB", store_and_copy(64 * 1024));
+    bench("copies of a zeroed 64 KiB array", zeroed_copies(64 * 1024));
+    bench("copies of a zeroed 1 MiB array", zeroed_copies(1024 * 1024));
+}
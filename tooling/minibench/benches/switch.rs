@@ -0,0 +1,43 @@
+//! Switches with many cases, like the ones a large `match` compiles to.
+
+use minibench::bench;
+use miniutil::build::*;
+use miniutil::*;
+
+/// How many times the loop executes the switch.
+const ROUNDS: u32 = 10_000;
+
+/// Builds a program that repeatedly switches on a value that hits one of `cases` non-contiguous case values
+/// about half of the time, and prints how often a case was hit.
+fn large_switch(cases: u32) -> Program {
+    let locals = [<u32>::get_type(), <u32>::get_type()];
+    let i = || load(local(0));
+    let hits = || load(local(1));
+
+    // The case values are spread out, so they cannot be handled as a contiguous range.
+    let case_values: Vec<(u32, u32)> = (0..cases).map(|c| (c * 2 * 8, 3)).collect();
+    let discr = rem(mul(i(), const_int(37u32)), const_int(cases * 2 * 8));
+
+    let b0 = block!(
+        storage_live(0),
+        storage_live(1),
+        assign(local(0), const_int(0u32)),
+        assign(local(1), const_int(0u32)),
+        goto(1)
+    );
+    let b1 = block!(if_(lt(i(), const_int(ROUNDS)), 2, 5));
+    let b2 = block!(switch_int(discr, &case_values, 4));
+    let b3 = block!(assign(local(1), add(hits(), const_int(1u32))), goto(4));
+    let b4 = block!(assign(local(0), add(i(), const_int(1u32))), goto(1));
+    let b5 = block!(print(hits(), 6));
+    let b6 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1, b2, b3, b4, b5, b6]);
+    program(&[f])
+}
+
+fn main() {
+    bench("switch with 2 cases", large_switch(2));
+    bench("switch with 128 cases", large_switch(128));
+    bench("switch with 1024 cases", large_switch(1024));
+}
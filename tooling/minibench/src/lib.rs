@@ -0,0 +1,25 @@
+//! A small harness for benchmarking the MiniRust interpreter on programs built with `miniutil`.
+
+use std::time::{Duration, Instant};
+
+use miniutil::run::*;
+use miniutil::*;
+
+/// How often each benchmark program is run.
+const ITERATIONS: u32 = 10;
+
+/// Runs `prog` a few times and prints the average time one run takes.
+/// Panics if the program does not stop normally.
+pub fn bench(name: &str, prog: Program) {
+    // Run once before measuring, to warm up caches.
+    assert_eq!(run_program::<BasicMem>(prog), TerminationInfo::MachineStop);
+
+    let mut total = Duration::ZERO;
+    for _ in 0..ITERATIONS {
+        let start = Instant::now();
+        let info = run_program::<BasicMem>(prog);
+        total += start.elapsed();
+        assert_eq!(info, TerminationInfo::MachineStop);
+    }
+    println!("{name}: {:?} per run", total / ITERATIONS);
+}
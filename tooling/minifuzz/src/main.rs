@@ -0,0 +1,70 @@
+//! Generates random MiniRust programs and runs them, looking for bugs in the interpreter.
+//!
+//! Usage: `minifuzz [--seed N] [--count N] [--max-ops N] [--no-pointers] [--no-enums] [--no-concurrency] [--dump] [--reduce]`
+//!
+//! Checks the programs for seeds `N..N+count` and prints every finding together with its seed.
+//! With `--dump`, the programs of the findings are printed as well.
+//! With `--reduce`, they are reduced to small programs with the same kind of finding and printed.
+//! Exits with a non-zero exit code if there were findings.
+
+use miniutil::fmt::dump_program;
+use miniutil::fuzz::*;
+use miniutil::reduce::reduce;
+
+fn usage() -> ! {
+    eprintln!(
+        "Usage: minifuzz [--seed N] [--count N] [--max-ops N] [--no-pointers] [--no-enums] [--no-concurrency] [--dump] [--reduce]"
+    );
+    std::process::exit(2)
+}
+
+fn parse_number<T: std::str::FromStr>(arg: Option<String>) -> T {
+    arg.and_then(|arg| arg.parse().ok()).unwrap_or_else(|| usage())
+}
+
+fn main() {
+    let mut config = FuzzConfig::default();
+    let mut seed = 0;
+    let mut count = 100;
+    let mut dump = false;
+    let mut reduce_findings = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => seed = parse_number(args.next()),
+            "--count" => count = parse_number(args.next()),
+            "--max-ops" => config.max_ops = parse_number(args.next()),
+            "--no-pointers" => config.pointers = false,
+            "--no-enums" => config.enums = false,
+            "--no-concurrency" => config.concurrency = false,
+            "--dump" => dump = true,
+            "--reduce" => reduce_findings = true,
+            _ => usage(),
+        }
+    }
+
+    // Panics are reported as findings, so don't print them when they happen.
+    std::panic::set_hook(Box::new(|_| {}));
+
+    let mut findings = 0;
+    for seed in seed..seed + count {
+        let Some(finding) = fuzz_one(seed, config) else { continue };
+        findings += 1;
+        println!("seed {seed}: {finding}");
+        let mut prog = generate_program(seed, config);
+        if reduce_findings {
+            let kind = std::mem::discriminant(&finding);
+            prog = reduce(prog, |prog| {
+                check_program(prog).is_some_and(|f| std::mem::discriminant(&f) == kind)
+            });
+        }
+        if dump || reduce_findings {
+            dump_program(prog);
+        }
+    }
+    println!("{findings} findings in {count} programs");
+    if findings > 0 {
+        std::process::exit(1);
+    }
+}
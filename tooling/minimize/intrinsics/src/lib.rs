@@ -7,7 +7,9 @@ use std::fmt::Display;
 use std::alloc::{System, Layout, Allocator};
 use std::ptr::NonNull;
 use std::sync::Mutex;
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicPtr, AtomicU32};
+pub use std::sync::atomic::Ordering;
 use std::thread::{JoinHandle, self, Thread};
 
 pub fn print(t: impl Display) {
@@ -33,6 +35,13 @@ pub unsafe fn deallocate(ptr: *mut u8, size: usize, align: usize) {
     unsafe { System.deallocate(ptr, layout); }
 }
 
+/// Replaces the uninitialized bytes of `*ptr` with arbitrary initialized bytes.
+pub unsafe fn freeze<T>(ptr: *mut T) {
+    // On real hardware, memory always holds some concrete bytes already.
+    // We just have to make sure the compiler does not exploit that they are uninitialized.
+    std::hint::black_box(ptr);
+}
+
 // This global keeps track of any join handles produced. It is needed
 // because the minirust intrinsic for spawn only returns an integer and
 // the join only takes an integer, so we have to map these integers to `JoinHandles`
@@ -73,6 +82,8 @@ pub fn join(thread_id: usize) {
 enum LockState {
     Open,
     Locked,
+    /// Held by this many readers.
+    Shared(usize),
 }
 
 // We cannot use the locks from the standard library since MiniRust locks are acquired
@@ -112,9 +123,38 @@ pub fn acquire(lock_id: usize) {
     }
 }
 
+// Like `acquire`, but the lock can be shared with other readers.
+pub fn acquire_shared(lock_id: usize) {
+    loop {
+        let mut locks = LOCKS.lock().unwrap();
+
+        match locks[lock_id] {
+            LockState::Open => {
+                locks[lock_id] = LockState::Shared(1);
+                return;
+            }
+            LockState::Shared(n) => {
+                locks[lock_id] = LockState::Shared(n + 1);
+                return;
+            }
+            LockState::Locked => {}
+        }
+
+        drop(locks);
+        WAITING.lock().unwrap().push(thread::current());
+        thread::park()
+    }
+}
+
 // Unparks all threads for simplicity.
 pub fn release(lock_id: usize) {
-    LOCKS.lock().unwrap()[lock_id] = LockState::Open;
+    {
+        let mut locks = LOCKS.lock().unwrap();
+        locks[lock_id] = match locks[lock_id] {
+            LockState::Shared(n) if n > 1 => LockState::Shared(n - 1),
+            _ => LockState::Open,
+        };
+    }
 
     let mut waiting = WAITING.lock().unwrap();
     // We don't precisely track who is waiting for which lock, so
@@ -126,31 +166,204 @@ pub fn release(lock_id: usize) {
 }
 
 
-pub unsafe fn atomic_store(ptr: *mut u32, value: u32) {
+struct CondVarWaiter {
+    thread: Thread,
+    notified: Arc<AtomicBool>,
+}
+
+// The threads waiting on each condition variable.
+static CONDVARS: Mutex<Vec<Vec<CondVarWaiter>>> = Mutex::new( Vec::new() );
+
+pub fn create_condvar() -> usize {
+    let mut condvars = CONDVARS.lock().unwrap();
+
+    let id = condvars.len();
+    condvars.push(Vec::new());
+    id
+}
+
+pub fn condvar_wait(condvar_id: usize, lock_id: usize) {
+    let notified = Arc::new(AtomicBool::new(false));
+    // We register as a waiter before releasing the lock, so no notification can get lost.
+    CONDVARS.lock().unwrap()[condvar_id].push(CondVarWaiter { thread: thread::current(), notified: notified.clone() });
+    release(lock_id);
+
+    // `park` can wake up spuriously, but we still have to wait for the notification:
+    // otherwise, a later notification could be used up by our stale entry.
+    while !notified.load(Ordering::SeqCst) {
+        thread::park();
+    }
+    acquire(lock_id);
+}
+
+fn notify(condvar_id: usize, all: bool) {
+    let mut condvars = CONDVARS.lock().unwrap();
+    let waiters = &mut condvars[condvar_id];
+    let count = if all { waiters.len() } else { waiters.len().min(1) };
+    for waiter in waiters.drain(..count) {
+        waiter.notified.store(true, Ordering::SeqCst);
+        waiter.thread.unpark();
+    }
+}
+
+pub fn condvar_notify_one(condvar_id: usize) {
+    notify(condvar_id, false);
+}
+
+pub fn condvar_notify_all(condvar_id: usize) {
+    notify(condvar_id, true);
+}
+
+struct FutexWaiter {
+    addr: usize,
+    thread: Thread,
+    woken: Arc<AtomicBool>,
+}
+
+// The threads blocked in `futex_wait`, together with the address they wait on.
+static FUTEX_WAITERS: Mutex<Vec<FutexWaiter>> = Mutex::new( Vec::new() );
+
+/// Blocks until woken by `futex_wake` if `*ptr == expected`, and returns whether it blocked.
+pub unsafe fn futex_wait(ptr: *mut u32, expected: u32) -> bool {
+    // `futex_wake` takes the same lock, so no wakeup can get lost between the check and blocking.
+    let mut waiters = FUTEX_WAITERS.lock().unwrap();
+    if AtomicU32::from_ptr(ptr).load(Ordering::SeqCst) != expected {
+        return false;
+    }
+
+    let woken = Arc::new(AtomicBool::new(false));
+    waiters.push(FutexWaiter { addr: ptr as usize, thread: thread::current(), woken: woken.clone() });
+    drop(waiters);
+
+    // `park` can wake up spuriously, so we wait until we were actually woken.
+    while !woken.load(Ordering::SeqCst) {
+        thread::park();
+    }
+    true
+}
+
+/// Wakes up one thread waiting on `ptr`, and returns whether there was one.
+pub fn futex_wake(ptr: *mut u32) -> bool {
+    let mut waiters = FUTEX_WAITERS.lock().unwrap();
+    let Some(idx) = waiters.iter().position(|waiter| waiter.addr == ptr as usize) else {
+        return false;
+    };
+
+    let waiter = waiters.remove(idx);
+    waiter.woken.store(true, Ordering::SeqCst);
+    waiter.thread.unpark();
+    true
+}
+
+
+// The orderings of these functions must be constants, since they become part of the MiniRust intrinsic.
+
+pub unsafe fn atomic_store(ptr: *mut u32, value: u32, ordering: Ordering) {
     let atomic = AtomicU32::from_ptr(ptr);
-    atomic.store(value, Ordering::SeqCst);
+    atomic.store(value, ordering);
 }
 
-pub unsafe fn atomic_load(ptr: *mut u32) -> u32 {
+pub unsafe fn atomic_load(ptr: *mut u32, ordering: Ordering) -> u32 {
     let atomic = AtomicU32::from_ptr(ptr);
-    atomic.load(Ordering::SeqCst)
+    atomic.load(ordering)
 }
 
-pub unsafe fn compare_exchange(ptr: *mut u32, current: u32, new: u32) -> u32 {
+pub unsafe fn compare_exchange(ptr: *mut u32, current: u32, new: u32, success: Ordering, failure: Ordering) -> u32 {
     let atomic = AtomicU32::from_ptr(ptr);
-    let res = atomic.compare_exchange(current, new, Ordering::SeqCst, Ordering::SeqCst);
+    let res = atomic.compare_exchange(current, new, success, failure);
     match res {
         Ok(ret) => ret,
         Err(ret) => ret,
     }
 }
 
-pub unsafe fn atomic_fetch_add(ptr: *mut u32, delta: u32) -> u32 {
+/// Returns the previous value and whether the exchange happened, which may spuriously not be the case.
+pub unsafe fn compare_exchange_weak(ptr: *mut u32, current: u32, new: u32, success: Ordering, failure: Ordering) -> (u32, bool) {
+    let atomic = AtomicU32::from_ptr(ptr);
+    let res = atomic.compare_exchange_weak(current, new, success, failure);
+    match res {
+        Ok(ret) => (ret, true),
+        Err(ret) => (ret, false),
+    }
+}
+
+pub unsafe fn atomic_fetch_add(ptr: *mut u32, delta: u32, ordering: Ordering) -> u32 {
+    let atomic = AtomicU32::from_ptr(ptr);
+    atomic.fetch_add(delta, ordering)
+}
+
+pub unsafe fn atomic_fetch_sub(ptr: *mut u32, delta: u32, ordering: Ordering) -> u32 {
+    let atomic = AtomicU32::from_ptr(ptr);
+    atomic.fetch_sub(delta, ordering)
+}
+
+pub unsafe fn atomic_fetch_and(ptr: *mut u32, val: u32, ordering: Ordering) -> u32 {
+    let atomic = AtomicU32::from_ptr(ptr);
+    atomic.fetch_and(val, ordering)
+}
+
+pub unsafe fn atomic_fetch_or(ptr: *mut u32, val: u32, ordering: Ordering) -> u32 {
+    let atomic = AtomicU32::from_ptr(ptr);
+    atomic.fetch_or(val, ordering)
+}
+
+pub unsafe fn atomic_fetch_xor(ptr: *mut u32, val: u32, ordering: Ordering) -> u32 {
+    let atomic = AtomicU32::from_ptr(ptr);
+    atomic.fetch_xor(val, ordering)
+}
+
+pub unsafe fn atomic_fetch_nand(ptr: *mut u32, val: u32, ordering: Ordering) -> u32 {
+    let atomic = AtomicU32::from_ptr(ptr);
+    atomic.fetch_nand(val, ordering)
+}
+
+pub unsafe fn atomic_fetch_min(ptr: *mut u32, val: u32, ordering: Ordering) -> u32 {
     let atomic = AtomicU32::from_ptr(ptr);
-    atomic.fetch_add(delta, Ordering::SeqCst)
+    atomic.fetch_min(val, ordering)
 }
 
-pub unsafe fn atomic_fetch_sub(ptr: *mut u32, delta: u32) -> u32 {
+pub unsafe fn atomic_fetch_max(ptr: *mut u32, val: u32, ordering: Ordering) -> u32 {
     let atomic = AtomicU32::from_ptr(ptr);
-    atomic.fetch_sub(delta, Ordering::SeqCst)
+    atomic.fetch_max(val, ordering)
+}
+
+pub unsafe fn atomic_fetch_min_i32(ptr: *mut i32, val: i32, ordering: Ordering) -> i32 {
+    let atomic = AtomicI32::from_ptr(ptr);
+    atomic.fetch_min(val, ordering)
+}
+
+pub unsafe fn atomic_fetch_max_i32(ptr: *mut i32, val: i32, ordering: Ordering) -> i32 {
+    let atomic = AtomicI32::from_ptr(ptr);
+    atomic.fetch_max(val, ordering)
+}
+
+pub unsafe fn atomic_swap(ptr: *mut u32, val: u32, ordering: Ordering) -> u32 {
+    let atomic = AtomicU32::from_ptr(ptr);
+    atomic.swap(val, ordering)
+}
+
+// Pointer versions of the atomic operations, for lock-free data structures built on `AtomicPtr`.
+
+pub unsafe fn atomic_store_ptr<T>(ptr: *mut *mut T, value: *mut T, ordering: Ordering) {
+    let atomic = AtomicPtr::from_ptr(ptr);
+    atomic.store(value, ordering);
+}
+
+pub unsafe fn atomic_load_ptr<T>(ptr: *mut *mut T, ordering: Ordering) -> *mut T {
+    let atomic = AtomicPtr::from_ptr(ptr);
+    atomic.load(ordering)
+}
+
+pub unsafe fn compare_exchange_ptr<T>(ptr: *mut *mut T, current: *mut T, new: *mut T, success: Ordering, failure: Ordering) -> *mut T {
+    let atomic = AtomicPtr::from_ptr(ptr);
+    let res = atomic.compare_exchange(current, new, success, failure);
+    match res {
+        Ok(ret) => ret,
+        Err(ret) => ret,
+    }
+}
+
+pub unsafe fn atomic_swap_ptr<T>(ptr: *mut *mut T, val: *mut T, ordering: Ordering) -> *mut T {
+    let atomic = AtomicPtr::from_ptr(ptr);
+    atomic.swap(val, ordering)
 }
@@ -3,11 +3,23 @@ use crate::*;
 // Some Rust features are not supported, and are ignored by `minimize`.
 // Those can be found by grepping "IGNORED".
 
-/// A MIR statement becomes either a MiniRust statement or an intrinsic with some arguments, which
-/// then starts a new basic block.
+/// A MIR statement becomes either a MiniRust statement, an intrinsic with some arguments, or
+/// some other terminator; the latter two then start a new basic block.
 enum StatementResult {
     Statement(Statement),
-    Intrinsic { intrinsic: IntrinsicOp, destination: PlaceExpr, arguments: List<ValueExpr> },
+    /// Several statements, to be executed in this order.
+    Statements(List<Statement>),
+    Intrinsic {
+        intrinsic: IntrinsicOp,
+        destination: PlaceExpr,
+        arguments: List<ValueExpr>,
+    },
+    /// End the current block with `terminator`, and continue in `next_block`.
+    /// All other blocks the terminator jumps to have already been inserted.
+    Terminator {
+        terminator: Terminator,
+        next_block: BbName,
+    },
 }
 
 /// A MIR terminator becomes a MiniRust terminator, possibly preceded by a list
@@ -24,10 +36,21 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
         let mut cur_block_name = name;
         let mut cur_block_statements = List::new();
         for stmt in bb.statements.iter() {
+            // Whatever this becomes, it ends up at the current end of the current block.
+            self.record_span(cur_block_name, cur_block_statements.len(), stmt.source_info.span);
             match self.translate_stmt(stmt) {
                 StatementResult::Statement(stmt) => {
                     cur_block_statements.push(stmt);
                 }
+                StatementResult::Statements(stmts) =>
+                    for new_stmt in stmts.iter() {
+                        self.record_span(
+                            cur_block_name,
+                            cur_block_statements.len(),
+                            stmt.source_info.span,
+                        );
+                        cur_block_statements.push(new_stmt);
+                    },
                 StatementResult::Intrinsic { intrinsic, destination, arguments } => {
                     // Generate a fresh bb name.
                     let next_bb = self.fresh_bb_name();
@@ -45,12 +68,22 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                     cur_block_name = next_bb;
                     cur_block_statements = List::new();
                 }
+                StatementResult::Terminator { terminator, next_block } => {
+                    let cur_block = BasicBlock { statements: cur_block_statements, terminator };
+                    let old = self.blocks.insert(cur_block_name, cur_block);
+                    assert!(old.is_none()); // make sure we do not overwrite a bb
+                    cur_block_name = next_block;
+                    cur_block_statements = List::new();
+                }
             }
         }
         let TerminatorResult { stmts, terminator } = self.translate_terminator(bb.terminator());
+        let span = bb.terminator().source_info.span;
         for stmt in stmts.iter() {
+            self.record_span(cur_block_name, cur_block_statements.len(), span);
             cur_block_statements.push(stmt);
         }
+        self.record_span(cur_block_name, cur_block_statements.len(), span);
         let cur_block = BasicBlock { statements: cur_block_statements, terminator };
         let old = self.blocks.insert(cur_block_name, cur_block);
         assert!(old.is_none()); // make sure we do not overwrite a bb
@@ -73,7 +106,6 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                         };
                     }
                     rs::Rvalue::Cast(rs::CastKind::PointerWithExposedProvenance, operand, _) => {
-                        // TODO untested so far! (Can't test because of `predict`)
                         let operand = self.translate_operand(operand, span);
                         return StatementResult::Intrinsic {
                             intrinsic: IntrinsicOp::PointerWithExposedProvenance,
@@ -81,19 +113,43 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                             arguments: list![operand],
                         };
                     }
+                    rs::Rvalue::BinaryOp(rs::BinOp::Cmp, box (l, r)) =>
+                        return self.translate_cmp(destination, l, r, span),
                     _ => {}
                 }
                 let source = self.translate_rvalue(rval, span);
-                Statement::Assign { destination, source }
+                let assign = Statement::Assign { destination, source };
+                // rustc does not emit retags for references, since Miri retags them when they are
+                // created, which `AddrOf` does as well. But two-phase borrows need a retag that
+                // reserves them, which we add explicitly.
+                if let rs::Rvalue::Ref(
+                    _,
+                    rs::mir::BorrowKind::Mut { kind: rs::MutBorrowKind::TwoPhaseBorrow },
+                    _,
+                ) = rval
+                {
+                    let validate = Statement::Validate {
+                        place: destination,
+                        fn_entry: false,
+                        two_phase: true,
+                        behind_reference: false,
+                    };
+                    return StatementResult::Statements(list![assign, validate]);
+                }
+                assign
             }
+            // FIXME: unsized locals get their size from the first value moved into them,
+            // so they would need the metadata of that value here.
             rs::StatementKind::StorageLive(local) =>
-                Statement::StorageLive(self.local_name_map[&local]),
+                Statement::StorageLive { local: self.local_name_map[&local], meta: None },
             rs::StatementKind::StorageDead(local) =>
                 Statement::StorageDead(self.local_name_map[&local]),
             rs::StatementKind::Retag(kind, place) => {
                 let place = self.translate_place(place, span);
                 let fn_entry = matches!(kind, rs::RetagKind::FnEntry);
-                Statement::Validate { place, fn_entry }
+                let two_phase = matches!(kind, rs::RetagKind::TwoPhase);
+                // FIXME: also emit `behind_reference` validation for the pointees of references.
+                Statement::Validate { place, fn_entry, two_phase, behind_reference: false }
             }
             rs::StatementKind::Deinit(place) => {
                 let place = self.translate_place(place, span);
@@ -120,8 +176,20 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                             arguments: list![op],
                         };
                     }
-                    rs::NonDivergingIntrinsic::CopyNonOverlapping(_) =>
-                        rs::span_bug!(span, "NonDivergingIntrinsic not supported: {intrinsic:?}"),
+                    rs::NonDivergingIntrinsic::CopyNonOverlapping(cno) => {
+                        let src_ty = cno.src.ty(&self.body, self.tcx);
+                        let elem = self.translate_ty(src_ty.builtin_deref(true).unwrap(), span);
+                        let src = self.translate_operand(&cno.src, span);
+                        let dst = self.translate_operand(&cno.dst, span);
+                        let count = self.translate_operand(&cno.count, span);
+                        // Doesn't return anything, get us a dummy place.
+                        let destination = build::unit_place();
+                        return StatementResult::Intrinsic {
+                            intrinsic: IntrinsicOp::CopyNonOverlapping(elem),
+                            destination,
+                            arguments: list![src, dst, count],
+                        };
+                    }
                 }
             }
             rs::StatementKind::PlaceMention(place) => {
@@ -129,23 +197,80 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                 Statement::PlaceMention(place)
             }
 
+            // Left behind by MIR transformations such as the coroutine lowering, and does nothing.
+            rs::StatementKind::Nop => return StatementResult::Statements(List::new()),
+            // Only counts steps for the const-eval step limit, which MiniRust does not have.
+            rs::StatementKind::ConstEvalCounter => return StatementResult::Statements(List::new()),
+
             rs::StatementKind::FakeRead(_)
             | rs::StatementKind::AscribeUserType(_, _)
-            | rs::StatementKind::Coverage(_)
-            | rs::StatementKind::ConstEvalCounter
-            | rs::StatementKind::Nop => {
+            | rs::StatementKind::Coverage(_) => {
                 rs::span_bug!(span, "Statement not supported: {:?}", stmt.kind);
             }
         })
     }
 
+    /// MIR's `Cmp` returns a `core::cmp::Ordering`, but MiniRust's returns an `i8`.
+    /// So we switch on that `i8` and assign the matching `Ordering` variant in each branch.
+    fn translate_cmp(
+        &mut self,
+        destination: PlaceExpr,
+        l: &rs::Operand<'tcx>,
+        r: &rs::Operand<'tcx>,
+        span: rs::Span,
+    ) -> StatementResult {
+        let l = self.translate_operand(l, span);
+        let r = self.translate_operand(r, span);
+
+        let ordering_ty: rs::Ty = self.tcx.ty_ordering_enum(None);
+        let rs::TyKind::Adt(adt_def, _) = ordering_ty.kind() else {
+            rs::span_bug!(span, "`Ordering` is not an ADT")
+        };
+        let enum_ty = self.translate_ty(ordering_ty, span);
+        let Type::Enum { variants, .. } = enum_ty else {
+            rs::span_bug!(span, "`Ordering` is not an enum")
+        };
+
+        let next_block = self.fresh_bb_name();
+        let mut cases = Map::new();
+        let mut fallback = None;
+        for (variant_idx, variant) in adt_def.variants().iter_enumerated() {
+            let discriminant = self.discriminant_for_variant(ordering_ty, variant_idx, span);
+            let data = ValueExpr::Tuple(List::new(), variants.get(discriminant).unwrap().ty);
+            let source = ValueExpr::Variant { discriminant, data: GcCow::new(data), enum_ty };
+            let bb_name = self.fresh_bb_name();
+            let block = BasicBlock {
+                statements: list![Statement::Assign { destination, source }],
+                terminator: Terminator::Goto(next_block),
+            };
+            let old = self.blocks.insert(bb_name, block);
+            assert!(old.is_none()); // make sure we do not overwrite a bb
+
+            // MiniRust's `Cmp` returns -1, 0, or 1.
+            match variant.name.as_str() {
+                "Less" => {
+                    cases.insert(Int::from(-1), bb_name);
+                }
+                "Equal" => {
+                    cases.insert(Int::ZERO, bb_name);
+                }
+                "Greater" => fallback = Some(bb_name),
+                name => rs::span_bug!(span, "unexpected `Ordering` variant `{name}`"),
+            }
+        }
+
+        let terminator =
+            Terminator::Switch { value: build::cmp(l, r), cases, fallback: fallback.unwrap() };
+        StatementResult::Terminator { terminator, next_block }
+    }
+
     fn translate_terminator(&mut self, terminator: &rs::Terminator<'tcx>) -> TerminatorResult {
         let span = terminator.source_info.span;
         let terminator = match &terminator.kind {
             rs::TerminatorKind::Return => Terminator::Return,
             rs::TerminatorKind::Goto { target } => Terminator::Goto(self.bb_name_map[&target]),
-            rs::TerminatorKind::Call { func, target, destination, args, .. } =>
-                return self.translate_call(func, args, destination, target, span),
+            rs::TerminatorKind::Call { func, target, destination, args, unwind, .. } =>
+                return self.translate_call(func, args, destination, target, unwind, span),
             rs::TerminatorKind::SwitchInt { discr, targets } => {
                 let ty = discr.ty(&self.body, self.tcx);
                 let ty = self.translate_ty(ty, span);
@@ -163,12 +288,15 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                             u8_inttype,
                         )
                     }
+                    Type::Char => {
+                        // Switch on the code point of the `char`.
+                        (build::transmute(discr_op, Type::Int(IntType::U32)), IntType::U32)
+                    }
                     Type::Int(ity) => (discr_op, ity),
-                    // FIXME: add support for switching on `char`
                     _ =>
                         rs::span_bug!(
                             span,
-                            "SwitchInt terminator currently only supports int and bool."
+                            "SwitchInt terminator currently only supports int, bool and char."
                         ),
                 };
 
@@ -185,7 +313,7 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                 Terminator::Switch { value, cases, fallback }
             }
             rs::TerminatorKind::Unreachable => Terminator::Unreachable,
-            rs::TerminatorKind::Assert { cond, expected, target, .. } => {
+            rs::TerminatorKind::Assert { cond, expected, target, unwind, .. } => {
                 let mut condition = self.translate_operand(cond, span);
                 // Check equality of `condition` and `expected`.
                 // We do this by inverting `condition` if `expected` is false
@@ -196,7 +324,10 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
 
                 // Create panic block in case of `expected != condition`
                 let panic_bb = self.fresh_bb_name();
-                let panic_block = BasicBlock { statements: list![], terminator: build::panic() };
+                let panic_block = BasicBlock {
+                    statements: list![],
+                    terminator: Terminator::StartUnwind(self.translate_unwind_action(unwind)),
+                };
                 self.blocks.try_insert(panic_bb, panic_block).unwrap();
 
                 let next_block = self.bb_name_map[target];
@@ -206,7 +337,7 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                     fallback: panic_bb,
                 }
             }
-            rs::TerminatorKind::Drop { place, target, .. } => {
+            rs::TerminatorKind::Drop { place, target, unwind, .. } => {
                 let ty = place.ty(&self.body, self.tcx).ty;
                 let place = self.translate_place(place, span);
                 let (drop_fn, ptr_to_drop) = match ty.kind() {
@@ -248,17 +379,38 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                     arguments: list![ArgumentExpr::ByValue(ptr_to_drop)],
                     ret: unit_place(),
                     next_block: Some(self.bb_name_map[&target]),
+                    unwind_block: self.translate_unwind_action(unwind),
                 }
             }
+            rs::TerminatorKind::UnwindResume => Terminator::ResumeUnwind,
+            rs::TerminatorKind::UnwindTerminate(_) => Terminator::UnwindTerminate,
 
-            rs::TerminatorKind::UnwindResume
-            | rs::TerminatorKind::UnwindTerminate(_)
-            | rs::TerminatorKind::TailCall { .. }
-            | rs::TerminatorKind::Yield { .. }
-            | rs::TerminatorKind::CoroutineDrop
-            | rs::TerminatorKind::FalseEdge { .. }
-            | rs::TerminatorKind::FalseUnwind { .. }
-            | rs::TerminatorKind::InlineAsm { .. } => {
+            rs::TerminatorKind::Yield { .. } | rs::TerminatorKind::CoroutineDrop => {
+                // The coroutine state transform turns these into ordinary control flow
+                // and state changes before we get to see the MIR.
+                rs::span_bug!(span, "Terminator should have been lowered: {:?}", terminator.kind);
+            }
+            rs::TerminatorKind::TailCall { func, args, .. } => {
+                // Translate this like a call that returns to our caller, and then make the callee
+                // take over our stack frame.
+                let TerminatorResult { terminator, stmts } = self.translate_call(
+                    func,
+                    args,
+                    &rs::Place::return_place(),
+                    &None,
+                    &rs::UnwindAction::Continue,
+                    span,
+                );
+                let Terminator::Call { callee, calling_convention, arguments, .. } = terminator
+                else {
+                    rs::span_bug!(span, "tail calls are only supported for regular functions")
+                };
+                assert!(stmts.is_empty());
+                Terminator::TailCall { callee, calling_convention, arguments }
+            }
+            rs::TerminatorKind::InlineAsm { operands, targets, .. } =>
+                self.translate_inline_asm(operands, targets, span),
+            rs::TerminatorKind::FalseEdge { .. } | rs::TerminatorKind::FalseUnwind { .. } => {
                 rs::span_bug!(span, "Terminator not supported: {:?}", terminator.kind);
             }
         };
@@ -274,27 +426,23 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
         target: &Option<rs::BasicBlock>,
         span: rs::Span,
     ) -> TerminatorResult {
-        let param_env = rs::ParamEnv::reveal_all();
         let intrinsic_name = self.tcx.item_name(intrinsic.def_id());
         match intrinsic_name {
             rs::sym::assert_inhabited
             | rs::sym::assert_zero_valid
             | rs::sym::assert_mem_uninitialized_valid => {
-                let ty = intrinsic.args.type_at(0);
-                let requirement =
-                    rs::layout::ValidityRequirement::from_intrinsic(intrinsic_name).unwrap();
-                let should_panic =
-                    !self.tcx.check_validity_requirement((requirement, param_env.and(ty))).unwrap();
-
-                let terminator = if should_panic {
-                    Terminator::Intrinsic {
-                        intrinsic: IntrinsicOp::Panic,
-                        arguments: list![],
-                        ret: unit_place(),
-                        next_block: None,
-                    }
-                } else {
-                    Terminator::Goto(self.bb_name_map[&target.unwrap()])
+                // The machine decides whether the type satisfies the requirement.
+                let ty = self.translate_ty(intrinsic.args.type_at(0), span);
+                let requirement = match intrinsic_name {
+                    rs::sym::assert_inhabited => ValidityRequirement::Inhabited,
+                    rs::sym::assert_zero_valid => ValidityRequirement::Zero,
+                    _ => ValidityRequirement::MemUninitialized,
+                };
+                let terminator = Terminator::Intrinsic {
+                    intrinsic: IntrinsicOp::AssertValidity(requirement, ty),
+                    arguments: list![],
+                    ret: unit_place(),
+                    next_block: target.as_ref().map(|t| self.bb_name_map[t]),
                 };
                 return TerminatorResult { terminator, stmts: List::new() };
             }
@@ -311,6 +459,30 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                         next_block: target.as_ref().map(|t| self.bb_name_map[t]),
                     },
                 },
+            rs::sym::copy
+            | rs::sym::copy_nonoverlapping
+            | rs::sym::write_bytes
+            | rs::sym::typed_swap => {
+                let elem = self.translate_ty(intrinsic.args.type_at(0), span);
+                let intrinsic = match intrinsic_name {
+                    rs::sym::copy => IntrinsicOp::Copy(elem),
+                    rs::sym::copy_nonoverlapping => IntrinsicOp::CopyNonOverlapping(elem),
+                    rs::sym::typed_swap => IntrinsicOp::TypedSwapNonOverlapping(elem),
+                    _ => IntrinsicOp::WriteBytes(elem),
+                };
+                return TerminatorResult {
+                    stmts: List::new(),
+                    terminator: Terminator::Intrinsic {
+                        intrinsic,
+                        arguments: args
+                            .iter()
+                            .map(|x| self.translate_operand(&x.node, x.span))
+                            .collect(),
+                        ret: self.translate_place(&destination, span),
+                        next_block: target.as_ref().map(|t| self.bb_name_map[t]),
+                    },
+                };
+            }
             rs::sym::arith_offset => {
                 let lty = args[0].node.ty(&self.body, self.tcx);
                 let rty = args[1].node.ty(&self.body, self.tcx);
@@ -342,8 +514,10 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                 let destination = self.translate_place(&destination, span);
 
                 // Compute distance in bytes.
+                // Both pointers must be in-bounds of the same allocation (or equal),
+                // which is exactly what `inbounds` checks.
                 let offset_bytes = ValueExpr::BinOp {
-                    operator: BinOp::PtrOffsetFrom { inbounds: false, nonneg: unsigned },
+                    operator: BinOp::PtrOffsetFrom { inbounds: true, nonneg: unsigned },
                     left: GcCow::new(l),
                     right: GcCow::new(r),
                 };
@@ -370,12 +544,35 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                 let terminator = Terminator::Goto(self.bb_name_map[&target.unwrap()]);
                 return TerminatorResult { stmts: list!(stmt), terminator };
             }
-            rs::sym::exact_div => {
+            rs::sym::exact_div
+            | rs::sym::unchecked_div
+            | rs::sym::unchecked_rem
+            | rs::sym::unchecked_add
+            | rs::sym::unchecked_sub
+            | rs::sym::unchecked_mul
+            | rs::sym::wrapping_add
+            | rs::sym::wrapping_sub
+            | rs::sym::wrapping_mul
+            | rs::sym::saturating_add
+            | rs::sym::saturating_sub => {
                 let l = self.translate_operand(&args[0].node, span);
                 let r = self.translate_operand(&args[1].node, span);
                 let destination = self.translate_place(&destination, span);
 
-                let val = build::div_exact(l, r);
+                let val = match intrinsic_name {
+                    rs::sym::exact_div => build::div_exact(l, r),
+                    // `div` and `rem` already have UB on division by zero and on overflow.
+                    rs::sym::unchecked_div => build::div(l, r),
+                    rs::sym::unchecked_rem => build::rem(l, r),
+                    rs::sym::unchecked_add => build::add_unchecked(l, r),
+                    rs::sym::unchecked_sub => build::sub_unchecked(l, r),
+                    rs::sym::unchecked_mul => build::mul_unchecked(l, r),
+                    rs::sym::wrapping_add => build::add(l, r),
+                    rs::sym::wrapping_sub => build::sub(l, r),
+                    rs::sym::wrapping_mul => build::mul(l, r),
+                    rs::sym::saturating_add => build::add_saturating(l, r),
+                    _ => build::sub_saturating(l, r),
+                };
 
                 let stmt = Statement::Assign { destination, source: val };
                 let terminator = Terminator::Goto(self.bb_name_map[&target.unwrap()]);
@@ -393,6 +590,26 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                 let terminator = Terminator::Goto(self.bb_name_map[&target.unwrap()]);
                 TerminatorResult { stmts: list!(stmt), terminator }
             }
+            rs::sym::ptr_metadata => {
+                let destination = self.translate_place(destination, span);
+                let ptr = self.translate_operand(&args[0].node, span);
+                let stmt = Statement::Assign { destination, source: build::get_metadata(ptr) };
+                let terminator = Terminator::Goto(self.bb_name_map[&target.unwrap()]);
+                TerminatorResult { stmts: list!(stmt), terminator }
+            }
+            rs::sym::aggregate_raw_ptr => {
+                let destination = self.translate_place(destination, span);
+                let ptr = self.translate_operand(&args[0].node, span);
+                let meta = self.translate_operand(&args[1].node, span);
+                let ptr_ty = self.translate_ty(intrinsic.args.type_at(0), span);
+                // The data pointer is `impl Thin`, so this matches `ConstructWidePointer` directly.
+                let stmt = Statement::Assign {
+                    destination,
+                    source: build::construct_wide_pointer(ptr, meta, ptr_ty),
+                };
+                let terminator = Terminator::Goto(self.bb_name_map[&target.unwrap()]);
+                TerminatorResult { stmts: list!(stmt), terminator }
+            }
             rs::sym::min_align_of_val => {
                 let destination = self.translate_place(destination, span);
                 let ptr = self.translate_operand(&args[0].node, span);
@@ -414,22 +631,161 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
 
                 return TerminatorResult { stmts: list!(stmt), terminator };
             }
+            rs::sym::unreachable => {
+                // `unreachable_unchecked` returns `!`, so reaching it is UB.
+                return TerminatorResult {
+                    stmts: List::new(),
+                    terminator: Terminator::Unreachable,
+                };
+            }
             name => rs::span_bug!(span, "unsupported Rust intrinsic `{}`", name),
         }
     }
 
+    /// Translates an `asm!` block to an opaque `InlineAsm` terminator.
+    /// Rust does not declare which memory the assembly accesses, so we only translate the register operands.
+    fn translate_inline_asm(
+        &mut self,
+        operands: &[rs::InlineAsmOperand<'tcx>],
+        targets: &[rs::BasicBlock],
+        span: rs::Span,
+    ) -> Terminator {
+        let mut inputs = List::new();
+        let mut outputs = List::new();
+        for operand in operands {
+            match operand {
+                rs::InlineAsmOperand::In { value, .. } =>
+                    inputs.push(self.translate_operand(value, span)),
+                rs::InlineAsmOperand::Out { place, .. } =>
+                    if let Some(place) = place {
+                        outputs.push(self.translate_place(place, span));
+                    },
+                rs::InlineAsmOperand::InOut { in_value, out_place, .. } => {
+                    inputs.push(self.translate_operand(in_value, span));
+                    if let Some(out_place) = out_place {
+                        outputs.push(self.translate_place(out_place, span));
+                    }
+                }
+                // These are resolved at compile time and do not affect the execution.
+                rs::InlineAsmOperand::Const { .. }
+                | rs::InlineAsmOperand::SymFn { .. }
+                | rs::InlineAsmOperand::SymStatic { .. } => {}
+                rs::InlineAsmOperand::Label { .. } =>
+                    rs::span_bug!(span, "`asm goto` is not supported"),
+            }
+        }
+        Terminator::InlineAsm {
+            inputs,
+            outputs,
+            reads: List::new(),
+            clobbers: List::new(),
+            // `noreturn` assembly has no fallthrough target.
+            next_block: targets.first().map(|t| self.bb_name_map[t]),
+        }
+    }
+
+    /// Translates the arguments of a call to a function with the given ABI.
+    fn translate_call_args(
+        &mut self,
+        abi: rs::ExternAbi,
+        rs_args: &[rs::Spanned<rs::Operand<'tcx>>],
+    ) -> List<ArgumentExpr> {
+        let mut args: List<_> = rs_args
+            .iter()
+            .map(|x| {
+                match &x.node {
+                    // Moves are passed in-place no matter their type. This de-initializes the
+                    // moved-from place, matching that MIR must not use it again.
+                    rs::Operand::Move(place) =>
+                        ArgumentExpr::InPlace(self.translate_place(place, x.span)),
+                    op => ArgumentExpr::ByValue(self.translate_operand(op, x.span)),
+                }
+            })
+            .collect();
+
+        // The "rust-call" ABI, used by the `Fn*` traits and closures, passes the last argument
+        // untupled: each field of the tuple becomes a separate argument.
+        if abi == rs::ExternAbi::RustCall {
+            let tupled = rs_args.last().unwrap();
+            let tupled_ty = tupled.node.ty(&self.body, self.tcx);
+            let rs::TyKind::Tuple(field_tys) = tupled_ty.kind() else {
+                rs::span_bug!(tupled.span, "\"rust-call\" ABI requires a tuple as last argument");
+            };
+            args = args.iter().take(rs_args.len() - 1).collect();
+            // Calls without arguments pass a `()` constant, there is nothing to untuple then.
+            if !field_tys.is_empty() {
+                let (place, in_place) = match &tupled.node {
+                    rs::Operand::Move(place) => (place, true),
+                    rs::Operand::Copy(place) => (place, false),
+                    rs::Operand::Constant(_) =>
+                        rs::span_bug!(tupled.span, "untupling constant arguments is not supported"),
+                };
+                let place = self.translate_place(place, tupled.span);
+                for i in 0..field_tys.len() {
+                    let field = build::field(place, i);
+                    args.push(if in_place {
+                        ArgumentExpr::InPlace(field)
+                    } else {
+                        ArgumentExpr::ByValue(build::load(field))
+                    });
+                }
+            }
+        }
+
+        args
+    }
+
+    /// Translates where to go when a call unwinds.
+    /// MiniRust has no dedicated "terminate" or "unreachable" unwind actions,
+    /// so we create a fresh block that does the right thing.
+    fn translate_unwind_action(&mut self, unwind: &rs::UnwindAction) -> Option<BbName> {
+        let terminator = match unwind {
+            rs::UnwindAction::Continue => return None,
+            rs::UnwindAction::Cleanup(bb) => return Some(self.bb_name_map[bb]),
+            rs::UnwindAction::Unreachable => Terminator::Unreachable,
+            rs::UnwindAction::Terminate(_) => Terminator::UnwindTerminate,
+        };
+        let bb_name = self.fresh_bb_name();
+        let block = BasicBlock { statements: list![], terminator };
+        self.blocks.try_insert(bb_name, block).unwrap();
+        Some(bb_name)
+    }
+
     fn translate_call(
         &mut self,
         func: &rs::Operand<'tcx>,
         rs_args: &[rs::Spanned<rs::Operand<'tcx>>],
         destination: &rs::Place<'tcx>,
         target: &Option<rs::BasicBlock>,
+        unwind: &rs::UnwindAction,
         span: rs::Span,
     ) -> TerminatorResult {
-        // For now we only support calling specific functions, not function pointers.
-        let rs::Operand::Constant(box f1) = func else { panic!() };
-        let rs::mir::Const::Val(_, f2) = f1.const_ else { panic!() };
-        let &rs::TyKind::FnDef(f, substs_ref) = f2.kind() else { panic!() };
+        let func_ty = func.ty(&self.body, self.tcx);
+        if let rs::TyKind::FnPtr(..) = func_ty.kind() {
+            // An indirect call: the callee is the function pointer that `func` evaluates to.
+            let sig = func_ty.fn_sig(self.tcx);
+            let abi = self
+                .cx
+                .tcx
+                .fn_abi_of_fn_ptr(rs::ParamEnv::reveal_all().and((sig, rs::List::empty())))
+                .unwrap();
+            let terminator = Terminator::Call {
+                callee: self.translate_operand(func, span),
+                calling_convention: translate_calling_convention(abi),
+                arguments: self.translate_call_args(sig.abi(), rs_args),
+                ret: self.translate_place(&destination, span),
+                next_block: target.as_ref().map(|t| self.bb_name_map[t]),
+                unwind_block: self.translate_unwind_action(unwind),
+            };
+            return TerminatorResult { terminator, stmts: List::new() };
+        }
+
+        // A direct call: the function item type of the callee determines the function.
+        // The callee is usually a constant, but may also be a local holding the zero-sized
+        // function item value, e.g. in the `FnOnce::call_once` shim of a function item.
+        let &rs::TyKind::FnDef(f, substs_ref) = func_ty.kind() else {
+            rs::span_bug!(span, "callee is neither a function pointer nor a function item")
+        };
         let param_env = rs::ParamEnv::reveal_all();
         let instance = rs::Instance::expect_resolve(self.tcx, param_env, f, substs_ref, span);
 
@@ -438,6 +794,18 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
             return self.translate_rs_intrinsic(instance, rs_args, destination, target, span);
         }
 
+        if self.is_thin_ptr_with_addr(f, rs_args) {
+            // `with_addr` is implemented via `wrapping_offset`; translate it to the primitive operation.
+            let ptr = self.translate_operand(&rs_args[0].node, rs_args[0].span);
+            let addr = self.translate_operand(&rs_args[1].node, rs_args[1].span);
+            let stmt = Statement::Assign {
+                destination: self.translate_place(&destination, span),
+                source: build::ptr_with_addr(ptr, addr),
+            };
+            let terminator = Terminator::Goto(self.bb_name_map[&target.unwrap()]);
+            return TerminatorResult { stmts: list!(stmt), terminator };
+        }
+
         let terminator = if self.tcx.crate_name(f.krate).as_str() == "intrinsics" {
             // Direct call to a MiniRust intrinsic.
             let intrinsic = match self.tcx.item_name(f).as_str() {
@@ -449,32 +817,109 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                 "deallocate" => IntrinsicOp::Deallocate,
                 "spawn" => IntrinsicOp::Spawn,
                 "join" => IntrinsicOp::Join,
+                "freeze" => IntrinsicOp::Freeze(self.translate_ty(substs_ref.type_at(0), span)),
                 "create_lock" => IntrinsicOp::Lock(IntrinsicLockOp::Create),
                 "acquire" => IntrinsicOp::Lock(IntrinsicLockOp::Acquire),
+                "acquire_shared" => IntrinsicOp::Lock(IntrinsicLockOp::AcquireShared),
                 "release" => IntrinsicOp::Lock(IntrinsicLockOp::Release),
-                "atomic_store" => IntrinsicOp::AtomicStore,
-                "atomic_load" => IntrinsicOp::AtomicLoad,
-                "compare_exchange" => IntrinsicOp::AtomicCompareExchange,
-                "atomic_fetch_add" => IntrinsicOp::AtomicFetchAndOp(IntBinOp::Add),
-                "atomic_fetch_sub" => IntrinsicOp::AtomicFetchAndOp(IntBinOp::Sub),
+                "create_condvar" => IntrinsicOp::Lock(IntrinsicLockOp::CreateCondVar),
+                "condvar_wait" => IntrinsicOp::Lock(IntrinsicLockOp::Wait),
+                "condvar_notify_one" => IntrinsicOp::Lock(IntrinsicLockOp::NotifyOne),
+                "condvar_notify_all" => IntrinsicOp::Lock(IntrinsicLockOp::NotifyAll),
+                "futex_wait" => IntrinsicOp::FutexWait,
+                "futex_wake" => IntrinsicOp::FutexWake,
+                "atomic_store" | "atomic_store_ptr" =>
+                    IntrinsicOp::AtomicStore(self.translate_atomic_ordering(&rs_args[2])),
+                "atomic_load" | "atomic_load_ptr" =>
+                    IntrinsicOp::AtomicLoad(self.translate_atomic_ordering(&rs_args[1])),
+                "compare_exchange" | "compare_exchange_ptr" =>
+                    IntrinsicOp::AtomicCompareExchange {
+                        success: self.translate_atomic_ordering(&rs_args[3]),
+                        failure: self.translate_atomic_ordering(&rs_args[4]),
+                    },
+                "compare_exchange_weak" =>
+                    IntrinsicOp::AtomicCompareExchangeWeak {
+                        success: self.translate_atomic_ordering(&rs_args[3]),
+                        failure: self.translate_atomic_ordering(&rs_args[4]),
+                    },
+                name @ ("atomic_fetch_add"
+                | "atomic_fetch_sub"
+                | "atomic_fetch_and"
+                | "atomic_fetch_or"
+                | "atomic_fetch_xor"
+                | "atomic_fetch_nand"
+                | "atomic_fetch_min"
+                | "atomic_fetch_max"
+                | "atomic_fetch_min_i32"
+                | "atomic_fetch_max_i32"
+                | "atomic_swap"
+                | "atomic_swap_ptr") => {
+                    // Min and max are signed or unsigned depending on the type, so the `_i32`
+                    // variants map to the same operation.
+                    let op = match name {
+                        "atomic_fetch_add" => AtomicRmwOp::IntBinOp(IntBinOp::Add),
+                        "atomic_fetch_sub" => AtomicRmwOp::IntBinOp(IntBinOp::Sub),
+                        "atomic_fetch_and" => AtomicRmwOp::IntBinOp(IntBinOp::BitAnd),
+                        "atomic_fetch_or" => AtomicRmwOp::IntBinOp(IntBinOp::BitOr),
+                        "atomic_fetch_xor" => AtomicRmwOp::IntBinOp(IntBinOp::BitXor),
+                        "atomic_fetch_nand" => AtomicRmwOp::Nand,
+                        "atomic_fetch_min" | "atomic_fetch_min_i32" => AtomicRmwOp::Min,
+                        "atomic_fetch_max" | "atomic_fetch_max_i32" => AtomicRmwOp::Max,
+                        "atomic_swap" | "atomic_swap_ptr" => AtomicRmwOp::Swap,
+                        _ => unreachable!(),
+                    };
+                    IntrinsicOp::AtomicFetchAndOp(op, self.translate_atomic_ordering(&rs_args[2]))
+                }
                 name => panic!("unsupported MiniRust intrinsic `{}`", name),
             };
+            // The orderings of atomic intrinsics are trailing arguments that are part of the `IntrinsicOp`.
+            let num_orderings = match intrinsic {
+                IntrinsicOp::AtomicStore(_)
+                | IntrinsicOp::AtomicLoad(_)
+                | IntrinsicOp::AtomicFetchAndOp(..) => 1,
+                IntrinsicOp::AtomicCompareExchange { .. }
+                | IntrinsicOp::AtomicCompareExchangeWeak { .. } => 2,
+                _ => 0,
+            };
             Terminator::Intrinsic {
                 intrinsic,
-                arguments: rs_args
+                arguments: rs_args[..rs_args.len() - num_orderings]
                     .iter()
                     .map(|x| self.translate_operand(&x.node, x.span))
                     .collect(),
                 ret: self.translate_place(&destination, span),
-                next_block: target.as_ref().map(|t| self.bb_name_map[t]),
+                // The Rust signatures of diverging intrinsics like `exit` still return `()`,
+                // but MiniRust requires them to have no next block.
+                next_block: if intrinsic.is_diverging() {
+                    None
+                } else {
+                    target.as_ref().map(|t| self.bb_name_map[t])
+                },
             }
         } else if is_panic_fn(&instance.to_string()) {
             // We can't translate this call, it takes a string. As a hack we just ignore the argument.
-            Terminator::Intrinsic {
-                intrinsic: IntrinsicOp::Panic,
-                arguments: list![],
-                ret: unit_place(),
-                next_block: None,
+            // Apart from `panic_nounwind`, which aborts, these functions start unwinding.
+            if instance.to_string() == "core::panicking::panic_nounwind" {
+                Terminator::Intrinsic {
+                    intrinsic: IntrinsicOp::Panic,
+                    arguments: list![],
+                    ret: unit_place(),
+                    next_block: None,
+                }
+            } else {
+                Terminator::StartUnwind(self.translate_unwind_action(unwind))
+            }
+        } else if self.tcx.is_foreign_item(f) {
+            // A function declared in an `extern` block, which the environment has to provide.
+            let name = self.tcx.item_name(f).to_string();
+            Terminator::ExternCall {
+                name: minirust_rs::prelude::String::from_internal(name),
+                arguments: rs_args
+                    .iter()
+                    .map(|x| self.translate_operand(&x.node, x.span))
+                    .collect(),
+                ret: self.translate_place(&destination, span),
+                next_block: target.as_ref().map(|t| self.bb_name_map[t]),
             }
         } else {
             let abi = self
@@ -482,18 +927,9 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                 .tcx
                 .fn_abi_of_instance(rs::ParamEnv::reveal_all().and((instance, rs::List::empty())))
                 .unwrap();
-            let conv = translate_calling_convention(abi.conv);
-
-            let mut args: List<_> = rs_args
-                .iter()
-                .map(|x| {
-                    match &x.node {
-                        rs::Operand::Move(place) =>
-                            ArgumentExpr::InPlace(self.translate_place(place, x.span)),
-                        op => ArgumentExpr::ByValue(self.translate_operand(op, x.span)),
-                    }
-                })
-                .collect();
+            let conv = translate_calling_convention(abi);
+
+            let mut args = self.translate_call_args(func_ty.fn_sig(self.tcx).abi(), rs_args);
 
             // Distinguish direct function calls or dynamic dispatch on a trait object.
             let callee = if let rs::InstanceKind::Virtual(_trait, method) = instance.def {
@@ -521,14 +957,34 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                 arguments: args,
                 ret: self.translate_place(&destination, span),
                 next_block: target.as_ref().map(|t| self.bb_name_map[t]),
+                unwind_block: self.translate_unwind_action(unwind),
             }
         };
         TerminatorResult { terminator, stmts: List::new() }
     }
+
+    /// Whether `f` is `with_addr` on a raw pointer with a sized pointee.
+    fn is_thin_ptr_with_addr(
+        &self,
+        f: rs::DefId,
+        rs_args: &[rs::Spanned<rs::Operand<'tcx>>],
+    ) -> bool {
+        if self.tcx.crate_name(f.krate) != rs::sym::core
+            || self.tcx.item_name(f).as_str() != "with_addr"
+        {
+            return false;
+        }
+        let Some(impl_id) = self.tcx.impl_of_method(f) else { return false };
+        if !self.tcx.type_of(impl_id).skip_binder().is_unsafe_ptr() {
+            return false;
+        }
+        let ptr_ty = rs_args[0].node.ty(&self.body, self.tcx);
+        ptr_ty.builtin_deref(true).is_some_and(|pointee| self.rs_layout_of(pointee).is_sized())
+    }
 }
 
 // HACK to skip translating some functions we can't handle yet.
-// These always panic so we just turn them into the panic intrinsic.
+// These always panic so we just turn them into a panic.
 fn is_panic_fn(name: &str) -> bool {
     let fns = [
         "core::panicking::panic",
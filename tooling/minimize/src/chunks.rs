@@ -45,6 +45,9 @@ fn mark_used_bytes(ty: Type, markers: &mut [bool]) {
     match ty {
         Type::Int(int_ty) => mark_size(int_ty.size, markers),
         Type::Bool => mark_size(Size::from_bytes_const(1), markers),
+        Type::Char => mark_size(IntType::U32.size, markers),
+        Type::Never => {}
+        Type::Float(float_ty) => mark_size(float_ty.size, markers),
         Type::Ptr(_) => mark_size(DefaultTarget::PTR_SIZE, markers),
         Type::Tuple { sized_fields, unsized_field, .. } => {
             assert!(unsized_field.extract().is_none(), "unsized types cannot be part of unions");
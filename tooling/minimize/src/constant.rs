@@ -2,6 +2,11 @@ use crate::*;
 
 impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
     pub fn translate_const(&mut self, c: &rs::mir::Const<'tcx>, span: rs::Span) -> ValueExpr {
+        if self.lower_consts {
+            if let Some(val) = self.lower_const_item(c, span) {
+                return val;
+            }
+        }
         let val = match c.eval(self.tcx, rs::ParamEnv::reveal_all(), rs::DUMMY_SP) {
             Ok(val) => val,
             Err(_) => rs::span_bug!(span, "const-eval failed"),
@@ -12,6 +17,123 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
         self.translate_const_val(v, &mut ecx, span)
     }
 
+    /// Translates a constant `std::sync::atomic::Ordering` argument of an atomic intrinsic.
+    /// Without MIR optimizations, the argument is a temporary that is assigned the ordering
+    /// before the call, so we look through that assignment.
+    pub fn translate_atomic_ordering(
+        &mut self,
+        arg: &rs::Spanned<rs::Operand<'tcx>>,
+    ) -> AtomicOrdering {
+        let span = arg.span;
+        let (ty, variant_idx) = match &arg.node {
+            rs::Operand::Constant(box c) => (c.ty(), self.const_variant_index(c, span)),
+            rs::Operand::Copy(place) | rs::Operand::Move(place) => {
+                let Some(local) = place.as_local() else {
+                    rs::span_bug!(span, "atomic orderings must be constants")
+                };
+                match self.defining_rvalue(local) {
+                    Some(rs::Rvalue::Use(rs::Operand::Constant(box c))) =>
+                        (c.ty(), self.const_variant_index(&c, span)),
+                    Some(rs::Rvalue::Aggregate(
+                        box rs::AggregateKind::Adt(_, variant_idx, ..),
+                        _,
+                    )) => (self.body.local_decls[local].ty, variant_idx),
+                    _ => rs::span_bug!(span, "atomic orderings must be constants"),
+                }
+            }
+        };
+        let rs::TyKind::Adt(adt_def, _) = ty.kind() else {
+            rs::span_bug!(span, "atomic ordering is not an enum")
+        };
+        match adt_def.variant(variant_idx).name.as_str() {
+            "Relaxed" => AtomicOrdering::Relaxed,
+            "Acquire" => AtomicOrdering::Acquire,
+            "Release" => AtomicOrdering::Release,
+            "AcqRel" => AtomicOrdering::AcqRel,
+            "SeqCst" => AtomicOrdering::SeqCst,
+            name => rs::span_bug!(span, "unknown atomic ordering `{name}`"),
+        }
+    }
+
+    /// The variant of the enum constant `c`.
+    fn const_variant_index(
+        &mut self,
+        c: &rs::ConstOperand<'tcx>,
+        span: rs::Span,
+    ) -> rs::VariantIdx {
+        let val = match c.const_.eval(self.tcx, rs::ParamEnv::reveal_all(), rs::DUMMY_SP) {
+            Ok(val) => val,
+            Err(_) => rs::span_bug!(span, "const-eval failed"),
+        };
+        let tcx_at = self.tcx.at(span);
+        let (ecx, v) =
+            rs::mk_eval_cx_for_const_val(tcx_at, rs::ParamEnv::reveal_all(), val, c.ty()).unwrap();
+        ecx.read_discriminant(&v).unwrap()
+    }
+
+    /// The rvalue that `local` is assigned, if the body assigns it exactly once.
+    fn defining_rvalue(&self, local: rs::Local) -> Option<rs::Rvalue<'tcx>> {
+        let mut assignments =
+            self.body.basic_blocks.iter().flat_map(|bb| &bb.statements).filter_map(|stmt| {
+                match &stmt.kind {
+                    rs::StatementKind::Assign(box (place, rvalue))
+                        if place.as_local() == Some(local) =>
+                        Some(rvalue.clone()),
+                    _ => None,
+                }
+            });
+        let rvalue = assignments.next()?;
+        assignments.next().is_none().then_some(rvalue)
+    }
+
+    /// Translates a use of a non-generic `const` item into a load from a global,
+    /// which is initialized by the MiniRust translation of the MIR body of that item.
+    /// Returns `None` if `c` is not such a use, then rustc has to evaluate the constant.
+    fn lower_const_item(&mut self, c: &rs::mir::Const<'tcx>, span: rs::Span) -> Option<ValueExpr> {
+        let rs::mir::Const::Unevaluated(uv, rs_ty) = *c else { return None };
+        if uv.promoted.is_some() || !uv.args.is_empty() {
+            return None;
+        }
+        if !matches!(self.tcx.def_kind(uv.def), rs::DefKind::Const | rs::DefKind::AssocConst) {
+            return None;
+        }
+
+        let ty = self.translate_ty(rs_ty, span);
+        let name = match self.const_map.get(&uv.def) {
+            Some(name) => *name,
+            None => {
+                let name = self.fresh_global_name();
+                self.cx.const_map.insert(uv.def, name);
+                let layout = ty.layout::<DefaultTarget>();
+                let size = layout.expect_size("constants are sized");
+                let global = Global {
+                    bytes: list![None; size.bytes()],
+                    relocations: List::new(),
+                    align: layout.expect_align("constants are sized"),
+                    mutbl: Mutability::Immutable,
+                };
+                self.cx.globals.insert(name, global);
+
+                // Reserve the name of the initializer before translating it,
+                // since that can name further functions.
+                let init = FnName(Name::from_internal(self.number_of_fns() as _));
+                self.cx.global_inits.push((name, init));
+                let f = FnCtxt::new_for_const(init, uv.def, &mut *self.cx).translate();
+                self.cx.functions.insert(init, f);
+                // The initializer has to run after the initializers of all constants it uses,
+                // which were added while translating it.
+                self.cx.global_inits = self
+                    .global_inits
+                    .iter()
+                    .filter(|(global, _init)| *global != name)
+                    .chain(std::iter::once((name, init)))
+                    .collect();
+                name
+            }
+        };
+        Some(build::load(build::deref(build::const_global_ptr(name, Offset::ZERO), ty)))
+    }
+
     pub fn translate_const_smir(&mut self, c: &smir::MirConst, span: rs::Span) -> ValueExpr {
         self.translate_const(&smir::internal(self.tcx, c), span)
     }
@@ -36,9 +158,31 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                 let val = ecx.read_scalar(&val).unwrap().to_bool().unwrap();
                 ValueExpr::Constant(Constant::Bool(val), ty)
             }
+            Type::Char => {
+                let val = ecx.read_scalar(&val).unwrap().to_char().unwrap();
+                ValueExpr::Constant(Constant::Int(u32::from(val).into()), ty)
+            }
+            Type::Float(_) => {
+                let scalar = ecx.read_scalar(&val).unwrap();
+                let bits: Int = scalar.to_bits(scalar.size()).unwrap().into();
+                ValueExpr::Constant(Constant::Float(bits), ty)
+            }
             Type::Ptr(ptr_ty) => {
                 if let PtrType::FnPtr = ptr_ty {
-                    rs::span_bug!(span, "Function pointers are currently not supported")
+                    let ptr = ecx.read_pointer(&val).unwrap();
+                    let (Some(prov), _) = ptr.into_parts() else {
+                        rs::span_bug!(span, "function pointer constant without provenance")
+                    };
+                    let rs::GlobalAlloc::Function { instance, .. } =
+                        self.tcx.global_alloc(prov.alloc_id())
+                    else {
+                        rs::span_bug!(
+                            span,
+                            "function pointer constant does not point to a function"
+                        )
+                    };
+                    let fn_name = self.cx.get_fn_name(instance);
+                    return ValueExpr::Constant(Constant::FnPointer(fn_name), ty);
                 }
 
                 let (thin_ptr, meta) = match ptr_ty.meta_kind() {
@@ -48,11 +192,25 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                         let el_count = meta.unwrap_meta().to_target_usize(ecx).unwrap();
                         (thin.to_pointer(ecx).unwrap(), Some(build::const_int(el_count)))
                     }
-                    PointerMetaKind::VTablePointer(..) =>
-                        rs::span_bug!(
-                            span,
-                            "Constant trait object pointers are currently not supported"
-                        ),
+                    PointerMetaKind::VTablePointer(trait_name) => {
+                        let (thin, meta) = ecx.read_immediate(&val).unwrap().to_scalar_and_meta();
+                        let vtable_ptr = meta.unwrap_meta().to_pointer(ecx).unwrap();
+                        let (Some(prov), _) = vtable_ptr.into_parts() else {
+                            rs::span_bug!(span, "vtable pointer constant without provenance")
+                        };
+                        let rs::GlobalAlloc::VTable(concrete_ty, _) =
+                            self.tcx.global_alloc(prov.alloc_id())
+                        else {
+                            rs::span_bug!(
+                                span,
+                                "vtable pointer constant does not point to a vtable"
+                            )
+                        };
+                        let trait_obj_ty = val.layout.ty.builtin_deref(true).unwrap();
+                        let vtable_name = self.cx.get_vtable(concrete_ty, trait_obj_ty);
+                        let meta = build::const_vtable(vtable_name, trait_name);
+                        (thin.to_pointer(ecx).unwrap(), Some(meta))
+                    }
                 };
                 // Depending on the provenance of the pointer, a different constant is built.
                 let (prov, offset) = thin_ptr.into_parts();
@@ -110,7 +268,19 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                 let data = GcCow::new(ValueExpr::Tuple(fields, variant_ty));
                 ValueExpr::Variant { discriminant, data, enum_ty: ty }
             }
-            Type::Array { .. } => {
+            Type::Array { elem, .. } => {
+                // Arrays of plain data that are already stored in memory are loaded from their global,
+                // instead of being rebuilt element by element.
+                if val.as_mplace_or_imm().is_left()
+                    && matches!(
+                        elem.extract(),
+                        Type::Int(_) | Type::Bool | Type::Char | Type::Float(_)
+                    )
+                {
+                    let Relocation { name, offset } =
+                        self.translate_const_to_global(&val, ecx, span);
+                    return build::load(build::deref(build::const_global_ptr(name, offset), ty));
+                }
                 let mut t: List<ValueExpr> = List::new();
                 let mut iter = ecx.project_array_fields(&val).unwrap();
                 while let Ok(Some((_, field))) = iter.next(ecx) {
@@ -119,19 +289,82 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                 }
                 ValueExpr::Tuple(t, ty)
             }
-            Type::Union { .. } =>
-                rs::span_bug!(span, "Constant Unions are currently not supported!"),
+            Type::Union { .. } => {
+                // There are no union value expressions, so we load the constant from a global allocation.
+                let Relocation { name, offset } = self.translate_const_to_global(&val, ecx, span);
+                build::load(build::deref(build::const_global_ptr(name, offset), ty))
+            }
+            Type::Never => rs::span_bug!(span, "constants of type `!` do not exist!"),
             Type::Slice { .. } | Type::TraitObject(..) =>
                 rs::span_bug!(span, "constant unsized values do not exist!"),
         }
     }
 
+    /// Returns a pointer to a global allocation that stores the constant `val`.
+    fn translate_const_to_global(
+        &mut self,
+        val: &rs::OpTy<'tcx>,
+        ecx: &mut rs::CompileTimeInterpCx<'tcx>,
+        span: rs::Span,
+    ) -> Relocation {
+        // Constants that live in memory already have a global allocation we can point to.
+        if let Some(mplace) = val.as_mplace_or_imm().left() {
+            let (prov, offset) = mplace.ptr().into_parts();
+            let Some(prov) = prov else {
+                rs::span_bug!(span, "constant in memory without provenance")
+            };
+            return self.translate_relocation(prov.alloc_id(), offset);
+        }
+
+        // Otherwise we create a new global holding the bytes of the immediate.
+        let size = translate_size(val.layout.size);
+        let bytes: List<Option<u8>> = match *ecx.read_immediate(val).unwrap() {
+            rs::Immediate::Scalar(rs::Scalar::Int(int)) => {
+                let bits: Int = int.to_bits(int.size()).into();
+                DefaultTarget::ENDIANNESS.encode(Unsigned, size, bits).unwrap().map(Some)
+            }
+            rs::Immediate::Uninit => list![None; size.bytes()],
+            _ => rs::span_bug!(span, "unsupported immediate constant: {val:?}"),
+        };
+        let name = self.fresh_global_name();
+        let align = translate_align(val.layout.align.abi);
+        let global =
+            Global { bytes, relocations: List::new(), align, mutbl: Mutability::Immutable };
+        self.cx.globals.insert(name, global);
+        Relocation { name, offset: Offset::ZERO }
+    }
+
     fn translate_relocation(&mut self, alloc_id: rs::AllocId, offset: rs::Size) -> Relocation {
         let name = self.translate_alloc_id(alloc_id);
         let offset = translate_size(offset);
         Relocation { name, offset }
     }
 
+    /// Translates a pointer stored in a constant allocation, which may also point to a function or vtable.
+    fn translate_relocation_target(
+        &mut self,
+        alloc_id: rs::AllocId,
+        offset: rs::Size,
+    ) -> RelocationTarget {
+        match self.tcx.global_alloc(alloc_id) {
+            rs::GlobalAlloc::Function { instance } => {
+                assert_eq!(offset, rs::Size::ZERO, "function pointers have no offset");
+                RelocationTarget::Fn(self.cx.get_fn_name(instance))
+            }
+            rs::GlobalAlloc::VTable(ty, preds) => {
+                assert_eq!(offset, rs::Size::ZERO, "vtable pointers have no offset");
+                let trait_obj_ty = rs::Ty::new_dynamic(
+                    self.tcx,
+                    preds,
+                    self.tcx.lifetimes.re_erased,
+                    rs::DynKind::Dyn,
+                );
+                RelocationTarget::VTable(self.cx.get_vtable(ty, trait_obj_ty))
+            }
+            _ => RelocationTarget::Global(self.translate_relocation(alloc_id, offset)),
+        }
+    }
+
     // calls `translate_const_allocation` with the allocation of alloc_id,
     // and adds the alloc_id and its newly-created global to alloc_map.
     fn translate_alloc_id(&mut self, alloc_id: rs::AllocId) -> GlobalName {
@@ -145,7 +378,8 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
         let alloc = match self.tcx.global_alloc(alloc_id) {
             rs::GlobalAlloc::Memory(alloc) => alloc,
             rs::GlobalAlloc::Static(def_id) => self.tcx.eval_static_initializer(def_id).unwrap(),
-            _ => panic!("unsupported!"),
+            rs::GlobalAlloc::Function { .. } | rs::GlobalAlloc::VTable(..) =>
+                panic!("functions and vtables are not global allocations"),
         };
         self.translate_const_allocation(alloc, name);
         name
@@ -183,14 +417,15 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                 let inner_offset = bytes[start..end].iter().map(|x| x.unwrap()).collect();
                 let inner_offset = DefaultTarget::ENDIANNESS.decode(Unsigned, inner_offset);
                 let inner_offset = rs::Size::from_bytes(inner_offset.try_to_usize().unwrap());
-                let relo = self.translate_relocation(alloc_id.alloc_id(), inner_offset);
+                let target = self.translate_relocation_target(alloc_id.alloc_id(), inner_offset);
 
                 let offset = translate_size(offset);
-                (offset, relo)
+                (offset, target)
             })
             .collect();
         let align = translate_align(allocation.align);
-        let global = Global { bytes: bytes.into_iter().collect(), relocations, align };
+        let mutbl = translate_mutbl(allocation.mutability);
+        let global = Global { bytes: bytes.into_iter().collect(), relocations, align, mutbl };
 
         self.cx.globals.insert(name, global);
     }
@@ -202,6 +437,7 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
             bytes: Default::default(),
             relocations: Default::default(),
             align: Align::ONE,
+            mutbl: Mutability::Mutable,
         };
         self.cx.globals.insert(name, default_global);
         name
@@ -79,13 +79,16 @@ impl<'tcx> Ctxt<'tcx> {
                             untagged_variant,
                             niche_variants,
                             niche_start,
-                        } if *untagged_variant != variant_idx => {
+                        } if *untagged_variant != variant_idx
+                            && niche_variants.contains(&variant_idx) =>
+                        {
                             // this is a tagged variant, meaning that it writes its tag and has a discriminator branch entry.
-                            let discr_int = int_from_bits(discr.val, tag_ty);
-                            let tag_int = (discr_int
-                                - Int::from(niche_variants.start().as_usize())
-                                + Int::from(*niche_start))
-                            .bring_in_bounds(tag_ty.signed, tag_ty.size);
+                            // The tag is computed from the variant *index* (not its discriminant): the niche variants
+                            // are numbered consecutively starting at `niche_start`, wrapping around in the tag type.
+                            let relative_idx = Int::from(variant_idx.as_usize())
+                                - Int::from(niche_variants.start().as_usize());
+                            let tag_int = (int_from_bits(*niche_start, tag_ty) + relative_idx)
+                                .bring_in_bounds(tag_ty.signed, tag_ty.size);
                             let tagger = [(tag_offset, (tag_ty, tag_int))]
                                 .into_iter()
                                 .collect::<Map<_, _>>();
@@ -103,8 +106,10 @@ impl<'tcx> Ctxt<'tcx> {
                             });
                         }
                         rs::TagEncoding::Niche { .. } => {
-                            // this is the untagged variant
-                            // we don't add it to the discriminator branches as it will be the fallback.
+                            // this is the untagged variant, or a variant that rustc considers absent
+                            // (uninhabited and zero-sized) and hence does not give a niche value.
+                            // We don't add it to the discriminator branches: the untagged variant is the fallback,
+                            // and absent variants can never be observed.
                             translated_variants.insert(discr_int, Variant {
                                 ty: build::tuple_ty(
                                     &fields.iter().collect::<Vec<_>>(),
@@ -175,6 +180,84 @@ impl<'tcx> Ctxt<'tcx> {
         Type::Enum { variants, discriminator, discriminant_ty, size, align }
     }
 
+    /// Translates a coroutine, which is an enum of its states (not resumed yet, returned, panicked,
+    /// and one for every suspension point).
+    /// The captured variables are stored in a prefix shared by all states; we make them the
+    /// first fields of every variant, followed by the locals saved in this state.
+    pub fn translate_coroutine(
+        &mut self,
+        ty: rs::Ty<'tcx>,
+        def_id: rs::DefId,
+        args: rs::GenericArgsRef<'tcx>,
+        span: rs::Span,
+    ) -> Type {
+        let layout = self.rs_layout_of(ty);
+        let size = translate_size(layout.size());
+        let align = translate_align(layout.align().abi);
+
+        let Type::Int(discriminant_ty) = self.translate_ty(ty.discriminant_ty(self.tcx), span)
+        else {
+            panic!("Discriminant type is not integer!")
+        };
+        let rs::Variants::Multiple {
+            tag,
+            tag_encoding: rs::TagEncoding::Direct,
+            tag_field,
+            variants,
+        } = layout.variants()
+        else {
+            rs::span_bug!(span, "coroutine layout without direct tag")
+        };
+        let tag_offset: Offset = translate_size(layout.fields().offset(*tag_field));
+        let Type::Int(tag_ty) = self.translate_ty(tag.primitive().to_int_ty(self.tcx), span) else {
+            panic!("coroutine tag has invalid primitive type")
+        };
+
+        let coroutine_args = args.as_coroutine();
+        let upvar_fields: Vec<(Offset, Type)> = coroutine_args
+            .prefix_tys()
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (translate_size(layout.fields().offset(i)), self.translate_ty(t, span)))
+            .collect();
+
+        let mut translated_variants = Map::new();
+        let mut discriminator_branches = Map::new();
+        for (variant_idx, state_tys) in coroutine_args.state_tys(def_id, self.tcx).enumerate() {
+            let variant_layout = &variants[rs::VariantIdx::from_usize(variant_idx)];
+            let mut fields = upvar_fields.clone();
+            for (i, t) in state_tys.enumerate() {
+                let offset = translate_size(variant_layout.fields.offset(i));
+                fields.push((offset, self.translate_ty(t, span)));
+            }
+            // The discriminant of a coroutine state is its index, and it is stored directly in the tag.
+            let discr_int = Int::from(variant_idx);
+            let tagger = [(tag_offset, (tag_ty, discr_int))]
+                .into_iter()
+                .collect::<Map<Offset, (IntType, Int)>>();
+            translated_variants
+                .insert(discr_int, Variant { ty: build::tuple_ty(&fields, size, align), tagger });
+            discriminator_branches
+                .insert((discr_int, discr_int + Int::ONE), Discriminator::Known(discr_int));
+        }
+
+        let discriminator = Discriminator::Branch {
+            offset: tag_offset,
+            value_type: tag_ty,
+            fallback: GcCow::new(Discriminator::Invalid),
+            children: discriminator_branches,
+        };
+
+        Type::Enum { variants: translated_variants, discriminator, discriminant_ty, size, align }
+    }
+
+    /// The number of captured variables of a coroutine type.
+    /// They come before the saved locals in every variant.
+    pub fn coroutine_upvar_count(&self, ty: rs::Ty<'tcx>) -> usize {
+        let rs::TyKind::Coroutine(_, args) = ty.kind() else { panic!("not a coroutine type") };
+        args.as_coroutine().prefix_tys().len()
+    }
+
     pub fn discriminant_for_variant_smir(
         &mut self,
         ty: smir::Ty,
@@ -194,6 +277,10 @@ impl<'tcx> Ctxt<'tcx> {
         variant_idx: rs::VariantIdx,
         span: rs::Span,
     ) -> Int {
+        if let rs::TyKind::Coroutine(..) = ty.kind() {
+            // Coroutine states are numbered by their index.
+            return Int::from(variant_idx.as_usize());
+        }
         let rs::TyKind::Adt(adt_def, _) = ty.kind() else {
             panic!("Getting discriminant for a variant of a non-enum type!")
         };
@@ -4,8 +4,8 @@ use crate::*;
 pub struct FnCtxt<'cx, 'tcx> {
     /// the body we intend to translate. substitutions are already applied.
     pub body: rs::Body<'tcx>,
-    /// the ABI of this function
-    abi: &'tcx rs::FnAbi<'tcx, rs::Ty<'tcx>>,
+    /// the calling convention of this function
+    calling_convention: CallingConvention,
 
     /// the list of local variable declarations (StableMIR) used to retrieve the type of some
     /// SMIR constructs.
@@ -13,6 +13,9 @@ pub struct FnCtxt<'cx, 'tcx> {
 
     pub cx: &'cx mut Ctxt<'tcx>,
 
+    /// the name of this function in the MiniRust program.
+    fn_name: FnName,
+
     /// associate names for each mir Local.
     pub local_name_map: HashMap<rs::Local, LocalName>,
 
@@ -41,7 +44,7 @@ impl<'cx, 'tcx> std::ops::DerefMut for FnCtxt<'cx, 'tcx> {
 }
 
 impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
-    pub fn new(instance: rs::Instance<'tcx>, cx: &'cx mut Ctxt<'tcx>) -> Self {
+    pub fn new(fn_name: FnName, instance: rs::Instance<'tcx>, cx: &'cx mut Ctxt<'tcx>) -> Self {
         let body = cx.tcx.instance_mir(instance.def);
         // We eagerly instantiate everything upfront once.
         // Then nothing else has to worry about generics.
@@ -54,12 +57,46 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
             .tcx
             .fn_abi_of_instance(rs::ParamEnv::reveal_all().and((instance, rs::List::empty())))
             .unwrap();
-        let locals_smir = smir::stable(&body).locals().to_vec();
+        Self::with_body(fn_name, body, translate_calling_convention(abi), cx)
+    }
+
+    /// Prepares translating the body of a non-generic `const` item
+    /// into a function that takes no arguments and returns the value of the constant.
+    pub fn new_for_const(fn_name: FnName, def_id: rs::DefId, cx: &'cx mut Ctxt<'tcx>) -> Self {
+        let body = cx.tcx.mir_for_ctfe(def_id);
+        let body = cx.tcx.instantiate_and_normalize_erasing_regions(
+            rs::List::empty(),
+            rs::ParamEnv::reveal_all(),
+            rs::EarlyBinder::bind(body.clone()),
+        );
+        Self::with_body(fn_name, body, CallingConvention::Rust, cx)
+    }
+
+    fn with_body(
+        fn_name: FnName,
+        body: rs::Body<'tcx>,
+        calling_convention: CallingConvention,
+        cx: &'cx mut Ctxt<'tcx>,
+    ) -> Self {
+        // Only convert the locals: converting the whole body would also convert terminators
+        // stable MIR does not support yet, such as tail calls.
+        let locals_smir = body
+            .local_decls
+            .iter()
+            .map(|decl| {
+                smir::LocalDecl {
+                    ty: smir::stable(decl.ty),
+                    span: smir::stable(decl.source_info.span),
+                    mutability: smir::stable(decl.mutability),
+                }
+            })
+            .collect();
 
         FnCtxt {
             body,
-            abi,
+            calling_convention,
             cx,
+            fn_name,
             local_name_map: Default::default(),
             bb_name_map: Default::default(),
             locals: Default::default(),
@@ -69,6 +106,12 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
         }
     }
 
+    /// Remembers that the statement (or terminator) at `idx` in block `bb` comes from `span`.
+    pub(crate) fn record_span(&mut self, bb: BbName, idx: Int, span: rs::Span) {
+        let fn_name = self.fn_name;
+        self.cx.spans.insert((fn_name, bb, idx), span);
+    }
+
     pub fn fresh_bb_name(&mut self) -> BbName {
         let name = self.next_bb;
         self.next_bb = name.checked_add(1).unwrap();
@@ -79,11 +122,8 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
     /// Any fn calls occuring during this translation will be added to the `FnNameMap`.
     pub fn translate(mut self) -> Function {
         // associate names for each mir BB.
+        // This includes cleanup blocks, they are executed while the function is unwinding.
         for bb_id in self.body.basic_blocks.indices() {
-            if self.body.basic_blocks[bb_id].is_cleanup {
-                // We don't support unwinding, so we don't translate cleanup blocks.
-                continue;
-            }
             let bb_name = self.fresh_bb_name();
             self.bb_name_map.insert(bb_id, bb_name);
         }
@@ -105,19 +145,59 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
         // the number of locals which are implicitly storage live.
         let free_argc = self.body.arg_count + 1;
 
+        // "The first local is the return value pointer, followed by arg_count locals for the function arguments, followed by any user-declared variables and temporaries."
+        // - https://doc.rust-lang.org/stable/nightly-rustc/rustc_middle/mir/struct.Body.html
+        let ret = LocalName(Name::from_internal(0));
+
+        let mut args = List::default();
+        for i in 0..self.body.arg_count {
+            let i = i + 1; // this starts counting with 1, as id 0 is the return value of the function.
+            let local_name = LocalName(Name::from_internal(i as _));
+            args.push(local_name);
+        }
+
         // add init basic block
         let init_bb = self.fresh_bb_name();
 
         // this block allocates all "always_storage_live_locals",
         // except for those which are implicitly storage live in Minirust;
         // like the return local and function args.
+        let mut init_stmts: List<Statement> = rs::always_storage_live_locals(&self.body)
+            .iter()
+            .map(|loc| self.local_name_map[&loc])
+            .filter(|LocalName(i)| i.get_internal() as usize >= free_argc)
+            .map(|local| Statement::StorageLive { local, meta: None })
+            .collect();
+
+        // Functions with the "rust-call" ABI, like closure shims, receive their last argument
+        // untupled, i.e., as separate arguments (see `translate_call`). If the body expects
+        // them as a tuple, we take the fields as separate arguments and re-assemble the tuple.
+        if let Some(spread_arg) = self.body.spread_arg {
+            let spread_local = self.local_name_map[&spread_arg];
+            let tuple_ty = self.locals.get(spread_local).unwrap();
+            let Type::Tuple { sized_fields, .. } = tuple_ty else {
+                panic!("spread argument must be a tuple")
+            };
+
+            let mut fields = List::new();
+            args = args.iter().filter(|arg| *arg != spread_local).collect();
+            for (_offset, field_ty) in sized_fields.iter() {
+                let field_local =
+                    LocalName(Name::from_internal(self.locals.len().try_to_usize().unwrap() as _));
+                self.locals.insert(field_local, field_ty);
+                args.push(field_local);
+                fields.push(build::load(PlaceExpr::Local(field_local)));
+            }
+
+            init_stmts.push(Statement::StorageLive { local: spread_local, meta: None });
+            init_stmts.push(Statement::Assign {
+                destination: PlaceExpr::Local(spread_local),
+                source: ValueExpr::Tuple(fields, tuple_ty),
+            });
+        }
+
         let init_blk = BasicBlock {
-            statements: rs::always_storage_live_locals(&self.body)
-                .iter()
-                .map(|loc| self.local_name_map[&loc])
-                .filter(|LocalName(i)| i.get_internal() as usize >= free_argc)
-                .map(Statement::StorageLive)
-                .collect(),
+            statements: init_stmts,
             terminator: Terminator::Goto(self.bb_name_map[&rs::mir::START_BLOCK]),
         };
         self.blocks.insert(init_bb, init_blk);
@@ -128,24 +208,15 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
             self.translate_bb(bb_name, bb_data);
         }
 
-        // "The first local is the return value pointer, followed by arg_count locals for the function arguments, followed by any user-declared variables and temporaries."
-        // - https://doc.rust-lang.org/stable/nightly-rustc/rustc_middle/mir/struct.Body.html
-        let ret = LocalName(Name::from_internal(0));
-
-        let mut args = List::default();
-        for i in 0..self.body.arg_count {
-            let i = i + 1; // this starts counting with 1, as id 0 is the return value of the function.
-            let local_name = LocalName(Name::from_internal(i as _));
-            args.push(local_name);
-        }
-
         let f = Function {
             locals: self.locals,
             args,
             ret,
             blocks: self.blocks,
             start: init_bb,
-            calling_convention: translate_calling_convention(self.abi.conv),
+            calling_convention: self.calling_convention,
+            // FIXME: translate C-variadic function definitions and their uses of `va_arg`.
+            c_variadic: false,
         };
 
         f
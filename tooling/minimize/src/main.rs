@@ -20,7 +20,10 @@ extern crate stable_mir;
 
 mod rs {
     pub use rustc_const_eval::const_eval::mk_eval_cx_for_const_val;
-    pub use rustc_const_eval::interpret::{InterpCx, OpTy};
+    pub use rustc_const_eval::interpret::{Immediate, InterpCx, OpTy};
+    pub use rustc_hir::def::DefKind;
+    pub use rustc_hir::def_id::DefId;
+    pub use rustc_hir::LangItem;
     pub use rustc_middle::mir::{self, interpret::*, *};
     pub use rustc_middle::span_bug;
     pub use rustc_middle::ty::*;
@@ -29,11 +32,13 @@ mod rs {
     pub use rustc_span::{DUMMY_SP, Span, sym};
     pub use rustc_target::abi::{self, Align, FieldIdx, Layout, Size, call::*};
     pub use rustc_target::abi::{FieldsShape, TagEncoding, VariantIdx, Variants};
+    pub use rustc_target::spec::abi::Abi as ExternAbi;
 
     pub type CompileTimeInterpCx<'tcx> =
         InterpCx<'tcx, rustc_const_eval::const_eval::CompileTimeMachine<'tcx>>;
 }
 // Traits
+pub use rustc_middle::ty::CoroutineArgsExt as _;
 pub use rustc_middle::ty::layout::IntegerExt as _;
 pub use rustc_target::abi::HasDataLayout as _;
 
@@ -55,10 +60,16 @@ pub use minirust_rs::prelude::*;
 
 pub use miniutil::BasicMem;
 pub use miniutil::DefaultTarget;
+pub use miniutil::StackedBorrowMem;
 pub use miniutil::TreeBorrowMem;
 pub use miniutil::build::{self, TypeConv as _, unit_place};
+pub use miniutil::debug::Debugger;
+pub use miniutil::env::MockEnv;
 pub use miniutil::fmt::dump_program;
 pub use miniutil::run::*;
+pub use miniutil::text::{program_from_text, program_to_json, program_to_text};
+pub use miniutil::validity::{ValidityReport, validity_checks};
+pub use miniutil::wf::check_program_wf;
 
 // Get back some `std` items
 pub use std::format;
@@ -117,23 +128,74 @@ macro_rules! show_error {
 fn main() {
     let (minimize_args, rustc_args) = split_args(std::env::args());
     let dump = minimize_args.iter().any(|x| x == "--minimize-dump");
+    let dump_text = minimize_args.iter().any(|x| x == "--minimize-dump-text");
+    let dump_json = minimize_args.iter().any(|x| x == "--minimize-dump-json");
+    let lower_consts = minimize_args.iter().any(|x| x == "--minimize-lower-consts");
+    let show_span = minimize_args.iter().any(|x| x == "--minimize-show-span");
+    let debug = minimize_args.iter().any(|x| x == "--minimize-debug");
 
-    get_mini(rustc_args, |_tcx, prog| {
+    // Ctrl-C sets `CTRL_C_RECEIVED`, which stops the machine between two steps.
+    rustc_driver::install_ctrlc_handler();
+
+    // A program dumped with `--minimize-dump-text` can be debugged without compiling anything.
+    if let Some(path) =
+        minimize_args.iter().find_map(|x| x.strip_prefix("--minimize-debug-program="))
+    {
+        debug_prog(load_prog(path));
+        return;
+    }
+
+    get_mini(rustc_args, lower_consts, |tcx, prog, spans| {
         if dump {
             dump_program(prog);
+        } else if dump_text {
+            print!("{}", program_to_text(prog));
+        } else if dump_json {
+            print!("{}", program_to_json(prog));
+        } else if debug {
+            debug_prog(prog);
         } else {
-            match run_prog(prog, &minimize_args) {
+            let run = run_prog(prog, &minimize_args);
+            let info = match run.status {
+                RunStatus::Terminated(info) => info,
+                RunStatus::Interrupted =>
+                    match run.location {
+                        Some(location) =>
+                            show_error!("interrupted after {} steps at {location}", run.steps),
+                        None => show_error!("interrupted before the program started"),
+                    },
+            };
+            match info {
                 // We can't use tcx.dcx().fatal due to <https://github.com/oli-obk/ui_test/issues/226>
                 TerminationInfo::IllFormed(err) =>
                     show_error!(
                         "program not well-formed (this is a bug in minimize):\n    {}",
-                        err.get_internal()
+                        err.msg().get_internal()
                     ),
                 TerminationInfo::MachineStop => { /* silent exit. */ }
                 TerminationInfo::Abort(err) => show_error!("Panic: {}", err.get_internal()),
-                TerminationInfo::Ub(err) => show_error!("UB: {}", err.get_internal()),
+                TerminationInfo::Ub(err, _) => {
+                    // Name the source line the UB comes from, if we know it.
+                    let span = run
+                        .location
+                        .filter(|_| show_span)
+                        .and_then(|loc| spans.get(&(loc.func, loc.block, loc.stmt)));
+                    match span {
+                        Some(span) =>
+                            show_error!(
+                                "UB: {}\n    at {}",
+                                err.get_internal(),
+                                tcx.sess.source_map().span_to_diagnostic_string(*span)
+                            ),
+                        None => show_error!("UB: {}", err.get_internal()),
+                    }
+                }
                 TerminationInfo::Deadlock => show_error!("program dead-locked"),
                 TerminationInfo::MemoryLeak => show_error!("program leaked memory"),
+                TerminationInfo::ResourceExhaustion(err) =>
+                    show_error!("resource exhausted: {}", err.get_internal()),
+                TerminationInfo::Unsupported(err) =>
+                    show_error!("unsupported operation: {}", err.get_internal()),
             }
         }
     });
@@ -153,24 +215,100 @@ fn split_args(args: Args) -> (Vec<String>, Vec<String>) {
     (minimize_args, rustc_args)
 }
 
-fn run_prog(prog: Program, args: &Vec<String>) -> TerminationInfo {
+fn run_prog(prog: Program, args: &Vec<String>) -> InterruptibleRun {
+    let validity_report = args.iter().any(|x| x == "--minimize-validity-report");
+    let config = MachineConfig {
+        warn_on_provenance_loss: args.iter().any(|x| x == "--minimize-warn-provenance-loss"),
+        trace: validity_report,
+        ..MachineConfig::DEFAULT
+    };
     if args.iter().any(|x| x == "--minimize-tree-borrows") {
-        run_program::<TreeBorrowMem>(prog)
+        run_prog_with::<TreeBorrowMem>(prog, config, validity_report)
+    } else if args.iter().any(|x| x == "--minimize-stacked-borrows") {
+        run_prog_with::<StackedBorrowMem>(prog, config, validity_report)
     } else {
-        run_program::<BasicMem>(prog)
+        run_prog_with::<BasicMem>(prog, config, validity_report)
+    }
+}
+
+/// Runs `prog` with `config`. With `validity_report`, afterwards prints which bytes the validity checks
+/// of its typed loads inspected to stderr; this needs `config.trace`.
+fn run_prog_with<M: MemoryTooling>(
+    prog: Program,
+    config: MachineConfig,
+    validity_report: bool,
+) -> InterruptibleRun {
+    // Programs print through `write`, since `std::io` is out of reach for minimize.
+    let mut env = MockEnv::<M>::new();
+    env.register_write();
+    let run = run_program_interruptible::<M>(
+        prog,
+        config,
+        &rustc_const_eval::CTRL_C_RECEIVED,
+        &mut env,
+        std::io::stdout(),
+        std::io::stderr(),
+        std::io::stderr(),
+    );
+    if validity_report {
+        eprint!("{}", ValidityReport::new(&validity_checks::<M::T>(run.trace)));
+    }
+    run
+}
+
+/// Reads a program in the textual format from `path`, and checks that it is well-formed.
+fn load_prog(path: &str) -> Program {
+    let src = std::fs::read_to_string(path)
+        .unwrap_or_else(|err| show_error!("cannot read `{path}`: {err}"));
+    let prog =
+        program_from_text(&src).unwrap_or_else(|err| show_error!("cannot parse `{path}`: {err}"));
+    // The program may have been edited by hand, so ill-formedness is not a bug in minimize here.
+    let errors = check_program_wf::<DefaultTarget>(prog);
+    if !errors.is_empty() {
+        let errors: Vec<String> = errors.iter().map(|err| format!("    {err}")).collect();
+        show_error!("program not well-formed:\n{}", errors.join("\n"));
+    }
+    prog
+}
+
+/// Runs `prog` in the debugger, reading commands from stdin.
+fn debug_prog(prog: Program) {
+    let mut debugger = match Debugger::<BasicMem>::new(
+        prog,
+        std::io::stdout(),
+        std::io::stderr(),
+        std::io::stderr(),
+    ) {
+        Ok(debugger) => debugger,
+        Err(TerminationInfo::IllFormed(err)) =>
+            show_error!(
+                "program not well-formed (this is a bug in minimize):\n    {}",
+                err.msg().get_internal()
+            ),
+        Err(info) => show_error!("program could not be started: {info:?}"),
+    };
+    if let Err(err) = debugger.repl(std::io::stdin().lock(), std::io::stdout()) {
+        show_error!("debugger I/O error: {err}");
     }
 }
 
-fn get_mini(mut args: Vec<String>, callback: impl FnOnce(rs::TyCtxt<'_>, Program) + Send + Copy) {
+/// If `lower_consts` is set, `const` items are translated to MiniRust code
+/// that computes their value, instead of being evaluated by rustc.
+fn get_mini(
+    mut args: Vec<String>,
+    lower_consts: bool,
+    callback: impl FnOnce(rs::TyCtxt<'_>, Program, &SpanMap) + Send + Copy,
+) {
     args.splice(1..1, DEFAULT_ARGS.iter().map(ToString::to_string));
-    rustc_driver::RunCompiler::new(&args, &mut Cb { callback }).run().unwrap();
+    rustc_driver::RunCompiler::new(&args, &mut Cb { callback, lower_consts }).run().unwrap();
 }
 
-struct Cb<F: FnOnce(rs::TyCtxt<'_>, Program) + Send + Copy> {
+struct Cb<F: FnOnce(rs::TyCtxt<'_>, Program, &SpanMap) + Send + Copy> {
     callback: F,
+    lower_consts: bool,
 }
 
-impl<F: FnOnce(rs::TyCtxt<'_>, Program) + Send + Copy> rustc_driver::Callbacks for Cb<F> {
+impl<F: FnOnce(rs::TyCtxt<'_>, Program, &SpanMap) + Send + Copy> rustc_driver::Callbacks for Cb<F> {
     fn after_analysis<'tcx>(
         &mut self,
         _compiler: &rustc_interface::interface::Compiler,
@@ -179,8 +317,9 @@ impl<F: FnOnce(rs::TyCtxt<'_>, Program) + Send + Copy> rustc_driver::Callbacks f
         queries.global_ctxt().unwrap().enter(|tcx| {
             // StableMIR can only be used inside a `run` call, to guarantee its context is properly
             // initialized. Calls to StableMIR functions will panic if done outside a run.
-            let prog = smir::run(tcx, || Ctxt::new(tcx).translate()).unwrap();
-            (self.callback)(tcx, prog);
+            let (prog, spans) =
+                smir::run(tcx, || Ctxt::new(tcx, self.lower_consts).translate()).unwrap();
+            (self.callback)(tcx, prog, &spans);
         });
 
         rustc_driver::Compilation::Stop
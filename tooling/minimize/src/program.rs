@@ -6,6 +6,11 @@ pub struct Ctxt<'tcx> {
     /// maps Rust function calls to MiniRust FnNames.
     pub fn_name_map: HashMap<rs::Instance<'tcx>, FnName>,
 
+    /// maps closures that were coerced to function pointers to the MiniRust FnNames implementing them.
+    /// The key is the `FnOnce` instance of the closure. Unlike that instance, the function
+    /// does not take the (zero-sized) closure environment as argument.
+    pub closure_fn_ptr_map: HashMap<rs::Instance<'tcx>, FnName>,
+
     /// maps Rust traits to MiniRust TraitNames and those to sets of methods.
     pub trait_map: HashMap<&'tcx rs::List<rs::PolyExistentialPredicate<'tcx>>, TraitName>,
     pub traits: Map<TraitName, Set<TraitMethodName>>,
@@ -22,11 +27,29 @@ pub struct Ctxt<'tcx> {
 
     pub functions: Map<FnName, Function>,
 
+    /// Whether to translate `const` items to initializers of globals, rather than letting rustc evaluate them.
+    pub lower_consts: bool,
+
+    /// Stores which `const` item is stored in which GlobalName, if they are lowered.
+    pub const_map: HashMap<rs::DefId, GlobalName>,
+
+    /// The initializers of the globals holding lowered `const` items.
+    pub global_inits: List<(GlobalName, FnName)>,
+
+    /// Caches the translation of (normalized, fully instantiated) types.
     pub ty_cache: HashMap<rs::Ty<'tcx>, Type>,
+
+    /// The source spans of the translated statements and terminators.
+    pub spans: SpanMap,
 }
 
+/// Maps a function, basic block, and statement index to the source span it was translated from.
+/// Statement indices are as in `Machine::active_location`, i.e., the terminator comes after the last statement.
+/// Statements that do not correspond to MIR, like the initial `StorageLive`s, have no span.
+pub type SpanMap = HashMap<(FnName, BbName, Int), rs::Span>;
+
 impl<'tcx> Ctxt<'tcx> {
-    pub fn new(tcx: rs::TyCtxt<'tcx>) -> Self {
+    pub fn new(tcx: rs::TyCtxt<'tcx>, lower_consts: bool) -> Self {
         // Ensure consistency with the DefaultTarget
         let dl = tcx.data_layout();
         assert_eq!(DefaultTarget::PTR_SIZE, translate_size(dl.pointer_size));
@@ -52,6 +75,7 @@ impl<'tcx> Ctxt<'tcx> {
         Ctxt {
             tcx,
             fn_name_map: Default::default(),
+            closure_fn_ptr_map: Default::default(),
             trait_map: Default::default(),
             vtable_map: Default::default(),
             traits: Default::default(),
@@ -59,11 +83,15 @@ impl<'tcx> Ctxt<'tcx> {
             alloc_map: Default::default(),
             globals: Default::default(),
             functions: Default::default(),
+            lower_consts,
+            const_map: Default::default(),
+            global_inits: Default::default(),
             ty_cache: Default::default(),
+            spans: Default::default(),
         }
     }
 
-    pub fn translate(mut self) -> Program {
+    pub fn translate(mut self) -> (Program, SpanMap) {
         let (entry, _ty) = self.tcx.entry_fn(()).unwrap();
         let entry_instance = rs::Instance::mono(self.tcx, entry);
         let entry_name = FnName(Name::from_internal(0));
@@ -78,29 +106,55 @@ impl<'tcx> Ctxt<'tcx> {
             let instance =
                 self.fn_name_map.iter().find(|(_, f)| **f == fn_name).map(|(r, _)| r).unwrap();
 
-            let f = FnCtxt::new(*instance, &mut self).translate();
+            let f = FnCtxt::new(fn_name, *instance, &mut self).translate();
             self.functions.insert(fn_name, f);
-        }
 
-        let number_of_fns = self.fn_name_map.len();
+            // Closures coerced to function pointers can only be discovered while translating
+            // other functions, and they can refer to not-yet-implemented functions themselves.
+            while let Some(fn_name) = self
+                .closure_fn_ptr_map
+                .values()
+                .find(|k| !self.functions.contains_key(**k))
+                .copied()
+            {
+                let instance = self
+                    .closure_fn_ptr_map
+                    .iter()
+                    .find(|(_, f)| **f == fn_name)
+                    .map(|(r, _)| r)
+                    .unwrap();
+
+                let f = FnCtxt::new(fn_name, *instance, &mut self).translate();
+                self.functions.insert(fn_name, reify_closure_fn(f));
+            }
+        }
 
         // add a `start` function, which calls `entry`.
-        let start = FnName(Name::from_internal(number_of_fns as _));
+        let start = FnName(Name::from_internal(self.number_of_fns() as _));
         self.functions.insert(start, mk_start_fn(0));
 
-        Program {
+        let prog = Program {
             start,
             functions: self.functions,
             globals: self.globals,
             vtables: self.vtables,
             traits: self.traits,
-        }
+            global_inits: self.global_inits,
+        };
+        (prog, self.spans)
+    }
+
+    /// The number of `FnName`s handed out so far, which is also the next free name.
+    pub fn number_of_fns(&self) -> usize {
+        self.fn_name_map.len()
+            + self.closure_fn_ptr_map.len()
+            + self.global_inits.len().try_to_usize().unwrap()
     }
 
     // Returns FnName associated with some key. If it does not exist it creates a new one.
     pub fn get_fn_name(&mut self, key: rs::Instance<'tcx>) -> FnName {
         // Used as the fn name if it is not named yet.
-        let len = self.fn_name_map.len();
+        let len = self.number_of_fns();
 
         *self.fn_name_map.entry(key).or_insert_with(|| FnName(Name::from_internal(len as _)))
     }
@@ -109,6 +163,15 @@ impl<'tcx> Ctxt<'tcx> {
         self.get_fn_name(smir::internal(self.tcx, key))
     }
 
+    // Returns the FnName of the function a closure coerces to when turned into a function pointer.
+    // `key` must be the `FnOnce` instance of the closure.
+    pub fn get_closure_fn_ptr_name(&mut self, key: rs::Instance<'tcx>) -> FnName {
+        // Used as the fn name if it is not named yet.
+        let len = self.number_of_fns();
+
+        *self.closure_fn_ptr_map.entry(key).or_insert_with(|| FnName(Name::from_internal(len as _)))
+    }
+
     pub fn rs_layout_of(&self, ty: rs::Ty<'tcx>) -> rs::Layout<'tcx> {
         self.tcx.layout_of(rs::ParamEnv::reveal_all().and(ty)).unwrap().layout
     }
@@ -117,6 +180,23 @@ impl<'tcx> Ctxt<'tcx> {
     }
 }
 
+/// Turns the `FnOnce` implementation of a non-capturing closure into a function that can be
+/// called through a function pointer. The closure environment is zero-sized, so callers
+/// do not pass it; instead it is an uninitialized local of the function.
+fn reify_closure_fn(mut f: Function) -> Function {
+    let mut args = f.args.iter();
+    let env = args.next().unwrap();
+    f.args = args.collect();
+
+    let mut start = f.blocks.get(f.start).unwrap();
+    start.statements = std::iter::once(Statement::StorageLive { local: env, meta: None })
+        .chain(start.statements.iter())
+        .collect();
+    f.blocks.insert(f.start, start);
+
+    f
+}
+
 fn mk_start_fn(entry: u32) -> Function {
     let b0_name = BbName(Name::from_internal(0));
     let b1_name = BbName(Name::from_internal(1));
@@ -130,6 +210,7 @@ fn mk_start_fn(entry: u32) -> Function {
             arguments: List::new(),
             ret: build::unit_place(),
             next_block: Some(b1_name),
+            unwind_block: None,
         },
     };
 
@@ -157,5 +238,6 @@ fn mk_start_fn(entry: u32) -> Function {
         blocks,
         start: b0_name,
         calling_convention: CallingConvention::C,
+        c_variadic: false,
     }
 }
@@ -50,6 +50,12 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                     (ShlUnchecked, Type::Int(_)) => build::shl_unchecked(l, r),
                     (ShrUnchecked, Type::Int(_)) => build::shr_unchecked(l, r),
 
+                    (Add, Type::Float(_)) => build::float_add(l, r),
+                    (Sub, Type::Float(_)) => build::float_sub(l, r),
+                    (Mul, Type::Float(_)) => build::float_mul(l, r),
+                    (Div, Type::Float(_)) => build::float_div(l, r),
+                    (Rem, Type::Float(_)) => build::float_rem(l, r),
+
                     (Lt, _) => build::lt(l, r),
                     (Le, _) => build::le(l, r),
                     (Gt, _) => build::gt(l, r),
@@ -57,14 +63,8 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                     (Eq, _) => build::eq(l, r),
                     (Ne, _) => build::ne(l, r),
 
-                    (Cmp, _) => {
-                        let res = build::cmp(l, r);
-                        // MiniRust expects an i8 for BinOp::Cmp but MIR uses an Ordering enum,
-                        // so we have to transmute the result.
-                        let ordering_ty: rs::Ty = self.tcx.ty_ordering_enum(None);
-                        let ordering_ty: Type = self.translate_ty(ordering_ty, span);
-                        build::transmute(res, ordering_ty)
-                    }
+                    // This needs control flow, see `translate_cmp`.
+                    (Cmp, _) => rs::span_bug!(span, "`Cmp` must be translated as a statement"),
 
                     (BitAnd, Type::Bool) => build::bool_and(l, r),
                     (BitOr, Type::Bool) => build::bool_or(l, r),
@@ -94,6 +94,7 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                 use smir::UnOp::*;
                 match (unop, ty) {
                     (Neg, Type::Int(_)) => build::neg(operand),
+                    (Neg, Type::Float(_)) => build::float_neg(operand),
                     (Not, Type::Int(_)) => build::bit_not(operand),
                     (Not, Type::Bool) => build::not(operand),
                     (PtrMetadata, Type::Ptr(_)) => build::get_metadata(operand),
@@ -165,12 +166,16 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                         ValueExpr::Tuple(ops, ty)
                     }
                     Type::Enum { variants, .. } => {
-                        let smir::AggregateKind::Adt(_, variant_idx, _, _, _) = agg else {
-                            panic!()
+                        let discriminant = match agg {
+                            smir::AggregateKind::Adt(_, variant_idx, _, _, _) => {
+                                let variant_ty = rv.ty(&self.locals_smir).unwrap();
+                                self.discriminant_for_variant_smir(variant_ty, *variant_idx, span)
+                            }
+                            // A new coroutine is in the "unresumed" state, which only stores the
+                            // captured variables.
+                            smir::AggregateKind::Coroutine(..) => Int::ZERO,
+                            _ => panic!(),
                         };
-                        let variant_ty = rv.ty(&self.locals_smir).unwrap();
-                        let discriminant =
-                            self.discriminant_for_variant_smir(variant_ty, *variant_idx, span);
                         let ops: List<_> =
                             operands.iter().map(|x| self.translate_operand_smir(x, span)).collect();
 
@@ -240,23 +245,35 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                         let operand_ty = operand.ty(&self.locals_smir).unwrap();
                         let operand_ty = self.translate_ty_smir(operand_ty, span);
                         let operand = self.translate_operand_smir(operand, span);
-                        let Type::Int(int_ty) = self.translate_ty_smir(*cast_ty, span) else {
-                            rs::span_bug!(span, "Attempting to IntToInt-Cast to non-int type!");
+                        let cast_ty = self.translate_ty_smir(*cast_ty, span);
+                        let int_ty = match cast_ty {
+                            Type::Int(int_ty) => int_ty,
+                            // Only `u8` can be cast to `char`, so going through `u32` always
+                            // yields a valid `char`.
+                            Type::Char => IntType::U32,
+                            _ =>
+                                rs::span_bug!(span, "Attempting to IntToInt-Cast to non-int type!"),
                         };
 
                         let operand = match operand_ty {
                             Type::Int(_) => operand,
                             // bool2int casts first go to u8, and then to the final type.
                             Type::Bool => build::transmute(operand, u8::get_type()),
+                            // char2int casts first go to u32, and then to the final type.
+                            Type::Char => build::transmute(operand, u32::get_type()),
                             _ =>
                                 rs::span_bug!(
                                     span,
                                     "Attempting to cast non-int and non-boolean type to int!"
                                 ),
                         };
-                        ValueExpr::UnOp {
+                        let result = ValueExpr::UnOp {
                             operator: UnOp::Cast(CastOp::IntToInt(int_ty)),
                             operand: GcCow::new(operand),
+                        };
+                        match cast_ty {
+                            Type::Char => build::transmute(result, Type::Char),
+                            _ => result,
                         }
                     }
 
@@ -375,13 +392,31 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                         | smir::PointerCoercion::ArrayToPointer,
                     ) => unreachable!("{cast_kind:?} casts should not occur in runtime MIR"),
 
+                    smir::CastKind::PointerCoercion(smir::PointerCoercion::ClosureFnPointer(
+                        ..,
+                    )) => {
+                        // Only non-capturing closures can be coerced, so we do not need the operand.
+                        let closure_ty = operand.ty(&self.locals_smir).unwrap();
+                        let closure_ty = smir::internal(self.tcx, closure_ty);
+                        let &rs::TyKind::Closure(def_id, args) = closure_ty.kind() else {
+                            rs::span_bug!(span, "ClosureFnPointer cast on non-closure type");
+                        };
+                        // Like rustc, we use the `FnOnce` implementation of the closure.
+                        let instance = rs::Instance::resolve_closure(
+                            self.tcx,
+                            def_id,
+                            args,
+                            rs::ClosureKind::FnOnce,
+                        );
+
+                        build::fn_ptr(self.cx.get_closure_fn_ptr_name(instance))
+                    }
+
                     smir::CastKind::FloatToFloat
                     | smir::CastKind::FloatToInt
                     | smir::CastKind::IntToFloat
-                    | smir::CastKind::DynStar
-                    | smir::CastKind::PointerCoercion(smir::PointerCoercion::ClosureFnPointer(
-                        ..,
-                    )) => rs::span_bug!(span, "cast not supported: {cast_kind:?}"),
+                    | smir::CastKind::DynStar =>
+                        rs::span_bug!(span, "cast not supported: {cast_kind:?}"),
                 }
             }
             smir::Rvalue::ShallowInitBox(op, ty) => {
@@ -422,8 +457,25 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                 let this_ty = proj.ty(place_ty).unwrap();
                 let this_expr = match proj {
                     smir::ProjectionElem::Field(f, _ty) => {
-                        let indirected = GcCow::new(expr);
-                        PlaceExpr::Field { root: indirected, field: (*f).into() }
+                        let rs_place_ty = smir::internal(self.tcx, place_ty);
+                        let (root, f) = if let rs::TyKind::Coroutine(..) = rs_place_ty.kind() {
+                            // All variants of a coroutine start with its captured variables,
+                            // followed by the locals saved in that state.
+                            let upvar_count = self.coroutine_upvar_count(rs_place_ty);
+                            match expr {
+                                PlaceExpr::Downcast { .. } => (expr, *f + upvar_count),
+                                // Captured variables are accessed without a downcast, so we can
+                                // use any variant to access them.
+                                _ => {
+                                    let root = GcCow::new(expr);
+                                    (PlaceExpr::Downcast { root, discriminant: Int::ZERO }, *f)
+                                }
+                            }
+                        } else {
+                            (expr, *f)
+                        };
+                        let indirected = GcCow::new(root);
+                        PlaceExpr::Field { root: indirected, field: f.into() }
                     }
                     smir::ProjectionElem::Deref => {
                         let x = GcCow::new(expr);
@@ -449,11 +501,34 @@ impl<'cx, 'tcx> FnCtxt<'cx, 'tcx> {
                         PlaceExpr::Downcast { root, discriminant }
                     }
 
-                    stable_mir::mir::ProjectionElem::ConstantIndex { .. }
-                    | stable_mir::mir::ProjectionElem::Subslice { .. }
-                    | stable_mir::mir::ProjectionElem::OpaqueCast(_)
-                    | stable_mir::mir::ProjectionElem::Subtype(_) => {
-                        rs::span_bug!(span, "Place Projection not supported: {:?}", proj);
+                    smir::ProjectionElem::ConstantIndex { offset, min_length: _, from_end } => {
+                        let root = GcCow::new(expr);
+                        PlaceExpr::ConstantIndex {
+                            root,
+                            offset: Int::from(*offset),
+                            from_end: *from_end,
+                        }
+                    }
+                    smir::ProjectionElem::Subslice { from, to, from_end } => {
+                        // MIR counts `to` from the end exactly for slices, like MiniRust.
+                        let is_slice = matches!(
+                            place_ty.kind(),
+                            smir::TyKind::RigidTy(smir::RigidTy::Slice(_))
+                        );
+                        assert_eq!(*from_end, is_slice);
+                        let root = GcCow::new(expr);
+                        PlaceExpr::Subslice { root, from: Int::from(*from), to: Int::from(*to) }
+                    }
+
+                    smir::ProjectionElem::OpaqueCast(_) | smir::ProjectionElem::Subtype(_) => {
+                        // Opaque types are revealed and lifetimes are erased during translation,
+                        // so these projections do not change the MiniRust type of the place.
+                        assert!(
+                            self.translate_ty_smir(place_ty, span)
+                                == self.translate_ty_smir(this_ty, span),
+                            "{proj:?} changes the type of the place"
+                        );
+                        expr
                     }
                 };
                 (this_expr, this_ty)
@@ -1,5 +1,7 @@
 use crate::*;
 
+use std::ops::Bound;
+
 impl<'tcx> Ctxt<'tcx> {
     pub fn pointee_info_of(&mut self, ty: rs::Ty<'tcx>, span: rs::Span) -> PointeeInfo {
         let layout = self.rs_layout_of(ty);
@@ -46,12 +48,17 @@ impl<'tcx> Ctxt<'tcx> {
     }
 
     pub fn translate_ty(&mut self, ty: rs::Ty<'tcx>, span: rs::Span) -> Type {
+        // Make sure the cache is keyed by the fully normalized type, so that e.g.
+        // `[u8; N]` with `N` not yet evaluated and `[u8; 4]` share an entry,
+        // while different instantiations of a generic type never do.
+        let ty = self.tcx.normalize_erasing_regions(rs::ParamEnv::reveal_all(), ty);
         if let Some(mini_ty) = self.ty_cache.get(&ty) {
             return *mini_ty;
         }
 
         let mini_ty = match ty.kind() {
             rs::TyKind::Bool => Type::Bool,
+            rs::TyKind::Char => Type::Char,
             rs::TyKind::Int(t) => {
                 let sz = rs::abi::Integer::from_int_ty(&self.tcx, *t).size();
                 Type::Int(IntType { size: translate_size(sz), signed: Signedness::Signed })
@@ -60,33 +67,40 @@ impl<'tcx> Ctxt<'tcx> {
                 let sz = rs::abi::Integer::from_uint_ty(&self.tcx, *t).size();
                 Type::Int(IntType { size: translate_size(sz), signed: Signedness::Unsigned })
             }
-            rs::TyKind::Tuple(ts) => {
-                let layout = self.rs_layout_of(ty);
-                let size = translate_size(layout.size());
-                let align = translate_align(layout.align().abi);
-
-                let fields = ts
-                    .iter()
-                    .enumerate()
-                    .map(|(i, t)| {
-                        let t = self.translate_ty(t, span);
-                        let offset = layout.fields().offset(i);
-                        let offset = translate_size(offset);
-
-                        (offset, t)
-                    })
-                    .collect::<Vec<_>>();
-
-                build::tuple_ty(&fields, size, align)
-            }
+            rs::TyKind::Float(t) =>
+                match t {
+                    rs::FloatTy::F32 => Type::Float(FloatType::F32),
+                    rs::FloatTy::F64 => Type::Float(FloatType::F64),
+                    _ => rs::span_bug!(span, "float type {t:?} not supported"),
+                },
+            rs::TyKind::Tuple(ts) => self.translate_tuple_like(ty, ts.iter(), span),
+            // A closure is an anonymous struct of its captured variables.
+            rs::TyKind::Closure(_, args) =>
+                self.translate_tuple_like(ty, args.as_closure().upvar_tys().iter(), span),
             rs::TyKind::Adt(adt_def, _) if adt_def.is_box() => {
                 let ty = ty.expect_boxed_ty();
                 let pointee = self.pointee_info_of(ty, span);
                 Type::Ptr(PtrType::Box { pointee })
             }
+            // `DynMetadata<dyn Trait>` is the metadata of a `dyn Trait` pointer. In Rust it wraps a pointer
+            // to an extern type, but MiniRust has a dedicated type for that pointer.
+            rs::TyKind::Adt(adt_def, sref)
+                if self.tcx.is_lang_item(adt_def.did(), rs::LangItem::DynMetadata) =>
+                Type::Ptr(PtrType::VTablePtr(self.get_trait_name(sref.type_at(0)))),
             rs::TyKind::Adt(adt_def, sref) if adt_def.is_struct() => {
                 let (fields, size, align) = self.translate_non_enum_adt(ty, *adt_def, sref, span);
-                build::tuple_ty(&fields.iter().collect::<Vec<_>>(), size, align)
+                let fields = fields.iter().collect::<Vec<_>>();
+                // `repr(packed)` lowers the alignment of the fields, which we need to record
+                // so that their offsets are well-formed. `repr(align)` is already part of `align`.
+                let mut ty = match adt_def.repr().pack {
+                    Some(pack) =>
+                        build::packed_tuple_ty(&fields, size, align, translate_align(pack)),
+                    None => build::tuple_ty(&fields, size, align),
+                };
+                if let Type::Tuple { valid_range, .. } = &mut ty {
+                    *valid_range = self.translate_valid_range(*adt_def, &fields);
+                }
+                ty
             }
             rs::TyKind::Adt(adt_def, sref) if adt_def.is_union() => {
                 let (fields, size, align) = self.translate_non_enum_adt(ty, *adt_def, sref, span);
@@ -95,6 +109,8 @@ impl<'tcx> Ctxt<'tcx> {
             }
             rs::TyKind::Adt(adt_def, sref) if adt_def.is_enum() =>
                 self.translate_enum(ty, *adt_def, sref, span),
+            rs::TyKind::Coroutine(def_id, args) =>
+                self.translate_coroutine(ty, *def_id, args, span),
             rs::TyKind::Ref(_, ty, mutbl) => {
                 let pointee = self.pointee_info_of(*ty, span);
                 let mutbl = translate_mutbl(*mutbl);
@@ -110,8 +126,9 @@ impl<'tcx> Ctxt<'tcx> {
                 Type::Array { elem, count }
             }
             rs::TyKind::FnPtr(..) => Type::Ptr(PtrType::FnPtr),
-            rs::TyKind::Never =>
-                build::enum_ty::<u8>(&[], Discriminator::Invalid, build::size(0), build::align(1)),
+            // A function item is a zero-sized value; calls through it name the function directly.
+            rs::TyKind::FnDef(..) => self.translate_tuple_like(ty, std::iter::empty(), span),
+            rs::TyKind::Never => Type::Never,
             rs::TyKind::Slice(ty) => {
                 let elem = GcCow::new(self.translate_ty(*ty, span));
                 Type::Slice { elem }
@@ -132,6 +149,31 @@ impl<'tcx> Ctxt<'tcx> {
         mini_ty
     }
 
+    /// Translates a type whose fields are laid out like a tuple with the given field types.
+    fn translate_tuple_like(
+        &mut self,
+        ty: rs::Ty<'tcx>,
+        field_tys: impl Iterator<Item = rs::Ty<'tcx>>,
+        span: rs::Span,
+    ) -> Type {
+        let layout = self.rs_layout_of(ty);
+        let size = translate_size(layout.size());
+        let align = translate_align(layout.align().abi);
+
+        let fields = field_tys
+            .enumerate()
+            .map(|(i, t)| {
+                let t = self.translate_ty(t, span);
+                let offset = layout.fields().offset(i);
+                let offset = translate_size(offset);
+
+                (offset, t)
+            })
+            .collect::<Vec<_>>();
+
+        build::tuple_ty(&fields, size, align)
+    }
+
     /// Constructs the fields of a given variant.
     pub fn translate_adt_variant_fields(
         &mut self,
@@ -157,6 +199,38 @@ impl<'tcx> Ctxt<'tcx> {
             .collect()
     }
 
+    /// Translates the `rustc_layout_scalar_valid_range_*` attributes of a struct,
+    /// which restrict the values of its only field (e.g. for `NonZero` and `NonNull`).
+    fn translate_valid_range(
+        &self,
+        adt_def: rs::AdtDef<'tcx>,
+        fields: &[(Offset, Type)],
+    ) -> Option<ValidRange> {
+        let (start, end) = self.tcx.layout_scalar_valid_range(adt_def.did());
+        if start == Bound::Unbounded && end == Bound::Unbounded {
+            return None;
+        }
+        let scalar_ty = match fields {
+            [(_, Type::Int(int_ty))] => *int_ty,
+            [(_, Type::Ptr(_))] => IntType::usize_ty::<DefaultTarget>(),
+            _ => panic!("valid range on a struct that does not wrap a single integer or pointer"),
+        };
+        // Like rustc, we work with the unsigned bits of the scalar; a range with `start > end` wraps around.
+        let rs_size = rs::Size::from_bytes(scalar_ty.size.bytes().try_to_u8().unwrap());
+        let start = match start {
+            Bound::Included(bits) => bits,
+            _ => 0,
+        };
+        let end = match end {
+            Bound::Included(bits) => bits,
+            _ => rs_size.unsigned_int_max(),
+        };
+        Some(ValidRange {
+            start: int_from_bits(start, scalar_ty),
+            end: int_from_bits(end, scalar_ty),
+        })
+    }
+
     fn translate_non_enum_adt(
         &mut self,
         ty: rs::Ty<'tcx>,
@@ -200,8 +274,10 @@ pub fn translate_align(align: rs::Align) -> Align {
     Align::from_bytes(align.bytes()).unwrap()
 }
 
-pub fn translate_calling_convention(conv: rs::Conv) -> CallingConvention {
-    match conv {
+pub fn translate_calling_convention(abi: &rs::FnAbi<'_, rs::Ty<'_>>) -> CallingConvention {
+    match abi.conv {
+        // `extern "C"` and `extern "C-unwind"` only differ in whether the function may unwind.
+        rs::Conv::C if abi.can_unwind => CallingConvention::CUnwind,
         rs::Conv::C => CallingConvention::C,
         rs::Conv::Rust => CallingConvention::Rust,
         _ => todo!(),
@@ -0,0 +1,29 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+struct Noisy(u32);
+
+impl Drop for Noisy {
+    fn drop(&mut self) {
+        print(self.0);
+    }
+}
+
+struct Pair {
+    _a: Noisy,
+    _b: Noisy,
+}
+
+fn get(arr: &[u32; 2], i: usize) -> u32 {
+    let _guard = Noisy(3);
+    arr[i]
+}
+
+fn main() {
+    let _x = Noisy(1);
+    let _pair = Pair { _a: Noisy(10), _b: Noisy(11) };
+    print(get(&[0, 0], 0));
+    // The out-of-bounds access unwinds, and the cleanup blocks drop all live locals.
+    get(&[0, 0], 2);
+    print(99);
+}
@@ -0,0 +1,5 @@
+#[allow(invalid_value)]
+fn main() {
+    // `mem::zeroed` checks that the type permits being zero-initialized.
+    let _x: &u8 = unsafe { std::mem::zeroed() };
+}
@@ -6,25 +6,64 @@ fn main() {
 
     let ptr = (&mut x) as *mut u32;
 
-    let v = unsafe { atomic_load(ptr) };
+    let v = unsafe { atomic_load(ptr, Ordering::Acquire) };
     print(v);
 
-    unsafe { atomic_store(ptr, 2) };
+    unsafe { atomic_store(ptr, 2, Ordering::Release) };
     print(x);
 
-    let v = unsafe { compare_exchange(ptr, 2, 3) };
+    let v = unsafe { compare_exchange(ptr, 2, 3, Ordering::AcqRel, Ordering::Acquire) };
     print(x);
     print(v);
 
-    let v = unsafe { compare_exchange(ptr, 2, 4) };
+    let v = unsafe { compare_exchange(ptr, 2, 4, Ordering::SeqCst, Ordering::Relaxed) };
     print(x);
     print(v);
 
-    let v = unsafe { atomic_fetch_add(ptr, 3) };
+    let v = unsafe { atomic_fetch_add(ptr, 3, Ordering::Relaxed) };
     print(x);
     print(v);
     
-    let v = unsafe { atomic_fetch_sub(ptr, 4) };
+    let v = unsafe { atomic_fetch_sub(ptr, 4, Ordering::SeqCst) };
     print(x);
     print(v);
+
+    let v = unsafe { atomic_fetch_and(ptr, 0b1100, Ordering::SeqCst) };
+    print(x);
+    print(v);
+
+    let v = unsafe { atomic_fetch_or(ptr, 0b0011, Ordering::Release) };
+    print(x);
+    print(v);
+
+    let v = unsafe { atomic_fetch_xor(ptr, 0b0101, Ordering::Acquire) };
+    print(x);
+    print(v);
+
+    let v = unsafe { atomic_fetch_nand(ptr, 0b1111, Ordering::AcqRel) };
+    print(x);
+    print(v);
+
+    let v = unsafe { atomic_fetch_min(ptr, 7, Ordering::Relaxed) };
+    print(x);
+    print(v);
+
+    let v = unsafe { atomic_fetch_max(ptr, 9, Ordering::SeqCst) };
+    print(x);
+    print(v);
+
+    let v = unsafe { atomic_swap(ptr, 5, Ordering::SeqCst) };
+    print(x);
+    print(v);
+
+    let mut y: i32 = -1;
+    let ptr = (&mut y) as *mut i32;
+
+    let v = unsafe { atomic_fetch_max_i32(ptr, 1, Ordering::SeqCst) };
+    print(y);
+    print(v);
+
+    let v = unsafe { atomic_fetch_min_i32(ptr, -3, Ordering::SeqCst) };
+    print(y);
+    print(v);
 }
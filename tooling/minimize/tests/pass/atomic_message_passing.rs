@@ -0,0 +1,26 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+static mut DATA: u32 = 0;
+static mut FLAG: u32 = 0;
+
+extern "C" fn writer(_: *const ()) {
+    unsafe {
+        DATA = 42;
+        atomic_store(&raw mut FLAG, 1, Ordering::Release);
+    }
+}
+
+fn main() {
+    let data_ptr = &() as *const ();
+
+    let fn_ptr = writer as extern "C" fn(*const ());
+    let thread_id = spawn(fn_ptr, data_ptr);
+
+    // Once we see the flag, the acquire load synchronizes with the writer, so reading `DATA` is not a data race
+    // and must see the write.
+    while unsafe { atomic_load(&raw mut FLAG, Ordering::Acquire) } == 0 {}
+    print(unsafe { DATA });
+
+    join(thread_id);
+}
@@ -0,0 +1,17 @@
+#![feature(bigint_helper_methods)]
+extern crate intrinsics;
+use intrinsics::*;
+
+fn main() {
+    let (sum, carry) = 200u8.carrying_add(55, true);
+    print(sum);
+    print(carry);
+
+    let (diff, borrow) = 1u32.borrowing_sub(1, true);
+    print(diff);
+    print(borrow);
+
+    let (low, high) = 200u8.widening_mul(200);
+    print(low);
+    print(high);
+}
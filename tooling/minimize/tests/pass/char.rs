@@ -0,0 +1,26 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+fn black_box<T>(t: T) -> T { t }
+
+fn classify(c: char) -> u8 {
+    match c {
+        'a' => 1,
+        'ß' => 2,
+        '\u{10FFFF}' => 3,
+        _ => 0,
+    }
+}
+
+fn main() {
+    print(classify(black_box('a'))); // 1
+    print(classify(black_box('ß'))); // 2
+    print(classify(black_box('\u{10FFFF}'))); // 3
+    print(classify(black_box('z'))); // 0
+
+    print(black_box('A') as u32); // 65
+    print(black_box('ß') as u8); // 223
+    print(black_box('\u{10FFFF}') as i64); // 1114111
+    print(black_box(98u8) as char == 'b'); // 1
+    print(black_box('a') < black_box('b')); // 1
+}
@@ -0,0 +1,35 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+fn call_fn(f: impl Fn(u32) -> u32, x: u32) -> u32 {
+    f(x)
+}
+
+fn call_fn_mut(mut f: impl FnMut(u32), x: u32) {
+    f(x);
+    f(x);
+}
+
+fn call_fn_once(f: impl FnOnce() -> u32) -> u32 {
+    f()
+}
+
+fn main() {
+    let offset = 10;
+    print(call_fn(|x| x + offset, 1));
+
+    let mut sum = 0;
+    call_fn_mut(|x| sum += x, 3);
+    print(sum);
+
+    let v = 7;
+    print(call_fn_once(move || v * 2));
+    // Calling an `Fn` closure through `FnOnce` goes through a shim.
+    print(call_fn_once(|| offset));
+
+    let add = |a: u32, b: u32| a + b;
+    print(add(2, 3));
+
+    // Calls through function pointers are not supported yet, but the coercion is.
+    let _triple: fn(u32) -> u32 = |x| x * 3;
+}
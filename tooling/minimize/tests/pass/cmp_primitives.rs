@@ -0,0 +1,24 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Key {
+    flag: bool,
+    c: char,
+}
+
+fn main() {
+    print(false.cmp(&true) as i8);
+    print('b'.cmp(&'a') as i8);
+
+    let a = Key { flag: true, c: 'a' };
+    let b = Key { flag: true, c: 'z' };
+    print(a.cmp(&b) as i8);
+    print(b.cmp(&b) as i8);
+
+    let arr = [0u8; 2];
+    let p0 = &arr[0] as *const u8;
+    let p1 = &arr[1] as *const u8;
+    print(p0.cmp(&p1) as i8);
+    print(p1.cmp(&p0) as i8);
+}
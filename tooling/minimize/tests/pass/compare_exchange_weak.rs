@@ -0,0 +1,25 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+fn main() {
+    let mut x: u32 = 1;
+
+    let ptr = (&mut x) as *mut u32;
+
+    // A weak compare-exchange can fail spuriously, so we retry until it succeeds.
+    let mut current = 1;
+    loop {
+        let (v, ok) = unsafe { compare_exchange_weak(ptr, current, current + 1, Ordering::AcqRel, Ordering::Acquire) };
+        if ok {
+            break;
+        }
+        current = v;
+    }
+    print(x);
+
+    // But it never succeeds if the value differs.
+    let (v, ok) = unsafe { compare_exchange_weak(ptr, 1, 3, Ordering::SeqCst, Ordering::Relaxed) };
+    print(x);
+    print(v);
+    print(ok);
+}
@@ -0,0 +1,60 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+// A bounded buffer with room for one item, protected by a lock and two condition variables.
+static mut LOCK: usize = 0;
+static mut NOT_EMPTY: usize = 0;
+static mut NOT_FULL: usize = 0;
+static mut FULL: bool = false;
+static mut ITEM: u32 = 0;
+
+fn put(item: u32) {
+    unsafe {
+        acquire(LOCK);
+        while FULL {
+            condvar_wait(NOT_FULL, LOCK);
+        }
+        ITEM = item;
+        FULL = true;
+        condvar_notify_one(NOT_EMPTY);
+        release(LOCK);
+    }
+}
+
+fn take() -> u32 {
+    unsafe {
+        acquire(LOCK);
+        while !FULL {
+            condvar_wait(NOT_EMPTY, LOCK);
+        }
+        let item = ITEM;
+        FULL = false;
+        condvar_notify_one(NOT_FULL);
+        release(LOCK);
+        item
+    }
+}
+
+extern "C" fn producer(_: *const ()) {
+    for i in 1..5 {
+        put(i);
+    }
+}
+
+fn main() {
+    unsafe {
+        LOCK = create_lock();
+        NOT_EMPTY = create_condvar();
+        NOT_FULL = create_condvar();
+    }
+
+    let data_ptr = &() as *const ();
+    let fn_ptr = producer as extern "C" fn(*const ());
+    let thread_id = spawn(fn_ptr, data_ptr);
+
+    // The items arrive in order, since the buffer only has room for one.
+    for _ in 0..4 {
+        print(take());
+    }
+    join(thread_id);
+}
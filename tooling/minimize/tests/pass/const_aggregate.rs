@@ -0,0 +1,42 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+trait Speak {
+    fn speak(&self) -> u32;
+}
+
+impl Speak for u8 {
+    fn speak(&self) -> u32 {
+        *self as u32 + 1
+    }
+}
+
+fn forty_two() -> u32 {
+    42
+}
+
+const BYTES: [u8; 4] = [1, 2, 3, 4];
+const NESTED: [(u8, &[u16; 2]); 2] = [(5, &[6, 7]), (8, &[9, 10])];
+const SOME: Option<&u32> = Some(&11);
+const NONE: Option<&u32> = None;
+const FN: fn() -> u32 = forty_two;
+const FNS: (fn() -> u32, Option<fn() -> u32>) = (forty_two, None);
+const DYN: &dyn Speak = &12u8;
+
+fn main() {
+    print(BYTES[0] + BYTES[3]);
+    print(NESTED[1].0);
+    print(NESTED[1].1[1]);
+    if let Some(x) = SOME {
+        print(*x);
+    }
+    if NONE.is_none() {
+        print(0);
+    }
+    print(FN());
+    print(FNS.0());
+    if FNS.1.is_none() {
+        print(0);
+    }
+    print(DYN.speak());
+}
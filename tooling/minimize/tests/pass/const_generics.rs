@@ -0,0 +1,52 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+// The same generic type used at many different instantiations must not share translations.
+
+struct Buf<T, const N: usize> {
+    len: usize,
+    data: [T; N],
+}
+
+impl<T: Copy, const N: usize> Buf<T, N> {
+    fn new(x: T) -> Self {
+        Buf { len: N, data: [x; N] }
+    }
+
+    fn get(&self, i: usize) -> T {
+        self.data[i]
+    }
+}
+
+fn sum<const N: usize>(arr: [u32; N]) -> u32 {
+    let mut s = 0;
+    let mut i = 0;
+    while i < N {
+        s += arr[i];
+        i += 1;
+    }
+    s
+}
+
+fn main() {
+    let a = Buf::<u8, 1>::new(3);
+    let b = Buf::<u8, 4>::new(5);
+    let c = Buf::<u32, 4>::new(7);
+    let d = Buf::<u16, 0>::new(9);
+    print(a.len);
+    print(a.get(0));
+    print(b.len);
+    print(b.get(3));
+    print(c.len);
+    print(c.get(2));
+    print(d.len);
+
+    print(std::mem::size_of::<Buf<u8, 1>>());
+    print(std::mem::size_of::<Buf<u8, 4>>());
+    print(std::mem::size_of::<Buf<u32, 4>>());
+    print(std::mem::size_of::<Buf<u16, 0>>());
+
+    print(sum([1, 2, 3]));
+    print(sum([10; 5]));
+    print(sum::<0>([]));
+}
@@ -0,0 +1,31 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+#[derive(Clone, Copy)]
+union Bytes {
+    x: u32,
+    y: [u8; 4],
+}
+
+#[derive(Clone, Copy)]
+union Scalar {
+    x: u64,
+}
+
+const BYTES: Bytes = Bytes { x: 0x01020304 };
+const SCALAR: Scalar = Scalar { x: 7 };
+
+static X: u32 = 5;
+
+fn main() {
+    let b = BYTES;
+    unsafe {
+        print(b.y[0]);
+        print(b.y[3]);
+    }
+    let s = SCALAR;
+    unsafe {
+        print(s.x);
+    }
+    print(X);
+}
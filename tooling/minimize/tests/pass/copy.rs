@@ -0,0 +1,37 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+use std::ptr;
+
+fn main() {
+    let src = [1u32, 2, 3, 4];
+    let mut dst = [0u32; 4];
+    unsafe {
+        ptr::copy_nonoverlapping(src.as_ptr(), dst.as_mut_ptr(), 4);
+    }
+    print(dst[0]);
+    print(dst[3]);
+
+    // Overlapping ranges behave like `memmove`.
+    let mut arr = [1u32, 2, 3, 4];
+    unsafe {
+        ptr::copy(arr.as_ptr(), arr.as_mut_ptr().add(1), 3);
+    }
+    print(arr[1]);
+    print(arr[3]);
+
+    // Copying pointers keeps their provenance.
+    let x = 42u32;
+    let src = [&x as *const u32];
+    let mut dst = [ptr::null::<u32>()];
+    unsafe {
+        ptr::copy_nonoverlapping(src.as_ptr(), dst.as_mut_ptr(), 1);
+        print(*dst[0]);
+    }
+
+    let mut arr = [0u16; 2];
+    unsafe {
+        ptr::write_bytes(arr.as_mut_ptr(), 1, 2);
+    }
+    print(arr[1]);
+}
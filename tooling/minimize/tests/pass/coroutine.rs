@@ -0,0 +1,29 @@
+#![feature(coroutines, coroutine_trait, stmt_expr_attributes)]
+
+extern crate intrinsics;
+use intrinsics::*;
+
+use std::ops::{Coroutine, CoroutineState};
+use std::pin::Pin;
+
+fn main() {
+    let start = 10;
+    let mut co = #[coroutine]
+    move || {
+        let mut x = start;
+        yield x;
+        x += 1;
+        yield x;
+        x * 2
+    };
+    let mut co = Pin::new(&mut co);
+    loop {
+        match co.as_mut().resume(()) {
+            CoroutineState::Yielded(v) => print(v),
+            CoroutineState::Complete(v) => {
+                print(v);
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+struct Noisy(u32);
+
+impl Drop for Noisy {
+    fn drop(&mut self) {
+        print(self.0);
+    }
+}
+
+struct Inner {
+    _a: Noisy,
+    _b: Noisy,
+}
+
+enum Either {
+    Left(Noisy),
+    Right(Inner),
+    Empty,
+}
+
+struct Outer {
+    _inner: Inner,
+    _either: Either,
+    _last: Noisy,
+}
+
+impl Drop for Outer {
+    fn drop(&mut self) {
+        print(0);
+    }
+}
+
+// Uses drop flags to only drop `a` if it was not moved.
+fn maybe_consume(cond: bool) {
+    let a = Noisy(7);
+    let _b = Noisy(8);
+    if cond {
+        consume(a);
+    }
+}
+
+fn consume(_n: Noisy) {
+    print(100);
+}
+
+fn main() {
+    // `Drop::drop` of the outer struct runs first, then the fields are dropped in order.
+    let mut outer = Outer {
+        _inner: Inner { _a: Noisy(1), _b: Noisy(2) },
+        _either: Either::Right(Inner { _a: Noisy(3), _b: Noisy(4) }),
+        _last: Noisy(5),
+    };
+    unsafe {
+        std::ptr::drop_in_place(&mut outer);
+    }
+    // prevent double-drop
+    std::mem::forget(outer);
+
+    maybe_consume(true);
+    maybe_consume(false);
+
+    let _left = Either::Left(Noisy(6));
+    let _empty = Either::Empty;
+}
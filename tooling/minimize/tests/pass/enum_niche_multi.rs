@@ -0,0 +1,123 @@
+//! Checks niche layouts where several variants share the niche.
+//! The expected tags are computed by rustc's const evaluation, so they are checked against rustc's layouts.
+use std::mem::transmute;
+
+#[derive(Clone, Copy)]
+enum Nested {
+    A(Option<bool>),
+    B,
+    C,
+}
+
+/// The niche variants come *before* the untagged variant.
+#[derive(Clone, Copy)]
+enum Before {
+    A,
+    B,
+    C(bool),
+    D,
+}
+
+/// The valid values of `Inner` end at `i8::MAX`, so the niche of `Outer` wraps around to negative values.
+#[derive(Clone, Copy)]
+#[repr(i8)]
+enum Inner {
+    V1 = 125,
+    V2 = 126,
+    V3 = 127,
+}
+
+#[derive(Clone, Copy)]
+enum Outer {
+    V1(Inner),
+    V2,
+    V3,
+    V4,
+}
+
+const OO_NONE: u8 = unsafe { transmute(None::<Option<bool>>) };
+const OO_SOME_NONE: u8 = unsafe { transmute(Some(None::<bool>)) };
+const NESTED_B: u8 = unsafe { transmute(Nested::B) };
+const NESTED_C: u8 = unsafe { transmute(Nested::C) };
+const BEFORE_A: u8 = unsafe { transmute(Before::A) };
+const BEFORE_B: u8 = unsafe { transmute(Before::B) };
+const BEFORE_D: u8 = unsafe { transmute(Before::D) };
+const OUTER_V2: i8 = unsafe { transmute(Outer::V2) };
+const OUTER_V3: i8 = unsafe { transmute(Outer::V3) };
+const OUTER_V4: i8 = unsafe { transmute(Outer::V4) };
+
+fn oo(x: Option<Option<bool>>) -> u8 {
+    match x {
+        None => 0,
+        Some(None) => 1,
+        Some(Some(false)) => 2,
+        Some(Some(true)) => 3,
+    }
+}
+
+fn nested(x: Nested) -> u8 {
+    match x {
+        Nested::A(None) => 0,
+        Nested::A(Some(false)) => 1,
+        Nested::A(Some(true)) => 2,
+        Nested::B => 3,
+        Nested::C => 4,
+    }
+}
+
+fn before(x: Before) -> u8 {
+    match x {
+        Before::A => 0,
+        Before::B => 1,
+        Before::C(false) => 2,
+        Before::C(true) => 3,
+        Before::D => 4,
+    }
+}
+
+fn outer(x: Outer) -> u8 {
+    match x {
+        Outer::V1(Inner::V1) => 0,
+        Outer::V1(Inner::V2) => 1,
+        Outer::V1(Inner::V3) => 2,
+        Outer::V2 => 3,
+        Outer::V3 => 4,
+        Outer::V4 => 5,
+    }
+}
+
+fn main() {
+    unsafe {
+        // Encoding matches rustc.
+        assert!(transmute::<_, u8>(None::<Option<bool>>) == OO_NONE);
+        assert!(transmute::<_, u8>(Some(None::<bool>)) == OO_SOME_NONE);
+        assert!(transmute::<_, u8>(Nested::B) == NESTED_B);
+        assert!(transmute::<_, u8>(Nested::C) == NESTED_C);
+        assert!(transmute::<_, u8>(Before::A) == BEFORE_A);
+        assert!(transmute::<_, u8>(Before::B) == BEFORE_B);
+        assert!(transmute::<_, u8>(Before::D) == BEFORE_D);
+        assert!(transmute::<_, i8>(Outer::V2) == OUTER_V2);
+        assert!(transmute::<_, i8>(Outer::V3) == OUTER_V3);
+        assert!(transmute::<_, i8>(Outer::V4) == OUTER_V4);
+
+        // Decoding matches rustc.
+        assert!(oo(transmute::<u8, _>(OO_NONE)) == 0);
+        assert!(oo(transmute::<u8, _>(OO_SOME_NONE)) == 1);
+        assert!(nested(transmute::<u8, _>(NESTED_B)) == 3);
+        assert!(nested(transmute::<u8, _>(NESTED_C)) == 4);
+        assert!(before(transmute::<u8, _>(BEFORE_A)) == 0);
+        assert!(before(transmute::<u8, _>(BEFORE_B)) == 1);
+        assert!(before(transmute::<u8, _>(BEFORE_D)) == 4);
+        assert!(outer(transmute::<i8, _>(OUTER_V2)) == 3);
+        assert!(outer(transmute::<i8, _>(OUTER_V3)) == 4);
+        assert!(outer(transmute::<i8, _>(OUTER_V4)) == 5);
+    }
+
+    assert!(oo(Some(Some(true))) == 3);
+    assert!(oo(Some(Some(false))) == 2);
+    assert!(nested(Nested::A(None)) == 0);
+    assert!(nested(Nested::A(Some(true))) == 2);
+    assert!(before(Before::C(true)) == 3);
+    assert!(outer(Outer::V1(Inner::V1)) == 0);
+    assert!(outer(Outer::V1(Inner::V3)) == 2);
+}
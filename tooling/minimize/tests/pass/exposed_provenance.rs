@@ -0,0 +1,18 @@
+#![feature(exposed_provenance)]
+
+extern crate intrinsics;
+use intrinsics::*;
+
+use std::ptr;
+
+fn main() {
+    let x = 42u32;
+    let addr = &x as *const u32 as usize;
+    let ptr = addr as *const u32;
+    print(unsafe { *ptr });
+
+    let arr = [1u32, 2, 3];
+    let end = arr.as_ptr().wrapping_add(3).expose_provenance();
+    let last = ptr::with_exposed_provenance::<u32>(end).wrapping_sub(1);
+    print(unsafe { *last });
+}
@@ -0,0 +1,24 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+fn add(x: f64, y: f64) -> f64 {
+    x + y
+}
+
+fn mul(x: f32, y: f32) -> f32 {
+    x * y
+}
+
+fn main() {
+    print(add(0.1, 0.2) == 0.30000000000000004);
+    print(add(0.1, 0.2) == 0.3);
+    print(mul(1.5, 2.0) == 3.0);
+    print(-mul(1.5, 2.0) < 0.0);
+    print(7.5f64 % 2.0 == 1.5);
+    print(1.0f32 / 3.0 > 0.33);
+
+    let nan = add(f64::INFINITY, f64::NEG_INFINITY);
+    print(nan == nan);
+    print(nan != nan);
+    print(nan < 1.0 || nan >= 1.0);
+}
@@ -0,0 +1,45 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+fn add(x: u32, y: u32) -> u32 {
+    x + y
+}
+
+fn mul(x: u32, y: u32) -> u32 {
+    x * y
+}
+
+fn apply(f: fn(u32, u32) -> u32, x: u32, y: u32) -> u32 {
+    f(x, y)
+}
+
+extern "C" fn c_fn(x: i32) -> i32 {
+    -x
+}
+
+struct Ops {
+    op: fn(u32, u32) -> u32,
+}
+
+fn main() {
+    let f: fn(u32, u32) -> u32 = add;
+    print(f(2, 3));
+    print(apply(mul, 2, 3));
+
+    let ops = [add as fn(u32, u32) -> u32, mul];
+    let mut i = 0;
+    while i < ops.len() {
+        print(ops[i](4, 5));
+        i += 1;
+    }
+
+    let s = Ops { op: mul };
+    print((s.op)(6, 7));
+
+    let g: extern "C" fn(i32) -> i32 = c_fn;
+    print(g(8));
+
+    // Closures without captures coerce to function pointers.
+    let h: fn(u32) -> u32 = |x| x + 100;
+    print(h(1));
+}
@@ -0,0 +1,31 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+use std::mem::MaybeUninit;
+
+#[repr(C)]
+struct Padded {
+    a: u8,
+    b: u16,
+}
+
+fn main() {
+    // After freezing, the padding byte can be read as an integer.
+    let mut p = Padded { a: 1, b: 2 };
+    unsafe {
+        freeze(&mut p);
+        let bytes = *(&p as *const Padded as *const [u8; 4]);
+        print(bytes[0]);
+        print(bytes[2]);
+        let pad = bytes[1];
+        print(pad == pad);
+    }
+
+    // Freezing uninitialized memory gives an arbitrary but fixed value.
+    let mut x = MaybeUninit::<u32>::uninit();
+    unsafe {
+        freeze(x.as_mut_ptr());
+        let x = x.assume_init();
+        print(x ^ x);
+    }
+}
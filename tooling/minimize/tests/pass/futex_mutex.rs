@@ -0,0 +1,48 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+// A mutex in the style of the standard library: 0 is unlocked, 1 is locked,
+// and 2 is locked with (possibly) some threads waiting on the futex.
+static mut STATE: u32 = 0;
+static mut COUNTER: u32 = 0;
+
+fn lock() {
+    unsafe {
+        if compare_exchange(&raw mut STATE, 0, 1, Ordering::Acquire, Ordering::Relaxed) == 0 {
+            return;
+        }
+        while atomic_swap(&raw mut STATE, 2, Ordering::Acquire) != 0 {
+            futex_wait(&raw mut STATE, 2);
+        }
+    }
+}
+
+fn unlock() {
+    unsafe {
+        if atomic_swap(&raw mut STATE, 0, Ordering::Release) == 2 {
+            futex_wake(&raw mut STATE);
+        }
+    }
+}
+
+extern "C" fn worker(_: *const ()) {
+    for _ in 0..3 {
+        lock();
+        unsafe { COUNTER += 1; }
+        unlock();
+    }
+}
+
+fn main() {
+    let data_ptr = &() as *const ();
+    let fn_ptr = worker as extern "C" fn(*const ());
+    let a = spawn(fn_ptr, data_ptr);
+    let b = spawn(fn_ptr, data_ptr);
+    join(a);
+    join(b);
+
+    print(unsafe { COUNTER });
+    // Nobody is waiting anymore.
+    print(futex_wake(&raw mut STATE));
+    print(unsafe { futex_wait(&raw mut STATE, 1) });
+}
@@ -0,0 +1,28 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+fn make_counter(start: u32) -> impl Fn(u32) -> u32 {
+    move |x| x + start
+}
+
+fn pair() -> (impl Copy, impl Copy) {
+    (1u8, 2u16)
+}
+
+fn iter_sum(it: impl Iterator<Item = u32>) -> u32 {
+    let mut sum = 0;
+    for x in it {
+        sum += x;
+    }
+    sum
+}
+
+fn main() {
+    let f = make_counter(10);
+    print(f(5));
+
+    let (a, b) = pair();
+    let _copy = (a, b);
+
+    print(iter_sum([1u32, 2, 3].into_iter()));
+}
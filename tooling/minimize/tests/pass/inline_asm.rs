@@ -0,0 +1,13 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+use std::arch::asm;
+
+fn main() {
+    let x = 7u32;
+    unsafe {
+        asm!("nop");
+        asm!("/* {} */", in(reg) x);
+    }
+    print(x);
+}
@@ -0,0 +1,21 @@
+#![allow(internal_features)]
+#![feature(core_intrinsics)]
+extern crate intrinsics;
+use intrinsics::*;
+
+use std::intrinsics as i;
+
+fn main() {
+    print(i::saturating_add(250u8, 10));
+    print(i::saturating_sub(-100i8, 100));
+    print(i::wrapping_add(250u8, 10));
+    print(i::wrapping_mul(100i8, 3));
+    unsafe {
+        print(i::exact_div(12u32, 4));
+        print(i::unchecked_rem(-7i32, 3));
+        print(i::unchecked_sub(7u32, 3));
+    }
+
+    print(200u8.saturating_add(100));
+    print(5u16.saturating_sub(6));
+}
@@ -0,0 +1,37 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+use std::alloc::Layout;
+use std::mem;
+
+#[repr(C)]
+struct S {
+    a: u8,
+    b: u32,
+    c: u16,
+}
+
+fn main() {
+    // `Layout::new` uses `NullOp::SizeOf` and `NullOp::AlignOf`.
+    let l = Layout::new::<S>();
+    print(l.size());
+    print(l.align());
+
+    let l = Layout::new::<[u16; 5]>();
+    print(l.size());
+    print(l.align());
+
+    let l = Layout::new::<()>();
+    print(l.size());
+    print(l.align());
+
+    // The `_val` forms on sized values.
+    let s = S { a: 0, b: 0, c: 0 };
+    print(mem::size_of_val(&s));
+    print(mem::align_of_val(&s));
+
+    // The `_val` forms on unsized values become `ComputeSize`/`ComputeAlign` with metadata.
+    let l = Layout::for_value::<[u32]>(&[1, 2, 3]);
+    print(l.size());
+    print(l.align());
+}
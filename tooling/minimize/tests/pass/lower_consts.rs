@@ -0,0 +1,31 @@
+//@ compile-flags: --minimize-lower-consts
+extern crate intrinsics;
+use intrinsics::*;
+
+const fn fib(n: u32) -> u32 {
+    let mut a = 0;
+    let mut b = 1;
+    let mut i = 0;
+    while i < n {
+        let t = a + b;
+        a = b;
+        b = t;
+        i += 1;
+    }
+    a
+}
+
+// `SUM` is used first, but its initializer has to run after the one of `FIB`.
+const SUM: u32 = FIB + 1;
+const FIB: u32 = fib(10);
+const PAIR: (u8, i64) = (1, -2);
+const ARRAY: [u16; 3] = [FIB as u16, 2, 3];
+
+fn main() {
+    print(SUM);
+    print(FIB);
+    let p = PAIR;
+    print(p.0);
+    print(p.1);
+    print(ARRAY[0] + ARRAY[2]);
+}
@@ -0,0 +1,31 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+struct Big([u32; 64]);
+
+fn sum(b: Big) -> u32 {
+    let mut s = 0;
+    let mut i = 0;
+    while i < 64 {
+        s += b.0[i];
+        i += 1;
+    }
+    s
+}
+
+fn take_box(b: Box<u32>) -> u32 {
+    *b
+}
+
+fn main() {
+    // A large aggregate, moved and thus passed in-place.
+    let mut big = Big([1; 64]);
+    print(sum(big));
+    // The moved-from place can be re-initialized and used again.
+    big = Big([2; 64]);
+    print(sum(big));
+
+    // A primitive that is moved (as it is not `Copy`).
+    let b = Box::new(7);
+    print(take_box(b));
+}
@@ -0,0 +1,40 @@
+#![feature(offset_of_enum)]
+
+extern crate intrinsics;
+use intrinsics::*;
+
+use std::mem::offset_of;
+
+#[repr(C)]
+struct Inner {
+    a: u16,
+    b: u32,
+}
+
+#[repr(C)]
+struct Pair(u8, u64);
+
+#[repr(C)]
+struct Outer {
+    x: u8,
+    inner: Inner,
+    pair: Pair,
+}
+
+#[repr(u8)]
+#[allow(dead_code)]
+enum E {
+    A(u8, u32),
+    B { inner: Inner },
+}
+
+fn main() {
+    print(offset_of!(Outer, x));
+    print(offset_of!(Outer, inner));
+    print(offset_of!(Outer, inner.b));
+    print(offset_of!(Outer, pair.1));
+
+    print(offset_of!(E, A.0));
+    print(offset_of!(E, A.1));
+    print(offset_of!(E, B.inner.b));
+}
@@ -0,0 +1,20 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+use std::cmp::Ordering;
+
+fn describe(o: Ordering) -> u8 {
+    match o {
+        Ordering::Less => 1,
+        Ordering::Equal => 2,
+        Ordering::Greater => 3,
+    }
+}
+
+fn main() {
+    print(describe(1u32.cmp(&2)));
+    print(describe((-5i64).cmp(&-5)));
+    print(describe('z'.cmp(&'a')));
+    print(3u8.cmp(&1).is_gt());
+    print(3u8.max(7));
+}
@@ -8,6 +8,7 @@ fn main() {
     ptr();
     ptr_mut();
     ptr_compare();
+    ptr_compare_dangling();
     ptr2ptr();
     offset();
     add();
@@ -41,6 +42,18 @@ fn ptr_compare() {
     assert!(xptr < yptr || xptr > yptr);
 }
 
+fn ptr_compare_dangling() {
+    let dangling = {
+        let x = 2;
+        &x as *const i32
+    };
+    let copy = dangling;
+    assert!(dangling == copy);
+    assert!(dangling <= copy);
+    assert!(dangling < dangling.wrapping_add(1));
+    assert!(!dangling.is_null());
+}
+
 fn ptr2ptr() {
     let x = -1;
     let y = &x as *const i32 as *const u32;
@@ -0,0 +1,41 @@
+#![feature(ptr_metadata)]
+
+extern crate intrinsics;
+use intrinsics::*;
+
+use std::ptr;
+
+trait Tr {
+    fn get(&self) -> u32;
+}
+impl Tr for u32 {
+    fn get(&self) -> u32 {
+        *self
+    }
+}
+
+fn main() {
+    let arr = [10_u32, 20, 30, 40];
+    let slice: *const [u32] = &arr[1..];
+
+    // Split a slice pointer and put it back together.
+    let (data, len) = slice.to_raw_parts();
+    print(len);
+    print(ptr::metadata(slice));
+    let rebuilt: *const [u32] = ptr::from_raw_parts(data, len - 1);
+    let rebuilt = unsafe { &*rebuilt };
+    print(rebuilt.len());
+    print(rebuilt[0]);
+    print(rebuilt[1]);
+
+    // Thin pointers have `()` metadata.
+    let thin: *const u32 = ptr::from_raw_parts(data, ());
+    print(unsafe { *thin });
+
+    // Trait objects carry their vtable.
+    let x = 7_u32;
+    let obj: *const dyn Tr = &x;
+    let (data, vtable) = obj.to_raw_parts();
+    let rebuilt: *const dyn Tr = ptr::from_raw_parts(data, vtable);
+    print(unsafe { (*rebuilt).get() });
+}
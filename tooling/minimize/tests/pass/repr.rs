@@ -0,0 +1,62 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+use std::mem;
+
+#[repr(align(16))]
+struct Aligned(u8);
+
+#[repr(C, packed(2))]
+struct Packed2 {
+    a: u8,
+    b: u32,
+    c: u16,
+}
+
+#[repr(C, packed)]
+struct Packed1 {
+    a: u8,
+    b: u64,
+}
+
+#[repr(transparent)]
+struct Transparent(u32);
+
+// A struct containing an over-aligned field.
+struct Outer {
+    a: u8,
+    b: Aligned,
+}
+
+fn main() {
+    print(mem::size_of::<Aligned>());
+    print(mem::align_of::<Aligned>());
+    print(mem::size_of::<Packed2>());
+    print(mem::align_of::<Packed2>());
+    print(mem::size_of::<Packed1>());
+    print(mem::align_of::<Packed1>());
+    print(mem::align_of::<Outer>());
+
+    let x = Aligned(3);
+    print(&x as *const Aligned as usize % 16);
+    print(x.0);
+
+    let mut p = Packed2 { a: 1, b: 2, c: 3 };
+    p.b += 40;
+    let b = p.b;
+    print(p.a);
+    print(b);
+    print(p.c);
+
+    let q = Packed1 { a: 4, b: 5 };
+    let b = q.b;
+    print(q.a);
+    print(b);
+
+    let t = Transparent(6);
+    print(t.0);
+
+    let o = Outer { a: 7, b: Aligned(8) };
+    print(o.a);
+    print(o.b.0);
+}
@@ -0,0 +1,39 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+// Two readers hold the lock at the same time, after the writer is done.
+static mut LOCK: usize = 0;
+static mut DATA: u32 = 0;
+static mut SEEN: [u32; 2] = [0; 2];
+
+extern "C" fn reader(data_ptr: *const ()) {
+    let idx = unsafe { *(data_ptr as *const usize) };
+    unsafe {
+        acquire_shared(LOCK);
+        SEEN[idx] = DATA;
+        release(LOCK);
+    }
+}
+
+fn main() {
+    unsafe {
+        LOCK = create_lock();
+    }
+
+    let fn_ptr = reader as extern "C" fn(*const ());
+    let idx0 = 0usize;
+    let idx1 = 1usize;
+
+    unsafe {
+        acquire(LOCK);
+        let thread0 = spawn(fn_ptr, &idx0 as *const usize as *const ());
+        let thread1 = spawn(fn_ptr, &idx1 as *const usize as *const ());
+        DATA = 42;
+        release(LOCK);
+
+        join(thread0);
+        join(thread1);
+        print(SEEN[0]);
+        print(SEEN[1]);
+    }
+}
@@ -0,0 +1,40 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+use std::mem;
+
+trait Tr {}
+impl Tr for u32 {}
+impl Tr for u64 {}
+impl Tr for [u16; 3] {}
+
+fn size_of_slice(s: &[u8]) -> usize {
+    mem::size_of_val(s)
+}
+
+fn align_of_slice(s: &[u16]) -> usize {
+    mem::align_of_val(s)
+}
+
+fn size_of_dyn(x: &dyn Tr) -> usize {
+    mem::size_of_val(x)
+}
+
+fn align_of_dyn(x: &dyn Tr) -> usize {
+    mem::align_of_val(x)
+}
+
+fn main() {
+    let arr = [1_u8, 2, 3, 4, 5];
+    print(size_of_slice(&arr));
+    print(size_of_slice(&arr[1..3]));
+    print(size_of_slice(&[]));
+    print(align_of_slice(&[0_u16; 3]));
+
+    let s: &str = "minirust";
+    print(mem::size_of_val(s));
+
+    print(size_of_dyn(&0_u32));
+    print(size_of_dyn(&[0_u16; 3]));
+    print(align_of_dyn(&0_u64));
+}
@@ -76,6 +76,10 @@ fn main() {
     assert!(sub_slice.len() == 4);
     assert!(sub_slice[0] == 50);
 
+    let full_slice = &x[..];
+    assert!(full_slice.len() == 5);
+    assert!(full_slice[4] == 10);
+
     // Check equality
     assert!(&slice[1..4] == &[-40, 30, -20]);
     assert!(slice[1..4] == [-40, 30, -20]);
@@ -0,0 +1,31 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+fn ends(s: &[u32]) -> u32 {
+    match s {
+        [] => 0,
+        [x] => *x,
+        [first, .., last] => first + last,
+    }
+}
+
+fn middle_len(s: &[u32]) -> usize {
+    match s {
+        [_, middle @ .., _] => middle.len(),
+        _ => 0,
+    }
+}
+
+fn main() {
+    let arr = [1u32, 2, 3, 4, 5];
+    let [first, rest @ .., last] = arr;
+    print(first);
+    print(rest[0]);
+    print(rest[2]);
+    print(last);
+
+    print(ends(&arr));
+    print(ends(&arr[..1]));
+    print(ends(&[]));
+    print(middle_len(&arr));
+}
@@ -0,0 +1,26 @@
+//@ compile-flags: --minimize-stacked-borrows
+
+// Two-phase borrows: the `&mut s` for the call is created before the argument reads `s`.
+
+struct Stack {
+    len: usize,
+    data: [usize; 4],
+}
+
+impl Stack {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn push(&mut self, x: usize) {
+        self.data[self.len] = x;
+        self.len += 1;
+    }
+}
+
+fn main() {
+    let mut s = Stack { len: 0, data: [0; 4] };
+    s.push(s.len());
+    s.push(s.len());
+    assert!(s.data[1] == 1);
+}
@@ -0,0 +1,34 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+use std::cell::UnsafeCell;
+
+/// A static with interior mutability.
+struct Counter(UnsafeCell<u32>);
+
+unsafe impl Sync for Counter {}
+
+static COUNTER: Counter = Counter(UnsafeCell::new(0));
+
+static mut FLAG: u32 = 0;
+
+extern "C" fn thread(_: *const ()) {
+    unsafe {
+        atomic_fetch_add(COUNTER.0.get(), 1, Ordering::SeqCst);
+        atomic_store(&raw mut FLAG, 1, Ordering::Release);
+    }
+}
+
+fn main() {
+    let data_ptr = &() as *const ();
+    let fn_ptr = thread as extern "C" fn(*const ());
+    let thread_id = spawn(fn_ptr, data_ptr);
+    unsafe {
+        atomic_fetch_add(COUNTER.0.get(), 1, Ordering::SeqCst);
+    }
+    join(thread_id);
+    unsafe {
+        print(*COUNTER.0.get());
+        print(FLAG);
+    }
+}
@@ -0,0 +1,23 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+use std::mem;
+
+fn main() {
+    let mut x = 1u32;
+    let mut y = 2u32;
+    mem::swap(&mut x, &mut y);
+    print(x);
+    print(y);
+
+    let mut a = (3u8, 4u64);
+    let mut b = (5u8, 6u64);
+    mem::swap(&mut a, &mut b);
+    print(a.0);
+    print(b.1);
+
+    let mut z = 7i32;
+    let old = mem::replace(&mut z, 8);
+    print(old);
+    print(z);
+}
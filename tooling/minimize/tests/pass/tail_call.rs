@@ -0,0 +1,26 @@
+#![feature(explicit_tail_calls)]
+#![allow(incomplete_features)]
+extern crate intrinsics;
+use intrinsics::*;
+
+// Deeper than the stack limit, which only works because tail calls do not grow the stack.
+fn count(n: u32, acc: u32) -> u32 {
+    if n == 0 { acc } else { become count(n - 1, acc + 2) }
+}
+
+fn is_even(n: u32) -> bool {
+    if n == 0 { true } else { become is_odd(n - 1) }
+}
+
+fn is_odd(n: u32) -> bool {
+    if n == 0 { false } else { become is_even(n - 1) }
+}
+
+fn main() {
+    print(count(10000, 0));
+    if is_even(5001) {
+        print(0);
+    } else {
+        print(1);
+    }
+}
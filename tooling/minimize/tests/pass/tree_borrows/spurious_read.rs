@@ -21,13 +21,13 @@ extern "C" fn thread(thread_data_ptr: *const ()) {
     let ThreadData { counter_ptr, data_ptr }  = unsafe { *(thread_data_ptr as *const ThreadData) };
 
     fn foo(y: &mut u8, counter_ptr: *mut u32) -> *mut u8 {
-        assert!(unsafe { atomic_fetch_add(counter_ptr, 1) } == 1); // 1 -> 2
-        while unsafe { atomic_load(counter_ptr) == 2 } {}
+        assert!(unsafe { atomic_fetch_add(counter_ptr, 1, Ordering::SeqCst) } == 1); // 1 -> 2
+        while unsafe { atomic_load(counter_ptr, Ordering::SeqCst) == 2 } {}
         let y = y as *mut u8;
         y
     }
 
-    while unsafe { atomic_load(counter_ptr) == 0 } {}
+    while unsafe { atomic_load(counter_ptr, Ordering::SeqCst) == 0 } {}
     let y = foo(unsafe { &mut *data_ptr }, counter_ptr);
     unsafe { *y = 2 };
 }
@@ -70,8 +70,8 @@ fn main() {
     // - remove `x`'s protector
     // Most of the complexity here is synchronization.
     fn foo(x: &mut u8, counter_ptr: *mut u32) -> *mut u8 {
-        assert!(unsafe { atomic_fetch_add(counter_ptr, 1) } == 0); // 0 -> 1
-        while unsafe { atomic_load(counter_ptr) == 1 } {}
+        assert!(unsafe { atomic_fetch_add(counter_ptr, 1, Ordering::SeqCst) } == 0); // 0 -> 1
+        while unsafe { atomic_load(counter_ptr, Ordering::SeqCst) == 1 } {}
         // This is ensuring taht we have UB *without* the spurious read,
         // so we don't read here.
         let x = x as *mut u8;
@@ -79,7 +79,7 @@ fn main() {
         x
     }
     foo(unsafe { &mut *data_ptr }, counter_ptr);
-    assert!(unsafe { atomic_fetch_add(counter_ptr, 1) } == 2); // 2 -> 3
+    assert!(unsafe { atomic_fetch_add(counter_ptr, 1, Ordering::SeqCst) } == 2); // 2 -> 3
     
     join(thread_id);
 }
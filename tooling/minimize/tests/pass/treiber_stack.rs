@@ -0,0 +1,57 @@
+extern crate intrinsics;
+use intrinsics::*;
+
+struct Node {
+    next: *mut Node,
+    value: u32,
+}
+
+// The bottom of the stack; using a sentinel avoids needing null pointers.
+static mut SENTINEL: Node = Node { next: &raw mut SENTINEL, value: 0 };
+static mut HEAD: *mut Node = &raw mut SENTINEL;
+
+static mut A: Node = Node { next: &raw mut SENTINEL, value: 1 };
+static mut B: Node = Node { next: &raw mut SENTINEL, value: 2 };
+
+extern "C" fn push(node: *const ()) {
+    let node = node as *mut Node;
+    unsafe {
+        let mut old = atomic_load_ptr(&raw mut HEAD, Ordering::Relaxed);
+        loop {
+            (*node).next = old;
+            let seen = compare_exchange_ptr(&raw mut HEAD, old, node, Ordering::Release, Ordering::Relaxed);
+            if seen == old {
+                break;
+            }
+            old = seen;
+        }
+    }
+}
+
+fn main() {
+    let fn_ptr = push as extern "C" fn(*const ());
+    let a = spawn(fn_ptr, &raw const A as *const ());
+    let b = spawn(fn_ptr, &raw const B as *const ());
+    join(a);
+    join(b);
+
+    // Walk the stack through the pointers that were published by the compare-exchanges.
+    let mut sum = 0;
+    let mut len = 0;
+    unsafe {
+        let mut cur = atomic_load_ptr(&raw mut HEAD, Ordering::Acquire);
+        while cur != &raw mut SENTINEL {
+            sum += (*cur).value;
+            len += 1;
+            cur = (*cur).next;
+        }
+    }
+    print(len);
+    print(sum);
+
+    // Pop the top node again with a swap.
+    unsafe {
+        let top = atomic_swap_ptr(&raw mut HEAD, &raw mut SENTINEL, Ordering::AcqRel);
+        print((*top).value + (*(*top).next).value);
+    }
+}
@@ -0,0 +1,18 @@
+#![allow(internal_features)]
+#![feature(core_intrinsics)]
+
+extern crate intrinsics;
+use intrinsics::*;
+
+fn get(x: Option<u32>) -> u32 {
+    match x {
+        Some(v) => v,
+        None => unsafe { std::hint::unreachable_unchecked() },
+    }
+}
+
+fn main() {
+    let x = 3_u8;
+    unsafe { std::intrinsics::assume(x == 3) };
+    print(get(Some(42)));
+}
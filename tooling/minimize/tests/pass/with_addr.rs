@@ -0,0 +1,22 @@
+#![feature(strict_provenance)]
+
+extern crate intrinsics;
+use intrinsics::*;
+
+fn main() {
+    let arr = [1u32, 2, 3];
+    let ptr = arr.as_ptr();
+    // We use `as usize` to get the address, since `addr` warns about losing provenance.
+    let addr = ptr as usize;
+
+    let second = ptr.with_addr(addr + 4);
+    print(unsafe { *second });
+
+    let third = ptr.map_addr(|a| a + 8);
+    print(unsafe { *third });
+
+    // Going through an out-of-bounds address is fine.
+    let far = ptr.with_addr(addr + 100);
+    let back = far.with_addr(addr);
+    print(unsafe { *back });
+}
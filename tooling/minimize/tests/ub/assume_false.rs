@@ -0,0 +1,11 @@
+#![allow(internal_features)]
+#![feature(core_intrinsics)]
+
+#[inline(never)]
+fn check(x: u8) {
+    unsafe { std::intrinsics::assume(x == 4) }
+}
+
+fn main() {
+    check(3);
+}
@@ -0,0 +1,9 @@
+use std::ptr;
+
+fn main() {
+    let mut arr = [1u32, 2, 3, 4];
+    unsafe {
+        // `copy_nonoverlapping` requires the ranges to be disjoint.
+        ptr::copy_nonoverlapping(arr.as_ptr(), arr.as_mut_ptr().add(1), 3);
+    }
+}
@@ -1,6 +1,6 @@
 fn foo(_x: *const dyn std::fmt::Debug) {}
 
 fn main() {
-    // cannot use `std::mem::zeroed`: unsupported Rust intrinsic `write_bytes`.
+    // cannot use `std::mem::zeroed`: it panics since a null vtable is invalid.
     foo(unsafe { core::mem::transmute::<[usize; 2], *const dyn std::fmt::Debug>([0_usize; 2]) });
 }
@@ -1,3 +1,4 @@
+//@ compile-flags: --minimize-warn-provenance-loss
 use std::mem::transmute;
 
 fn main() { unsafe {
@@ -0,0 +1,8 @@
+fn main() {
+    let x = 0u32;
+    let y = 0u32;
+    unsafe {
+        // The pointers must point into the same allocation.
+        let _diff = (&x as *const u32).offset_from(&y);
+    }
+}
@@ -0,0 +1,6 @@
+//@ compile-flags: --minimize-show-span
+
+fn main() {
+    let ptr = std::ptr::null::<i32>();
+    let _val = unsafe { *ptr };
+}
@@ -0,0 +1,14 @@
+//@ compile-flags: --minimize-stacked-borrows
+
+// Taken from Miri's `fail/stacked_borrows/pass_invalid_mut.rs`.
+// Tree Borrows accepts this program, see `tests/pass/tree_borrows/sb_fails.rs`.
+
+fn foo(_: &mut i32) {}
+
+fn main() {
+    let x = &mut 42;
+    let xraw = x as *mut _;
+    let xref = unsafe { &mut *xraw };
+    let _val = unsafe { *xraw }; // This invalidates xref...
+    foo(xref); // ...which then cannot be reborrowed here.
+}
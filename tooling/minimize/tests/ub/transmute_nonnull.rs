@@ -0,0 +1,6 @@
+use std::ptr::NonNull;
+use std::mem::transmute;
+
+fn main() { unsafe {
+    let _x = transmute::<*mut u8, NonNull<u8>>(std::ptr::null_mut());
+} }
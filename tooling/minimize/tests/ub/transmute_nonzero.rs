@@ -0,0 +1,6 @@
+use std::num::NonZeroU32;
+use std::mem::transmute;
+
+fn main() { unsafe {
+    let _x = transmute::<u32, NonZeroU32>(0);
+} }
@@ -22,14 +22,14 @@ extern "C" fn thread(thread_data_ptr: *const ()) {
     let ThreadData { counter_ptr, data_ptr }  = unsafe { *(thread_data_ptr as *const ThreadData) };
 
     fn inner(y: &mut u8, counter_ptr: *mut u32) -> *mut u8 {
-        assert!(unsafe { atomic_fetch_add(counter_ptr, 1) } == 1); // 1 -> 2
+        assert!(unsafe { atomic_fetch_add(counter_ptr, 1, Ordering::SeqCst) } == 1); // 1 -> 2
         let y = y as *mut u8;
-        while unsafe { atomic_load(counter_ptr) == 2 } {}
+        while unsafe { atomic_load(counter_ptr, Ordering::SeqCst) == 2 } {}
         unsafe { *y = 2 };// UB! The implicit read during releasing the x makes y conflicted.
         y
     }
 
-    while unsafe { atomic_load(counter_ptr) == 0 } {}
+    while unsafe { atomic_load(counter_ptr, Ordering::SeqCst) == 0 } {}
     let _ = inner(unsafe { &mut *data_ptr }, counter_ptr);
 }
 
@@ -71,15 +71,15 @@ fn main() {
     // - remove `x`'s protector
     // Most of the complexity here is synchronization.
     fn inner(x: &mut u8, counter_ptr: *mut u32) -> *mut u8 {
-        assert!(unsafe { atomic_fetch_add(counter_ptr, 1) } == 0); // 0 -> 1
-        while unsafe { atomic_load(counter_ptr) == 1 } {}
+        assert!(unsafe { atomic_fetch_add(counter_ptr, 1, Ordering::SeqCst) } == 0); // 0 -> 1
+        while unsafe { atomic_load(counter_ptr, Ordering::SeqCst) == 1 } {}
         // This is ensuring taht we have UB *without* the spurious read,
         // so we don't read here.
         let x = x as *mut u8;
         x
     }
     inner(unsafe { &mut *data_ptr }, counter_ptr);
-    assert!(unsafe { atomic_fetch_add(counter_ptr, 1) } == 2); // 2 -> 3
+    assert!(unsafe { atomic_fetch_add(counter_ptr, 1, Ordering::SeqCst) } == 2); // 2 -> 3
     
     join(thread_id);
 }
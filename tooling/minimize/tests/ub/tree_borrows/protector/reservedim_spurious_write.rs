@@ -29,18 +29,18 @@ extern "C" fn thread(thread_data_ptr: *const ()) {
     let ThreadData { counter_ptr, data_ptr }  = unsafe { *(thread_data_ptr as *const ThreadData) };
 
     fn inner(y: &mut Cell<()>, counter_ptr: *mut u32) -> *mut u8 {
-        assert!(unsafe { atomic_fetch_add(counter_ptr, 1) } == 1); // 1 -> 2
-        while unsafe { atomic_load(counter_ptr) == 2 } {}
+        assert!(unsafe { atomic_fetch_add(counter_ptr, 1, Ordering::SeqCst) } == 1); // 1 -> 2
+        while unsafe { atomic_load(counter_ptr, Ordering::SeqCst) == 2 } {}
         // `y` is not retagged for any bytes, so the pointer we return
         // has its permission lazily initialized.
         y as *mut Cell<()> as *mut u8
     }
 
-    while unsafe { atomic_load(counter_ptr) == 0 } {}
+    while unsafe { atomic_load(counter_ptr, Ordering::SeqCst) == 0 } {}
     let y_zst = unsafe { &mut *(data_ptr as *mut Cell<()>) };
     let y = inner(y_zst, counter_ptr);
-    assert!(unsafe { atomic_fetch_add(counter_ptr, 1) } == 3); // 3 -> 4
-    while unsafe { atomic_load(counter_ptr) == 4 } {}
+    assert!(unsafe { atomic_fetch_add(counter_ptr, 1, Ordering::SeqCst) } == 3); // 3 -> 4
+    while unsafe { atomic_load(counter_ptr, Ordering::SeqCst) == 4 } {}
     unsafe { *y = 13 }
 }
 
@@ -70,16 +70,16 @@ fn main() {
     // Then do a spurious write. Finally exit the function after the other thread.
     fn inner(x: &mut u8, counter_ptr: *mut u32) {
         *x = 42; // activate immediately
-        assert!(unsafe { atomic_fetch_add(counter_ptr, 1) } == 0);
-        while unsafe { atomic_load(counter_ptr) == 1 } {}
+        assert!(unsafe { atomic_fetch_add(counter_ptr, 1, Ordering::SeqCst) } == 0);
+        while unsafe { atomic_load(counter_ptr, Ordering::SeqCst) == 1 } {}
         // A spurious write should be valid here because `x` is
         // `Active` and protected.
         if cfg!(with) { *x = 64 };
-        assert!(unsafe { atomic_fetch_add(counter_ptr, 1) } == 2); // 2 -> 3
-        while unsafe { atomic_load(counter_ptr) == 3 } {}
+        assert!(unsafe { atomic_fetch_add(counter_ptr, 1, Ordering::SeqCst) } == 2); // 2 -> 3
+        while unsafe { atomic_load(counter_ptr, Ordering::SeqCst) == 3 } {}
     }
     inner(unsafe { &mut *data_ptr }, counter_ptr);
-    assert!(unsafe { atomic_fetch_add(counter_ptr, 1) } == 4); // 4 -> 5
+    assert!(unsafe { atomic_fetch_add(counter_ptr, 1, Ordering::SeqCst) } == 4); // 4 -> 5
 
     join(thread_id);
 }
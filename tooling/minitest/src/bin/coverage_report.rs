@@ -0,0 +1,34 @@
+//! Lists the spec rules that no test exercised.
+//!
+//! Usage: `coverage_report DIR [SPEC_DIR]`
+//!
+//! `DIR` is the directory the test suite recorded its coverage in, i.e. the value of
+//! `MINIRUST_COVERAGE` while running it (`./mini coverage` does all of that).
+//! `SPEC_DIR` defaults to the `spec` directory of this repository.
+
+use std::path::PathBuf;
+
+use miniutil::coverage::*;
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let Some(dir) = args.next() else {
+        eprintln!("Usage: coverage_report DIR [SPEC_DIR]");
+        std::process::exit(2)
+    };
+    let spec_dir = args
+        .next()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/../../spec")));
+
+    let coverage = Coverage::read(dir.as_ref()).expect("failed to read the recorded coverage");
+    let rules = spec_rules(&spec_dir).expect("failed to read the spec");
+    let uncovered: Vec<&SpecRule> =
+        rules.iter().filter(|spec_rule| !coverage.covers(&spec_rule.rule)).collect();
+
+    for spec_rule in &uncovered {
+        let file = spec_rule.file.strip_prefix(&spec_dir).unwrap_or(&spec_rule.file);
+        println!("{}:{}: {}", file.display(), spec_rule.line, spec_rule.rule);
+    }
+    println!("{} of {} rules covered", rules.len() - uncovered.len(), rules.len());
+}
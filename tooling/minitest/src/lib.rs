@@ -1,9 +1,24 @@
 #![cfg(test)]
+#![feature(never_type)]
 
 pub use miniutil::BasicMem;
+pub use miniutil::StackedBorrowMem;
+pub use miniutil::TreeBorrowMem;
 pub use miniutil::build::*;
+pub use miniutil::coverage::*;
+pub use miniutil::debug::*;
+pub use miniutil::env::*;
 pub use miniutil::fmt::*;
+pub use miniutil::fuzz::*;
+pub use miniutil::interp::*;
+pub use miniutil::oracle::*;
+pub use miniutil::reduce::*;
+pub use miniutil::repr::*;
 pub use miniutil::run::*;
+pub use miniutil::schedule::*;
+pub use miniutil::text::*;
+pub use miniutil::validity::*;
+pub use miniutil::wf::*;
 
 pub use minirust_rs::libspecr::hidden::*;
 pub use minirust_rs::libspecr::prelude::*;
@@ -20,38 +35,165 @@ pub use std::string::String;
 mod tests;
 
 #[track_caller]
-pub fn assert_stop<M: Memory>(prog: Program) {
+pub fn assert_stop<M: MemoryTooling>(prog: Program) {
     assert_eq!(run_program::<M>(prog), TerminationInfo::MachineStop);
 }
 
+/// Asserts that the program stops normally within `fuel` steps.
 #[track_caller]
-pub fn assert_stop_always<M: Memory>(prog: Program, attempts: usize) {
+pub fn assert_stop_within<M: MemoryTooling>(prog: Program, fuel: u64) {
+    let mut interp = Interpreter::<M>::new(prog).unwrap();
+    match interp.run(fuel) {
+        Some(info) => assert_eq!(info, TerminationInfo::MachineStop),
+        None => panic!("program did not stop within {fuel} steps"),
+    }
+}
+
+#[track_caller]
+pub fn assert_stop_always<M: MemoryTooling>(prog: Program, attempts: usize) {
     for _ in 0..attempts {
         assert_eq!(run_program::<M>(prog), TerminationInfo::MachineStop);
     }
 }
 
+/// Runs the program and asserts how it terminated, and everything it printed to stdout and stderr.
+/// Use `abort_info` and `ub_info` to build the expected `TerminationInfo` from a message.
 #[track_caller]
-pub fn assert_abort<M: Memory>(prog: Program, msg: &str) {
-    let msg = prelude::String::from_internal(msg.to_string());
-    assert_eq!(run_program::<M>(prog), TerminationInfo::Abort(msg));
+pub fn assert_output<M: MemoryTooling>(
+    prog: Program,
+    info: TerminationInfo,
+    stdout: &[&str],
+    stderr: &[&str],
+) {
+    assert_output_with::<M>(prog, info, stdout, stderr, |_machine| {});
 }
 
+/// Like `assert_output`, but also passes the final machine to `inspect` for custom checks.
+/// Ill-formed programs never create a machine, so `inspect` is not called for them.
 #[track_caller]
-pub fn assert_ub<M: Memory>(prog: Program, msg: &str) {
-    assert_eq!(
-        run_program::<M>(prog),
-        TerminationInfo::Ub(minirust_rs::prelude::String::from_internal(msg.to_string()))
-    );
+pub fn assert_output_with<M: MemoryTooling>(
+    prog: Program,
+    info: TerminationInfo,
+    stdout: &[&str],
+    stderr: &[&str],
+    inspect: impl FnOnce(&Machine<M>),
+) {
+    // The GC frees everything the machine cannot reach, including the strings in `info`,
+    // so we only keep its printed form while the program runs.
+    let info = format!("{info:?}");
+    let (output, _) = run_program_inspect::<M, ()>(prog, inspect);
+    assert_eq!(format!("{:?}", output.info), info);
+    assert_eq!(output.stdout, stdout, "unexpected stdout");
+    assert_eq!(output.stderr, stderr, "unexpected stderr");
+}
+
+/// Runs function `f` of `prog` with the given arguments (instead of the start function)
+/// and asserts that it returns the integer or Boolean `expected`.
+#[track_caller]
+pub fn assert_returns<M: MemoryTooling>(
+    prog: Program,
+    f: FnName,
+    args: &[ValueExpr],
+    expected: impl std::fmt::Display,
+) {
+    let out = get_stdout::<M>(program_calling(prog, f, args)).unwrap();
+    assert_eq!(out, [expected.to_string()]);
 }
 
+/// Asserts that the program aborts with the given message, without printing anything.
 #[track_caller]
-pub fn assert_ub_eventually<M: Memory>(prog: Program, attempts: usize, msg: &str) {
+pub fn assert_abort<M: MemoryTooling>(prog: Program, msg: &str) {
+    assert_output::<M>(prog, abort_info(msg), &[], &[]);
+}
+
+/// Asserts that the program deadlocks, without printing anything.
+#[track_caller]
+pub fn assert_deadlock<M: MemoryTooling>(prog: Program) {
+    assert_output::<M>(prog, TerminationInfo::Deadlock, &[], &[]);
+}
+
+/// Asserts that the program leaks memory, without printing anything.
+#[track_caller]
+pub fn assert_memory_leak<M: MemoryTooling>(prog: Program) {
+    assert_output::<M>(prog, TerminationInfo::MemoryLeak, &[], &[]);
+}
+
+/// The termination of a program that aborted with the given message.
+pub fn abort_info(msg: &str) -> TerminationInfo {
+    TerminationInfo::Abort(prelude::String::from_internal(msg.to_string()))
+}
+
+/// The termination of a program that has UB with the given message, not caused by a memory access.
+pub fn ub_info(msg: &str) -> TerminationInfo {
+    TerminationInfo::Ub(prelude::String::from_internal(msg.to_string()), None)
+}
+
+/// Asserts that the program runs out of stack, when every thread may have at most `max_stack_frames` frames.
+#[track_caller]
+pub fn assert_stack_overflow<M: MemoryTooling>(prog: Program, max_stack_frames: Option<u64>) {
+    let config = MachineConfig { max_stack_frames, ..MachineConfig::DEFAULT };
+    let info = run_program_with_config::<M>(prog, config).info;
+    // Only create the expected message after the run, so that the GC cannot free it.
+    let msg = prelude::String::from_internal("stack exhausted".to_string());
+    assert_eq!(info, TerminationInfo::ResourceExhaustion(msg));
+}
+
+#[track_caller]
+pub fn assert_ub<M: MemoryTooling>(prog: Program, msg: &str) {
+    match run_program::<M>(prog) {
+        TerminationInfo::Ub(res, _) => assert_eq!(res.get_internal(), msg),
+        info => panic!("expected UB, but program terminated with {info:?}"),
+    }
+}
+
+/// Asserts that the program has UB with the given message, and that the UB is raised
+/// by the statement or terminator at `location`.
+#[track_caller]
+pub fn assert_ub_matches<M: MemoryTooling>(prog: Program, msg: &str, location: Location) {
+    let (info, ub_location) = run_program_with_location::<M>(prog);
+    let TerminationInfo::Ub(res, _) = info else {
+        panic!("expected UB, but program terminated with {info:?}")
+    };
+    assert_eq!((res.get_internal().as_str(), ub_location), (msg, Some(location)));
+}
+
+/// Asserts that the program has UB, and returns the report so that tests can check its fields.
+#[track_caller]
+pub fn ub_report<M: MemoryTooling>(prog: Program) -> UbReport {
+    match run_program_ub_report::<M>(prog) {
+        Ok(report) => report,
+        Err(info) => panic!("expected UB, but program terminated with {info:?}"),
+    }
+}
+
+/// Asserts that the program is fine under the basic memory model,
+/// but has UB with the given message under Tree Borrows.
+#[track_caller]
+pub fn assert_ub_tree_borrows(prog: Program, msg: &str) {
+    assert_stop::<BasicMem>(prog);
+    assert_ub::<TreeBorrowMem>(prog, msg);
+}
+
+/// Asserts that the program is fine under the basic memory model,
+/// but has UB with the given message under Stacked Borrows.
+#[track_caller]
+pub fn assert_ub_stacked_borrows(prog: Program, msg: &str) {
+    assert_stop::<BasicMem>(prog);
+    assert_ub::<StackedBorrowMem>(prog, msg);
+}
+
+/// Casts a pointer to a `u32` to `*mut u32`, for the aliasing model tests.
+pub fn raw_mut(v: ValueExpr) -> ValueExpr {
+    ptr_to_ptr(v, <*mut u32>::get_type())
+}
+
+#[track_caller]
+pub fn assert_ub_eventually<M: MemoryTooling>(prog: Program, attempts: usize, msg: &str) {
     let msg = minirust_rs::prelude::String::from_internal(msg.to_string());
     for _ in 0..attempts {
         match run_program::<M>(prog) {
             TerminationInfo::MachineStop => continue,
-            TerminationInfo::Ub(res) if res == msg => {
+            TerminationInfo::Ub(res, _) if res == msg => {
                 // Got the expected result.
                 return;
             }
@@ -65,7 +207,7 @@ pub fn assert_ub_eventually<M: Memory>(prog: Program, attempts: usize, msg: &str
 
 /// Create program that assigns `expr` to local of type T and checks if it causes UB.
 #[track_caller]
-pub fn assert_ub_expr<T: TypeConv, M: Memory>(expr: ValueExpr, msg: &str) {
+pub fn assert_ub_expr<T: TypeConv, M: MemoryTooling>(expr: ValueExpr, msg: &str) {
     let mut p = ProgramBuilder::new();
 
     let mut f = p.declare_function();
@@ -81,33 +223,47 @@ pub fn assert_ub_expr<T: TypeConv, M: Memory>(expr: ValueExpr, msg: &str) {
 }
 
 #[track_caller]
-pub fn assert_ill_formed<M: Memory>(prog: Program, msg: &str) {
+pub fn assert_ill_formed<M: MemoryTooling>(prog: Program, msg: &str) {
     let TerminationInfo::IllFormed(info) = run_program::<M>(prog) else {
         panic!("program is not ill formed!")
     };
-    assert_eq!(info.get_internal(), msg, "program is ill-formed with a different error message");
-}
-
-#[track_caller]
-pub fn assert_deadlock<M: Memory>(prog: Program) {
-    assert_eq!(run_program::<M>(prog), TerminationInfo::Deadlock);
+    assert_eq!(
+        info.msg().get_internal(),
+        msg,
+        "program is ill-formed with a different error message"
+    );
 }
 
+/// Checks that the program is ill-formed, and that one of its violations is of the given kind,
+/// like `WfKind::StatementAssign`. Unlike `assert_ill_formed`, this also finds violations other than the first.
 #[track_caller]
-pub fn assert_memory_leak<M: Memory>(prog: Program) {
-    assert_eq!(run_program::<M>(prog), TerminationInfo::MemoryLeak);
+pub fn assert_ill_formed_because<M: MemoryTooling>(prog: Program, kind: WfKind) {
+    let errors = check_program_wf::<M::T>(prog);
+    let TerminationInfo::IllFormed(info) = run_program::<M>(prog) else {
+        panic!("program is not ill formed!")
+    };
+    // The machine reports the first violation.
+    assert_eq!(errors.first().map(WfError::msg), Some(info.msg().get_internal().to_string()));
+    if !errors.iter().any(|error| error.kind == kind) {
+        let errors: Vec<String> = errors.iter().map(WfError::to_string).collect();
+        panic!(
+            "program is not ill-formed because of `{}`, but because of:\n{}",
+            kind.name(),
+            errors.join("\n")
+        );
+    }
 }
 
 /// Run the program multiple times. Checks if we get a data race in some execution
 /// This automatically fails if the program does not terminate correctly if the data race did not occur.
 #[track_caller]
-pub fn has_data_race<M: Memory>(prog: Program) -> bool {
+pub fn has_data_race<M: MemoryTooling>(prog: Program) -> bool {
     let data_race_string = minirust_rs::prelude::String::from_internal("Data race".to_string());
 
     for _ in 0..32 {
         match run_program::<M>(prog) {
             TerminationInfo::MachineStop => {}
-            TerminationInfo::Ub(ub) if ub == data_race_string => {
+            TerminationInfo::Ub(ub, _) if ub == data_race_string => {
                 return true;
             }
             termination_info => {
@@ -118,3 +274,66 @@ pub fn has_data_race<M: Memory>(prog: Program) -> bool {
 
     false
 }
+
+/// The preemption bound and step limit used when exploring schedules in tests.
+const SCHEDULE_MAX_PREEMPTIONS: usize = 2;
+const SCHEDULE_MAX_STEPS: u64 = 10_000;
+
+/// Explores the schedules of the program (up to a small number of preemptions) and asserts that
+/// at least one of them has UB with the given message. All other schedules must stop normally
+/// within `SCHEDULE_MAX_STEPS` steps.
+#[track_caller]
+pub fn assert_ub_under_any_schedule<M: MemoryTooling>(prog: Program, msg: &str) {
+    let found =
+        explore_schedules::<M>(prog, SCHEDULE_MAX_PREEMPTIONS, SCHEDULE_MAX_STEPS, |output| {
+            match output.map(|output| output.info) {
+                Some(TerminationInfo::Ub(res, _)) if res.get_internal() == msg => true,
+                Some(TerminationInfo::MachineStop) => false,
+                None => panic!("a schedule did not terminate within {SCHEDULE_MAX_STEPS} steps"),
+                Some(termination_info) => {
+                    panic!(
+                        "unexpected outcome in `assert_ub_under_any_schedule`: {:?}",
+                        termination_info
+                    );
+                }
+            }
+        });
+    assert!(found, "no schedule has the expected UB");
+}
+
+/// Like `assert_ub_under_any_schedule`, but for a deadlock.
+#[track_caller]
+pub fn assert_deadlock_under_any_schedule<M: MemoryTooling>(prog: Program) {
+    let found =
+        explore_schedules::<M>(prog, SCHEDULE_MAX_PREEMPTIONS, SCHEDULE_MAX_STEPS, |output| {
+            match output.map(|output| output.info) {
+                Some(TerminationInfo::Deadlock) => true,
+                Some(TerminationInfo::MachineStop) => false,
+                None => panic!("a schedule did not terminate within {SCHEDULE_MAX_STEPS} steps"),
+                Some(termination_info) => {
+                    panic!(
+                        "unexpected outcome in `assert_deadlock_under_any_schedule`: {:?}",
+                        termination_info
+                    );
+                }
+            }
+        });
+    assert!(found, "no schedule deadlocks");
+}
+
+/// Explores the schedules of the program (up to a small number of preemptions) and asserts that
+/// all of them stop normally.
+#[track_caller]
+pub fn assert_stop_under_every_schedule<M: MemoryTooling>(prog: Program) {
+    explore_schedules::<M>(prog, SCHEDULE_MAX_PREEMPTIONS, SCHEDULE_MAX_STEPS, |output| {
+        match output.map(|output| output.info) {
+            Some(TerminationInfo::MachineStop) => false,
+            termination_info => {
+                panic!(
+                    "unexpected outcome in `assert_stop_under_every_schedule`: {:?}",
+                    termination_info
+                );
+            }
+        }
+    });
+}
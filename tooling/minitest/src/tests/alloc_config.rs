@@ -0,0 +1,191 @@
+//! Configuring how allocations are placed, and allocation failure.
+
+use crate::*;
+
+fn config(alloc: AllocConfig) -> MachineConfig {
+    MachineConfig { alloc, ..MachineConfig::DEFAULT }
+}
+
+fn strategy(addr_strategy: AddrStrategy) -> MachineConfig {
+    config(AllocConfig { addr_strategy, ..AllocConfig::DEFAULT })
+}
+
+fn memory_limit(bytes: u64) -> MachineConfig {
+    config(AllocConfig {
+        memory_limit: Some(Size::from_bytes_const(bytes)),
+        ..AllocConfig::DEFAULT
+    })
+}
+
+/// Runs the program with the given config, asserting that it stops normally, and returns its stdout.
+#[track_caller]
+fn stdout_with_config<M: MemoryTooling>(prog: Program, config: MachineConfig) -> Vec<String> {
+    let output = run_program_with_config::<M>(prog, config);
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    output.stdout
+}
+
+/// Prints the address of `x`, then the address of `y`, then the address of `x` after it was re-allocated.
+fn addresses_program() -> Program {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let y = f.declare_local::<u32>();
+    f.storage_live(x);
+    f.storage_live(y);
+    f.print(transmute(addr_of(x, <*const u32>::get_type()), <usize>::get_type()));
+    f.print(transmute(addr_of(y, <*const u32>::get_type()), <usize>::get_type()));
+    f.storage_dead(x);
+    f.storage_live(x);
+    f.print(transmute(addr_of(x, <*const u32>::get_type()), <usize>::get_type()));
+    f.exit();
+    let f = p.finish_function(f);
+
+    p.finish_program(f)
+}
+
+fn addresses(out: &[String]) -> [u64; 3] {
+    [0, 1, 2].map(|i| out[i].parse().unwrap())
+}
+
+/// Allocations are packed as tightly as possible, and addresses are reused right away.
+#[test]
+fn adversarial_layout() {
+    let out =
+        stdout_with_config::<BasicMem>(addresses_program(), strategy(AddrStrategy::Adversarial));
+    let [x, y, x_again] = addresses(&out);
+    assert_eq!(y, x + 4);
+    assert_eq!(x_again, x);
+}
+
+/// Allocations are placed one after the other, and addresses are not reused.
+#[test]
+fn sequential_layout() {
+    let out =
+        stdout_with_config::<BasicMem>(addresses_program(), strategy(AddrStrategy::Sequential));
+    let [x, y, x_again] = addresses(&out);
+    assert!(x < y);
+    assert!(y < x_again);
+    assert_eq!(
+        out,
+        stdout_with_config::<BasicMem>(addresses_program(), strategy(AddrStrategy::Sequential))
+    );
+}
+
+#[test]
+fn seeded_layout_is_reproducible() {
+    let run = |seed: i32| {
+        stdout_with_config::<BasicMem>(
+            addresses_program(),
+            strategy(AddrStrategy::Seeded(Int::from(seed))),
+        )
+    };
+    assert_eq!(run(42), run(42));
+    assert_ne!(run(42), run(43));
+}
+
+#[test]
+fn strategies_work_with_all_memory_models() {
+    let out = stdout_with_config::<TreeBorrowMem>(
+        addresses_program(),
+        strategy(AddrStrategy::Adversarial),
+    );
+    let [x, y, _] = addresses(&out);
+    assert_eq!(y, x + 4);
+    let out = stdout_with_config::<StackedBorrowMem>(
+        addresses_program(),
+        strategy(AddrStrategy::Adversarial),
+    );
+    let [x, y, _] = addresses(&out);
+    assert_eq!(y, x + 4);
+}
+
+/// Allocates `size` bytes on the heap, prints whether that succeeded, and frees the memory again.
+fn heap_program(size: usize) -> Program {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let ptr = f.declare_local::<*mut u8>();
+    f.storage_live(ptr);
+    f.allocate(const_int(size), const_int(1usize), ptr);
+    let is_null = eq(transmute(load(ptr), <usize>::get_type()), const_int(0usize));
+    f.if_(
+        is_null,
+        |f| f.print(const_bool(false)),
+        |f| {
+            f.print(const_bool(true));
+            f.deallocate(load(ptr), const_int(size), const_int(1usize));
+        },
+    );
+    f.exit();
+    let f = p.finish_function(f);
+
+    p.finish_program(f)
+}
+
+/// Exceeding the memory limit makes `Allocate` return null, which the program can handle.
+#[test]
+fn heap_memory_limit() {
+    let out = stdout_with_config::<BasicMem>(heap_program(16), memory_limit(64));
+    assert_eq!(out, &["true"]);
+    let out = stdout_with_config::<BasicMem>(heap_program(128), memory_limit(64));
+    assert_eq!(out, &["false"]);
+}
+
+#[test]
+fn heap_spurious_failure() {
+    let config = MachineConfig { spurious_heap_failure: true, ..MachineConfig::DEFAULT };
+    let mut seen = std::collections::HashSet::new();
+    for _ in 0..32 {
+        seen.insert(stdout_with_config::<BasicMem>(heap_program(4), config).join(","));
+    }
+    assert_eq!(seen.len(), 2);
+}
+
+/// Stack allocations cannot report failure to the program, so the machine stops.
+#[test]
+fn stack_memory_limit() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<[u8; 128]>();
+    f.storage_live(x);
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    let output = run_program_with_config::<BasicMem>(p, memory_limit(64));
+    assert_eq!(
+        output.info,
+        TerminationInfo::ResourceExhaustion(minirust_rs::prelude::String::from_internal(
+            "out of memory when allocating a stack variable".to_string()
+        ))
+    );
+}
+
+/// Running out of address space is also reported as resource exhaustion, rather than the machine getting stuck.
+#[test]
+fn address_space_exhausted() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let huge = array_ty(<u8>::get_type(), isize::MAX);
+    let x = f.declare_local_with_ty(huge);
+    let y = f.declare_local_with_ty(huge);
+    let z = f.declare_local_with_ty(huge);
+    f.storage_live(x);
+    f.storage_live(y);
+    f.storage_live(z);
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    let output = run_program_with_config::<BasicMem>(p, strategy(AddrStrategy::Adversarial));
+    assert_eq!(
+        output.info,
+        TerminationInfo::ResourceExhaustion(minirust_rs::prelude::String::from_internal(
+            "out of memory when allocating a stack variable".to_string()
+        ))
+    );
+}
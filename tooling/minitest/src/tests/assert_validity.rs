@@ -0,0 +1,60 @@
+//! The `AssertValidity` intrinsic panics if a type does not satisfy a validity requirement.
+
+use crate::*;
+
+fn assert_validity_program(requirement: ValidityRequirement, ty: Type) -> Program {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    f.assert_validity(requirement, ty);
+    f.print(const_int(1u32));
+    f.exit();
+    let f = p.finish_function(f);
+
+    p.finish_program(f)
+}
+
+#[track_caller]
+fn assert_satisfied(requirement: ValidityRequirement, ty: Type) {
+    let p = assert_validity_program(requirement, ty);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["1"], &[]);
+}
+
+#[track_caller]
+fn assert_violated(requirement: ValidityRequirement, ty: Type) {
+    let p = assert_validity_program(requirement, ty);
+    assert_output::<BasicMem>(
+        p,
+        abort_info("attempted to instantiate a type that does not permit it"),
+        &[],
+        &[],
+    );
+}
+
+#[test]
+fn assert_inhabited() {
+    assert_satisfied(ValidityRequirement::Inhabited, <u8>::get_type());
+    assert_satisfied(ValidityRequirement::Inhabited, <&u8>::get_type());
+    assert_violated(ValidityRequirement::Inhabited, <!>::get_type());
+    assert_violated(ValidityRequirement::Inhabited, <[!; 1]>::get_type());
+}
+
+#[test]
+fn assert_zero_valid() {
+    assert_satisfied(ValidityRequirement::Zero, <u32>::get_type());
+    assert_satisfied(ValidityRequirement::Zero, <bool>::get_type());
+    assert_satisfied(ValidityRequirement::Zero, <*const u8>::get_type());
+    assert_violated(ValidityRequirement::Zero, <&u8>::get_type());
+    assert_violated(ValidityRequirement::Zero, valid_range_ty(<u32>::get_type(), 1, u32::MAX));
+    assert_violated(ValidityRequirement::Zero, <!>::get_type());
+}
+
+/// The check for `mem::uninitialized` is lax: it only looks at memory filled with `0x01`.
+#[test]
+fn assert_mem_uninitialized_valid() {
+    assert_satisfied(ValidityRequirement::MemUninitialized, <u32>::get_type());
+    assert_satisfied(ValidityRequirement::MemUninitialized, <bool>::get_type());
+    assert_satisfied(ValidityRequirement::MemUninitialized, <&u8>::get_type());
+    assert_violated(ValidityRequirement::MemUninitialized, valid_range_ty(<u8>::get_type(), 2, 3));
+    assert_violated(ValidityRequirement::MemUninitialized, <!>::get_type());
+}
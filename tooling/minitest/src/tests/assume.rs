@@ -49,3 +49,15 @@ fn assume_wrong_argty() {
     let p = program(&[f]);
     assert_ub::<BasicMem>(p, "invalid argument for `Assume` intrinsic: not a Boolean");
 }
+
+/// A violated `Assume` in a branch that is not taken is not UB.
+#[test]
+fn assume_false_not_reached() {
+    let locals = [];
+    let b0 = block!(if_(const_bool(true), 2, 1));
+    let b1 = block!(assume(const_bool(false), 2));
+    let b2 = block!(exit());
+    let f = function(Ret::No, 0, &locals, &[b0, b1, b2]);
+    let p = program(&[f]);
+    assert_stop::<BasicMem>(p);
+}
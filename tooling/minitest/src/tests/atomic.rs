@@ -25,7 +25,7 @@ fn atomic_store_success() {
 #[test]
 fn atomic_store_arg_count() {
     let b0 = block!(Terminator::Intrinsic {
-        intrinsic: IntrinsicOp::AtomicStore,
+        intrinsic: IntrinsicOp::AtomicStore(AtomicOrdering::SeqCst),
         arguments: list!(),
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(1)))
@@ -40,7 +40,7 @@ fn atomic_store_arg_count() {
 #[test]
 fn atomic_store_arg_type1() {
     let b0 = block!(Terminator::Intrinsic {
-        intrinsic: IntrinsicOp::AtomicStore,
+        intrinsic: IntrinsicOp::AtomicStore(AtomicOrdering::SeqCst),
         arguments: list!(const_int::<u32>(0), const_int::<u32>(0)),
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(1)))
@@ -64,7 +64,7 @@ fn atomic_store_arg_type_pow() {
         array(&[const_int::<u8>(0), const_int::<u8>(1), const_int::<u8>(69)], <u8>::get_type());
 
     let b0 = block!(storage_live(0), Terminator::Intrinsic {
-        intrinsic: IntrinsicOp::AtomicStore,
+        intrinsic: IntrinsicOp::AtomicStore(AtomicOrdering::SeqCst),
         arguments: list!(addr_of(local(0), ptr_ty), arr),
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(1)))
@@ -88,7 +88,7 @@ fn atomic_store_arg_type_size() {
     let arr = array(&[const_int::<u64>(0), const_int::<u64>(1)], <u64>::get_type());
 
     let b0 = block!(storage_live(0), Terminator::Intrinsic {
-        intrinsic: IntrinsicOp::AtomicStore,
+        intrinsic: IntrinsicOp::AtomicStore(AtomicOrdering::SeqCst),
         arguments: list!(addr_of(local(0), ptr_ty), arr),
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(1)))
@@ -107,7 +107,7 @@ fn atomic_store_ret_type() {
     let ptr_ty = raw_void_ptr_ty();
 
     let b0 = block!(storage_live(0), Terminator::Intrinsic {
-        intrinsic: IntrinsicOp::AtomicStore,
+        intrinsic: IntrinsicOp::AtomicStore(AtomicOrdering::SeqCst),
         arguments: list!(addr_of(local(0), ptr_ty), const_int::<u64>(0)),
         ret: local(0),
         next_block: Some(BbName(Name::from_internal(1)))
@@ -146,7 +146,7 @@ fn atomic_load_arg_count() {
     let locals = [<u32>::get_type()];
 
     let b0 = block!(storage_live(0), Terminator::Intrinsic {
-        intrinsic: IntrinsicOp::AtomicLoad,
+        intrinsic: IntrinsicOp::AtomicLoad(AtomicOrdering::SeqCst),
         arguments: list!(),
         ret: local(0),
         next_block: Some(BbName(Name::from_internal(1)))
@@ -163,7 +163,7 @@ fn atomic_load_arg_type() {
     let locals = [<u32>::get_type()];
 
     let b0 = block!(storage_live(0), Terminator::Intrinsic {
-        intrinsic: IntrinsicOp::AtomicLoad,
+        intrinsic: IntrinsicOp::AtomicLoad(AtomicOrdering::SeqCst),
         arguments: list!(unit()),
         ret: local(0),
         next_block: Some(BbName(Name::from_internal(1)))
@@ -206,3 +206,116 @@ fn atomic_load_ret_type_size() {
     let p = program(&[f]);
     assert_ub::<BasicMem>(p, "invalid return type for `AtomicLoad` intrinsic: size too big")
 }
+
+#[test]
+fn atomic_store_misaligned() {
+    let locals = [<u64>::get_type()];
+
+    // `local(0)` is 8-aligned, so this pointer is definitely not 4-aligned.
+    let u8ptr = addr_of(local(0), <*const u8>::get_type());
+    let nonaligned = ptr_offset(u8ptr, const_int(1usize), InBounds::Yes);
+
+    let b0 = block!(storage_live(0), atomic_store(nonaligned, const_int::<u32>(0), 1));
+    let b1 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+    let p = program(&[f]);
+    assert_ub::<BasicMem>(
+        p,
+        "invalid first argument to `AtomicStore` intrinsic: pointer not aligned to access size",
+    )
+}
+
+#[test]
+fn atomic_load_misaligned() {
+    let locals = [<u64>::get_type(), <u32>::get_type()];
+
+    // `local(0)` is 8-aligned, so this pointer is definitely not 4-aligned.
+    let u8ptr = addr_of(local(0), <*const u8>::get_type());
+    let nonaligned = ptr_offset(u8ptr, const_int(1usize), InBounds::Yes);
+
+    let b0 = block!(
+        storage_live(0),
+        storage_live(1),
+        assign(local(0), const_int::<u64>(0)),
+        atomic_load(local(1), nonaligned, 1)
+    );
+    let b1 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+    let p = program(&[f]);
+    assert_ub::<BasicMem>(
+        p,
+        "invalid first argument to `AtomicLoad` intrinsic: pointer not aligned to access size",
+    )
+}
+
+#[test]
+fn atomic_store_acquire_ill_formed() {
+    let locals = [<u32>::get_type()];
+    let ptr_ty = raw_void_ptr_ty();
+
+    let b0 = block!(
+        storage_live(0),
+        atomic_store_ordered(
+            addr_of(local(0), ptr_ty),
+            const_int::<u32>(1),
+            AtomicOrdering::Acquire,
+            1
+        )
+    );
+    let b1 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+    let p = program(&[f]);
+    assert_ill_formed::<BasicMem>(p, "IntrinsicOp::AtomicStore: invalid ordering");
+}
+
+#[test]
+fn atomic_load_release_ill_formed() {
+    let locals = [<u32>::get_type(); 2];
+    let ptr_ty = raw_void_ptr_ty();
+
+    let b0 = block!(
+        storage_live(0),
+        storage_live(1),
+        atomic_load_ordered(local(0), addr_of(local(1), ptr_ty), AtomicOrdering::Release, 1)
+    );
+    let b1 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+    let p = program(&[f]);
+    assert_ill_formed::<BasicMem>(p, "IntrinsicOp::AtomicLoad: invalid ordering");
+}
+
+/// Atomic stores to a dead allocation must not be observed by a new allocation at the same address.
+#[test]
+fn atomic_load_after_address_reuse() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let r = f.declare_local::<u32>();
+    f.storage_live(r);
+    f.storage_live(x);
+    f.atomic_store(u32_ptr(x), const_int(1u32));
+    f.storage_dead(x);
+    // The adversarial strategy puts `x` at the same address again.
+    f.storage_live(x);
+    // There are no stores to pick from, so this reads the uninitialized memory of the new allocation.
+    f.atomic_load(r, u32_ptr(x));
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+
+    let alloc = AllocConfig { addr_strategy: AddrStrategy::Adversarial, ..AllocConfig::DEFAULT };
+    let output =
+        run_program_with_config::<BasicMem>(p, MachineConfig { alloc, ..MachineConfig::DEFAULT });
+    let TerminationInfo::Ub(msg, _) = output.info else {
+        panic!("expected UB, but program terminated with {:?}", output.info)
+    };
+    assert_eq!(
+        msg.get_internal(),
+        "load at type Int(IntType { signed: Unsigned, size: Size(4 bytes) }) but the data in memory violates the language invariant"
+    );
+}
@@ -31,12 +31,74 @@ fn atomic_fetch_success() {
     assert_eq!(output[1], "2");
 }
 
+/// Applies `op` with `operand` to a location holding `init`, and checks that it returns `init`
+/// and leaves `expected` in memory.
+fn assert_fetch_op<T: TypeConv + Into<Int> + Copy>(
+    op: FetchBinOp,
+    init: T,
+    operand: T,
+    expected: T,
+) {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<T>();
+    let old = f.declare_local::<T>();
+    f.storage_live(x);
+    f.storage_live(old);
+    f.assign(x, const_int(init));
+    f.atomic_fetch(op, old, addr_of(x, <*mut T>::get_type()), const_int(operand));
+    f.assume(eq(load(old), const_int(init)));
+    f.assume(eq(load(x), const_int(expected)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
+
+#[test]
+fn atomic_fetch_bitwise() {
+    assert_fetch_op(FetchBinOp::And, 0b1100u32, 0b1010, 0b1000);
+    assert_fetch_op(FetchBinOp::Or, 0b1100u32, 0b1010, 0b1110);
+    assert_fetch_op(FetchBinOp::Xor, 0b1100u32, 0b1010, 0b0110);
+    assert_fetch_op(FetchBinOp::Nand, 0b1100u32, 0b1010, !0b1000);
+    assert_fetch_op(FetchBinOp::Nand, -1i32, -1, 0);
+}
+
+/// Min and max compare signed or unsigned depending on the type.
+#[test]
+fn atomic_fetch_min_max() {
+    assert_fetch_op(FetchBinOp::Min, 5u32, 3, 3);
+    assert_fetch_op(FetchBinOp::Max, 5u32, 3, 5);
+    assert_fetch_op(FetchBinOp::Min, u32::MAX, 1, 1);
+    assert_fetch_op(FetchBinOp::Max, u32::MAX, 1, u32::MAX);
+    assert_fetch_op(FetchBinOp::Min, -1i32, 1, -1);
+    assert_fetch_op(FetchBinOp::Max, -1i32, 1, 1);
+    assert_fetch_op(FetchBinOp::Min, i64::MIN, i64::MAX, i64::MIN);
+}
+
+#[test]
+fn atomic_fetch_swap() {
+    assert_fetch_op(FetchBinOp::Swap, 7u32, 42, 42);
+    assert_fetch_op(FetchBinOp::Swap, -7i8, 7, 7);
+}
+
+#[test]
+fn atomic_fetch_add_wraps() {
+    assert_fetch_op(FetchBinOp::Add, u8::MAX, 2, 1);
+    assert_fetch_op(FetchBinOp::Sub, i16::MIN, 1, i16::MAX);
+}
+
 #[test]
 fn atomic_fetch_arg_count() {
     let locals = [];
 
     let b0 = block!(Terminator::Intrinsic {
-        intrinsic: IntrinsicOp::AtomicFetchAndOp(IntBinOp::Add),
+        intrinsic: IntrinsicOp::AtomicFetchAndOp(
+            AtomicRmwOp::IntBinOp(IntBinOp::Add),
+            AtomicOrdering::SeqCst
+        ),
         arguments: list!(),
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(1)))
@@ -114,7 +176,7 @@ fn atomic_fetch_ret_ty() {
 
     assert_ub::<BasicMem>(
         p,
-        "invalid return type for `AtomicFetchAndOp` intrinsic: only works with integers",
+        "invalid return type for `AtomicFetchAndOp` intrinsic: only works with integers and thin raw pointers",
     );
 }
 
@@ -149,7 +211,10 @@ fn atomic_fetch_op() {
         storage_live(1),
         assign(local(0), const_int::<u32>(3)),
         Terminator::Intrinsic {
-            intrinsic: IntrinsicOp::AtomicFetchAndOp(IntBinOp::Mul),
+            intrinsic: IntrinsicOp::AtomicFetchAndOp(
+                AtomicRmwOp::IntBinOp(IntBinOp::Mul),
+                AtomicOrdering::SeqCst
+            ),
             arguments: list!(addr_of(local(0), ptr_ty), const_int::<u32>(1)),
             ret: local(1),
             next_block: Some(BbName(Name::from_internal(1)))
@@ -162,3 +227,27 @@ fn atomic_fetch_op() {
 
     assert_ill_formed::<BasicMem>(p, "IntrinsicOp::AtomicFetchAndOp: non atomic op");
 }
+
+#[test]
+fn atomic_fetch_misaligned() {
+    let locals = [<u64>::get_type(), <u32>::get_type()];
+
+    // `local(0)` is 8-aligned, so this pointer is definitely not 4-aligned.
+    let u8ptr = addr_of(local(0), <*const u8>::get_type());
+    let nonaligned = ptr_offset(u8ptr, const_int(1usize), InBounds::Yes);
+
+    let b0 = block!(
+        storage_live(0),
+        storage_live(1),
+        assign(local(0), const_int::<u64>(0)),
+        atomic_fetch(FetchBinOp::Add, local(1), nonaligned, const_int::<u32>(1), 1)
+    );
+    let b1 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+    let p = program(&[f]);
+    assert_ub::<BasicMem>(
+        p,
+        "invalid first argument to `AtomicFetchAndOp` intrinsic: pointer not aligned to access size",
+    );
+}
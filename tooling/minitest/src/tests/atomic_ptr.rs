@@ -0,0 +1,224 @@
+//! Atomic read-modify-write operations on pointers, as used by `AtomicPtr`.
+
+use crate::*;
+
+fn slot_ptr(place: PlaceExpr) -> ValueExpr {
+    addr_of(place, <*mut *const u32>::get_type())
+}
+
+/// A pointer with the same address as `ptr`, but without provenance.
+fn without_provenance(ptr: ValueExpr) -> ValueExpr {
+    transmute(ptr_addr(ptr), <*const u32>::get_type())
+}
+
+fn load_u32(ptr: ValueExpr) -> ValueExpr {
+    load(deref(ptr, <u32>::get_type()))
+}
+
+/// Compare-exchange compares pointers by address: the expected value needs no provenance.
+/// Both the returned and the stored pointer keep their provenance.
+#[test]
+fn compare_exchange_ptr_by_address() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let y = f.declare_local::<u32>();
+    let slot = f.declare_local::<*const u32>();
+    let old = f.declare_local::<*const u32>();
+    f.storage_live(x);
+    f.storage_live(y);
+    f.storage_live(slot);
+    f.storage_live(old);
+    f.assign(x, const_int(1u32));
+    f.assign(y, const_int(2u32));
+    f.assign(slot, u32_ptr(x));
+    f.compare_exchange(old, slot_ptr(slot), without_provenance(u32_ptr(x)), u32_ptr(y));
+    f.print(load_u32(load(old)));
+    f.print(load_u32(load(slot)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["1", "2"], &[]);
+}
+
+/// On success, the new pointer is stored with its own provenance, even if it has none.
+#[test]
+fn compare_exchange_ptr_stores_new_provenance() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let slot = f.declare_local::<*const u32>();
+    let old = f.declare_local::<*const u32>();
+    f.storage_live(x);
+    f.storage_live(slot);
+    f.storage_live(old);
+    f.assign(slot, u32_ptr(x));
+    f.compare_exchange(old, slot_ptr(slot), u32_ptr(x), without_provenance(u32_ptr(x)));
+    f.print(load_u32(load(slot)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(p, "dereferencing pointer without provenance");
+}
+
+/// Integer operations on pointers act on the address and keep the provenance in memory.
+#[test]
+fn fetch_add_ptr_keeps_provenance() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let arr = f.declare_local::<[u32; 2]>();
+    let slot = f.declare_local::<*const u32>();
+    let old = f.declare_local::<*const u32>();
+    f.storage_live(arr);
+    f.storage_live(slot);
+    f.storage_live(old);
+    f.assign(arr, array(&[const_int(10u32), const_int(11u32)], <u32>::get_type()));
+    f.assign(slot, addr_of(index(arr, const_int(0usize)), <*const u32>::get_type()));
+    // The operand is an address without provenance, like in `AtomicPtr::fetch_byte_add`.
+    f.atomic_fetch(
+        FetchBinOp::Add,
+        old,
+        slot_ptr(slot),
+        transmute(const_int(4usize), <*const u32>::get_type()),
+    );
+    f.print(load_u32(load(old)));
+    f.print(load_u32(load(slot)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["10", "11"], &[]);
+}
+
+/// Swapping stores the operand with its own provenance.
+#[test]
+fn fetch_swap_ptr() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let y = f.declare_local::<u32>();
+    let slot = f.declare_local::<*const u32>();
+    let old = f.declare_local::<*const u32>();
+    f.storage_live(x);
+    f.storage_live(y);
+    f.storage_live(slot);
+    f.storage_live(old);
+    f.assign(x, const_int(1u32));
+    f.assign(y, const_int(2u32));
+    f.assign(slot, u32_ptr(x));
+    f.atomic_fetch(FetchBinOp::Swap, old, slot_ptr(slot), u32_ptr(y));
+    f.print(load_u32(load(old)));
+    f.print(load_u32(load(slot)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["1", "2"], &[]);
+}
+
+#[test]
+fn fetch_min_ptr() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let slot = f.declare_local::<*const u32>();
+    let old = f.declare_local::<*const u32>();
+    f.storage_live(x);
+    f.storage_live(slot);
+    f.storage_live(old);
+    f.assign(slot, u32_ptr(x));
+    f.atomic_fetch(FetchBinOp::Min, old, slot_ptr(slot), u32_ptr(x));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(
+        p,
+        "invalid operation for `AtomicFetchAndOp` intrinsic: only integer operations and `Swap` work with pointers",
+    );
+}
+
+/// `(next: *const Node, value: u32)`
+fn node_ty() -> Type {
+    tuple_ty(
+        &[(offset(0), <*const ()>::get_type()), (offset(8), <u32>::get_type())],
+        size(16),
+        align(8),
+    )
+}
+
+/// A Treiber stack: two threads concurrently push a node each with a compare-exchange loop.
+/// Afterwards, the main thread walks the list through the pointers that were compare-exchanged.
+#[test]
+fn treiber_stack() {
+    let mut p = ProgramBuilder::new();
+    let head = p.declare_global_zero_initialized::<*const ()>();
+    let head_ptr = addr_of(head, <*mut *const ()>::get_type());
+
+    // Push the node given as the data pointer.
+    let push = {
+        let mut f = p.declare_function();
+        let node = f.declare_arg::<*const ()>();
+        let old = f.declare_local::<*const ()>();
+        let seen = f.declare_local::<*const ()>();
+        let done = f.declare_local::<bool>();
+        f.storage_live(old);
+        f.storage_live(seen);
+        f.storage_live(done);
+        f.atomic_load(old, head_ptr);
+        f.assign(done, const_bool(false));
+        f.while_(not(load(done)), |f| {
+            f.assign(field(deref(load(node), node_ty()), 0), load(old));
+            f.compare_exchange(seen, head_ptr, load(old), load(node));
+            f.if_(
+                eq(load(seen), load(old)),
+                |f| f.assign(done, const_bool(true)),
+                |f| f.assign(old, load(seen)),
+            );
+        });
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let mut main = p.declare_function();
+    let a = main.declare_local_with_ty(node_ty());
+    let b = main.declare_local_with_ty(node_ty());
+    let id_a = main.declare_local::<u32>();
+    let id_b = main.declare_local::<u32>();
+    let cur = main.declare_local::<*const ()>();
+    main.storage_live(a);
+    main.storage_live(b);
+    main.storage_live(id_a);
+    main.storage_live(id_b);
+    main.storage_live(cur);
+    main.assign(field(a, 1), const_int(1u32));
+    main.assign(field(b, 1), const_int(2u32));
+    main.spawn(push, addr_of(a, <*const ()>::get_type()), id_a);
+    main.spawn(push, addr_of(b, <*const ()>::get_type()), id_b);
+    main.join(load(id_a));
+    main.join(load(id_b));
+    main.atomic_load(cur, head_ptr);
+    main.while_(ne(load(cur), null()), |f| {
+        f.print(load(field(deref(load(cur), node_ty()), 1)));
+        f.assign(cur, load(field(deref(load(cur), node_ty()), 0)));
+    });
+    main.exit();
+    let main = p.finish_function(main);
+
+    let p = p.finish_program(main);
+    let mut seen = std::collections::HashSet::new();
+    for _ in 0..32 {
+        let out = get_stdout::<BasicMem>(p).unwrap();
+        seen.insert(out.join(","));
+    }
+    let expected: std::collections::HashSet<String> =
+        ["2,1", "1,2"].iter().map(|x| x.to_string()).collect();
+    assert_eq!(seen, expected);
+}
@@ -0,0 +1,97 @@
+//! The `CarryingAdd`, `BorrowingSub`, and `WideningMul` intrinsics.
+
+use crate::*;
+
+/// The type of a `u8` paired with an overflow flag.
+fn u8_with_overflow() -> Type {
+    tuple_ty(&[(offset(0), <u8>::get_type()), (offset(1), <bool>::get_type())], size(2), align(1))
+}
+
+/// Prints both fields of a `(u8, bool)` pair.
+fn print_pair(f: &mut FunctionBuilder, pair: PlaceExpr) {
+    f.print(load(field(pair, 0)));
+    f.print(load(field(pair, 1)));
+}
+
+#[test]
+fn carrying_add() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let res = f.declare_local_with_ty(u8_with_overflow());
+    f.storage_live(res);
+    f.carrying_add(res, const_int(200u8), const_int(55u8), const_bool(false));
+    print_pair(&mut f, res);
+    f.carrying_add(res, const_int(200u8), const_int(55u8), const_bool(true));
+    print_pair(&mut f, res);
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["255", "false", "0", "true"], &[]);
+}
+
+#[test]
+fn borrowing_sub() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let res = f.declare_local_with_ty(u8_with_overflow());
+    f.storage_live(res);
+    f.borrowing_sub(res, const_int(1u8), const_int(1u8), const_bool(false));
+    print_pair(&mut f, res);
+    f.borrowing_sub(res, const_int(1u8), const_int(1u8), const_bool(true));
+    print_pair(&mut f, res);
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["0", "false", "255", "true"], &[]);
+}
+
+#[test]
+fn widening_mul() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let unsigned = f.declare_local_with_ty(tuple_ty(
+        &[(offset(0), <u8>::get_type()), (offset(1), <u8>::get_type())],
+        size(2),
+        align(1),
+    ));
+    let signed = f.declare_local_with_ty(tuple_ty(
+        &[(offset(0), <u8>::get_type()), (offset(1), <i8>::get_type())],
+        size(2),
+        align(1),
+    ));
+    f.storage_live(unsigned);
+    f.storage_live(signed);
+    // 200 * 200 = 40000 = 156 * 256 + 64
+    f.widening_mul(unsigned, const_int(200u8), const_int(200u8));
+    f.print(load(field(unsigned, 0)));
+    f.print(load(field(unsigned, 1)));
+    // -3 * 100 = -300 = -2 * 256 + 212
+    f.widening_mul(signed, const_int(-3i8), const_int(100i8));
+    f.print(load(field(signed, 0)));
+    f.print(load(field(signed, 1)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["64", "156", "212", "-2"], &[]);
+}
+
+#[test]
+fn carrying_add_wrong_ret_ty() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let res = f.declare_local::<u8>();
+    f.storage_live(res);
+    f.carrying_add(res, const_int(1u8), const_int(1u8), const_bool(false));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(p, "invalid return type for `CarryingAdd` intrinsic");
+}
@@ -95,3 +95,19 @@ fn bool_xor_works() {
     let prog = program(&[function(Ret::No, 0, &locals, &blocks)]);
     assert_stop::<BasicMem>(prog);
 }
+
+#[test]
+fn bool_cmp_works() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    f.assume(eq(cmp(const_bool(false), const_bool(true)), const_int(-1_i8)));
+    f.assume(eq(cmp(const_bool(true), const_bool(false)), const_int(1_i8)));
+    f.assume(eq(cmp(const_bool(true), const_bool(true)), const_int(0_i8)));
+    f.assume(lt(const_bool(false), const_bool(true)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
@@ -171,3 +171,89 @@ fn no_exit() {
     let p = p.finish_program(f);
     assert_stop::<BasicMem>(p);
 }
+
+#[test]
+fn global_with_bytes() {
+    let mut p = ProgramBuilder::new();
+    // Little-endian representation of `0x01020304u32`.
+    let g = p.declare_global(&[Some(4), Some(3), Some(2), Some(1)], align(4));
+
+    let mut f = p.declare_function();
+    f.print(load(global_by_name::<u32>(g)));
+    // Read the second byte on its own.
+    f.print(load(deref(const_global_ptr(g, size(1)), <u8>::get_type())));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["16909060", "3"], &[]);
+}
+
+#[test]
+fn global_with_uninit_bytes() {
+    let mut p = ProgramBuilder::new();
+    let g = p.declare_global(&[Some(0), None], align(2));
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u16>();
+    f.storage_live(x);
+    f.assign(x, load(global_by_name::<u16>(g)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(
+        p,
+        "load at type Int(IntType { signed: Unsigned, size: Size(2 bytes) }) but the data in memory violates the language invariant",
+    );
+}
+
+#[test]
+fn global_with_relocation() {
+    let mut p = ProgramBuilder::new();
+    let target = p.declare_global(&[Some(1), Some(2), Some(3), Some(4)], align(1));
+    // A pointer to the third byte of `target`.
+    let ptr_bytes = vec![None; <*const u8>::get_size().bytes().try_to_usize().unwrap()];
+    let relocation = Relocation { name: target, offset: size(2) };
+    let g = p.declare_global_with_relocations(
+        &ptr_bytes,
+        &[(size(0), RelocationTarget::Global(relocation))],
+        <*const u8>::get_align(),
+    );
+
+    let mut f = p.declare_function();
+    let ptr = load(global_by_name::<*const u8>(g));
+    f.print(load(deref(ptr, <u8>::get_type())));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["3"], &[]);
+}
+
+/// Globals can also store pointers to functions.
+#[test]
+fn global_with_fn_relocation() {
+    let mut p = ProgramBuilder::new();
+
+    let mut callee = p.declare_function();
+    callee.print(const_int(42u32));
+    callee.return_();
+    let callee = p.finish_function(callee);
+
+    let ptr_bytes = vec![None; <*const u8>::get_size().bytes().try_to_usize().unwrap()];
+    let g = p.declare_global_with_relocations(
+        &ptr_bytes,
+        &[(size(0), RelocationTarget::Fn(callee))],
+        <*const u8>::get_align(),
+    );
+
+    let mut f = p.declare_function();
+    let fn_ptr = load(deref(const_global_ptr(g, size(0)), Type::Ptr(PtrType::FnPtr)));
+    f.call_ignoreret(fn_ptr, &[]);
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["42"], &[]);
+}
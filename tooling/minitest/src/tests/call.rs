@@ -17,6 +17,7 @@ fn call_success() {
         arguments: list![by_value(unit())],
         ret: local(0),
         next_block: Some(BbName(Name::from_internal(1))),
+        unwind_block: None,
     });
     let b1 = block!(exit());
 
@@ -36,6 +37,7 @@ fn call_non_exist() {
         arguments: list![by_value(unit())],
         ret: local(0),
         next_block: Some(BbName(Name::from_internal(1))),
+        unwind_block: None,
     });
     let b1 = block!(exit());
 
@@ -55,6 +57,7 @@ fn call_arg_count() {
         arguments: list![],
         ret: local(0),
         next_block: Some(BbName(Name::from_internal(1))),
+        unwind_block: None,
     });
     let b1 = block!(exit());
 
@@ -74,6 +77,7 @@ fn call_arg_abi() {
         arguments: list![by_value(const_int::<i32>(42))],
         ret: local(0),
         next_block: Some(BbName(Name::from_internal(1))),
+        unwind_block: None,
     });
     let b1 = block!(exit());
 
@@ -93,6 +97,7 @@ fn call_ret_abi() {
         arguments: list![by_value(unit())],
         ret: local(0),
         next_block: Some(BbName(Name::from_internal(1))),
+        unwind_block: None,
     });
     let b1 = block!(exit());
 
@@ -126,3 +131,391 @@ fn ret_mismatch() {
     dump_program(p);
     assert_ub::<BasicMem>(p, "call ABI violation: return types are not compatible");
 }
+
+#[test]
+fn stack_overflow() {
+    let mut p = ProgramBuilder::new();
+
+    let mut rec = p.declare_function();
+    rec.call_ignoreret(fn_ptr(rec.name()), &[]);
+    rec.return_();
+    let rec = p.finish_function(rec);
+
+    let mut main = p.declare_function();
+    main.call_ignoreret(fn_ptr(rec), &[]);
+    main.exit();
+    let main = p.finish_function(main);
+
+    let p = p.finish_program(main);
+    assert_stack_overflow::<BasicMem>(p, None);
+    assert_stack_overflow::<BasicMem>(p, Some(16));
+}
+
+/// The configured stack limit counts all frames of the thread, including the one of the start function.
+#[test]
+fn stack_limit() {
+    let mut p = ProgramBuilder::new();
+
+    let mut inner = p.declare_function();
+    inner.exit();
+    let inner = p.finish_function(inner);
+
+    let mut outer = p.declare_function();
+    outer.call_ignoreret(fn_ptr(inner), &[]);
+    outer.return_();
+    let outer = p.finish_function(outer);
+
+    let mut main = p.declare_function();
+    main.call_ignoreret(fn_ptr(outer), &[]);
+    main.exit();
+    let main = p.finish_function(main);
+
+    let p = p.finish_program(main);
+    let config = MachineConfig { max_stack_frames: Some(3), ..MachineConfig::DEFAULT };
+    assert_eq!(run_program_with_config::<BasicMem>(p, config).info, TerminationInfo::MachineStop);
+    assert_stack_overflow::<BasicMem>(p, Some(2));
+}
+
+#[test]
+fn call_function_directly() {
+    let mut p = ProgramBuilder::new();
+
+    let double = {
+        let mut double = p.declare_function();
+        let ret = double.declare_ret::<u32>();
+        let x = double.declare_arg::<u32>();
+        double.assign(ret, add(load(x), load(x)));
+        double.return_();
+        p.finish_function(double)
+    };
+    let is_zero = {
+        let mut is_zero = p.declare_function();
+        let ret = is_zero.declare_ret::<bool>();
+        let x = is_zero.declare_arg::<u32>();
+        is_zero.assign(ret, eq(load(x), const_int(0u32)));
+        is_zero.return_();
+        p.finish_function(is_zero)
+    };
+
+    // The start function is never run.
+    let mut main = p.declare_function();
+    main.unreachable();
+    let main = p.finish_function(main);
+
+    let p = p.finish_program(main);
+    assert_returns::<BasicMem>(p, double, &[const_int(21u32)], 42);
+    assert_returns::<BasicMem>(p, is_zero, &[const_int(0u32)], true);
+    assert_ub::<BasicMem>(
+        program_calling(p, double, &[const_int(21u64)]),
+        "call ABI violation: argument types are not compatible",
+    );
+}
+
+/// Declares a function taking a `T` that does nothing.
+fn ignore_arg<T: TypeConv>(p: &mut ProgramBuilder) -> FnName {
+    let mut f = p.declare_function();
+    f.declare_arg::<T>();
+    f.return_();
+    p.finish_function(f)
+}
+
+/// After an in-place argument was passed, the place is uninitialized.
+#[test]
+fn in_place_primitive_read_after_call() {
+    let mut p = ProgramBuilder::new();
+    let callee = ignore_arg::<u32>(&mut p);
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let y = f.declare_local::<u32>();
+    f.storage_live(x);
+    f.storage_live(y);
+    f.assign(x, const_int(42u32));
+    f.call_ignoreret(fn_ptr(callee), &[in_place(x)]);
+    f.assign(y, load(x));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(
+        p,
+        "load at type Int(IntType { signed: Unsigned, size: Size(4 bytes) }) but the data in memory violates the language invariant",
+    );
+}
+
+#[test]
+fn in_place_aggregate_read_after_call() {
+    let mut p = ProgramBuilder::new();
+    let callee = ignore_arg::<[u32; 64]>(&mut p);
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<[u32; 64]>();
+    let y = f.declare_local::<u32>();
+    f.storage_live(x);
+    f.storage_live(y);
+    f.assign(x, array(&[const_int(0u32); 64], <u32>::get_type()));
+    f.call_ignoreret(fn_ptr(callee), &[in_place(x)]);
+    f.assign(y, load(index(x, const_int(0usize))));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(
+        p,
+        "load at type Int(IntType { signed: Unsigned, size: Size(4 bytes) }) but the data in memory violates the language invariant",
+    );
+}
+
+#[test]
+fn in_place_reinit_after_call() {
+    let mut p = ProgramBuilder::new();
+    let callee = ignore_arg::<[u32; 64]>(&mut p);
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<[u32; 64]>();
+    let y = f.declare_local::<[u32; 64]>();
+    f.storage_live(x);
+    f.storage_live(y);
+    f.assign(x, array(&[const_int(0u32); 64], <u32>::get_type()));
+    f.call_ignoreret(fn_ptr(callee), &[in_place(x)]);
+    // The place is still allocated and can be written again.
+    f.assign(x, array(&[const_int(1u32); 64], <u32>::get_type()));
+    f.assign(y, load(x));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
+
+#[test]
+fn in_place_twice_in_one_call() {
+    let mut p = ProgramBuilder::new();
+    let callee = {
+        let mut f = p.declare_function();
+        f.declare_arg::<u32>();
+        f.declare_arg::<u32>();
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    f.storage_live(x);
+    f.assign(x, const_int(42u32));
+    // The second argument sees the place already de-initialized by the first.
+    f.call_ignoreret(fn_ptr(callee), &[in_place(x), in_place(x)]);
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(
+        p,
+        "load at type Int(IntType { signed: Unsigned, size: Size(4 bytes) }) but the data in memory violates the language invariant",
+    );
+}
+
+/// Calls a function that takes an argument of type `callee_ty` with `arg`.
+fn call_with_arg(arg: ValueExpr, callee_ty: Type) -> Program {
+    let mut p = ProgramBuilder::new();
+    let mut callee = p.declare_function();
+    callee.declare_arg_with_ty(callee_ty);
+    callee.return_();
+    let callee = p.finish_function(callee);
+
+    let mut main = p.declare_function();
+    main.call_ignoreret(fn_ptr(callee), &[by_value(arg)]);
+    main.exit();
+    let main = p.finish_function(main);
+    p.finish_program(main)
+}
+
+#[track_caller]
+fn assert_arg_abi_mismatch(arg: ValueExpr, callee_ty: Type) {
+    assert_ub::<BasicMem>(
+        call_with_arg(arg, callee_ty),
+        "call ABI violation: argument types are not compatible",
+    );
+}
+
+#[test]
+fn call_arg_abi_int_sign() {
+    assert_arg_abi_mismatch(const_int(1u32), <i32>::get_type());
+}
+
+#[test]
+fn call_arg_abi_int_size() {
+    assert_arg_abi_mismatch(const_int(1u16), <u32>::get_type());
+}
+
+#[test]
+fn call_arg_abi_bool_int() {
+    assert_arg_abi_mismatch(const_bool(true), <u8>::get_type());
+}
+
+#[test]
+fn call_arg_abi_float_int() {
+    assert_arg_abi_mismatch(const_f32(1.0), <u32>::get_type());
+}
+
+#[test]
+fn call_arg_abi_ptr_metadata() {
+    assert_arg_abi_mismatch(null(), <*const [u8]>::get_type());
+}
+
+/// The kind of pointer does not matter for the ABI, only its metadata.
+#[test]
+fn call_arg_abi_ptr_kind() {
+    assert_stop::<BasicMem>(call_with_arg(null(), <*const u64>::get_type()));
+    assert_stop::<BasicMem>(call_with_arg(null(), <&u64>::get_type()));
+}
+
+#[test]
+fn call_arg_abi_tuple_offsets() {
+    let caller_ty = tuple_ty(
+        &[(offset(0), <u8>::get_type()), (offset(1), <u8>::get_type())],
+        size(2),
+        align(1),
+    );
+    let callee_ty = tuple_ty(
+        &[(offset(1), <u8>::get_type()), (offset(0), <u8>::get_type())],
+        size(2),
+        align(1),
+    );
+    assert_arg_abi_mismatch(tuple(&[const_int(1u8), const_int(2u8)], caller_ty), callee_ty);
+}
+
+#[test]
+fn call_arg_abi_tuple_size() {
+    let caller_ty = tuple_ty(&[(offset(0), <u8>::get_type())], size(1), align(1));
+    let callee_ty = tuple_ty(&[(offset(0), <u8>::get_type())], size(2), align(1));
+    assert_arg_abi_mismatch(tuple(&[const_int(1u8)], caller_ty), callee_ty);
+}
+
+#[test]
+fn call_arg_abi_array_count() {
+    assert_arg_abi_mismatch(
+        array(&[const_int(1u8), const_int(2u8)], <u8>::get_type()),
+        <[u8; 3]>::get_type(),
+    );
+}
+
+#[test]
+fn call_calling_convention() {
+    let locals = [<()>::get_type()];
+
+    let b0 = block!(storage_live(0), Terminator::Call {
+        callee: fn_ptr_internal(1),
+        calling_convention: CallingConvention::Rust,
+        arguments: list![by_value(unit())],
+        ret: local(0),
+        next_block: Some(BbName(Name::from_internal(1))),
+        unwind_block: None,
+    });
+    let b1 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+    let p = program(&[f, other_f()]);
+    assert_ub::<BasicMem>(p, "call ABI violation: calling conventions are not the same");
+}
+
+#[test]
+fn call_ret_abi_int_sign() {
+    let mut p = ProgramBuilder::new();
+    let mut callee = p.declare_function();
+    let ret = callee.declare_ret::<i32>();
+    callee.assign(ret, const_int(0i32));
+    callee.return_();
+    let callee = p.finish_function(callee);
+
+    let mut main = p.declare_function();
+    let x = main.declare_local::<u32>();
+    main.storage_live(x);
+    main.call(x, fn_ptr(callee), &[]);
+    main.exit();
+    let main = p.finish_function(main);
+    let p = p.finish_program(main);
+    assert_ub::<BasicMem>(p, "call ABI violation: return types are not compatible");
+}
+
+/// Declares a function `count(n: u32) -> u32` that tail-calls itself `n` times, and then returns 42.
+fn tail_recursive_count(p: &mut ProgramBuilder) -> FnName {
+    let mut f = p.declare_function();
+    let ret = f.declare_ret::<u32>();
+    let n = f.declare_arg::<u32>();
+    f.if_(
+        eq(load(n), const_int(0u32)),
+        |f| f.assign(ret, const_int(42u32)),
+        |f| {
+            let me = fn_ptr(f.name());
+            f.tail_call(me, &[by_value(sub(load(n), const_int(1u32)))]);
+        },
+    );
+    f.return_();
+    p.finish_function(f)
+}
+
+/// Tail calls replace the current frame, so they do not count against the stack limit.
+#[test]
+fn tail_call_stack() {
+    let mut p = ProgramBuilder::new();
+    let count = tail_recursive_count(&mut p);
+
+    let mut main = p.declare_function();
+    let x = main.declare_local::<u32>();
+    main.storage_live(x);
+    main.call(x, fn_ptr(count), &[by_value(const_int(100u32))]);
+    main.print(load(x));
+    main.exit();
+    let main = p.finish_function(main);
+
+    let p = p.finish_program(main);
+    let config = MachineConfig { max_stack_frames: Some(2), ..MachineConfig::DEFAULT };
+    let output = run_program_with_config::<BasicMem>(p, config);
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    assert_eq!(output.stdout, &["42"]);
+}
+
+/// The callee of a tail call returns on behalf of the current function, so their return types must be compatible.
+#[test]
+fn tail_call_return_type_mismatch() {
+    let mut p = ProgramBuilder::new();
+
+    let mut callee = p.declare_function();
+    let ret = callee.declare_ret::<u64>();
+    callee.assign(ret, const_int(0u64));
+    callee.return_();
+    let callee = p.finish_function(callee);
+
+    let mut caller = p.declare_function();
+    caller.declare_ret::<u32>();
+    caller.tail_call(fn_ptr(callee), &[]);
+    let caller = p.finish_function(caller);
+
+    let mut main = p.declare_function();
+    let x = main.declare_local::<u32>();
+    main.storage_live(x);
+    main.call(x, fn_ptr(caller), &[]);
+    main.exit();
+    let main = p.finish_function(main);
+
+    let p = p.finish_program(main);
+    assert_ub::<BasicMem>(p, "call ABI violation: return types are not compatible");
+}
+
+/// Tail calls cannot be used to return from the start function.
+#[test]
+fn tail_call_start_function() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    f.return_();
+    let f = p.finish_function(f);
+
+    let mut main = p.declare_function();
+    main.tail_call(fn_ptr(f), &[]);
+    let main = p.finish_function(main);
+
+    let p = p.finish_program(main);
+    assert_ub::<BasicMem>(p, "the start function must not return");
+}
@@ -0,0 +1,116 @@
+use crate::*;
+
+/// Transmutes `code_point` to `char` and stores the result in a local.
+fn transmute_to_char(code_point: u32) -> Program {
+    let locals = [<char>::get_type()];
+
+    let b0 = block!(
+        storage_live(0),
+        assign(local(0), transmute(const_int(code_point), <char>::get_type())),
+        exit()
+    );
+
+    let f = function(Ret::No, 0, &locals, &[b0]);
+    program(&[f])
+}
+
+#[test]
+fn transmute_valid_char() {
+    assert_stop::<BasicMem>(transmute_to_char(0));
+    assert_stop::<BasicMem>(transmute_to_char('a' as u32));
+    assert_stop::<BasicMem>(transmute_to_char(0xD7FF));
+    assert_stop::<BasicMem>(transmute_to_char(0xE000));
+    assert_stop::<BasicMem>(transmute_to_char(0x10FFFF));
+}
+
+#[test]
+fn transmute_surrogate_to_char() {
+    assert_ub::<BasicMem>(transmute_to_char(0xD800), "transmuted value is not valid at new type");
+    assert_ub::<BasicMem>(transmute_to_char(0xDFFF), "transmuted value is not valid at new type");
+}
+
+#[test]
+fn transmute_overlarge_to_char() {
+    assert_ub::<BasicMem>(transmute_to_char(0x110000), "transmuted value is not valid at new type");
+    assert_ub::<BasicMem>(transmute_to_char(u32::MAX), "transmuted value is not valid at new type");
+}
+
+/// Loading a surrogate code point from memory at type `char` is UB.
+#[test]
+fn load_invalid_char() {
+    let locals = [<u32>::get_type(), <char>::get_type()];
+    let as_char = deref(addr_of(local(0), <*const char>::get_type()), <char>::get_type());
+
+    let b0 = block!(
+        storage_live(0),
+        storage_live(1),
+        assign(local(0), const_int(0xD800u32)),
+        assign(local(1), load(as_char)),
+        exit()
+    );
+
+    let f = function(Ret::No, 0, &locals, &[b0]);
+    let p = program(&[f]);
+    assert_ub::<BasicMem>(
+        p,
+        "load at type Char but the data in memory violates the language invariant",
+    );
+}
+
+/// A `char` round-trips through `u32` unchanged.
+#[test]
+fn char_to_u32() {
+    let locals = [];
+    let b0 = block!(switch_int(transmute(const_char('ß'), <u32>::get_type()), &[(0xDFu32, 1)], 2));
+    let b1 = block!(exit());
+    let b2 = block!(unreachable());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1, b2]);
+    let p = program(&[f]);
+    assert_stop::<BasicMem>(p);
+}
+
+/// `char`s are ordered by their code point.
+#[test]
+fn compare_chars() {
+    let locals = [];
+    let b0 = block!(if_(lt(const_char('a'), const_char('b')), 1, 3));
+    let b1 = block!(if_(eq(const_char('\u{10FFFF}'), const_char('\u{10FFFF}')), 2, 3));
+    let b2 = block!(exit());
+    let b3 = block!(unreachable());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1, b2, b3]);
+    let p = program(&[f]);
+    assert_stop::<BasicMem>(p);
+}
+
+/// Chars are ordered by their code point.
+#[test]
+fn cmp_chars() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    f.assume(eq(cmp(const_char('a'), const_char('b')), const_int(-1_i8)));
+    f.assume(eq(cmp(const_char('\u{10FFFF}'), const_char('\u{E000}')), const_int(1_i8)));
+    f.assume(eq(cmp(const_char('x'), const_char('x')), const_int(0_i8)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
+
+#[test]
+fn const_surrogate_ill_formed() {
+    let locals = [<char>::get_type()];
+
+    let b0 = block!(
+        storage_live(0),
+        assign(local(0), ValueExpr::Constant(Constant::Int(0xD800.into()), <char>::get_type())),
+        exit()
+    );
+
+    let f = function(Ret::No, 0, &locals, &[b0]);
+    let p = program(&[f]);
+    assert_ill_formed::<BasicMem>(p, "Constant::Char: invalid char value");
+}
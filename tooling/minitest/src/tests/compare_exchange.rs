@@ -60,7 +60,10 @@ fn compare_exchange_arg_count() {
         storage_live(1),
         assign(local(0), const_int::<u32>(10)),
         Terminator::Intrinsic {
-            intrinsic: IntrinsicOp::AtomicCompareExchange,
+            intrinsic: IntrinsicOp::AtomicCompareExchange {
+                success: AtomicOrdering::SeqCst,
+                failure: AtomicOrdering::SeqCst,
+            },
             arguments: list!(addr0),
             ret: local(1),
             next_block: Some(BbName(Name::from_internal(1))),
@@ -117,7 +120,7 @@ fn compare_exchange_ret_type() {
     let p = program(&[f]);
     assert_ub::<BasicMem>(
         p,
-        "invalid return type for `Intrinis::AtomicCompareExchange`: only works with integers",
+        "invalid return type for `Intrinis::AtomicCompareExchange`: only works with integers and thin raw pointers",
     );
 }
 
@@ -189,3 +192,138 @@ fn compare_exchange_arg_size_max() {
         "invalid return type for `AtomicCompareExchange` intrinsic: size too big",
     );
 }
+
+#[test]
+fn compare_exchange_misaligned() {
+    let locals = [<u64>::get_type(), <u32>::get_type()];
+
+    // `local(0)` is 8-aligned, so this pointer is definitely not 4-aligned.
+    let u8ptr = addr_of(local(0), <*const u8>::get_type());
+    let nonaligned = ptr_offset(u8ptr, const_int(1usize), InBounds::Yes);
+
+    let b0 = block!(
+        storage_live(0),
+        storage_live(1),
+        assign(local(0), const_int::<u64>(0)),
+        compare_exchange(local(1), nonaligned, const_int::<u32>(0), const_int::<u32>(1), 1)
+    );
+    let b1 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+    let p = program(&[f]);
+    assert_ub::<BasicMem>(
+        p,
+        "invalid first argument to `AtomicCompareExchange` intrinsic: pointer not aligned to access size",
+    );
+}
+
+#[test]
+fn compare_exchange_failure_release_ill_formed() {
+    let locals = [<u32>::get_type(); 2];
+    let addr0 = addr_of(local(0), raw_void_ptr_ty());
+
+    let b0 = block!(
+        storage_live(0),
+        storage_live(1),
+        assign(local(0), const_int::<u32>(0)),
+        compare_exchange_ordered(
+            local(1),
+            addr0,
+            const_int::<u32>(0),
+            const_int::<u32>(1),
+            AtomicOrdering::AcqRel,
+            AtomicOrdering::Release,
+            1
+        )
+    );
+    let b1 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+    let p = program(&[f]);
+    assert_ill_formed::<BasicMem>(
+        p,
+        "IntrinsicOp::AtomicCompareExchange: invalid failure ordering",
+    );
+}
+
+/// The `(u32, bool)` type returned by a weak compare-exchange on a `u32`.
+fn weak_ret_ty() -> Type {
+    tuple_ty(&[(size(0), <u32>::get_type()), (size(4), <bool>::get_type())], size(8), align(4))
+}
+
+/// A weak compare-exchange may fail even though the values are equal, but it may also succeed.
+#[test]
+fn compare_exchange_weak_spurious_failure() {
+    let locals = [<u32>::get_type(), weak_ret_ty()];
+    let addr0 = addr_of(local(0), raw_void_ptr_ty());
+
+    let b0 = block!(
+        storage_live(0),
+        storage_live(1),
+        assign(local(0), const_int::<u32>(0)),
+        compare_exchange_weak(local(1), addr0, const_int::<u32>(0), const_int::<u32>(1), 1),
+    );
+    let b1 = block!(
+        // The returned value is always the value before the operation,
+        // and the location is updated iff the exchange happened.
+        assume(eq(load(field(local(1), 0)), const_int::<u32>(0)), 2),
+    );
+    let b2 =
+        block!(assume(eq(load(field(local(1), 1)), eq(load(local(0)), const_int::<u32>(1))), 3),);
+    let b3 = block!(print(load(field(local(1), 1)), 4));
+    let b4 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1, b2, b3, b4]);
+    let p = program(&[f]);
+
+    let mut seen = std::collections::HashSet::new();
+    for _ in 0..64 {
+        let out = get_stdout::<BasicMem>(p).unwrap();
+        seen.insert(out[0].clone());
+    }
+    let expected: std::collections::HashSet<String> =
+        ["true", "false"].iter().map(|x| x.to_string()).collect();
+    assert_eq!(seen, expected);
+}
+
+/// A weak compare-exchange never succeeds if the values differ.
+#[test]
+fn compare_exchange_weak_mismatch() {
+    let locals = [<u32>::get_type(), weak_ret_ty()];
+    let addr0 = addr_of(local(0), raw_void_ptr_ty());
+
+    let b0 = block!(
+        storage_live(0),
+        storage_live(1),
+        assign(local(0), const_int::<u32>(0)),
+        compare_exchange_weak(local(1), addr0, const_int::<u32>(3), const_int::<u32>(42), 1),
+    );
+    let b1 = block!(assume(not(load(field(local(1), 1))), 2));
+    let b2 = block!(assume(eq(load(local(0)), const_int::<u32>(0)), 3));
+    let b3 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1, b2, b3]);
+    let p = program(&[f]);
+    assert_stop_always::<BasicMem>(p, 16);
+}
+
+#[test]
+fn compare_exchange_weak_ret_type() {
+    let locals = [<u32>::get_type(); 2];
+    let addr0 = addr_of(local(0), raw_void_ptr_ty());
+
+    let b0 = block!(
+        storage_live(0),
+        storage_live(1),
+        assign(local(0), const_int::<u32>(0)),
+        compare_exchange_weak(local(1), addr0, const_int::<u32>(0), const_int::<u32>(1), 1),
+    );
+    let b1 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+    let p = program(&[f]);
+    assert_ub::<BasicMem>(
+        p,
+        "invalid return type for `AtomicCompareExchangeWeak` intrinsic: not a tuple",
+    );
+}
@@ -0,0 +1,191 @@
+use crate::*;
+
+/// A producer hands `count` items to a consumer through a one-item buffer:
+/// the producer waits on `not_full` and the consumer waits on `not_empty`, both under `lock`.
+/// The consumer prints the items it takes.
+fn producer_consumer(count: u32) -> Program {
+    let mut p = ProgramBuilder::new();
+    let lock = p.declare_global_zero_initialized::<u32>();
+    let not_empty = p.declare_global_zero_initialized::<u32>();
+    let not_full = p.declare_global_zero_initialized::<u32>();
+    let full = p.declare_global_zero_initialized::<bool>();
+    let item = p.declare_global_zero_initialized::<u32>();
+
+    let producer = {
+        let mut f = p.declare_function();
+        let _arg = f.declare_arg::<*const ()>();
+        let i = f.declare_local::<u32>();
+        f.storage_live(i);
+        f.assign(i, const_int(0u32));
+        f.while_(lt(load(i), const_int(count)), |f| {
+            f.lock_acquire(load(lock));
+            f.while_(load(full), |f| f.condvar_wait(load(not_full), load(lock)));
+            f.assign(i, add(load(i), const_int(1u32)));
+            f.assign(item, load(i));
+            f.assign(full, const_bool(true));
+            f.condvar_notify_one(load(not_empty));
+            f.lock_release(load(lock));
+        });
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let mut main = p.declare_function();
+    let id = main.declare_local::<u32>();
+    let i = main.declare_local::<u32>();
+    main.storage_live(id);
+    main.storage_live(i);
+    main.lock_create(lock);
+    main.condvar_create(not_empty);
+    main.condvar_create(not_full);
+    main.spawn(producer, null(), id);
+    main.assign(i, const_int(0u32));
+    main.while_(lt(load(i), const_int(count)), |f| {
+        f.lock_acquire(load(lock));
+        f.while_(not(load(full)), |f| f.condvar_wait(load(not_empty), load(lock)));
+        f.print(load(item));
+        f.assign(full, const_bool(false));
+        f.condvar_notify_one(load(not_full));
+        f.lock_release(load(lock));
+        f.assign(i, add(load(i), const_int(1u32)));
+    });
+    main.join(load(id));
+    main.exit();
+    let main = p.finish_function(main);
+
+    p.finish_program(main)
+}
+
+#[test]
+fn producer_consumer_in_order() {
+    let p = producer_consumer(4);
+    for _ in 0..8 {
+        assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["1", "2", "3", "4"], &[]);
+    }
+}
+
+/// `NotifyAll` wakes up every waiter. Each of them re-acquires the lock before continuing.
+#[test]
+fn notify_all() {
+    let mut p = ProgramBuilder::new();
+    let lock = p.declare_global_zero_initialized::<u32>();
+    let cv = p.declare_global_zero_initialized::<u32>();
+    let go = p.declare_global_zero_initialized::<bool>();
+    let done = p.declare_global_zero_initialized::<u32>();
+
+    let waiter = {
+        let mut f = p.declare_function();
+        let _arg = f.declare_arg::<*const ()>();
+        f.lock_acquire(load(lock));
+        f.while_(not(load(go)), |f| f.condvar_wait(load(cv), load(lock)));
+        f.assign(done, add(load(done), const_int(1u32)));
+        f.lock_release(load(lock));
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let mut main = p.declare_function();
+    let id_a = main.declare_local::<u32>();
+    let id_b = main.declare_local::<u32>();
+    main.storage_live(id_a);
+    main.storage_live(id_b);
+    main.lock_create(lock);
+    main.condvar_create(cv);
+    main.spawn(waiter, null(), id_a);
+    main.spawn(waiter, null(), id_b);
+    main.lock_acquire(load(lock));
+    main.assign(go, const_bool(true));
+    main.condvar_notify_all(load(cv));
+    main.lock_release(load(lock));
+    main.join(load(id_a));
+    main.join(load(id_b));
+    main.print(load(done));
+    main.exit();
+    let main = p.finish_function(main);
+
+    let p = p.finish_program(main);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["2"], &[]);
+}
+
+/// Notifying without waiters does nothing.
+#[test]
+fn notify_without_waiters() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let cv = f.declare_local::<u32>();
+    f.storage_live(cv);
+    f.condvar_create(cv);
+    f.condvar_notify_one(load(cv));
+    f.condvar_notify_all(load(cv));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
+
+#[test]
+fn wait_without_lock() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let lock = f.declare_local::<u32>();
+    let cv = f.declare_local::<u32>();
+    f.storage_live(lock);
+    f.storage_live(cv);
+    f.lock_create(lock);
+    f.condvar_create(cv);
+    f.condvar_wait(load(cv), load(lock));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(p, "waiting on condition variable without holding the lock");
+}
+
+#[test]
+fn wait_non_existing() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let lock = f.declare_local::<u32>();
+    f.storage_live(lock);
+    f.lock_create(lock);
+    f.lock_acquire(load(lock));
+    f.condvar_wait(const_int(0u32), load(lock));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(p, "waiting on non-existing condition variable");
+}
+
+#[test]
+fn notify_non_existing() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    f.condvar_notify_one(const_int(0u32));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(p, "notifying non-existing condition variable");
+}
+
+#[test]
+fn create_condvar_ret_type() {
+    let locals = [<bool>::get_type()];
+    let b0 = block!(storage_live(0), Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::Lock(IntrinsicLockOp::CreateCondVar),
+        arguments: list!(),
+        ret: local(0),
+        next_block: Some(BbName(Name::from_internal(1))),
+    });
+    let b1 = block!(exit());
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+
+    let p = program(&[f]);
+    assert_ub::<BasicMem>(p, "invalid return type for `CreateCondVar` lock intrinsic")
+}
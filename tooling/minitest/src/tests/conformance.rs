@@ -0,0 +1,55 @@
+use miniutil::conformance::*;
+
+use crate::*;
+
+/// The spec itself must pass its own conformance kit, under every memory model.
+#[track_caller]
+fn assert_conforms<M: MemoryTooling>() {
+    let failures = run_conformance(&mut SpecEvaluator::<M>::default());
+    assert!(failures.is_empty(), "conformance failures: {failures:#?}");
+}
+
+#[test]
+fn conformance_basic_mem() {
+    assert_conforms::<BasicMem>();
+}
+
+#[test]
+fn conformance_tree_borrows() {
+    assert_conforms::<TreeBorrowMem>();
+}
+
+#[test]
+fn conformance_stacked_borrows() {
+    assert_conforms::<StackedBorrowMem>();
+}
+
+/// An evaluator that gets a verdict wrong is reported.
+#[test]
+fn wrong_evaluator_fails() {
+    let mut always_stop = |_prog: Program| Verdict::Stop(vec![]);
+    let failures = run_conformance(&mut always_stop);
+    let expected_failures = conformance_tests()
+        .into_iter()
+        .filter(|test| test.expected != Verdict::Stop(vec![]))
+        .count();
+    assert_eq!(failures.len(), expected_failures);
+    assert!(
+        failures.iter().any(|f| f.name == "uninit_int_load" && f.actual == Verdict::Stop(vec![]))
+    );
+}
+
+/// The kit covers all the categories it promises.
+#[test]
+fn all_categories_covered() {
+    let tests = conformance_tests();
+    for category in [
+        Category::Padding,
+        Category::Provenance,
+        Category::Uninit,
+        Category::Unions,
+        Category::Enums,
+    ] {
+        assert!(tests.iter().any(|test| test.category == category), "no test for {category:?}");
+    }
+}
@@ -0,0 +1,261 @@
+//! Const evaluation of the initializers of globals.
+
+use crate::*;
+
+/// Declares a function returning a `u32`, whose body is `body` followed by a return.
+fn init_fn(p: &mut ProgramBuilder, body: impl FnOnce(&mut FunctionBuilder, PlaceExpr)) -> FnName {
+    let mut f = p.declare_function();
+    let ret = f.declare_ret::<u32>();
+    body(&mut f, ret);
+    f.return_();
+    p.finish_function(f)
+}
+
+/// Finishes the program with a start function that prints `global`.
+fn print_global(mut p: ProgramBuilder, global: PlaceExpr) -> Program {
+    let mut f = p.declare_function();
+    f.print(load(global));
+    f.exit();
+    let f = p.finish_function(f);
+
+    p.finish_program(f)
+}
+
+#[test]
+fn global_init() {
+    let mut p = ProgramBuilder::new();
+    let init = init_fn(&mut p, |f, ret| f.assign(ret, mul(const_int(6u32), const_int(7u32))));
+    let global = p.declare_global_with_init::<u32>(init);
+
+    let p = print_global(p, global);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["42"], &[]);
+}
+
+/// Initializers can read globals, including the ones computed by earlier initializers.
+#[test]
+fn global_init_reads_globals() {
+    let mut p = ProgramBuilder::new();
+    let x = p.declare_global_int(40u32);
+    let init_y = init_fn(&mut p, |f, ret| f.assign(ret, add(load(x), const_int(1u32))));
+    let y = p.declare_global_with_init::<u32>(init_y);
+    let init_z = init_fn(&mut p, |f, ret| f.assign(ret, add(load(y), const_int(1u32))));
+    let z = p.declare_global_with_init::<u32>(init_z);
+
+    let p = print_global(p, z);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["42"], &[]);
+}
+
+/// An initializer cannot read a global whose initializer runs after it.
+#[test]
+fn global_init_reads_later_global() {
+    let mut p = ProgramBuilder::new();
+    let init_y = init_fn(&mut p, |f, ret| f.assign(ret, const_int(41u32)));
+    let y = p.declare_global_with_init::<u32>(init_y);
+    let init_z = init_fn(&mut p, |f, ret| f.assign(ret, add(load(y), const_int(1u32))));
+    let z = p.declare_global_with_init::<u32>(init_z);
+
+    let mut p = print_global(p, z);
+    // Run the initializer of `z` first.
+    p.global_inits = p.global_inits.iter().rev().collect();
+    assert_ill_formed::<BasicMem>(
+        p,
+        "reading a global before its initializer has run is not supported during const evaluation",
+    );
+}
+
+#[test]
+fn global_init_calls_function() {
+    let mut p = ProgramBuilder::new();
+    let double = {
+        let mut double = p.declare_function();
+        let ret = double.declare_ret::<u32>();
+        let x = double.declare_arg::<u32>();
+        double.assign(ret, add(load(x), load(x)));
+        double.return_();
+        p.finish_function(double)
+    };
+    let init = init_fn(&mut p, |f, ret| {
+        f.call(ret, fn_ptr(double), &[by_value(const_int(21u32))]);
+    });
+    let global = p.declare_global_with_init::<u32>(init);
+
+    let p = print_global(p, global);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["42"], &[]);
+}
+
+#[test]
+fn global_init_ub() {
+    let mut p = ProgramBuilder::new();
+    // `init_fn` ends the body with a return, so we cannot use it for a body that does not return.
+    let mut f = p.declare_function();
+    f.declare_ret::<u32>();
+    f.unreachable();
+    let init = p.finish_function(f);
+    let global = p.declare_global_with_init::<u32>(init);
+
+    let p = print_global(p, global);
+    assert_ub::<BasicMem>(p, "reached unreachable code");
+}
+
+/// An initializer that does not finish stops the machine once it has taken too many steps.
+#[test]
+fn global_init_does_not_terminate() {
+    let mut p = ProgramBuilder::new();
+    let init = init_fn(&mut p, |f, _ret| f.while_(const_bool(true), |_f| {}));
+    let global = p.declare_global_with_init::<u32>(init);
+
+    let p = print_global(p, global);
+    let config = MachineConfig { max_const_eval_steps: 100, ..MachineConfig::DEFAULT };
+    let info = run_program_with_config::<BasicMem>(p, config).info;
+    // Only create the expected message after the run, so that the GC cannot free it.
+    let msg = prelude::String::from_internal(
+        "the initializer of a global did not finish within 100 steps".to_string(),
+    );
+    assert_eq!(info, TerminationInfo::ResourceExhaustion(msg));
+}
+
+#[test]
+fn global_init_no_heap() {
+    let mut p = ProgramBuilder::new();
+    let init = init_fn(&mut p, |f, ret| {
+        let ptr = f.declare_local::<*mut u32>();
+        f.storage_live(ptr);
+        f.allocate(const_int(4usize), const_int(4usize), ptr);
+        f.assign(ret, const_int(0u32));
+    });
+    let global = p.declare_global_with_init::<u32>(init);
+
+    let p = print_global(p, global);
+    assert_ill_formed::<BasicMem>(
+        p,
+        "intrinsic `Allocate` is not supported during const evaluation",
+    );
+}
+
+#[test]
+fn global_init_no_io() {
+    let mut p = ProgramBuilder::new();
+    let init = init_fn(&mut p, |f, ret| {
+        f.print(const_int(1u32));
+        f.assign(ret, const_int(0u32));
+    });
+    let global = p.declare_global_with_init::<u32>(init);
+
+    let p = print_global(p, global);
+    assert_ill_formed::<BasicMem>(
+        p,
+        "intrinsic `PrintStdout` is not supported during const evaluation",
+    );
+}
+
+/// Const evaluation is deterministic, so `Freeze` cannot pick the value of uninitialized memory.
+#[test]
+fn global_init_no_freeze() {
+    let mut p = ProgramBuilder::new();
+    let init = init_fn(&mut p, |f, ret| {
+        f.freeze(addr_of(ret, <*mut u32>::get_type()), <u32>::get_type());
+    });
+    let global = p.declare_global_with_init::<u32>(init);
+
+    let p = print_global(p, global);
+    let intrinsic = IntrinsicOp::Freeze(<u32>::get_type());
+    assert_ill_formed::<BasicMem>(
+        p,
+        &format!("intrinsic `{intrinsic:?}` is not supported during const evaluation"),
+    );
+}
+
+/// Const evaluation is deterministic, so a weak compare-exchange cannot fail spuriously.
+#[test]
+fn global_init_no_weak_compare_exchange() {
+    let mut p = ProgramBuilder::new();
+    let init = init_fn(&mut p, |f, ret| {
+        let result_ty = tuple_ty(
+            &[(size(0), <u32>::get_type()), (size(4), <bool>::get_type())],
+            size(8),
+            align(4),
+        );
+        let result = f.declare_local_with_ty(result_ty);
+        f.storage_live(result);
+        f.assign(ret, const_int(0u32));
+        f.compare_exchange_weak(
+            result,
+            addr_of(ret, <*mut u32>::get_type()),
+            const_int(0u32),
+            const_int(1u32),
+        );
+    });
+    let global = p.declare_global_with_init::<u32>(init);
+
+    let p = print_global(p, global);
+    let intrinsic = IntrinsicOp::AtomicCompareExchangeWeak {
+        success: AtomicOrdering::SeqCst,
+        failure: AtomicOrdering::SeqCst,
+    };
+    assert_ill_formed::<BasicMem>(
+        p,
+        &format!("intrinsic `{intrinsic:?}` is not supported during const evaluation"),
+    );
+}
+
+#[test]
+fn global_init_writes_global() {
+    let mut p = ProgramBuilder::new();
+    let x = p.declare_global_int(0u32);
+    let init = init_fn(&mut p, |f, ret| {
+        f.assign(x, const_int(1u32));
+        f.assign(ret, const_int(0u32));
+    });
+    let global = p.declare_global_with_init::<u32>(init);
+
+    let p = print_global(p, global);
+    assert_ill_formed::<BasicMem>(
+        p,
+        "writing to a global is not supported during const evaluation",
+    );
+}
+
+/// The machine checks the accesses of the initializer itself, so the memory model does not matter.
+#[test]
+fn global_init_writes_global_tree_borrows() {
+    let mut p = ProgramBuilder::new();
+    let x = p.declare_global_int(0u32);
+    let init = init_fn(&mut p, |f, ret| {
+        f.assign(x, const_int(1u32));
+        f.assign(ret, const_int(0u32));
+    });
+    let global = p.declare_global_with_init::<u32>(init);
+
+    let p = print_global(p, global);
+    assert_ill_formed::<TreeBorrowMem>(
+        p,
+        "writing to a global is not supported during const evaluation",
+    );
+}
+
+/// Once the program runs, globals with an initializer can be written to like all others.
+#[test]
+fn global_init_then_write() {
+    let mut p = ProgramBuilder::new();
+    let init = init_fn(&mut p, |f, ret| f.assign(ret, const_int(1u32)));
+    let global = p.declare_global_with_init::<u32>(init);
+
+    let mut f = p.declare_function();
+    f.assign(global, add(load(global), const_int(1u32)));
+    f.print(load(global));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["2"], &[]);
+}
+
+#[test]
+fn global_init_wrong_size() {
+    let mut p = ProgramBuilder::new();
+    let init = init_fn(&mut p, |f, ret| f.assign(ret, const_int(0u32)));
+    let global = p.declare_global_with_init::<u64>(init);
+
+    let p = print_global(p, global);
+    assert_ill_formed::<BasicMem>(p, "Program: global initializer returns value of wrong size");
+}
@@ -0,0 +1,191 @@
+//! The `Copy`, `CopyNonOverlapping`, and `WriteBytes` intrinsics.
+
+use crate::*;
+
+/// A pointer to element `i` of the `u32` array `arr`.
+fn elem_ptr(arr: PlaceExpr, i: usize) -> ValueExpr {
+    addr_of(index(arr, const_int(i)), <*mut u32>::get_type())
+}
+
+fn u32_array(vals: [u32; 4]) -> ValueExpr {
+    array(&vals.map(const_int), <u32>::get_type())
+}
+
+/// Initializes `arr` to `[1, 2, 3, 4]`, calls `body`, then prints all elements of `arr`.
+fn array_program(body: impl FnOnce(&mut FunctionBuilder, PlaceExpr)) -> Program {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let arr = f.declare_local::<[u32; 4]>();
+    f.storage_live(arr);
+    f.assign(arr, u32_array([1, 2, 3, 4]));
+    body(&mut f, arr);
+    for i in 0..4 {
+        f.print(load(index(arr, const_int(i as usize))));
+    }
+    f.exit();
+    let f = p.finish_function(f);
+
+    p.finish_program(f)
+}
+
+#[test]
+fn copy_nonoverlapping() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let src = f.declare_local::<[u32; 4]>();
+    let dst = f.declare_local::<[u32; 4]>();
+    f.storage_live(src);
+    f.storage_live(dst);
+    f.assign(src, u32_array([1, 2, 3, 4]));
+    f.copy_nonoverlapping(elem_ptr(src, 0), elem_ptr(dst, 0), const_int(4usize), <u32>::get_type());
+    f.print(load(index(dst, const_int(0usize))));
+    f.print(load(index(dst, const_int(3usize))));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["1", "4"], &[]);
+}
+
+/// `Copy` reads the whole source before writing the destination.
+#[test]
+fn copy_overlapping() {
+    let forward = array_program(|f, arr| {
+        f.copy(elem_ptr(arr, 0), elem_ptr(arr, 1), const_int(3usize), <u32>::get_type());
+    });
+    assert_output::<BasicMem>(forward, TerminationInfo::MachineStop, &["1", "1", "2", "3"], &[]);
+
+    let backward = array_program(|f, arr| {
+        f.copy(elem_ptr(arr, 1), elem_ptr(arr, 0), const_int(3usize), <u32>::get_type());
+    });
+    assert_output::<BasicMem>(backward, TerminationInfo::MachineStop, &["2", "3", "4", "4"], &[]);
+}
+
+#[test]
+fn copy_nonoverlapping_overlap() {
+    let p = array_program(|f, arr| {
+        f.copy_nonoverlapping(
+            elem_ptr(arr, 0),
+            elem_ptr(arr, 1),
+            const_int(3usize),
+            <u32>::get_type(),
+        );
+    });
+    assert_ub::<BasicMem>(p, "`CopyNonOverlapping` intrinsic called on overlapping ranges");
+}
+
+/// Adjacent ranges do not overlap.
+#[test]
+fn copy_nonoverlapping_adjacent() {
+    let p = array_program(|f, arr| {
+        f.copy_nonoverlapping(
+            elem_ptr(arr, 0),
+            elem_ptr(arr, 2),
+            const_int(2usize),
+            <u32>::get_type(),
+        );
+    });
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["1", "2", "1", "2"], &[]);
+}
+
+/// Copying pointers keeps their provenance.
+#[test]
+fn copy_preserves_provenance() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let src = f.declare_local::<*const u32>();
+    let dst = f.declare_local::<*const u32>();
+    f.storage_live(x);
+    f.storage_live(src);
+    f.storage_live(dst);
+    f.assign(x, const_int(42u32));
+    f.assign(src, addr_of(x, <*const u32>::get_type()));
+    f.copy(
+        addr_of(src, <*const *const u32>::get_type()),
+        addr_of(dst, <*mut *const u32>::get_type()),
+        const_int(1usize),
+        <*const u32>::get_type(),
+    );
+    f.print(load(deref(load(dst), <u32>::get_type())));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["42"], &[]);
+}
+
+/// Copying uninitialized memory is fine, but the copy is uninitialized as well.
+#[test]
+fn copy_preserves_uninit() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let src = f.declare_local::<u32>();
+    let dst = f.declare_local::<u32>();
+    f.storage_live(src);
+    f.storage_live(dst);
+    f.copy(
+        addr_of(src, <*const u32>::get_type()),
+        addr_of(dst, <*mut u32>::get_type()),
+        const_int(1usize),
+        <u32>::get_type(),
+    );
+    f.print(load(dst));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(
+        p,
+        "load at type Int(IntType { signed: Unsigned, size: Size(4 bytes) }) but the data in memory violates the language invariant",
+    );
+}
+
+#[test]
+fn copy_out_of_bounds() {
+    let p = array_program(|f, arr| {
+        f.copy(elem_ptr(arr, 2), elem_ptr(arr, 0), const_int(3usize), <u32>::get_type());
+    });
+    assert_ub::<BasicMem>(p, "dereferencing pointer outside the bounds of its allocation");
+}
+
+#[test]
+fn copy_size_overflow() {
+    let p = array_program(|f, arr| {
+        f.copy(elem_ptr(arr, 0), elem_ptr(arr, 1), const_int(usize::MAX / 2), <u32>::get_type());
+    });
+    assert_ub::<BasicMem>(p, "`Copy` intrinsic called with a size that overflows `isize`");
+}
+
+#[test]
+fn write_bytes() {
+    let p = array_program(|f, arr| {
+        f.write_bytes(elem_ptr(arr, 1), const_int(1u8), const_int(2usize), <u32>::get_type());
+    });
+    assert_output::<BasicMem>(
+        p,
+        TerminationInfo::MachineStop,
+        &["1", "16843009", "16843009", "4"],
+        &[],
+    );
+}
+
+#[test]
+fn write_bytes_out_of_bounds() {
+    let p = array_program(|f, arr| {
+        f.write_bytes(elem_ptr(arr, 3), const_int(0u8), const_int(2usize), <u32>::get_type());
+    });
+    assert_ub::<BasicMem>(p, "dereferencing pointer outside the bounds of its allocation");
+}
+
+#[test]
+fn write_bytes_not_u8() {
+    let p = array_program(|f, arr| {
+        f.write_bytes(elem_ptr(arr, 0), const_int(0u32), const_int(1usize), <u32>::get_type());
+    });
+    assert_ub::<BasicMem>(p, "invalid second argument to `WriteBytes` intrinsic: not a `u8`");
+}
@@ -0,0 +1,96 @@
+//! Coverage of the spec's rules.
+
+use std::path::Path;
+
+use crate::*;
+
+fn spec_dir() -> &'static Path {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/../../spec"))
+}
+
+#[test]
+fn spec_rules_found() {
+    let rules: Vec<Rule> =
+        spec_rules(spec_dir()).unwrap().into_iter().map(|spec_rule| spec_rule.rule).collect();
+    for rule in [
+        Rule::Step("Statement::Assign".to_string()),
+        Rule::Step("Terminator::Goto".to_string()),
+        Rule::Step("IntrinsicOp::PrintStdout".to_string()),
+        Rule::Step("IntrinsicOp::Lock(IntrinsicLockOp::Acquire)".to_string()),
+        Rule::Ub("division by zero".to_string()),
+        Rule::Ub("invalid number of arguments for `{name}` intrinsic".to_string()),
+        Rule::Ub("Value::Ptr: null safe pointer".to_string()),
+    ] {
+        assert!(rules.contains(&rule), "`{rule}` not found");
+    }
+    // The generic declarations of the step functions are not rules, and neither is forwarding a message.
+    assert!(!rules.iter().any(|rule| matches!(rule, Rule::Step(name) if name.ends_with("::"))));
+    assert!(!rules.contains(&Rule::Ub("{}".to_string())));
+}
+
+#[test]
+fn templates() {
+    assert!(template_matches("division by zero", "division by zero"));
+    assert!(!template_matches("division by zero", "division by zero!"));
+    assert!(template_matches(
+        "invalid number of arguments for `{name}` intrinsic",
+        "invalid number of arguments for `Exit` intrinsic"
+    ));
+    assert!(template_matches(
+        "deallocating {:?} memory with {:?} deallocation operation",
+        "deallocating Heap memory with Global deallocation operation"
+    ));
+    assert!(!template_matches("deallocating {:?} memory", "deallocating Heap"));
+}
+
+#[test]
+fn operation_rules_of_program() {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    f.storage_live(x);
+    f.print(const_int(1u32));
+    f.exit();
+    let f = p.finish_function(f);
+    let prog = p.finish_program(f);
+
+    let func = prog.functions.get(prog.start).unwrap();
+    let mut rules = Vec::new();
+    for block in func.blocks.values() {
+        for stmt in block.statements.iter() {
+            rules.extend(operation_rules(Operation::Statement(stmt)));
+        }
+        rules.extend(operation_rules(Operation::Terminator(block.terminator)));
+    }
+    for rule in [
+        "Statement::StorageLive",
+        "Terminator::Intrinsic",
+        "IntrinsicOp::PrintStdout",
+        "IntrinsicOp::Exit",
+    ] {
+        assert!(rules.iter().any(|r| r == rule), "`{rule}` not in {rules:?}");
+    }
+}
+
+#[test]
+fn read_coverage() {
+    let dir = std::env::temp_dir().join(format!("minirust-coverage-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("covered-1.txt"), "Statement::Assign\nUB: division by zero\n").unwrap();
+    std::fs::write(
+        dir.join("covered-2.txt"),
+        "UB: invalid number of arguments for `Exit` intrinsic\n",
+    )
+    .unwrap();
+    let coverage = Coverage::read(&dir).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(coverage.covers(&Rule::Step("Statement::Assign".to_string())));
+    assert!(!coverage.covers(&Rule::Step("Statement::Deinit".to_string())));
+    assert!(coverage.covers(&Rule::Ub("division by zero".to_string())));
+    assert!(
+        coverage
+            .covers(&Rule::Ub("invalid number of arguments for `{name}` intrinsic".to_string()))
+    );
+    assert!(!coverage.covers(&Rule::Ub("overflow in division".to_string())));
+}
@@ -28,19 +28,46 @@ fn access_block(access: AccessPattern, support_global: u32, next: u32) -> BasicB
     }
 }
 
+/// An atomic access to global(0), which is a `u32`: the access type, the size in bytes, and the offset.
+struct MixedSizeAccess(AccessType, u8, u8);
+
+// Like `access_block`, but with the access size and offset of `access`.
+fn mixed_size_block(access: MixedSizeAccess, support_global: u32, next: u32) -> BasicBlock {
+    let MixedSizeAccess(ty, size, offset) = access;
+    let base = addr_of(global::<u32>(0), <*const u8>::get_type());
+    let addr = ptr_offset(base, const_int(offset as usize), InBounds::Yes);
+    let support = match size {
+        4 => global::<u32>(support_global),
+        2 => global::<u16>(support_global),
+        _ => unreachable!(),
+    };
+    match ty {
+        AccessType::Load => block!(atomic_load(support, addr, next)),
+        AccessType::Store => block!(atomic_store(addr, load(support), next)),
+    }
+}
+
 fn racy_program(main_access: AccessPattern, s_access: AccessPattern) -> Program {
+    racy_program_from_blocks(access_block(main_access, 1, 2), access_block(s_access, 2, 1))
+}
+
+fn mixed_size_racy_program(main_access: MixedSizeAccess, s_access: MixedSizeAccess) -> Program {
+    racy_program_from_blocks(mixed_size_block(main_access, 1, 2), mixed_size_block(s_access, 2, 1))
+}
+
+/// `main_b1` does the access of the main thread and continues to block 2,
+/// `s_b0` does the access of the second thread and continues to block 1.
+fn racy_program_from_blocks(main_b1: BasicBlock, s_b0: BasicBlock) -> Program {
     // The main thread.
     let main_locals = [<u32>::get_type()];
 
     let main_b0 = block!(storage_live(0), spawn(fn_ptr_internal(1), null(), local(0), 1),);
-    let main_b1 = access_block(main_access, 1, 2);
     let main_b2 = block!(join(load(local(0)), 3),);
     let main_b3 = block!(exit());
     let main = function(Ret::No, 0, &main_locals, &[main_b0, main_b1, main_b2, main_b3]);
 
     // The second thread.
     let s_locals = [<()>::get_type(), <*const ()>::get_type()];
-    let s_b0 = access_block(s_access, 2, 1);
     let s_b1 = block!(return_());
     let s_fun = function(Ret::Yes, 1, &s_locals, &[s_b0, s_b1]);
 
@@ -151,3 +178,57 @@ fn non_atomic_store_non_atomic_store() {
 
     assert!(has_data_race::<BasicMem>(p))
 }
+
+/// Atomic stores of different sizes to the same address race.
+#[test]
+fn mixed_size_atomic_store_atomic_store() {
+    let p = mixed_size_racy_program(
+        MixedSizeAccess(AccessType::Store, 4, 0),
+        MixedSizeAccess(AccessType::Store, 2, 0),
+    );
+
+    assert!(has_data_race::<BasicMem>(p))
+}
+
+/// An atomic store racing with an atomic load that partially overlaps it.
+#[test]
+fn mixed_size_atomic_load_atomic_store() {
+    let p = mixed_size_racy_program(
+        MixedSizeAccess(AccessType::Load, 4, 0),
+        MixedSizeAccess(AccessType::Store, 2, 2),
+    );
+
+    assert!(has_data_race::<BasicMem>(p))
+}
+
+#[test]
+fn mixed_size_atomic_store_atomic_load() {
+    let p = mixed_size_racy_program(
+        MixedSizeAccess(AccessType::Store, 4, 0),
+        MixedSizeAccess(AccessType::Load, 2, 0),
+    );
+
+    assert!(has_data_race::<BasicMem>(p))
+}
+
+/// Mixed-size atomic loads do not race.
+#[test]
+fn mixed_size_atomic_load_atomic_load() {
+    let p = mixed_size_racy_program(
+        MixedSizeAccess(AccessType::Load, 4, 0),
+        MixedSizeAccess(AccessType::Load, 2, 2),
+    );
+
+    assert!(!has_data_race::<BasicMem>(p))
+}
+
+/// Atomic accesses to disjoint parts of the same `u32` do not race.
+#[test]
+fn disjoint_atomic_store_atomic_store() {
+    let p = mixed_size_racy_program(
+        MixedSizeAccess(AccessType::Store, 2, 0),
+        MixedSizeAccess(AccessType::Store, 2, 2),
+    );
+
+    assert!(!has_data_race::<BasicMem>(p))
+}
@@ -0,0 +1,91 @@
+//! Driving programs through the debugger.
+
+use crate::*;
+
+use miniutil::mock_write::MockWrite;
+
+/// Stores 7 in a local, prints it, and exits.
+fn store_and_print() -> Program {
+    let locals = [<u32>::get_type()];
+    let b0 = block!(storage_live(0), assign(local(0), const_int(7u32)), goto(1));
+    let b1 = block!(print(load(local(0)), 2));
+    let b2 = block!(exit());
+    let f = function(Ret::No, 0, &locals, &[b0, b1, b2]);
+    program(&[f])
+}
+
+fn debugger(prog: Program, out: &MockWrite) -> Debugger<BasicMem> {
+    Debugger::new(prog, out.clone(), MockWrite::new(), MockWrite::new()).unwrap()
+}
+
+/// Runs `cmd`, which must not be `quit`.
+fn cmd(dbg: &mut Debugger<BasicMem>, cmd: &str) -> String {
+    dbg.command(cmd).unwrap()
+}
+
+#[test]
+fn step_through_statements() {
+    let mut dbg = debugger(store_and_print(), &MockWrite::new());
+    assert!(cmd(&mut dbg, "where").starts_with("at f0, bb0, statement 0: "));
+    assert!(cmd(&mut dbg, "step").starts_with("at f0, bb0, statement 1: "));
+    // Stepping over the terminator enters the next block.
+    assert!(cmd(&mut dbg, "step 2").starts_with("at f0, bb1, statement 0: "));
+    assert_eq!(dbg.terminated(), None);
+}
+
+#[test]
+fn break_and_continue() {
+    let out = MockWrite::new();
+    let mut dbg = debugger(store_and_print(), &out);
+    assert_eq!(cmd(&mut dbg, "break f0 bb2"), "breakpoint at f0 bb2");
+    assert!(cmd(&mut dbg, "continue").starts_with("at f0, bb2, statement 0: "));
+    // The print has happened, the exit has not.
+    assert_eq!(dbg.terminated(), None);
+    assert_eq!(out.clone().into_strings(), &["7"]);
+
+    assert!(cmd(&mut dbg, "continue").starts_with("program terminated: MachineStop"));
+    assert_eq!(dbg.terminated(), Some(TerminationInfo::MachineStop));
+    // Further steps do nothing.
+    assert!(cmd(&mut dbg, "step").starts_with("program terminated: MachineStop"));
+}
+
+#[test]
+fn inspect_locals_and_memory() {
+    let mut dbg = debugger(store_and_print(), &MockWrite::new());
+    // Only the unit return local `_1` is live at the start.
+    assert_eq!(cmd(&mut dbg, "locals"), "_1 = Tuple(List([]))");
+    cmd(&mut dbg, "step");
+    // The local is live but not yet initialized.
+    assert_eq!(cmd(&mut dbg, "locals"), "_0 = <invalid>\n_1 = Tuple(List([]))");
+    assert_eq!(cmd(&mut dbg, "mem _0"), "__ __ __ __");
+    cmd(&mut dbg, "step");
+    assert!(cmd(&mut dbg, "locals").contains('7'));
+    assert_eq!(cmd(&mut dbg, "mem _0"), "07 00 00 00");
+    assert_eq!(cmd(&mut dbg, "mem 0 2"), "07 00");
+    assert_eq!(cmd(&mut dbg, "mem _2"), "no live local _2");
+}
+
+#[test]
+fn repl_until_quit() {
+    let mut dbg = debugger(store_and_print(), &MockWrite::new());
+    let input = "step\nfrobnicate\nquit\nstep\n";
+    let mut output = Vec::new();
+    dbg.repl(input.as_bytes(), &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("at f0, bb0, statement 1: "));
+    assert!(output.contains("unknown command: frobnicate"));
+    // Nothing after `quit` is executed.
+    assert_eq!(dbg.location().unwrap().stmt, Int::from(1));
+}
+
+/// Programs dumped in the textual format can be debugged, like `minimize --minimize-debug-program=<file>` does.
+#[test]
+fn debug_text_program() {
+    let prog = program_from_text(include_str!("text_print.mr")).unwrap();
+    let out = MockWrite::new();
+    let mut dbg = debugger(prog, &out);
+    assert!(cmd(&mut dbg, "where").starts_with("at f0, bb0, statement 0: "));
+    cmd(&mut dbg, "step");
+    assert_eq!(out.clone().into_strings(), &["42"]);
+    assert!(cmd(&mut dbg, "continue").starts_with("program terminated: MachineStop"));
+}
@@ -0,0 +1,141 @@
+//! Drop glue for nested types, written the way `minimize` translates `drop_in_place`:
+//! each drop function calls the drop functions of the fields, and if one of them
+//! unwinds, a cleanup block drops the remaining fields before unwinding continues.
+
+use crate::*;
+
+const U8_INTTYPE: IntType =
+    IntType { signed: Signedness::Unsigned, size: Size::from_bytes_const(1) };
+
+const DROP_NOISY: u32 = 1;
+const DROP_PAIR: u32 = 2;
+const DROP_ENUM: u32 = 3;
+
+/// `(u32, u32)`
+fn pair_ty() -> Type {
+    tuple_ty(&[(offset(0), <u32>::get_type()), (offset(4), <u32>::get_type())], size(8), align(4))
+}
+
+/// `enum { Empty, Full((u32, u32)) }`, with the tag stored after the data.
+fn option_pair_ty() -> Type {
+    enum_ty::<u8>(
+        &[
+            (0, enum_variant(empty_variant_ty(), &[(offset(8), (U8_INTTYPE, 0.into()))])),
+            (1, enum_variant(full_variant_ty(), &[(offset(8), (U8_INTTYPE, 1.into()))])),
+        ],
+        discriminator_branch::<u8>(offset(8), discriminator_invalid(), &[
+            ((0, 1), discriminator_known(0)),
+            ((1, 2), discriminator_known(1)),
+        ]),
+        size(12),
+        align(4),
+    )
+}
+
+fn empty_variant_ty() -> Type {
+    tuple_ty(&[], size(12), align(4))
+}
+
+fn full_variant_ty() -> Type {
+    tuple_ty(&[(offset(0), pair_ty())], size(12), align(4))
+}
+
+fn full(a: u32, b: u32) -> ValueExpr {
+    let pair = tuple(&[const_int(a), const_int(b)], pair_ty());
+    variant(1, tuple(&[pair], full_variant_ty()), option_pair_ty())
+}
+
+/// The drop function of a `u32` with a noisy `Drop` impl: it prints the value,
+/// and panics if the value is 0.
+fn drop_noisy() -> Function {
+    let locals = [<()>::get_type(), raw_void_ptr_ty()];
+    let val = load(deref(load(local(1)), <u32>::get_type()));
+
+    let b0 = block!(print(val, 1));
+    let b1 = block!(if_(eq(val, const_int(0u32)), 2, 3));
+    let b2 = block!(start_unwind(None));
+    let b3 = block!(return_());
+
+    c_unwind(function(Ret::Yes, 1, &locals, &[b0, b1, b2, b3]))
+}
+
+/// The drop glue of the pair: drops both fields, even if dropping the first one unwinds.
+fn drop_pair() -> Function {
+    let locals = [<()>::get_type(), raw_void_ptr_ty()];
+    let pair = deref(load(local(1)), pair_ty());
+    let field_ptr = |i: u32| by_value(addr_of(field(pair, i), raw_void_ptr_ty()));
+
+    let b0 = block!(call_unwind(DROP_NOISY, &[field_ptr(0)], local(0), Some(1), Some(2)));
+    let b1 = block!(call_unwind(DROP_NOISY, &[field_ptr(1)], local(0), Some(3), None));
+    // Cleanup: drop the remaining field, then keep unwinding.
+    // Unwinding out of a cleanup block aborts.
+    let b2 = block!(call_unwind(DROP_NOISY, &[field_ptr(1)], local(0), Some(4), Some(5)));
+    let b3 = block!(return_());
+    let b4 = block!(resume_unwind());
+    let b5 = block!(unwind_terminate());
+
+    c_unwind(function(Ret::Yes, 1, &locals, &[b0, b1, b2, b3, b4, b5]))
+}
+
+/// The drop glue of the enum: only the `Full` variant has something to drop.
+fn drop_enum() -> Function {
+    let locals = [<()>::get_type(), raw_void_ptr_ty()];
+    let data = downcast(deref(load(local(1)), option_pair_ty()), 1);
+    let pair_ptr = by_value(addr_of(field(data, 0), raw_void_ptr_ty()));
+
+    let b0 = block!(switch_int(
+        get_discriminant(deref(load(local(1)), option_pair_ty())),
+        &[(1u8, 1)],
+        2
+    ));
+    let b1 = block!(call_unwind(DROP_PAIR, &[pair_ptr], local(0), Some(2), None));
+    let b2 = block!(return_());
+
+    c_unwind(function(Ret::Yes, 1, &locals, &[b0, b1, b2]))
+}
+
+/// Builds a program whose `main` stores `val` in a local and drops it.
+/// If dropping unwinds, `main` catches the panic and prints `99`.
+fn drop_program(val: ValueExpr) -> Program {
+    let locals = [option_pair_ty()];
+    let ptr = by_value(addr_of(local(0), raw_void_ptr_ty()));
+
+    let b0 = block!(
+        storage_live(0),
+        assign(local(0), val),
+        call_unwind(DROP_ENUM, &[ptr], unit_place(), Some(1), Some(2))
+    );
+    let b1 = block!(exit());
+    let b2 = block!(stop_unwind(3));
+    let b3 = block!(print(const_int(99u32), 1));
+
+    let main = function(Ret::No, 0, &locals, &[b0, b1, b2, b3]);
+    program(&[main, drop_noisy(), drop_pair(), drop_enum()])
+}
+
+#[test]
+fn drop_nested() {
+    let p = drop_program(full(1, 2));
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["1", "2"], &[]);
+}
+
+#[test]
+fn drop_empty_variant() {
+    let p = drop_program(variant(0, tuple(&[], empty_variant_ty()), option_pair_ty()));
+    assert_eq!(get_stdout::<BasicMem>(p).unwrap(), Vec::<String>::new());
+}
+
+/// When dropping the first field panics, the second field still gets dropped.
+#[test]
+fn drop_unwinds_through_cleanup() {
+    let p = drop_program(full(0, 2));
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["0", "2", "99"], &[]);
+}
+
+/// Panicking in a drop that runs during unwinding aborts the program.
+/// Both drops print their field before panicking.
+#[test]
+fn drop_panics_while_unwinding() {
+    let p = drop_program(full(0, 0));
+    assert_output::<BasicMem>(p, abort_info("panic while unwinding"), &["0", "0"], &[]);
+}
@@ -352,3 +352,97 @@ fn space_optimized_enum_works() {
     let program = program(&[function(Ret::No, 0, &locals, &blocks)]);
     assert_stop::<BasicMem>(program);
 }
+
+/// A space-optimized version of `Option<NonZeroU8>`: variant 0 is untagged and stores a `u8`
+/// in the same byte that variant 1 uses for its tag `0`.
+fn niche_enum_ty() -> Type {
+    let u8_t = int_ty(Signedness::Unsigned, size(1));
+    enum_ty::<u8>(
+        &[
+            (0, enum_variant(u8_t, &[])),
+            (
+                1,
+                enum_variant(tuple_ty(&[], size(1), align(1)), &[(
+                    offset(0),
+                    (U8_INTTYPE, 0.into()),
+                )]),
+            ),
+        ],
+        discriminator_branch::<u8>(offset(0), discriminator_known(0), &[(
+            (0, 1),
+            discriminator_known(1),
+        )]),
+        size(1),
+        align(1),
+    )
+}
+
+/// Getting the discriminant only requires the tag bytes to be initialized, not the data.
+#[test]
+fn discriminant_of_partially_initialized_enum() {
+    // single variant enum with layout (u8 data, u8 tag) and tag 1
+    let enum_ty = enum_ty::<u8>(
+        &[(
+            0,
+            enum_variant(
+                tuple_ty(&[(offset(0), int_ty(Signedness::Unsigned, size(1)))], size(2), align(1)),
+                &[(offset(1), (U8_INTTYPE, 1.into()))],
+            ),
+        )],
+        discriminator_branch::<u8>(offset(1), discriminator_invalid(), &[(
+            (1, 2),
+            discriminator_known(0),
+        )]),
+        size(2),
+        align(1),
+    );
+    let locals = [enum_ty];
+    let blocks = [
+        block!(
+            storage_live(0),
+            // This only initializes the tag byte.
+            set_discriminant(local(0), 0),
+            if_(eq(get_discriminant(local(0)), const_int(0u8)), 1, 2)
+        ),
+        block!(exit()),
+        block!(unreachable()),
+    ];
+    let program = program(&[function(Ret::No, 0, &locals, &blocks)]);
+    assert_stop::<BasicMem>(program);
+}
+
+/// The untagged variant of a niche-encoded enum is identified by its data, without any tag write.
+#[test]
+fn niche_untagged_variant_from_data() {
+    let u8_t = int_ty(Signedness::Unsigned, size(1));
+    let locals = [union_ty(&[(offset(0), niche_enum_ty()), (offset(0), u8_t)], size(1), align(1))];
+    let blocks = [
+        block!(
+            storage_live(0),
+            assign(field(local(0), 1), const_int(42u8)),
+            if_(eq(get_discriminant(field(local(0), 0)), const_int(0u8)), 1, 2)
+        ),
+        block!(exit()),
+        block!(unreachable()),
+    ];
+    let program = program(&[function(Ret::No, 0, &locals, &blocks)]);
+    assert_stop::<BasicMem>(program);
+}
+
+/// Setting the untagged variant of a niche-encoded enum writes nothing,
+/// so the niche byte remains uninitialized and getting the discriminant is UB.
+#[test]
+fn ub_niche_untagged_variant_uninit() {
+    let locals = [niche_enum_ty()];
+    let blocks = [
+        block!(
+            storage_live(0),
+            set_discriminant(local(0), 0),
+            if_(eq(get_discriminant(local(0)), const_int(0u8)), 1, 2)
+        ),
+        block!(exit()),
+        block!(unreachable()),
+    ];
+    let program = program(&[function(Ret::No, 0, &locals, &blocks)]);
+    assert_ub::<BasicMem>(program, "ValueExpr::GetDiscriminant encountered invalid discriminant.");
+}
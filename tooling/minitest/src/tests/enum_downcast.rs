@@ -114,3 +114,65 @@ fn downcasts_give_different_place2() {
     let prog = small_program(locals, stmts);
     assert_stop::<BasicMem>(prog);
 }
+
+/// An enum where both variants store their data in the first byte and the tag in the second byte.
+/// Variant 0 stores a `u8`, variant 1 stores a `bool`.
+fn u8_or_bool_enum() -> Type {
+    let u8_t = int_ty(Signedness::Unsigned, size(1));
+    let variant0 = enum_variant(tuple_ty(&[(offset(0), u8_t)], size(2), align(1)), &[(
+        offset(1),
+        (U8_INTTYPE, 0.into()),
+    )]);
+    let variant1 = enum_variant(tuple_ty(&[(offset(0), bool_ty())], size(2), align(1)), &[(
+        offset(1),
+        (U8_INTTYPE, 1.into()),
+    )]);
+    let discriminator = discriminator_branch::<u8>(offset(1), discriminator_invalid(), &[
+        ((0, 1), discriminator_known(0)),
+        ((1, 2), discriminator_known(1)),
+    ]);
+    enum_ty::<u8>(&[(0.into(), variant0), (1.into(), variant1)], discriminator, size(2), align(1))
+}
+
+/// UB: Reading a field of the wrong variant is UB if the bytes are invalid at that field's type.
+#[test]
+fn wrong_variant_read_invalid() {
+    let enum_ty = u8_or_bool_enum();
+    let u8_t = int_ty(Signedness::Unsigned, size(1));
+    let variant0 = tuple(&[const_int(2u8)], tuple_ty(&[(offset(0), u8_t)], size(2), align(1)));
+
+    let locals = &[enum_ty, bool_ty()];
+    let stmts = &[
+        storage_live(0),
+        storage_live(1),
+        assign(local(0), variant(0, variant0, enum_ty)),
+        // Creating the place for variant 1 is fine, but `2` is not a valid `bool`.
+        assign(local(1), load(field(downcast(local(0), 1), 0))),
+    ];
+    let prog = small_program(locals, stmts);
+    assert_ub::<BasicMem>(
+        prog,
+        "load at type Bool but the data in memory violates the language invariant",
+    );
+}
+
+/// Works: Writing through a downcast to the wrong variant does not change the discriminant.
+#[test]
+fn wrong_variant_write_keeps_discriminant() {
+    let enum_ty = u8_or_bool_enum();
+    let u8_t = int_ty(Signedness::Unsigned, size(1));
+    let variant0 = tuple(&[const_int(2u8)], tuple_ty(&[(offset(0), u8_t)], size(2), align(1)));
+
+    let locals = &[enum_ty];
+    let b0 = block!(
+        storage_live(0),
+        assign(local(0), variant(0, variant0, enum_ty)),
+        assign(field(downcast(local(0), 1), 0), const_bool(true)),
+        if_(eq(get_discriminant(local(0)), const_int(0u8)), 1, 2)
+    );
+    let b1 = block!(exit());
+    let b2 = block!(unreachable());
+    let f = function(Ret::No, 0, locals, &[b0, b1, b2]);
+    let prog = program(&[f]);
+    assert_stop::<BasicMem>(prog);
+}
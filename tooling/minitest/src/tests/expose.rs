@@ -35,3 +35,237 @@ fn requires_pointer() {
         "invalid argument for `PointerExposeProvenance` intrinsic: not a thin pointer",
     );
 }
+
+/// Casts the address of `x` back to a pointer after calling `expose`,
+/// and prints what the pointer points to, offset by `offset` bytes.
+/// `x` is an array `[1, 2]`, and the address points `addr_offset` bytes into it.
+fn roundtrip_program(
+    addr_offset: usize,
+    offset: isize,
+    expose: impl FnOnce(&mut FunctionBuilder, PlaceExpr),
+) -> Program {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<[u32; 2]>();
+    let addr = f.declare_local::<usize>();
+    let ptr = f.declare_local::<*const u32>();
+    f.storage_live(x);
+    f.storage_live(addr);
+    f.storage_live(ptr);
+    f.assign(x, array(&[const_int(1u32), const_int(2u32)], <u32>::get_type()));
+    expose(&mut f, x);
+    let x_ptr = addr_of(x, <*const u32>::get_type());
+    f.assign(
+        addr,
+        transmute(ptr_offset(x_ptr, const_int(addr_offset), InBounds::Yes), <usize>::get_type()),
+    );
+    f.with_exposed_provenance(ptr, load(addr));
+    let ptr = ptr_offset(load(ptr), const_int(offset), InBounds::No);
+    f.print(load(deref(ptr, <u32>::get_type())));
+    f.exit();
+    let f = p.finish_function(f);
+
+    p.finish_program(f)
+}
+
+fn expose_x(f: &mut FunctionBuilder, x: PlaceExpr) {
+    let exposed = f.declare_local::<usize>();
+    f.storage_live(exposed);
+    f.expose_provenance(exposed, addr_of(x, <*const u32>::get_type()));
+}
+
+#[test]
+fn roundtrip() {
+    let p = roundtrip_program(4, 0, expose_x);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["2"], &[]);
+}
+
+/// A one-past-the-end address gets the provenance of its allocation.
+#[test]
+fn roundtrip_one_past_the_end() {
+    let p = roundtrip_program(8, -4, expose_x);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["2"], &[]);
+}
+
+#[test]
+fn roundtrip_not_exposed() {
+    let p = roundtrip_program(4, 0, |_f, _x| {});
+    assert_ub::<BasicMem>(p, "dereferencing pointer without provenance");
+}
+
+/// Exposing some other allocation does not help.
+/// The cast could pick the provenance of `y`, so only the lookahead can tell that this is UB.
+#[test]
+fn roundtrip_other_exposed() {
+    let p = roundtrip_program(4, 0, |f, _x| {
+        let y = f.declare_local::<u32>();
+        let exposed = f.declare_local::<usize>();
+        f.storage_live(y);
+        f.storage_live(exposed);
+        f.expose_provenance(exposed, addr_of(y, <*const u32>::get_type()));
+    });
+    let output = run_with_lookahead(p, MachineConfig::DEFAULT, 1_000);
+    let TerminationInfo::Ub(msg, _) = output.info else {
+        panic!("expected UB, but program terminated with {:?}", output.info)
+    };
+    assert_eq!(msg.get_internal(), "dereferencing pointer without provenance");
+}
+
+/// Declares three adjacent `u32` locals with values 1, 2, 3 and exposes all of them.
+/// `AddrStrategy::Adversarial` places them right next to each other.
+fn adjacent_locals(f: &mut FunctionBuilder) -> [PlaceExpr; 3] {
+    let locals = [(); 3].map(|()| f.declare_local::<u32>());
+    let exposed = f.declare_local::<usize>();
+    for local in locals {
+        f.storage_live(local);
+    }
+    f.storage_live(exposed);
+    for (local, val) in locals.into_iter().zip(1u32..) {
+        f.assign(local, const_int(val));
+        f.expose_provenance(exposed, addr_of(local, <*const u32>::get_type()));
+    }
+    locals
+}
+
+/// Casts the address of the second local from `adjacent_locals` to a pointer,
+/// and prints what it points to, offset by `offset` bytes.
+fn adjacent_program(offset: isize, inbounds: InBounds) -> Program {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let [_x, y, _z] = adjacent_locals(&mut f);
+    let addr = f.declare_local::<usize>();
+    let ptr = f.declare_local::<*const u32>();
+    f.storage_live(addr);
+    f.storage_live(ptr);
+    f.assign(addr, transmute(addr_of(y, <*const u32>::get_type()), <usize>::get_type()));
+    f.with_exposed_provenance(ptr, load(addr));
+    let ptr = ptr_offset(load(ptr), const_int(offset), inbounds);
+    f.print(load(deref(ptr, <u32>::get_type())));
+    f.exit();
+    let f = p.finish_function(f);
+
+    p.finish_program(f)
+}
+
+fn adversarial() -> MachineConfig {
+    MachineConfig {
+        alloc: AllocConfig { addr_strategy: AddrStrategy::Adversarial, ..AllocConfig::DEFAULT },
+        ..MachineConfig::DEFAULT
+    }
+}
+
+/// Runs `prog` with `config`, letting casts look ahead up to `fuel` steps.
+fn run_with_lookahead(prog: Program, config: MachineConfig, fuel: u64) -> Output {
+    let mut interp = Interpreter::<BasicMem>::new_with_config(prog, config).unwrap();
+    interp.set_int2ptr_lookahead(Some(fuel));
+    interp.run_to_completion();
+    interp.output().unwrap()
+}
+
+/// The address is both the start of `y` and the end of `x`; using it directly needs the provenance of `y`.
+#[test]
+fn roundtrip_ambiguous_start() {
+    let output =
+        run_program_with_config::<BasicMem>(adjacent_program(0, InBounds::Yes), adversarial());
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    assert_eq!(output.stdout, ["2"]);
+}
+
+/// The address is both the start of `y` and the end of `x`; moving back into `x` needs the provenance of `x`.
+/// The other option is only ruled out by the in-bounds offset after the cast, so this needs lookahead.
+/// The output of the lookahead must not show up.
+#[test]
+fn roundtrip_ambiguous_end() {
+    let output = run_with_lookahead(adjacent_program(-4, InBounds::Yes), adversarial(), 1_000);
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    assert_eq!(output.stdout, ["1"]);
+}
+
+/// Without the lookahead, the cast takes its first option, the provenance of `y`.
+/// That is only a guess, so the UB it leads to is not reported as UB of the program.
+#[test]
+fn roundtrip_ambiguous_end_without_lookahead() {
+    let output =
+        run_program_with_config::<BasicMem>(adjacent_program(-4, InBounds::Yes), adversarial());
+    let TerminationInfo::Unsupported(msg) = output.info else {
+        panic!("expected the guess to be reported, but program terminated with {:?}", output.info)
+    };
+    assert_eq!(
+        msg.get_internal(),
+        "UB after guessing the provenance of an integer-to-pointer cast: \
+         dereferencing pointer outside the bounds of its allocation"
+    );
+}
+
+/// UB that does not involve the guessed provenance is reported as UB of the program.
+#[test]
+fn guess_does_not_hide_other_ub() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let [_x, y, _z] = adjacent_locals(&mut f);
+    let addr = f.declare_local::<usize>();
+    let ptr = f.declare_local::<*const u32>();
+    f.storage_live(addr);
+    f.storage_live(ptr);
+    f.assign(addr, transmute(addr_of(y, <*const u32>::get_type()), <usize>::get_type()));
+    f.with_exposed_provenance(ptr, load(addr));
+    f.unreachable();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+
+    let output = run_program_with_config::<BasicMem>(p, adversarial());
+    let TerminationInfo::Ub(msg, _) = output.info else {
+        panic!("expected UB, but program terminated with {:?}", output.info)
+    };
+    assert_eq!(msg.get_internal(), "reached unreachable code");
+}
+
+/// If every option runs into UB, the lookahead knows the program has UB no matter which option `predict` picks.
+#[test]
+fn roundtrip_ambiguous_all_options_ub() {
+    let output = run_with_lookahead(adjacent_program(-8, InBounds::Yes), adversarial(), 1_000);
+    let TerminationInfo::Ub(msg, _) = output.info else {
+        panic!("expected UB, but program terminated with {:?}", output.info)
+    };
+    assert_eq!(msg.get_internal(), "dereferencing pointer outside the bounds of its allocation");
+}
+
+/// With several exposed provenances, the cast can also pick one the address is not even close to,
+/// if the pointer is moved there before it is used.
+#[test]
+fn roundtrip_other_exposed_used() {
+    let output = run_with_lookahead(adjacent_program(4, InBounds::No), adversarial(), 1_000);
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    assert_eq!(output.stdout, ["3"]);
+}
+
+/// If no option terminates within the fuel, the lookahead cannot tell which one avoids UB.
+#[test]
+fn lookahead_out_of_fuel() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let [_x, y, _z] = adjacent_locals(&mut f);
+    let addr = f.declare_local::<usize>();
+    let ptr = f.declare_local::<*const u32>();
+    f.storage_live(addr);
+    f.storage_live(ptr);
+    f.assign(addr, transmute(addr_of(y, <*const u32>::get_type()), <usize>::get_type()));
+    f.with_exposed_provenance(ptr, load(addr));
+    f.while_(const_bool(true), |_f| {});
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+
+    let output = run_with_lookahead(p, adversarial(), 50);
+    let TerminationInfo::Unsupported(msg) = output.info else {
+        panic!("expected the lookahead to give up, but program terminated with {:?}", output.info)
+    };
+    assert_eq!(
+        msg.get_internal(),
+        "the lookahead for an integer-to-pointer cast did not terminate within 50 steps"
+    );
+}
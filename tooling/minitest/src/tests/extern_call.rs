@@ -0,0 +1,395 @@
+//! Calls to extern functions, provided by mock environments.
+
+use crate::*;
+
+/// Calls the extern function `name` with `args`, prints the result of type `T`, and exits.
+fn call_and_print<T: TypeConv>(name: &str, args: &[ValueExpr]) -> Program {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<T>();
+    f.storage_live(x);
+    f.extern_call(x, name, args);
+    f.print(load(x));
+    f.exit();
+    let f = p.finish_function(f);
+
+    p.finish_program(f)
+}
+
+fn int_arg<M: Memory>(call: &ExternCall<M>, i: usize) -> Int {
+    let (Value::Int(i), _) = call.arguments.index_at(i) else {
+        panic!("argument is not an integer")
+    };
+    i
+}
+
+#[test]
+fn extern_call_returns() {
+    let mut env = MockEnv::<BasicMem>::new();
+    env.register("add", |_machine, call| Ok(Value::Int(int_arg(&call, 0) + int_arg(&call, 1))));
+
+    let p = call_and_print::<u32>("add", &[const_int(40u32), const_int(2u32)]);
+    let output = run_program_with_env(p, &mut env);
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    assert_eq!(output.stdout, &["42"]);
+}
+
+/// Calling a function the environment does not provide stops the machine at the call.
+#[test]
+fn extern_call_not_provided() {
+    let p = call_and_print::<u32>("add", &[]);
+    let unsupported = TerminationInfo::Unsupported(prelude::String::from_internal(
+        "extern function `add` is not provided by the environment".to_string(),
+    ));
+
+    let (info, location) = run_program_with_location::<BasicMem>(p);
+    assert_eq!(info, unsupported);
+    assert!(matches!(
+        location.unwrap().operation(p),
+        Some(Operation::Terminator(Terminator::ExternCall { .. }))
+    ));
+
+    let output = run_program_with_env(p, &mut MockEnv::<BasicMem>::new());
+    assert_eq!(output.info, unsupported);
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn extern_call_abort() {
+    let mut env = MockEnv::<BasicMem>::new();
+    env.register("abort", |_machine, _call| {
+        Err(TerminationInfo::Abort(prelude::String::from_internal("abort() called".to_string())))
+    });
+
+    let p = call_and_print::<()>("abort", &[]);
+    let output = run_program_with_env(p, &mut env);
+    assert_eq!(
+        output.info,
+        TerminationInfo::Abort(prelude::String::from_internal("abort() called".to_string()))
+    );
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn extern_call_invalid_return_value() {
+    let mut env = MockEnv::<BasicMem>::new();
+    env.register("two", |_machine, _call| Ok(Value::Int(Int::from(2))));
+
+    let p = call_and_print::<bool>("two", &[]);
+    let output = run_program_with_env(p, &mut env);
+    assert_eq!(
+        output.info,
+        TerminationInfo::Unsupported(prelude::String::from_internal(
+            "extern function `two` returned an invalid value for its return type".to_string()
+        ))
+    );
+}
+
+/// `malloc` returns heap memory that the program can use and free.
+#[test]
+fn extern_call_malloc() {
+    let mut env = MockEnv::<BasicMem>::new();
+    env.register("malloc", |machine, call| {
+        let size = Size::from_bytes(int_arg(&call, 0)).unwrap();
+        let ptr =
+            machine.extern_allocate(size, Align::from_bytes(8).unwrap()).get_internal()?.unwrap();
+        Ok(Value::Ptr(ptr.widen(None)))
+    });
+
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let ptr = f.declare_local::<*mut u32>();
+    f.storage_live(ptr);
+    f.extern_call(ptr, "malloc", &[const_int(4usize)]);
+    f.assign(deref(load(ptr), <u32>::get_type()), const_int(7u32));
+    f.print(load(deref(load(ptr), <u32>::get_type())));
+    f.deallocate(load(ptr), const_int(4usize), const_int(8usize));
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+
+    let output = run_program_with_env(p, &mut env);
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    assert_eq!(output.stdout, &["7"]);
+}
+
+/// `getenv` writes a C string into fresh memory and returns a pointer to it.
+#[test]
+fn extern_call_getenv() {
+    let mut env = MockEnv::<BasicMem>::new();
+    env.register("getenv", |machine, _call| {
+        let bytes: List<AbstractByte<_>> =
+            "on\0".bytes().map(|b| AbstractByte::Init(b, None)).collect();
+        let size = Size::from_bytes(bytes.len()).unwrap();
+        let ptr = machine.extern_allocate(size, Align::ONE).get_internal()?.unwrap();
+        machine.extern_store(ptr, bytes).get_internal()?;
+        Ok(Value::Ptr(ptr.widen(None)))
+    });
+
+    let p = {
+        let mut p = ProgramBuilder::new();
+        let mut f = p.declare_function();
+        let ptr = f.declare_local::<*const u8>();
+        f.storage_live(ptr);
+        f.extern_call(ptr, "getenv", &[]);
+        f.print(load(deref(load(ptr), <u8>::get_type())));
+        f.exit();
+        let f = p.finish_function(f);
+        p.finish_program(f)
+    };
+
+    let output = run_program_with_env(p, &mut env);
+    // The string is never freed.
+    assert_eq!(output.info, TerminationInfo::MemoryLeak);
+    assert_eq!(output.stdout, &["111"]);
+}
+
+/// Passes a reference of type `ref_ty` to a local that contains 0 to the unknown function `opaque`,
+/// then prints the local.
+fn opaque_call_program(ref_ty: Type) -> Program {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    f.storage_live(x);
+    f.assign(x, const_int(0u32));
+    f.extern_call(unit_place(), "opaque", &[addr_of(x, ref_ty)]);
+    f.print(load(x));
+    f.exit();
+    let f = p.finish_function(f);
+    p.finish_program(f)
+}
+
+/// Unknown code may change the memory behind a mutable reference it is passed.
+#[test]
+fn extern_call_havoc_mutable_reference() {
+    let mut env = MockEnv::<BasicMem>::new();
+    env.havoc_unregistered(0);
+
+    let p = opaque_call_program(ref_mut_ty_default_markers_for(<u32>::get_type()));
+    let output = run_program_with_env(p, &mut env);
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    assert_ne!(output.stdout, &["0"]);
+}
+
+/// Unknown code cannot change the memory behind a shared reference it is passed.
+#[test]
+fn extern_call_havoc_shared_reference() {
+    let mut env = MockEnv::<BasicMem>::new();
+    env.havoc_unregistered(0);
+
+    let p = opaque_call_program(ref_ty_default_markers_for(<u32>::get_type()));
+    let output = run_program_with_env(p, &mut env);
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    assert_eq!(output.stdout, &["0"]);
+}
+
+/// Unknown code can read, but not write, through a shared reference, and its accesses are recorded.
+#[test]
+fn extern_call_havoc_records_accesses() {
+    let mut env = MockEnv::<BasicMem>::new();
+    env.havoc_unregistered(0);
+
+    let p = opaque_call_program(ref_ty_default_markers_for(<u32>::get_type()));
+    run_program_with_env(p, &mut env);
+    let [accesses] = env.havoc_accesses() else { panic!("expected a single call") };
+    assert_eq!(accesses.name, "opaque");
+    assert!(matches!(accesses.reads[..], [(_, 4)]));
+    assert!(accesses.writes.is_empty());
+}
+
+/// Unknown code may change the entire allocation a raw pointer it is passed points to.
+#[test]
+fn extern_call_havoc_raw_pointer() {
+    let mut env = MockEnv::<BasicMem>::new();
+    env.havoc_unregistered(0);
+
+    let p = opaque_call_program(<*mut u32>::get_type());
+    let output = run_program_with_env(p, &mut env);
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    assert_ne!(output.stdout, &["0"]);
+}
+
+/// Unknown code may change memory the program exposed, even if it is not passed a pointer to it.
+#[test]
+fn extern_call_havoc_exposed() {
+    let mut env = MockEnv::<BasicMem>::new();
+    env.havoc_unregistered(0);
+
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let addr = f.declare_local::<usize>();
+    f.storage_live(x);
+    f.storage_live(addr);
+    f.assign(x, const_int(0u32));
+    f.expose_provenance(addr, addr_of(x, <*const u32>::get_type()));
+    f.extern_call(unit_place(), "opaque", &[]);
+    f.print(load(x));
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+
+    let output = run_program_with_env(p, &mut env);
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    assert_ne!(output.stdout, &["0"]);
+}
+
+/// Stores a pointer to a local that contains 0 in another local, passes a reference of type `ref_ty`
+/// to that other local to the unknown function `opaque`, then prints what the pointer points to.
+fn opaque_transitive_program(ref_ty: Type) -> Program {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let ptr = f.declare_local::<*mut u32>();
+    f.storage_live(x);
+    f.storage_live(ptr);
+    f.assign(x, const_int(0u32));
+    f.assign(ptr, addr_of(x, <*mut u32>::get_type()));
+    f.extern_call(unit_place(), "opaque", &[addr_of(ptr, ref_ty)]);
+    f.print(load(deref(load(ptr), <u32>::get_type())));
+    f.exit();
+    let f = p.finish_function(f);
+    p.finish_program(f)
+}
+
+/// Unknown code follows the pointers stored in memory it can write to, and may change what they point to.
+#[test]
+fn extern_call_havoc_transitive_mutable() {
+    let mut env = MockEnv::<BasicMem>::new();
+    env.havoc_unregistered(0);
+
+    // The pointer itself gets overwritten as well, so we only check that the program still runs.
+    let p = opaque_transitive_program(ref_mut_ty_default_markers_for(<*mut u32>::get_type()));
+    run_program_with_env(p, &mut env);
+    let [accesses] = env.havoc_accesses() else { panic!("expected a single call") };
+    assert_eq!(accesses.writes.len(), 2);
+}
+
+/// Memory unknown code can only reach through a shared reference stays read-only: it changes
+/// neither the pointer behind the shared reference, nor what that pointer points to.
+#[test]
+fn extern_call_havoc_transitive_shared() {
+    let mut env = MockEnv::<BasicMem>::new();
+    env.havoc_unregistered(0);
+
+    let p = opaque_transitive_program(ref_ty_default_markers_for(<*mut u32>::get_type()));
+    let output = run_program_with_env(p, &mut env);
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    assert_eq!(output.stdout, &["0"]);
+    let [accesses] = env.havoc_accesses() else { panic!("expected a single call") };
+    assert_eq!(accesses.reads.len(), 2);
+    assert!(accesses.writes.is_empty());
+}
+
+/// Unknown code returns an arbitrary, but valid value.
+#[test]
+fn extern_call_havoc_return_value() {
+    let mut env = MockEnv::<BasicMem>::new();
+    env.havoc_unregistered(0);
+
+    let p = call_and_print::<bool>("opaque", &[]);
+    let output = run_program_with_env(p, &mut env);
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    assert!(output.stdout == ["true"] || output.stdout == ["false"]);
+}
+
+/// Registered functions are still called when the unregistered ones havoc.
+#[test]
+fn extern_call_havoc_registered() {
+    let mut env = MockEnv::<BasicMem>::new();
+    env.havoc_unregistered(0);
+    env.register("two", |_machine, _call| Ok(Value::Int(Int::from(2))));
+
+    let p = call_and_print::<u32>("two", &[]);
+    let output = run_program_with_env(p, &mut env);
+    assert_eq!(output.stdout, &["2"]);
+}
+
+/// Writes the bytes of `msg` to file descriptor `fd` with `write`, then prints what `write` returned.
+fn write_program(fd: i32, msg: &[u8]) -> Program {
+    let bytes: Vec<ValueExpr> = msg.iter().map(|&b| const_int(b)).collect();
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let buf = f.declare_local_with_ty(array_ty(<u8>::get_type(), msg.len()));
+    let written = f.declare_local::<isize>();
+    f.storage_live(buf);
+    f.storage_live(written);
+    f.assign(buf, array(&bytes, <u8>::get_type()));
+    f.extern_call(written, "write", &[
+        const_int(fd),
+        addr_of(buf, <*const u8>::get_type()),
+        const_int(msg.len()),
+    ]);
+    f.print(load(written));
+    f.exit();
+    let f = p.finish_function(f);
+    p.finish_program(f)
+}
+
+#[test]
+fn extern_call_write_stdout() {
+    let mut env = MockEnv::<BasicMem>::new();
+    env.register_write();
+
+    let output = run_program_with_env(write_program(1, b"hi\n"), &mut env);
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    assert_eq!(output.stdout, &["hi", "3"]);
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn extern_call_write_stderr() {
+    let mut env = MockEnv::<BasicMem>::new();
+    env.register_write();
+
+    let output = run_program_with_env(write_program(2, b"hi\n"), &mut env);
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    assert_eq!(output.stdout, &["3"]);
+    assert_eq!(output.stderr, &["hi"]);
+}
+
+/// The written bytes are decoded as UTF-8, so multi-byte characters come out whole.
+#[test]
+fn extern_call_write_utf8() {
+    let mut env = MockEnv::<BasicMem>::new();
+    env.register_write();
+
+    let output = run_program_with_env(write_program(1, "grüße ✓\n".as_bytes()), &mut env);
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    assert_eq!(output.stdout, &["grüße ✓", "12"]);
+}
+
+/// Malformed UTF-8 becomes the replacement character.
+#[test]
+fn extern_call_write_invalid_utf8() {
+    let mut env = MockEnv::<BasicMem>::new();
+    env.register_write();
+
+    let output = run_program_with_env(write_program(1, b"a\xC3b\xFF\n"), &mut env);
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    assert_eq!(output.stdout, &["a\u{FFFD}b\u{FFFD}", "5"]);
+}
+
+/// Overlong encodings are malformed, too, so they do not sneak in a NUL character.
+#[test]
+fn extern_call_write_overlong_utf8() {
+    let mut env = MockEnv::<BasicMem>::new();
+    env.register_write();
+
+    let output = run_program_with_env(write_program(1, b"a\xC0\x80b\n"), &mut env);
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    assert_eq!(output.stdout, &["a\u{FFFD}\u{FFFD}b", "5"]);
+}
+
+/// Other file descriptors do not exist, so `write` fails.
+#[test]
+fn extern_call_write_unknown_fd() {
+    let mut env = MockEnv::<BasicMem>::new();
+    env.register_write();
+
+    let output = run_program_with_env(write_program(3, b"hi\n"), &mut env);
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    assert_eq!(output.stdout, &["-1"]);
+    assert!(output.stderr.is_empty());
+}
@@ -0,0 +1,186 @@
+use crate::*;
+
+/// Reinterprets an `f32` expression as its bit pattern.
+fn bits32(v: ValueExpr) -> ValueExpr {
+    transmute(v, <u32>::get_type())
+}
+
+#[test]
+fn arith_works() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+
+    f.assume(eq(float_add(const_f64(0.1), const_f64(0.2)), const_f64(0.30000000000000004)));
+    f.assume(eq(float_sub(const_f64(1.0), const_f64(0.25)), const_f64(0.75)));
+    f.assume(eq(float_mul(const_f32(1.5), const_f32(2.0)), const_f32(3.0)));
+    f.assume(eq(float_div(const_f32(1.0), const_f32(3.0)), const_f32(1.0 / 3.0)));
+    f.assume(eq(float_rem(const_f64(7.5), const_f64(2.0)), const_f64(1.5)));
+    f.assume(eq(float_rem(const_f64(-7.5), const_f64(2.0)), const_f64(-1.5)));
+    f.assume(eq(float_div(const_f64(1.0), const_f64(0.0)), const_f64(f64::INFINITY)));
+
+    f.exit();
+
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
+
+#[test]
+fn comparisons() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+
+    f.assume(lt(const_f32(1.0), const_f32(2.0)));
+    f.assume(ge(const_f32(2.0), const_f32(2.0)));
+    // Zeros compare equal independent of their sign.
+    f.assume(eq(const_f64(0.0), const_f64(-0.0)));
+    // NaN is unordered: all comparisons except for `!=` are false, even with itself.
+    f.assume(not(eq(const_f64(f64::NAN), const_f64(f64::NAN))));
+    f.assume(ne(const_f64(f64::NAN), const_f64(f64::NAN)));
+    f.assume(not(lt(const_f64(f64::NAN), const_f64(1.0))));
+    f.assume(not(ge(const_f64(f64::NAN), const_f64(1.0))));
+
+    f.exit();
+
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
+
+#[test]
+fn neg_flips_sign_bit() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+
+    f.assume(eq(bits32(float_neg(const_f32(0.0))), const_int(0x8000_0000u32)));
+    f.assume(eq(float_neg(const_f32(-2.5)), const_f32(2.5)));
+    // Negation is exact even for NaNs, the payload is preserved.
+    f.assume(eq(
+        bits32(float_neg(const_f32(f32::from_bits(0x7f80_0001)))),
+        const_int(0xff80_0001u32),
+    ));
+
+    f.exit();
+
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
+
+#[test]
+fn encode_decode() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<f64>();
+    let y = f.declare_local::<u64>();
+
+    f.storage_live(x);
+    f.storage_live(y);
+    f.assign(x, const_f64(1.0));
+    f.assign(y, transmute(load(x), <u64>::get_type()));
+    f.assume(eq(load(y), const_int(0x3ff0_0000_0000_0000u64)));
+    f.assign(x, transmute(const_int(0x4000_0000_0000_0000u64), <f64>::get_type()));
+    f.assume(eq(load(x), const_f64(2.0)));
+    f.exit();
+
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
+
+#[test]
+fn uninit_float() {
+    let locals = [<f32>::get_type(), <f32>::get_type()];
+
+    let b0 = block!(storage_live(0), storage_live(1), assign(local(1), load(local(0))), exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0]);
+    let p = program(&[f]);
+    assert_ub::<BasicMem>(
+        p,
+        "load at type Float(FloatType { size: Size(4 bytes) }) but the data in memory violates the language invariant",
+    );
+}
+
+/// A NaN produced from non-NaN inputs is a quiet NaN with empty payload, but its sign is arbitrary.
+#[test]
+fn nan_from_non_nan_inputs() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let nan = f.declare_local::<u32>();
+
+    f.storage_live(nan);
+    f.assign(nan, bits32(float_div(const_f32(0.0), const_f32(0.0))));
+    f.assume(bool_or(
+        eq(load(nan), const_int(0x7fc0_0000u32)),
+        eq(load(nan), const_int(0xffc0_0000u32)),
+    ));
+    f.exit();
+
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop_always::<BasicMem>(p, 32);
+}
+
+/// A NaN input may have its payload propagated (made quiet), or the result can be the preferred NaN.
+#[test]
+fn nan_payload_propagation() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+
+    let nan = float_add(const_f32(f32::from_bits(0x7f80_0001)), const_f32(1.0));
+    // Ignore the sign.
+    let payload = bit_and(bits32(nan), const_int(0x7fff_ffffu32));
+    f.print(payload);
+    f.exit();
+
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    let mut seen = std::collections::HashSet::new();
+    for _ in 0..64 {
+        let out = get_stdout::<BasicMem>(p).unwrap();
+        assert_eq!(out.len(), 1);
+        seen.insert(out[0].clone());
+    }
+    let expected: std::collections::HashSet<String> =
+        [0x7fc0_0000u32, 0x7fc0_0001u32].iter().map(|x| x.to_string()).collect();
+    assert_eq!(seen, expected);
+}
+
+#[test]
+fn cmp_on_floats_ill_formed() {
+    let locals = [<i8>::get_type()];
+
+    let b0 = block!(storage_live(0), assign(local(0), cmp(const_f32(1.0), const_f32(2.0))), exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0]);
+    let p = program(&[f]);
+    assert_ill_formed::<BasicMem>(p, "BinOp::Rel: `Cmp` on floats");
+}
+
+#[test]
+fn float_op_on_ints_ill_formed() {
+    let locals = [<u32>::get_type()];
+
+    let b0 = block!(
+        storage_live(0),
+        assign(local(0), float_add(const_int(1u32), const_int(2u32))),
+        exit()
+    );
+
+    let f = function(Ret::No, 0, &locals, &[b0]);
+    let p = program(&[f]);
+    assert_ill_formed::<BasicMem>(p, "BinOp::Float: invalid left type");
+}
@@ -0,0 +1,110 @@
+//! The `Freeze` intrinsic.
+
+use crate::*;
+
+/// Declares a `u16` local of which only the first byte is initialized, to `1`.
+/// If `freeze`, the local is frozen. Then prints the low byte and whether the local equals itself.
+fn half_init_program(freeze: bool) -> Program {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u16>();
+    f.storage_live(x);
+    f.assign(deref(addr_of(x, <*mut u8>::get_type()), <u8>::get_type()), const_int(1u8));
+    if freeze {
+        f.freeze(addr_of(x, <*mut u16>::get_type()), <u16>::get_type());
+    }
+    f.print(load(deref(addr_of(x, <*mut u8>::get_type()), <u8>::get_type())));
+    f.print(eq(load(x), load(x)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    p.finish_program(f)
+}
+
+#[test]
+fn freeze_partially_uninit() {
+    assert_eq!(get_stdout::<BasicMem>(half_init_program(true)).unwrap(), &["1", "true"]);
+}
+
+#[test]
+fn partially_uninit_without_freeze() {
+    assert_ub::<BasicMem>(
+        half_init_program(false),
+        "load at type Int(IntType { signed: Unsigned, size: Size(2 bytes) }) but the data in memory violates the language invariant",
+    );
+}
+
+/// Every run can pick different bytes.
+#[test]
+fn freeze_is_nondeterministic() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u8>();
+    f.storage_live(x);
+    f.freeze(addr_of(x, <*mut u8>::get_type()), <u8>::get_type());
+    f.print(load(x));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    let mut seen = std::collections::HashSet::new();
+    for _ in 0..32 {
+        seen.insert(get_stdout::<BasicMem>(p).unwrap().join(","));
+    }
+    assert!(seen.len() > 1);
+}
+
+/// Initialized bytes keep their value and provenance.
+#[test]
+fn freeze_init() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let ptr = f.declare_local::<*const u32>();
+    f.storage_live(x);
+    f.storage_live(ptr);
+    f.assign(x, const_int(42u32));
+    f.assign(ptr, addr_of(x, <*const u32>::get_type()));
+    f.freeze(addr_of(ptr, <*mut *const u32>::get_type()), <*const u32>::get_type());
+    f.print(load(deref(load(ptr), <u32>::get_type())));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["42"], &[]);
+}
+
+#[test]
+fn freeze_dangling() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let ptr = f.declare_local::<*mut u32>();
+    f.storage_live(x);
+    f.storage_live(ptr);
+    f.assign(ptr, addr_of(x, <*mut u32>::get_type()));
+    f.storage_dead(x);
+    f.freeze(load(ptr), <u32>::get_type());
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(p, "dereferencing pointer to dead allocation");
+}
+
+#[test]
+fn freeze_not_a_pointer() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    f.freeze(const_int(0usize), <u32>::get_type());
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(p, "invalid argument to `Freeze` intrinsic: not a thin pointer");
+}
@@ -0,0 +1,75 @@
+//! Running programs incrementally with a step budget.
+
+use crate::*;
+
+/// Prints 0, 1, 2, ... forever.
+fn count_forever() -> Program {
+    let locals = [<u32>::get_type()];
+    let b0 = block!(storage_live(0), assign(local(0), const_int(0u32)), goto(1));
+    let b1 = block!(print(load(local(0)), 2));
+    let b2 = block!(assign(local(0), add(load(local(0)), const_int(1u32))), goto(1));
+    let f = function(Ret::No, 0, &locals, &[b0, b1, b2]);
+    program(&[f])
+}
+
+#[test]
+fn stop_within_fuel() {
+    let p = small_program(&[], &[]);
+    assert_stop_within::<BasicMem>(p, 10);
+}
+
+#[test]
+#[should_panic = "program did not stop within 100 steps"]
+fn infinite_loop_runs_out_of_fuel() {
+    assert_stop_within::<BasicMem>(count_forever(), 100);
+}
+
+/// Running in several slices behaves like one long run.
+#[test]
+fn resume_after_running_out_of_fuel() {
+    let mut interp = Interpreter::<BasicMem>::new(count_forever()).unwrap();
+    assert_eq!(interp.run(20), None);
+    assert_eq!(interp.steps(), 20);
+    let printed = interp.stdout().len();
+    assert!(printed > 0);
+
+    assert_eq!(interp.run(20), None);
+    assert_eq!(interp.steps(), 40);
+    let stdout = interp.stdout();
+    assert!(stdout.len() > printed);
+    for (i, line) in stdout.iter().enumerate() {
+        assert_eq!(*line, i.to_string());
+    }
+    assert!(interp.output().is_none());
+}
+
+/// Once the program terminated, running it again does nothing.
+#[test]
+fn run_after_termination() {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    f.print(const_int(42u32));
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+
+    let mut interp = Interpreter::<BasicMem>::new(p).unwrap();
+    assert_eq!(interp.run(100), Some(TerminationInfo::MachineStop));
+    let steps = interp.steps();
+    assert!(steps < 100);
+    assert_eq!(interp.run(100), Some(TerminationInfo::MachineStop));
+    assert_eq!(interp.steps(), steps);
+
+    let output = interp.output().unwrap();
+    assert_eq!(output.stdout, &["42"]);
+}
+
+#[test]
+fn ill_formed_program_does_not_start() {
+    let locals = [<u32>::get_type()];
+    // `_1` is the return local, `_2` does not exist.
+    let b0 = block!(storage_live(2), exit());
+    let f = function(Ret::No, 0, &locals, &[b0]);
+    let p = program(&[f]);
+    assert!(matches!(Interpreter::<BasicMem>::new(p), Err(TerminationInfo::IllFormed(_))));
+}
@@ -0,0 +1,187 @@
+use crate::*;
+
+/// Waiting returns immediately if the value does not match.
+#[test]
+fn wait_value_mismatch() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let blocked = f.declare_local::<bool>();
+    f.storage_live(x);
+    f.storage_live(blocked);
+    f.assign(x, const_int(1u32));
+    f.futex_wait(blocked, u32_ptr(x), const_int(0u32));
+    f.print(load(blocked));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["false"], &[]);
+}
+
+/// Waking without waiters does nothing.
+#[test]
+fn wake_without_waiters() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let woken = f.declare_local::<bool>();
+    f.storage_live(x);
+    f.storage_live(woken);
+    f.futex_wake(woken, u32_ptr(x));
+    f.print(load(woken));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["false"], &[]);
+}
+
+/// Waiting with nobody to wake us up is a deadlock.
+#[test]
+fn wait_forever() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let blocked = f.declare_local::<bool>();
+    f.storage_live(x);
+    f.storage_live(blocked);
+    f.assign(x, const_int(0u32));
+    f.futex_wait(blocked, u32_ptr(x), const_int(0u32));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::Deadlock, &[], &[]);
+}
+
+/// The main thread waits for a flag without busy-waiting.
+#[test]
+fn wait_and_wake() {
+    let mut p = ProgramBuilder::new();
+    let data = p.declare_global_zero_initialized::<u32>();
+    let flag = p.declare_global_zero_initialized::<u32>();
+
+    let setter = {
+        let mut f = p.declare_function();
+        let _arg = f.declare_arg::<*const ()>();
+        let woken = f.declare_local::<bool>();
+        f.storage_live(woken);
+        f.assign(data, const_int(42u32));
+        f.atomic_store_ordered(u32_ptr(flag), const_int(1u32), AtomicOrdering::Release);
+        f.futex_wake(woken, u32_ptr(flag));
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let mut main = p.declare_function();
+    let id = main.declare_local::<u32>();
+    let val = main.declare_local::<u32>();
+    let blocked = main.declare_local::<bool>();
+    main.storage_live(id);
+    main.storage_live(val);
+    main.storage_live(blocked);
+    main.spawn(setter, null(), id);
+    main.atomic_load_ordered(val, u32_ptr(flag), AtomicOrdering::Acquire);
+    main.while_(eq(load(val), const_int(0u32)), |f| {
+        f.futex_wait(blocked, u32_ptr(flag), const_int(0u32));
+        f.atomic_load_ordered(val, u32_ptr(flag), AtomicOrdering::Acquire);
+    });
+    main.print(load(data));
+    main.join(load(id));
+    main.exit();
+    let main = p.finish_function(main);
+
+    let p = p.finish_program(main);
+    for _ in 0..16 {
+        assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["42"], &[]);
+    }
+}
+
+/// Each wake-up wakes exactly one waiter.
+/// The woken thread is synchronized with the waker, so the non-atomic accesses to `data` do not race.
+#[test]
+fn wake_one_at_a_time() {
+    let mut p = ProgramBuilder::new();
+    let futex = p.declare_global_zero_initialized::<u32>();
+    let data = p.declare_global_zero_initialized::<u32>();
+
+    let waiter = {
+        let mut f = p.declare_function();
+        let _arg = f.declare_arg::<*const ()>();
+        let blocked = f.declare_local::<bool>();
+        f.storage_live(blocked);
+        // The futex value never changes, so this always blocks.
+        f.futex_wait(blocked, u32_ptr(futex), const_int(0u32));
+        f.print(load(data));
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let mut main = p.declare_function();
+    let id_a = main.declare_local::<u32>();
+    let id_b = main.declare_local::<u32>();
+    let woken = main.declare_local::<bool>();
+    main.storage_live(id_a);
+    main.storage_live(id_b);
+    main.storage_live(woken);
+    main.spawn(waiter, null(), id_a);
+    main.spawn(waiter, null(), id_b);
+    main.assign(data, const_int(7u32));
+    // Wake up threads until both have been woken.
+    main.assign(woken, const_bool(false));
+    main.while_(not(load(woken)), |f| f.futex_wake(woken, u32_ptr(futex)));
+    main.assign(woken, const_bool(false));
+    main.while_(not(load(woken)), |f| f.futex_wake(woken, u32_ptr(futex)));
+    main.join(load(id_a));
+    main.join(load(id_b));
+    main.futex_wake(woken, u32_ptr(futex));
+    main.print(load(woken));
+    main.exit();
+    let main = p.finish_function(main);
+
+    let p = p.finish_program(main);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["7", "7", "false"], &[]);
+}
+
+#[test]
+fn wait_misaligned() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<[u32; 2]>();
+    let blocked = f.declare_local::<bool>();
+    f.storage_live(x);
+    f.storage_live(blocked);
+    let misaligned =
+        ptr_offset(u32_ptr(index(x, const_int(0usize))), const_int(1usize), InBounds::Yes);
+    f.futex_wait(blocked, misaligned, const_int(0u32));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(
+        p,
+        "invalid first argument to `FutexWait` intrinsic: pointer not aligned",
+    );
+}
+
+#[test]
+fn wait_expected_type() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let blocked = f.declare_local::<bool>();
+    f.storage_live(x);
+    f.storage_live(blocked);
+    f.futex_wait(blocked, u32_ptr(x), const_int(0i32));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(p, "invalid second argument to `FutexWait` intrinsic: not a `u32`");
+}
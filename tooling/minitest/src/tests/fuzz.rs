@@ -0,0 +1,49 @@
+//! The random program generator of `minifuzz`.
+
+use crate::*;
+
+const NO_FEATURES: FuzzConfig =
+    FuzzConfig { pointers: false, enums: false, concurrency: false, max_ops: 8 };
+
+#[test]
+fn same_seed_same_program() {
+    let config = FuzzConfig::default();
+    assert_eq!(generate_program(7, config), generate_program(7, config));
+    assert_ne!(generate_program(7, config), generate_program(8, config));
+}
+
+#[test]
+fn no_findings() {
+    let configs = [
+        NO_FEATURES,
+        FuzzConfig { pointers: true, ..NO_FEATURES },
+        FuzzConfig { enums: true, ..NO_FEATURES },
+        FuzzConfig { concurrency: true, ..NO_FEATURES },
+        FuzzConfig::default(),
+    ];
+    for config in configs {
+        for seed in 0..10 {
+            assert_eq!(fuzz_one(seed, config), None, "seed {seed}, {config:?}");
+        }
+    }
+}
+
+#[test]
+fn concurrency_spawns_a_thread() {
+    let prog = generate_program(0, NO_FEATURES);
+    assert_eq!(prog.functions.len(), 1);
+    assert!(prog.globals.is_empty());
+
+    let prog = generate_program(0, FuzzConfig { concurrency: true, ..NO_FEATURES });
+    assert_eq!(prog.functions.len(), 2);
+    assert_eq!(prog.globals.len(), 1);
+}
+
+#[test]
+fn finds_ill_formed_programs() {
+    let p = program(&[]);
+    assert_eq!(
+        check_program(p),
+        Some(Finding::IllFormed("Program: start function does not exist".to_string()))
+    );
+}
@@ -32,10 +32,11 @@ fn alloc_argcount() {
         intrinsic: IntrinsicOp::Allocate,
         arguments: list![],
         ret: local(0),
-        next_block: None,
+        next_block: Some(BbName(Name::from_internal(1))),
     },);
+    let b1 = block!(exit());
 
-    let f = function(Ret::No, 0, &locals, &[b0]);
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
     let p = program(&[f]);
     dump_program(p);
     assert_ub::<BasicMem>(p, "invalid number of arguments for `Allocate` intrinsic");
@@ -1,3 +1,5 @@
+use miniutil::DefaultTarget;
+
 use crate::*;
 
 #[test]
@@ -36,3 +38,31 @@ fn type_mismatch() {
     let p = small_program(locals, stmts);
     assert_ill_formed::<BasicMem>(p, "Statement::Assign: destination and source type differ");
 }
+
+#[test]
+fn array_of_unsized_array() {
+    // The inner array is ill-formed, this must be reported before computing its layout.
+    let ty = array_ty(array_ty(slice_ty(<u8>::get_type()), 2), 2);
+    let locals = &[ty];
+    let stmts = &[storage_live(0)];
+
+    let p = small_program(locals, stmts);
+    assert_ill_formed::<BasicMem>(p, "Type::Array: unsized element type");
+}
+
+#[test]
+fn too_large_vtable_size() {
+    let mut p = ProgramBuilder::new();
+    let trait_ = p.declare_trait();
+    let trait_ = p.finish_trait(trait_);
+    let too_large = size(Int::from(2).pow(DefaultTarget::PTR_SIZE.bits()));
+    let vtable = p.declare_vtable(trait_, too_large, align(1));
+    p.finish_vtable(vtable);
+
+    let mut main = p.declare_function();
+    main.exit();
+    let main = p.finish_function(main);
+
+    let p = p.finish_program(main);
+    assert_ill_formed::<BasicMem>(p, "Program: size stored in vtable not valid");
+}
@@ -0,0 +1,109 @@
+//! Inline assembly as an opaque operation.
+
+use crate::*;
+
+fn four() -> Size {
+    Size::from_bytes_const(4)
+}
+
+#[test]
+fn asm_records_event() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let y = f.declare_local::<u32>();
+    f.storage_live(x);
+    f.storage_live(y);
+    f.assign(x, const_int(1u32));
+    f.inline_asm(&[const_int(2u32)], &[], &[(u32_ptr(x), four())], &[(u32_ptr(y), four())]);
+    f.print(load(x));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output_with::<BasicMem>(p, TerminationInfo::MachineStop, &["1"], &[], |machine| {
+        let events = machine.external_events();
+        assert_eq!(events.len(), 1);
+        let ExternalEvent::InlineAsm { thread, reads, writes } = events.index_at(0);
+        assert_eq!(thread, ThreadId::ZERO);
+        assert_eq!(reads.len(), 1);
+        assert_eq!(writes.len(), 1);
+        assert_eq!(reads.index_at(0).1, four());
+    });
+}
+
+/// We do not know what the assembly writes, so reading its outputs is UB.
+#[test]
+fn asm_output_is_uninit() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    f.storage_live(x);
+    f.assign(x, const_int(1u32));
+    f.inline_asm(&[], &[x], &[], &[]);
+    f.print(load(x));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(
+        p,
+        "load at type Int(IntType { signed: Unsigned, size: Size(4 bytes) }) but the data in memory violates the language invariant",
+    );
+}
+
+#[test]
+fn asm_reads_dead_memory() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let dangling = f.declare_local::<*mut u32>();
+    f.storage_live(x);
+    f.storage_live(dangling);
+    f.assign(dangling, u32_ptr(x));
+    f.storage_dead(x);
+    f.inline_asm(&[], &[], &[(load(dangling), four())], &[]);
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(p, "dereferencing pointer to dead allocation");
+}
+
+#[test]
+fn asm_memory_operand_not_a_pointer() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    f.inline_asm(&[], &[], &[(const_int(0usize), four())], &[]);
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ill_formed::<BasicMem>(p, "Terminator::InlineAsm: memory operand is not a pointer");
+}
+
+/// We do not know what happens after assembly that does not return, so we cannot continue.
+#[test]
+fn asm_noreturn_is_unsupported() {
+    let b0 = block!(Terminator::InlineAsm {
+        inputs: list![],
+        outputs: list![],
+        reads: list![],
+        clobbers: list![],
+        next_block: None,
+    });
+    let f = function(Ret::No, 0, &[], &[b0]);
+    let p = program(&[f]);
+
+    let info = run_program::<BasicMem>(p);
+    assert_eq!(
+        info,
+        TerminationInfo::Unsupported(prelude::String::from_internal(
+            "inline assembly that does not return".to_string()
+        ))
+    );
+}
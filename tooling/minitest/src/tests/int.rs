@@ -126,6 +126,27 @@ fn div_exact_remainder() {
     assert_ub::<BasicMem>(p, "non-zero remainder in exact division");
 }
 
+#[test]
+fn saturating_arith() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+
+    f.assume(eq(add_saturating(const_int(1u8), const_int(7u8)), const_int(8u8)));
+    f.assume(eq(add_saturating(const_int(250u8), const_int(7u8)), const_int(255u8)));
+    f.assume(eq(sub_saturating(const_int(1u8), const_int(7u8)), const_int(0u8)));
+    f.assume(eq(add_saturating(const_int(100i8), const_int(100i8)), const_int(127i8)));
+    f.assume(eq(sub_saturating(const_int(-100i8), const_int(100i8)), const_int(-128i8)));
+    f.assume(eq(sub_saturating(const_int(100i8), const_int(-100i8)), const_int(127i8)));
+    f.assume(eq(sub_saturating(const_int(-1i8), const_int(-100i8)), const_int(99i8)));
+
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
+
 /// Test that IntBinOp::BitAnd works for ints
 #[test]
 fn bit_and_int_works() {
@@ -0,0 +1,71 @@
+//! Exhaustive tests for `CastOp::IntToInt`: every pair of integer types is tested on the
+//! boundary values of all integer types, and compared against Rust's `as` casts.
+
+use crate::*;
+
+/// Invokes `$m!($args..., $ty)` for every integer type `$ty`.
+macro_rules! for_each_int_type {
+    ($m:ident $(, $arg:tt)*) => {
+        $m!($($arg,)* u8);
+        $m!($($arg,)* u16);
+        $m!($($arg,)* u32);
+        $m!($($arg,)* u64);
+        $m!($($arg,)* u128);
+        $m!($($arg,)* i8);
+        $m!($($arg,)* i16);
+        $m!($($arg,)* i32);
+        $m!($($arg,)* i64);
+        $m!($($arg,)* i128);
+    };
+}
+
+/// Pushes the extremes of `$ty`, and the values just outside of them, wrapped into `$src`.
+macro_rules! push_boundaries {
+    ($vals:ident, $src:tt, $ty:tt) => {
+        $vals.push(<$ty>::MIN as $src);
+        $vals.push(<$ty>::MAX as $src);
+        $vals.push((<$ty>::MIN as $src).wrapping_sub(1));
+        $vals.push((<$ty>::MAX as $src).wrapping_add(1));
+    };
+}
+
+/// Asserts that casting all `$vals` from `$src` to `$dst` matches Rust.
+macro_rules! assume_casts {
+    ($f:ident, $vals:ident, $src:tt, $dst:tt) => {
+        for &v in &$vals {
+            $f.assume(eq(int_cast::<$dst>(const_int::<$src>(v)), const_int::<$dst>(v as $dst)));
+        }
+    };
+}
+
+macro_rules! test_casts_from {
+    ($name:ident, $src:tt) => {
+        #[test]
+        fn $name() {
+            let mut vals: Vec<$src> = vec![0, 1];
+            for_each_int_type!(push_boundaries, vals, $src);
+
+            let mut p = ProgramBuilder::new();
+
+            let mut f = p.declare_function();
+            for_each_int_type!(assume_casts, f, vals, $src);
+            f.exit();
+
+            let f = p.finish_function(f);
+
+            let p = p.finish_program(f);
+            assert_stop::<BasicMem>(p);
+        }
+    };
+}
+
+test_casts_from!(casts_from_u8, u8);
+test_casts_from!(casts_from_u16, u16);
+test_casts_from!(casts_from_u32, u32);
+test_casts_from!(casts_from_u64, u64);
+test_casts_from!(casts_from_u128, u128);
+test_casts_from!(casts_from_i8, i8);
+test_casts_from!(casts_from_i16, i16);
+test_casts_from!(casts_from_i32, i32);
+test_casts_from!(casts_from_i64, i64);
+test_casts_from!(casts_from_i128, i128);
@@ -0,0 +1,76 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use miniutil::mock_write::MockWrite;
+
+use crate::*;
+
+/// A program that prints `1` and then loops forever in block 1.
+fn endless_loop() -> Program {
+    let locals = [];
+    let b0 = block!(print(const_int(1u32), 1));
+    let b1 = block!(goto(1));
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+    program(&[f])
+}
+
+fn run_interruptible(prog: Program, interrupt: &AtomicBool, out: &MockWrite) -> InterruptibleRun {
+    run_program_interruptible::<BasicMem>(
+        prog,
+        MachineConfig::DEFAULT,
+        interrupt,
+        &mut MockEnv::new(),
+        out.clone(),
+        MockWrite::new(),
+        MockWrite::new(),
+    )
+}
+
+#[test]
+fn interrupt_endless_loop() {
+    let interrupt = Arc::new(AtomicBool::new(false));
+    let trigger = {
+        let interrupt = Arc::clone(&interrupt);
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            interrupt.store(true, Ordering::Relaxed);
+        })
+    };
+
+    let out = MockWrite::new();
+    let run = run_interruptible(endless_loop(), &interrupt, &out);
+    trigger.join().unwrap();
+
+    assert_eq!(run.status, RunStatus::Interrupted);
+    assert!(run.steps > 1);
+    // Output printed before the interrupt is not lost.
+    assert_eq!(out.into_strings(), ["1"]);
+    let location = run.location.unwrap();
+    assert_eq!(location.block, BbName(Name::from_internal(1)));
+}
+
+/// An interrupt that was requested before the run starts stops the machine before its first step.
+#[test]
+fn interrupt_before_start() {
+    let interrupt = AtomicBool::new(true);
+    let out = MockWrite::new();
+    let run = run_interruptible(endless_loop(), &interrupt, &out);
+
+    assert_eq!(run.status, RunStatus::Interrupted);
+    assert_eq!(run.steps, 0);
+    assert!(out.into_strings().is_empty());
+    let location = run.location.unwrap();
+    assert_eq!(location.block, BbName(Name::from_internal(0)));
+    assert_eq!(location.stmt, Int::ZERO);
+}
+
+#[test]
+fn no_interrupt() {
+    let interrupt = AtomicBool::new(false);
+    let out = MockWrite::new();
+    let prog = small_program(&[<u32>::get_type()], &[storage_live(0)]);
+    let run = run_interruptible(prog, &interrupt, &out);
+
+    assert_eq!(run.status, RunStatus::Terminated(TerminationInfo::MachineStop));
+    assert!(run.steps > 0);
+}
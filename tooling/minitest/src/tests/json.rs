@@ -0,0 +1,53 @@
+//! The JSON encoding of programs.
+
+use crate::*;
+
+#[track_caller]
+fn assert_roundtrip(prog: Program) {
+    let json = program_to_json(prog);
+    let parsed = program_from_json(&json).unwrap_or_else(|err| panic!("{err}\n{json}"));
+    assert_eq!(parsed, prog);
+}
+
+#[test]
+fn roundtrip_text_program() {
+    let p = program_from_text(include_str!("text_print.mr")).unwrap();
+    assert_roundtrip(p);
+    let p = program_from_json(&program_to_json(p)).unwrap();
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["42"], &[]);
+}
+
+#[test]
+fn roundtrip_large_ints_and_globals() {
+    let mut p = ProgramBuilder::new();
+    let counter = p.declare_global_int::<i64>(-7);
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u128>();
+    f.storage_live(x);
+    f.assign(x, const_int(u128::MAX));
+    f.assign(counter, add(load(counter), const_int(1_i64)));
+    f.print(load(counter));
+    f.exit();
+    let f = p.finish_function(f);
+    assert_roundtrip(p.finish_program(f));
+}
+
+#[test]
+fn json_shape() {
+    let json = program_to_json(small_program(&[], &[]));
+    assert!(json.starts_with("[\"Program\", [\"functions\", "), "{json}");
+    assert!(json.contains("[\"start\", \"f0\"]"), "{json}");
+    assert!(json.contains("\"Exit\""), "{json}");
+}
+
+#[test]
+fn json_errors() {
+    let err = program_from_json("[\"Program\",\n  [\"functions\", []]").unwrap_err();
+    assert_eq!(err.to_string(), "line 1: unclosed `[`");
+
+    let err = program_from_json("[\"Function\"]").unwrap_err();
+    assert_eq!(err.to_string(), "line 1: expected `(Program ..)`, found `(Function)`");
+
+    let err = program_from_json("{\"name\": \"x\"}").unwrap_err();
+    assert_eq!(err.to_string(), "line 1: unexpected key `name`");
+}
@@ -0,0 +1,163 @@
+//! Loads and stores in allocations of several kilobytes, and in huge allocations that are mostly untouched.
+
+use crate::*;
+
+/// Fills a `[u32; 1024]` element by element in a loop, then sums it up in a second loop.
+#[test]
+fn fill_and_sum_array() {
+    const LEN: u32 = 1024;
+    let locals = [<[u32; LEN as usize]>::get_type(), <usize>::get_type(), <u32>::get_type()];
+    let i = || load(local(1));
+    let sum = || load(local(2));
+    let elem = || index(local(0), i());
+
+    let b0 = block!(
+        storage_live(0),
+        storage_live(1),
+        storage_live(2),
+        assign(local(1), const_int(0usize)),
+        goto(1)
+    );
+    // for i in 0..LEN { a[i] = i }
+    let b1 = block!(if_(lt(i(), const_int(LEN as usize)), 2, 3));
+    let b2 = block!(
+        assign(elem(), int_cast::<u32>(i())),
+        assign(local(1), add(i(), const_int(1usize))),
+        goto(1)
+    );
+    let b3 =
+        block!(assign(local(1), const_int(0usize)), assign(local(2), const_int(0u32)), goto(4));
+    // for i in 0..LEN { sum += a[i] }
+    let b4 = block!(if_(lt(i(), const_int(LEN as usize)), 5, 6));
+    let b5 = block!(
+        assign(local(2), add(sum(), load(elem()))),
+        assign(local(1), add(i(), const_int(1usize))),
+        goto(4)
+    );
+    let b6 = block!(print(sum(), 7));
+    let b7 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1, b2, b3, b4, b5, b6, b7]);
+    let p = program(&[f]);
+    assert_eq!(get_stdout::<BasicMem>(p).unwrap(), &[(LEN * (LEN - 1) / 2).to_string()]);
+}
+
+/// `manual_align`, scaled up: stores a `u64` at the first 8-aligned address in the back half of a
+/// `[u8; 4096]`, and checks that exactly those bytes changed.
+#[test]
+fn manual_align_large() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let arr = f.declare_local::<[u8; 4096]>();
+    let offset = f.declare_local::<usize>();
+    f.storage_live(arr);
+    f.storage_live(offset);
+    f.assign(arr, array(&[const_int(0u8); 4096], <u8>::get_type()));
+    // offset = 2048 + (8 - addr % 8) % 8; This guarantees alignment of 8 for (&raw arr) + offset
+    let addr = ptr_addr(addr_of(arr, <*const u8>::get_type()));
+    f.assign(
+        offset,
+        add(
+            const_int(2048usize),
+            rem(sub(const_int(8usize), rem(addr, const_int(8usize))), const_int(8usize)),
+        ),
+    );
+    f.assign(
+        deref(
+            ptr_offset(addr_of(arr, <*mut u64>::get_type()), load(offset), InBounds::Yes),
+            <u64>::get_type(),
+        ),
+        const_int(u64::MAX),
+    );
+    // The bytes around the `u64` are untouched, and the first and last one of it are written.
+    let byte = |i: ValueExpr| load(index(arr, i));
+    f.assume(eq(byte(const_int(0usize)), const_int(0u8)));
+    f.assume(eq(byte(sub(load(offset), const_int(1usize))), const_int(0u8)));
+    f.assume(eq(byte(load(offset)), const_int(u8::MAX)));
+    f.assume(eq(byte(add(load(offset), const_int(7usize))), const_int(u8::MAX)));
+    f.assume(eq(byte(add(load(offset), const_int(8usize))), const_int(0u8)));
+    f.assume(eq(byte(const_int(4095usize)), const_int(0u8)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
+
+/// Copying a large array leaves the original unchanged when the copy is written to.
+#[test]
+fn copy_large_array() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let orig = f.declare_local::<[u16; 2048]>();
+    let copy = f.declare_local::<[u16; 2048]>();
+    f.storage_live(orig);
+    f.storage_live(copy);
+    f.assign(orig, array(&[const_int(7u16); 2048], <u16>::get_type()));
+    f.assign(copy, load(orig));
+    let elem = |arr: PlaceExpr, i: usize| index(arr, const_int(i));
+    f.assign(elem(copy, 1000), const_int(42u16));
+    f.assume(eq(load(elem(orig, 1000)), const_int(7u16)));
+    f.assume(eq(load(elem(copy, 1000)), const_int(42u16)));
+    f.assume(eq(load(elem(copy, 999)), const_int(7u16)));
+    f.assume(eq(load(elem(copy, 2047)), const_int(7u16)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
+
+/// Writes a few bytes spread over a 1 GiB array and reads them back.
+/// Only the parts of the array that are written to are stored, so this is cheap.
+#[test]
+fn huge_sparse_array() {
+    const LEN: usize = 1 << 30;
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let arr = f.declare_local::<[u8; LEN]>();
+    f.storage_live(arr);
+    let elem = |i: usize| index(arr, const_int(i));
+    for (i, val) in [(0, 1u8), (LEN / 2, 2), (LEN - 1, 3)] {
+        f.assign(elem(i), const_int(val));
+    }
+    for (i, val) in [(0, 1u8), (LEN / 2, 2), (LEN - 1, 3)] {
+        f.assume(eq(load(elem(i)), const_int(val)));
+    }
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
+
+/// Arrays whose elements are all the same pointer keep the provenance of every element
+/// when they are copied, even though homogeneous arrays are handled compactly.
+#[test]
+fn homogeneous_pointer_array() {
+    const LEN: usize = 512;
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let ptrs = f.declare_local::<[*const u32; LEN]>();
+    let copy = f.declare_local::<[*const u32; LEN]>();
+    f.storage_live(x);
+    f.storage_live(ptrs);
+    f.storage_live(copy);
+    f.assign(x, const_int(7u32));
+    let ptr = addr_of(x, <*const u32>::get_type());
+    f.assign(ptrs, array(&[ptr; LEN], <*const u32>::get_type()));
+    f.assign(copy, load(ptrs));
+    let deref_elem = |i: usize| load(deref(load(index(copy, const_int(i))), <u32>::get_type()));
+    f.assume(eq(deref_elem(0), const_int(7u32)));
+    f.assume(eq(deref_elem(LEN - 1), const_int(7u32)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
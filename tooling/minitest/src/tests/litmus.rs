@@ -0,0 +1,305 @@
+//! Classic weak-memory litmus tests.
+//!
+//! Each test lists the outcomes that are allowed for the orderings it uses.
+//! Where C++ allows an outcome that our model cannot produce, the test says so.
+//! We explore every schedule of the test with a bounded number of preemptions, and every store
+//! that each atomic load may read from, and check that no other outcome is observed.
+//! Tests of weaker orderings also check that a weak outcome is actually observed.
+
+use std::collections::HashSet;
+
+use crate::*;
+
+/// The threads of a litmus test are short, so few preemptions already cover all interesting interleavings.
+const MAX_PREEMPTIONS: usize = 2;
+const MAX_STEPS: u64 = 10_000;
+
+/// Finishes a litmus test program: `main` spawns all `threads`, joins them,
+/// and then prints the final values of all `results`.
+fn litmus_program(mut p: ProgramBuilder, threads: &[FnName], results: &[PlaceExpr]) -> Program {
+    let mut main = p.declare_function();
+    let ids: Vec<PlaceExpr> = threads.iter().map(|_| main.declare_local::<u32>()).collect();
+    for (&thread, &id) in threads.iter().zip(&ids) {
+        main.storage_live(id);
+        main.spawn(thread, null(), id);
+    }
+    for &id in &ids {
+        main.join(load(id));
+    }
+    for &result in results {
+        main.print(load(result));
+    }
+    main.exit();
+    let main = p.finish_function(main);
+    p.finish_program(main)
+}
+
+/// Runs the litmus test under every schedule with at most `MAX_PREEMPTIONS` preemptions
+/// and asserts that every observed outcome is in `allowed`. Returns the set of observed outcomes.
+#[track_caller]
+fn assert_litmus(prog: Program, allowed: &[&[u32]]) -> HashSet<Vec<u32>> {
+    assert_litmus_with_preemptions(prog, allowed, MAX_PREEMPTIONS)
+}
+
+/// Like `assert_litmus`, but with at most `max_preemptions` preemptions.
+#[track_caller]
+fn assert_litmus_with_preemptions(
+    prog: Program,
+    allowed: &[&[u32]],
+    max_preemptions: usize,
+) -> HashSet<Vec<u32>> {
+    let mut seen = HashSet::new();
+    explore_schedules::<BasicMem>(prog, max_preemptions, MAX_STEPS, |output| {
+        let Some(output) = output else {
+            panic!("a schedule did not terminate within {MAX_STEPS} steps");
+        };
+        assert_eq!(output.info, TerminationInfo::MachineStop);
+        let outcome: Vec<u32> = output.stdout.iter().map(|s| s.parse().unwrap()).collect();
+        assert!(
+            allowed.iter().any(|a| *a == outcome.as_slice()),
+            "forbidden litmus outcome: {outcome:?}"
+        );
+        seen.insert(outcome);
+        false
+    });
+    seen
+}
+
+/// Message passing: if the reader sees the flag, it must also see the data.
+#[test]
+fn message_passing() {
+    let mut p = ProgramBuilder::new();
+    let data = p.declare_global_zero_initialized::<u32>();
+    let flag = p.declare_global_zero_initialized::<u32>();
+    let r_flag = p.declare_global_zero_initialized::<u32>();
+    let r_data = p.declare_global_zero_initialized::<u32>();
+
+    let writer = p.thread_function(|f, _arg| {
+        f.atomic_store(u32_ptr(data), const_int(1u32));
+        f.atomic_store(u32_ptr(flag), const_int(1u32));
+    });
+    let reader = p.thread_function(|f, _arg| {
+        f.atomic_load(r_flag, u32_ptr(flag));
+        f.atomic_load(r_data, u32_ptr(data));
+    });
+
+    let prog = litmus_program(p, &[writer, reader], &[r_flag, r_data]);
+    // Forbidden: `[1, 0]`.
+    assert_litmus(prog, &[&[0, 0], &[0, 1], &[1, 1]]);
+}
+
+/// Message passing with release/acquire: still, if the reader sees the flag, it must also see the data.
+#[test]
+fn message_passing_release_acquire() {
+    let mut p = ProgramBuilder::new();
+    let data = p.declare_global_zero_initialized::<u32>();
+    let flag = p.declare_global_zero_initialized::<u32>();
+    let r_flag = p.declare_global_zero_initialized::<u32>();
+    let r_data = p.declare_global_zero_initialized::<u32>();
+
+    let writer = p.thread_function(|f, _arg| {
+        f.atomic_store_ordered(u32_ptr(data), const_int(1u32), AtomicOrdering::Relaxed);
+        f.atomic_store_ordered(u32_ptr(flag), const_int(1u32), AtomicOrdering::Release);
+    });
+    let reader = p.thread_function(|f, _arg| {
+        f.atomic_load_ordered(r_flag, u32_ptr(flag), AtomicOrdering::Acquire);
+        f.atomic_load_ordered(r_data, u32_ptr(data), AtomicOrdering::Relaxed);
+    });
+
+    let prog = litmus_program(p, &[writer, reader], &[r_flag, r_data]);
+    // Forbidden: `[1, 0]`.
+    assert_litmus(prog, &[&[0, 0], &[0, 1], &[1, 1]]);
+}
+
+/// Message passing with relaxed accesses: the reader can see the flag but not the data.
+#[test]
+fn message_passing_relaxed() {
+    let mut p = ProgramBuilder::new();
+    let data = p.declare_global_zero_initialized::<u32>();
+    let flag = p.declare_global_zero_initialized::<u32>();
+    let r_flag = p.declare_global_zero_initialized::<u32>();
+    let r_data = p.declare_global_zero_initialized::<u32>();
+
+    let writer = p.thread_function(|f, _arg| {
+        f.atomic_store_ordered(u32_ptr(data), const_int(1u32), AtomicOrdering::Relaxed);
+        f.atomic_store_ordered(u32_ptr(flag), const_int(1u32), AtomicOrdering::Relaxed);
+    });
+    let reader = p.thread_function(|f, _arg| {
+        f.atomic_load_ordered(r_flag, u32_ptr(flag), AtomicOrdering::Relaxed);
+        f.atomic_load_ordered(r_data, u32_ptr(data), AtomicOrdering::Relaxed);
+    });
+
+    let prog = litmus_program(p, &[writer, reader], &[r_flag, r_data]);
+    let seen = assert_litmus(prog, &[&[0, 0], &[0, 1], &[1, 0], &[1, 1]]);
+    assert!(seen.contains(&vec![1, 0]), "weak outcome is not possible");
+}
+
+/// Store buffering: at least one thread must see the other thread's store.
+#[test]
+fn store_buffering() {
+    let mut p = ProgramBuilder::new();
+    let x = p.declare_global_zero_initialized::<u32>();
+    let y = p.declare_global_zero_initialized::<u32>();
+    let r1 = p.declare_global_zero_initialized::<u32>();
+    let r2 = p.declare_global_zero_initialized::<u32>();
+
+    let t1 = p.thread_function(|f, _arg| {
+        f.atomic_store(u32_ptr(x), const_int(1u32));
+        f.atomic_load(r1, u32_ptr(y));
+    });
+    let t2 = p.thread_function(|f, _arg| {
+        f.atomic_store(u32_ptr(y), const_int(1u32));
+        f.atomic_load(r2, u32_ptr(x));
+    });
+
+    let prog = litmus_program(p, &[t1, t2], &[r1, r2]);
+    // Forbidden: `[0, 0]`.
+    assert_litmus(prog, &[&[0, 1], &[1, 0], &[1, 1]]);
+}
+
+/// Store buffering with release/acquire: both threads can miss the other thread's store.
+#[test]
+fn store_buffering_release_acquire() {
+    let mut p = ProgramBuilder::new();
+    let x = p.declare_global_zero_initialized::<u32>();
+    let y = p.declare_global_zero_initialized::<u32>();
+    let r1 = p.declare_global_zero_initialized::<u32>();
+    let r2 = p.declare_global_zero_initialized::<u32>();
+
+    let t1 = p.thread_function(|f, _arg| {
+        f.atomic_store_ordered(u32_ptr(x), const_int(1u32), AtomicOrdering::Release);
+        f.atomic_load_ordered(r1, u32_ptr(y), AtomicOrdering::Acquire);
+    });
+    let t2 = p.thread_function(|f, _arg| {
+        f.atomic_store_ordered(u32_ptr(y), const_int(1u32), AtomicOrdering::Release);
+        f.atomic_load_ordered(r2, u32_ptr(x), AtomicOrdering::Acquire);
+    });
+
+    let prog = litmus_program(p, &[t1, t2], &[r1, r2]);
+    let seen = assert_litmus(prog, &[&[0, 0], &[0, 1], &[1, 0], &[1, 1]]);
+    assert!(seen.contains(&vec![0, 0]), "weak outcome is not possible");
+}
+
+/// Coherence: a thread that read a store cannot afterwards read an older store to the same location.
+#[test]
+fn coherence_read_read() {
+    let mut p = ProgramBuilder::new();
+    let x = p.declare_global_zero_initialized::<u32>();
+    let r1 = p.declare_global_zero_initialized::<u32>();
+    let r2 = p.declare_global_zero_initialized::<u32>();
+
+    let writer = p.thread_function(|f, _arg| {
+        f.atomic_store_ordered(u32_ptr(x), const_int(1u32), AtomicOrdering::Relaxed);
+    });
+    let reader = p.thread_function(|f, _arg| {
+        f.atomic_load_ordered(r1, u32_ptr(x), AtomicOrdering::Relaxed);
+        f.atomic_load_ordered(r2, u32_ptr(x), AtomicOrdering::Relaxed);
+    });
+
+    let prog = litmus_program(p, &[writer, reader], &[r1, r2]);
+    // Forbidden: `[1, 0]`.
+    assert_litmus(prog, &[&[0, 0], &[0, 1], &[1, 1]]);
+}
+
+/// Load buffering: with `SeqCst` accesses, the loads cannot both observe the store
+/// the other thread makes after its load.
+#[test]
+fn load_buffering() {
+    let mut p = ProgramBuilder::new();
+    let x = p.declare_global_zero_initialized::<u32>();
+    let y = p.declare_global_zero_initialized::<u32>();
+    let r1 = p.declare_global_zero_initialized::<u32>();
+    let r2 = p.declare_global_zero_initialized::<u32>();
+
+    let t1 = p.thread_function(|f, _arg| {
+        f.atomic_load(r1, u32_ptr(x));
+        f.atomic_store(u32_ptr(y), const_int(1u32));
+    });
+    let t2 = p.thread_function(|f, _arg| {
+        f.atomic_load(r2, u32_ptr(y));
+        f.atomic_store(u32_ptr(x), const_int(1u32));
+    });
+
+    let prog = litmus_program(p, &[t1, t2], &[r1, r2]);
+    // Forbidden: `[1, 1]`.
+    assert_litmus(prog, &[&[0, 0], &[0, 1], &[1, 0]]);
+}
+
+/// Load buffering with relaxed accesses.
+/// C++ allows both loads to observe the other thread's later store, i.e., the outcome `[1, 1]`.
+/// Our model cannot produce it, since a load can only read from stores that were already
+/// executed. That is a gap in the model, not a guarantee of the language, so this only checks
+/// that the other outcomes occur.
+#[test]
+fn load_buffering_relaxed() {
+    let mut p = ProgramBuilder::new();
+    let x = p.declare_global_zero_initialized::<u32>();
+    let y = p.declare_global_zero_initialized::<u32>();
+    let r1 = p.declare_global_zero_initialized::<u32>();
+    let r2 = p.declare_global_zero_initialized::<u32>();
+
+    let t1 = p.thread_function(|f, _arg| {
+        f.atomic_load_ordered(r1, u32_ptr(x), AtomicOrdering::Relaxed);
+        f.atomic_store_ordered(u32_ptr(y), const_int(1u32), AtomicOrdering::Relaxed);
+    });
+    let t2 = p.thread_function(|f, _arg| {
+        f.atomic_load_ordered(r2, u32_ptr(y), AtomicOrdering::Relaxed);
+        f.atomic_store_ordered(u32_ptr(x), const_int(1u32), AtomicOrdering::Relaxed);
+    });
+
+    let prog = litmus_program(p, &[t1, t2], &[r1, r2]);
+    let seen = assert_litmus(prog, &[&[0, 0], &[0, 1], &[1, 0], &[1, 1]]);
+    for outcome in [[0, 0], [0, 1], [1, 0]] {
+        assert!(seen.contains(&outcome.to_vec()), "outcome {outcome:?} is not possible");
+    }
+}
+
+/// Independent reads of independent writes: the two readers must agree on the order of the writes.
+fn iriw_program() -> Program {
+    let mut p = ProgramBuilder::new();
+    let x = p.declare_global_zero_initialized::<u32>();
+    let y = p.declare_global_zero_initialized::<u32>();
+    let r1 = p.declare_global_zero_initialized::<u32>();
+    let r2 = p.declare_global_zero_initialized::<u32>();
+    let r3 = p.declare_global_zero_initialized::<u32>();
+    let r4 = p.declare_global_zero_initialized::<u32>();
+
+    let w1 = p.thread_function(|f, _arg| f.atomic_store(u32_ptr(x), const_int(1u32)));
+    let w2 = p.thread_function(|f, _arg| f.atomic_store(u32_ptr(y), const_int(1u32)));
+    let rd1 = p.thread_function(|f, _arg| {
+        f.atomic_load(r1, u32_ptr(x));
+        f.atomic_load(r2, u32_ptr(y));
+    });
+    let rd2 = p.thread_function(|f, _arg| {
+        f.atomic_load(r3, u32_ptr(y));
+        f.atomic_load(r4, u32_ptr(x));
+    });
+
+    litmus_program(p, &[w1, w2, rd1, rd2], &[r1, r2, r3, r4])
+}
+
+/// All 16 outcomes of `iriw_program` except `[1, 0, 1, 0]` (the readers disagree on the order of the writes).
+/// All accesses are `SeqCst`, so these are exactly the outcomes of interleaving the accesses.
+fn iriw_allowed() -> Vec<Vec<u32>> {
+    let mut allowed = Vec::new();
+    for bits in 0..16u32 {
+        let outcome: Vec<u32> = (0..4).map(|i| (bits >> (3 - i)) & 1).collect();
+        if outcome != [1, 0, 1, 0] {
+            allowed.push(outcome);
+        }
+    }
+    allowed
+}
+
+/// One preemption suffices to reach every outcome in `iriw_allowed`: a reader that reads `0` and
+/// then `1` needs a preemption between its loads only if the writes happen in the other order,
+/// and if both readers do that, the writes can happen in the order one of them needs.
+/// So this also explores the schedules in which a model that lets the readers disagree would
+/// produce the forbidden outcome.
+#[test]
+fn iriw() {
+    let allowed = iriw_allowed();
+    let allowed: Vec<&[u32]> = allowed.iter().map(|o| o.as_slice()).collect();
+    let seen = assert_litmus_with_preemptions(iriw_program(), &allowed, 1);
+    assert_eq!(seen.len(), allowed.len(), "not every allowed outcome was observed: {seen:?}");
+}
@@ -1,46 +1,97 @@
 mod align;
+mod alloc_config;
+mod assert_validity;
 mod assume;
 mod atomic;
 mod atomic_fetch;
+mod atomic_ptr;
+mod bigint;
 mod bool;
 mod builder_api;
 mod call;
+mod char;
 mod compare_exchange;
 mod compute_align;
 mod compute_size;
 mod concurrency;
+mod condvar;
+mod conformance;
+mod const_eval;
+mod copy;
+mod coverage;
 mod data_race;
+mod debugger;
 mod dereferenceable;
+mod drop;
 mod enum_discriminant;
 mod enum_downcast;
 mod enum_representation;
 mod expose;
+mod extern_call;
+mod float;
+mod freeze;
+mod fuel;
+mod futex;
+mod fuzz;
 mod heap_intrinsics;
 mod ill_formed;
+mod inline_asm;
 mod int;
+mod int_cast;
+mod interrupt;
+mod json;
+mod large_alloc;
+mod litmus;
 mod locals;
 mod locks;
 mod main;
 mod negative_index;
+mod never;
 mod no_preserve_padding;
 mod no_preserve_prov;
 mod null;
+mod offset_of;
+mod oracle;
 mod packed;
 mod panic;
 mod place_mention;
 mod print;
 mod ptr;
+mod ptr_fragments;
 mod ptr_offset;
 mod ptr_offset_from;
 mod raw_eq;
+mod reduce;
+mod repr_align;
+mod repr_roundtrip;
 mod return_;
+mod return_place;
+mod rwlock;
+mod schedule;
 mod slice;
 mod spawn_join;
+mod stacked_borrows;
+mod statics;
+mod subslice;
 mod switch;
+mod text;
+mod thread_provenance;
 mod too_large_alloc;
+mod trace;
 mod trait_object;
+mod tree_borrows;
+mod typed_swap;
+mod ub_report;
 mod uninit_read;
 mod unreachable;
+mod unsized_locals;
 mod unsized_struct;
+mod unwind;
+mod valid_range;
+mod validate_pointee;
+mod validity;
+mod variadic;
+mod wf_report;
 mod wide_ptr;
+mod with_addr;
 mod zst;
@@ -0,0 +1,60 @@
+//! The uninhabited type `!`.
+
+use crate::*;
+
+#[test]
+fn never_inhabited() {
+    assert!(!never_ty().inhabited());
+    assert!(<[!; 0]>::get_type().inhabited());
+    assert!(!<[!; 2]>::get_type().inhabited());
+    let tuple = tuple_ty(&[(size(0), <u8>::get_type()), (size(1), never_ty())], size(1), align(1));
+    assert!(!tuple.inhabited());
+    let empty_enum = enum_ty::<u8>(&[], discriminator_invalid(), size(0), align(1));
+    assert!(!empty_enum.inhabited());
+    assert!(<&u8>::get_type().inhabited());
+}
+
+/// Producing a value of type `!` is UB.
+#[test]
+fn never_load() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<!>();
+    let y = f.declare_local::<!>();
+    f.storage_live(x);
+    f.storage_live(y);
+    f.assign(y, load(x));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(
+        p,
+        "load at type Never but the data in memory violates the language invariant",
+    );
+}
+
+/// Returning from a function with return type `!` is UB.
+#[test]
+fn never_return() {
+    let mut p = ProgramBuilder::new();
+
+    let mut diverge = p.declare_function();
+    diverge.declare_ret::<!>();
+    diverge.return_();
+    let diverge = p.finish_function(diverge);
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<!>();
+    f.storage_live(x);
+    f.call(x, fn_ptr(diverge), &[]);
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(
+        p,
+        "load at type Never but the data in memory violates the language invariant",
+    );
+}
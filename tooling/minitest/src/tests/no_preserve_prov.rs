@@ -1,8 +1,7 @@
 use crate::*;
 
 // see https://github.com/rust-lang/miri/issues/2182
-#[test]
-fn no_preserve_prov() {
+fn no_preserve_prov_program() -> Program {
     let union_ty = union_ty(
         &[
             (size(0), <[&i32; 1]>::get_type()),
@@ -37,7 +36,89 @@ fn no_preserve_prov() {
         ),
     ];
 
-    let p = small_program(&locals, &stmts);
+    small_program(&locals, &stmts)
+}
+
+#[test]
+fn no_preserve_prov() {
+    let p = no_preserve_prov_program();
     dump_program(p);
     assert_ub::<BasicMem>(p, "Value::Ptr: non-dereferenceable safe pointer");
 }
+
+fn warn_on_provenance_loss() -> MachineConfig {
+    MachineConfig { warn_on_provenance_loss: true, ..MachineConfig::DEFAULT }
+}
+
+/// Asserts that the machine warns about the load that strips provenance.
+#[test]
+fn no_preserve_prov_warns() {
+    let output =
+        run_program_with_config::<BasicMem>(no_preserve_prov_program(), warn_on_provenance_loss());
+    assert_eq!(output.warnings.len(), 1);
+    assert_eq!(
+        output.warnings[0],
+        "warning: provenance lost when loading or transmuting at type `[u64; 1]`"
+    );
+}
+
+/// Asserts that a ptr-to-int transmute warns, even though it is not UB.
+#[test]
+fn ptr_to_int_transmute_warns() {
+    let mut p = ProgramBuilder::new();
+
+    let f = {
+        let mut f = p.declare_function();
+        let x = f.declare_local::<i32>();
+        let addr = f.declare_local::<usize>();
+        f.storage_live(x);
+        f.storage_live(addr);
+        f.assign(addr, transmute(addr_of(x, <*const i32>::get_type()), <usize>::get_type()));
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let output =
+        run_program_with_config::<BasicMem>(p.finish_program(f), warn_on_provenance_loss());
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    assert_eq!(output.warnings.len(), 1);
+    assert_eq!(
+        output.warnings[0],
+        "warning: provenance lost when loading or transmuting at type `u64`"
+    );
+}
+
+/// Asserts that copying pointers and integers around does not warn.
+#[test]
+fn no_warning_without_provenance_loss() {
+    let mut p = ProgramBuilder::new();
+
+    let f = {
+        let mut f = p.declare_function();
+        let x = f.declare_local::<i32>();
+        let ptr = f.declare_local::<*const i32>();
+        let copy = f.declare_local::<*const i32>();
+        f.storage_live(x);
+        f.storage_live(ptr);
+        f.storage_live(copy);
+        f.assign(x, const_int(42_i32));
+        f.assign(ptr, addr_of(x, <*const i32>::get_type()));
+        f.assign(copy, load(ptr));
+        f.print(load(deref(load(copy), <i32>::get_type())));
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let output =
+        run_program_with_config::<BasicMem>(p.finish_program(f), warn_on_provenance_loss());
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    assert_eq!(output.stdout, ["42"]);
+    assert!(output.warnings.is_empty());
+}
+
+/// The check is only done when it is enabled.
+#[test]
+fn no_warning_by_default() {
+    let output = run_program_with_output::<BasicMem>(no_preserve_prov_program());
+    assert!(output.warnings.is_empty());
+}
@@ -0,0 +1,93 @@
+use crate::*;
+
+use FieldProjection::*;
+
+const U8_INTTYPE: IntType =
+    IntType { signed: Signedness::Unsigned, size: Size::from_bytes_const(1) };
+
+/// `(u8, (u16, u32))` with explicit offsets.
+fn nested_tuple_ty() -> Type {
+    let inner = tuple_ty(
+        &[(offset(0), <u16>::get_type()), (offset(4), <u32>::get_type())],
+        size(8),
+        align(4),
+    );
+    tuple_ty(&[(offset(0), <u8>::get_type()), (offset(4), inner)], size(12), align(4))
+}
+
+#[test]
+fn offset_of_nested_fields() {
+    let ty = nested_tuple_ty();
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    f.assume(eq(offset_of(ty, &[]), const_int(0_usize)));
+    f.assume(eq(offset_of(ty, &[Field(Int::from(1))]), const_int(4_usize)));
+    f.assume(eq(offset_of(ty, &[Field(Int::from(1)), Field(Int::from(1))]), const_int(8_usize)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
+
+#[test]
+fn offset_of_union_field() {
+    let ty = union_ty(
+        &[(offset(0), <u8>::get_type()), (offset(2), nested_tuple_ty())],
+        size(16),
+        align(4),
+    );
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    f.assume(eq(offset_of(ty, &[Field(Int::from(1)), Field(Int::from(1))]), const_int(6_usize)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
+
+/// Entering an enum variant does not change the offset.
+#[test]
+fn offset_of_variant_field() {
+    let ty = enum_ty::<u8>(
+        &[
+            (
+                0,
+                enum_variant(tuple_ty(&[], size(12), align(4)), &[(
+                    offset(1),
+                    (U8_INTTYPE, 0.into()),
+                )]),
+            ),
+            (1, enum_variant(nested_tuple_ty(), &[(offset(1), (U8_INTTYPE, 1.into()))])),
+        ],
+        discriminator_branch::<u8>(offset(1), discriminator_invalid(), &[
+            ((0, 1), discriminator_known(0)),
+            ((1, 2), discriminator_known(1)),
+        ]),
+        size(12),
+        align(4),
+    );
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    f.assume(eq(
+        offset_of(ty, &[Variant(Int::from(1)), Field(Int::from(1)), Field(Int::from(0))]),
+        const_int(4_usize),
+    ));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
+
+#[test]
+fn field_offset_invalid_path() {
+    let ty = nested_tuple_ty();
+    assert!(ty.field_offset(list![Field(Int::from(2))]).is_none());
+    assert!(ty.field_offset(list![Variant(Int::from(0))]).is_none());
+    assert!(<u32>::get_type().field_offset(list![Field(Int::from(0))]).is_none());
+}
@@ -0,0 +1,204 @@
+//! Letting an oracle make the non-deterministic choices of the machine.
+
+use crate::*;
+
+/// Runs `prog` to completion with `oracle` making the choices, and returns what it printed.
+fn stdout_with_oracle(
+    prog: Program,
+    config: MachineConfig,
+    mut oracle: impl Oracle,
+) -> Vec<String> {
+    let mut interp = Interpreter::<BasicMem>::new_with_config(prog, config).unwrap();
+    let info = loop {
+        if let Some(info) = interp.step_with_oracle(&mut oracle) {
+            break info;
+        }
+    };
+    assert_eq!(info, TerminationInfo::MachineStop);
+    interp.stdout()
+}
+
+/// An oracle that takes option `answer` for choices of the given kind, and the first option otherwise.
+fn answer(kind: ChoiceKind, answer: usize) -> impl Oracle {
+    move |k: ChoiceKind, _options: usize| if k == kind { answer } else { 0 }
+}
+
+/// `main` prints 1 and 2 and the other thread prints 3.
+fn print_program() -> Program {
+    let mut p = ProgramBuilder::new();
+
+    let other = {
+        let mut f = p.declare_function();
+        let _arg = f.declare_arg::<*const ()>();
+        f.print(const_int(3u32));
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let mut main = p.declare_function();
+    let id = main.declare_local::<u32>();
+    main.storage_live(id);
+    main.spawn(other, null(), id);
+    main.print(const_int(1u32));
+    main.print(const_int(2u32));
+    main.join(load(id));
+    main.exit();
+    let main = p.finish_function(main);
+
+    p.finish_program(main)
+}
+
+#[test]
+fn oracle_picks_thread() {
+    let out =
+        stdout_with_oracle(print_program(), MachineConfig::DEFAULT, answer(ChoiceKind::Thread, 0));
+    assert_eq!(out, &["1", "2", "3"]);
+    let out =
+        stdout_with_oracle(print_program(), MachineConfig::DEFAULT, answer(ChoiceKind::Thread, 1));
+    assert_eq!(out, &["3", "1", "2"]);
+}
+
+/// `main` holds a lock while spawning two threads that wait for it, and then releases it.
+/// Each of the threads prints its number once it got the lock.
+#[test]
+fn oracle_picks_lock_hand_over() {
+    let mut p = ProgramBuilder::new();
+    let lock = p.declare_global_zero_initialized::<u32>();
+
+    let mut waiter = |n: u32| {
+        let mut f = p.declare_function();
+        let _arg = f.declare_arg::<*const ()>();
+        f.lock_acquire(load(lock));
+        f.print(const_int(n));
+        f.lock_release(load(lock));
+        f.return_();
+        p.finish_function(f)
+    };
+    let first = waiter(1);
+    let second = waiter(2);
+
+    let mut main = p.declare_function();
+    let id1 = main.declare_local::<u32>();
+    let id2 = main.declare_local::<u32>();
+    main.storage_live(id1);
+    main.storage_live(id2);
+    main.lock_create(lock);
+    main.lock_acquire(load(lock));
+    main.spawn(first, null(), id1);
+    main.spawn(second, null(), id2);
+    main.lock_release(load(lock));
+    main.join(load(id1));
+    main.join(load(id2));
+    main.exit();
+    let main = p.finish_function(main);
+    let p = p.finish_program(main);
+
+    // Running the newest thread first makes both threads wait for the lock before `main` releases it.
+    let oracle = |hand_over: usize| {
+        move |kind: ChoiceKind, options: usize| {
+            match kind {
+                ChoiceKind::Thread => options - 1,
+                ChoiceKind::LockHandOver => hand_over,
+                _ => 0,
+            }
+        }
+    };
+    assert_eq!(stdout_with_oracle(p, MachineConfig::DEFAULT, oracle(0)), &["1", "2"]);
+    assert_eq!(stdout_with_oracle(p, MachineConfig::DEFAULT, oracle(1)), &["2", "1"]);
+}
+
+/// A weak compare-exchange with the expected value prints whether it succeeded.
+#[test]
+fn oracle_picks_spurious_cas_failure() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let ret = f.declare_local_with_ty(tuple_ty(
+        &[(size(0), <u32>::get_type()), (size(4), <bool>::get_type())],
+        size(8),
+        align(4),
+    ));
+    f.storage_live(x);
+    f.storage_live(ret);
+    f.assign(x, const_int(0u32));
+    f.compare_exchange_weak(ret, addr_of(x, raw_void_ptr_ty()), const_int(0u32), const_int(1u32));
+    f.print(load(field(ret, 1)));
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+
+    let out =
+        stdout_with_oracle(p, MachineConfig::DEFAULT, answer(ChoiceKind::SpuriousCasFailure, 0));
+    assert_eq!(out, &["true"]);
+    let out =
+        stdout_with_oracle(p, MachineConfig::DEFAULT, answer(ChoiceKind::SpuriousCasFailure, 1));
+    assert_eq!(out, &["false"]);
+}
+
+/// Allocates 4 bytes on the heap and prints whether that succeeded.
+#[test]
+fn oracle_picks_spurious_heap_failure() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let ptr = f.declare_local::<*mut u8>();
+    f.storage_live(ptr);
+    f.allocate(const_int(4usize), const_int(1usize), ptr);
+    f.print(ne(transmute(load(ptr), <usize>::get_type()), const_int(0usize)));
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+
+    let config = MachineConfig { spurious_heap_failure: true, ..MachineConfig::DEFAULT };
+    let out = stdout_with_oracle(p, config, answer(ChoiceKind::SpuriousHeapFailure, 0));
+    assert_eq!(out, &["true"]);
+    let out = stdout_with_oracle(p, config, answer(ChoiceKind::SpuriousHeapFailure, 1));
+    assert_eq!(out, &["false"]);
+    // Without the config, allocations do not fail spuriously, so there is nothing to choose.
+    let out = stdout_with_oracle(p, MachineConfig::DEFAULT, |kind: ChoiceKind, _options: usize| {
+        assert_ne!(kind, ChoiceKind::SpuriousHeapFailure);
+        0
+    });
+    assert_eq!(out, &["true"]);
+}
+
+/// Every uninitialized byte is a separate choice.
+#[test]
+fn oracle_picks_freeze_bytes() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u16>();
+    f.storage_live(x);
+    f.freeze(addr_of(x, <*mut u16>::get_type()), <u16>::get_type());
+    f.print(load(x));
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+
+    let mut bytes = vec![0x12, 0x34].into_iter();
+    let out = stdout_with_oracle(p, MachineConfig::DEFAULT, |kind: ChoiceKind, options: usize| {
+        assert_eq!((kind, options), (ChoiceKind::FreezeByte, 256));
+        bytes.next().unwrap()
+    });
+    // x86_64 is little-endian.
+    assert_eq!(out, &[0x3412.to_string()]);
+}
+
+/// `0.0 / 0.0` has no NaN inputs, so its result is the preferred NaN with either sign.
+#[test]
+fn oracle_picks_nan() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    f.print(transmute(float_div(const_f32(0.0), const_f32(0.0)), <u32>::get_type()));
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+
+    let out = stdout_with_oracle(p, MachineConfig::DEFAULT, answer(ChoiceKind::NanPayload, 0));
+    assert_eq!(out, &[0x7fc0_0000u32.to_string()]);
+    let out = stdout_with_oracle(p, MachineConfig::DEFAULT, answer(ChoiceKind::NanPayload, 1));
+    assert_eq!(out, &[0xffc0_0000u32.to_string()]);
+}
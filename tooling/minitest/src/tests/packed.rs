@@ -1,7 +1,7 @@
 use crate::*;
 
 fn make_packed() -> Type {
-    tuple_ty(&[(size(0), <i32>::get_type())], size(4), align(1))
+    packed_tuple_ty(&[(size(0), <i32>::get_type())], size(4), align(1), align(1))
 }
 
 #[test]
@@ -38,3 +38,68 @@ fn packed_is_not_aligned() {
     let p = p.finish_program(f);
     assert_ub_eventually::<BasicMem>(p, 16, "Value::Ptr: unaligned safe pointer");
 }
+
+/// A field may be at an offset that is only aligned to the packed attribute.
+#[test]
+fn packed_field_at_unaligned_offset() {
+    let packed_ty = packed_tuple_ty(
+        &[(size(0), <u8>::get_type()), (size(1), <u32>::get_type())],
+        size(5),
+        align(1),
+        align(1),
+    );
+    let locals = [packed_ty, <u32>::get_type()];
+    let b0 = block!(
+        storage_live(0),
+        assign(field(local(0), 1), const_int(42u32)),
+        storage_live(1),
+        assign(local(1), load(field(local(0), 1))),
+        if_(eq(load(local(1)), const_int(42u32)), 1, 2),
+    );
+    let b1 = block!(exit());
+    let b2 = block!(unreachable());
+    let f = function(Ret::No, 0, &locals, &[b0, b1, b2]);
+    let p = program(&[f]);
+    assert_stop::<BasicMem>(p);
+}
+
+/// Without the packed attribute, fields must be aligned.
+#[test]
+fn ill_unaligned_field_offset() {
+    let ill_ty =
+        tuple_ty(&[(size(0), <u8>::get_type()), (size(1), <u32>::get_type())], size(8), align(4));
+    let program = small_program(&[ill_ty], &[storage_live(0)]);
+    assert_ill_formed::<BasicMem>(program, "Type::Tuple: field offset not aligned");
+}
+
+/// The packed attribute caps the alignment of the fields, but the offsets must still respect it.
+#[test]
+fn ill_field_offset_not_aligned_to_packed() {
+    let ill_ty = packed_tuple_ty(
+        &[(size(0), <u8>::get_type()), (size(1), <u32>::get_type())],
+        size(6),
+        align(2),
+        align(2),
+    );
+    let program = small_program(&[ill_ty], &[storage_live(0)]);
+    assert_ill_formed::<BasicMem>(program, "Type::Tuple: field offset not aligned");
+}
+
+/// Without the packed attribute, the tuple must be at least as aligned as its fields.
+#[test]
+fn ill_tuple_less_aligned_than_field() {
+    let ill_ty = tuple_ty(&[(size(0), <u32>::get_type())], size(4), align(1));
+    let program = small_program(&[ill_ty], &[storage_live(0)]);
+    assert_ill_formed::<BasicMem>(
+        program,
+        "Type::Tuple: field alignment bigger than tuple alignment",
+    );
+}
+
+/// `repr(packed)` and `repr(align)` cannot be combined.
+#[test]
+fn ill_packed_and_raised_align() {
+    let ill_ty = packed_tuple_ty(&[(size(0), <u8>::get_type())], size(4), align(4), align(1));
+    let program = small_program(&[ill_ty], &[storage_live(0)]);
+    assert_ill_formed::<BasicMem>(program, "TupleHeadLayout: align bigger than packed attribute");
+}
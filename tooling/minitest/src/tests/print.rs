@@ -15,6 +15,54 @@ fn print_success() {
     assert_eq!(get_stdout::<BasicMem>(p).unwrap(), &["42"]);
 }
 
+#[test]
+fn print_success_output() {
+    let locals = [];
+
+    let b0 = block!(print(const_int::<u32>(42), 1));
+    let b1 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+    let p = program(&[f]);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["42"], &[]);
+}
+
+#[test]
+fn print_stdout_and_stderr() {
+    let locals = [];
+
+    let b0 = block!(print(const_int::<u32>(1), 1));
+    let b1 = block!(eprint(const_bool(true), 2));
+    let b2 = block!(print(const_int::<i8>(-3), 3));
+    let b3 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1, b2, b3]);
+    let p = program(&[f]);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["1", "-3"], &["true"]);
+}
+
+#[test]
+fn print_before_ub() {
+    let locals = [];
+
+    let b0 = block!(print(const_int::<u32>(7), 1));
+    let b1 = block!(unreachable());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+    let p = program(&[f]);
+    assert_output_with::<BasicMem>(
+        p,
+        ub_info("reached unreachable code"),
+        &["7"],
+        &[],
+        |machine| {
+            // The machine stopped at the `Unreachable` terminator.
+            let (_, block, _) = machine.active_location().unwrap();
+            assert_eq!(block, BbName(Name::from_internal(1)));
+        },
+    );
+}
+
 #[test]
 fn print_fail() {
     let locals = [];
@@ -44,3 +44,76 @@ fn pointer_rel() {
     let p = p.finish_program(f);
     assert_stop::<BasicMem>(p);
 }
+
+/// `Cmp` on pointers agrees with `Lt` and `Eq`.
+#[test]
+fn pointer_cmp() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let local1 = f.declare_local::<i32>();
+    let local2 = f.declare_local::<i32>();
+    f.storage_live(local1);
+    f.storage_live(local2);
+    let addr1 = addr_of(local1, <*const i32>::get_type());
+    let addr2 = addr_of(local2, <*const i32>::get_type());
+    f.assume(eq(cmp(addr1, addr1), const_int(0i8)));
+    f.assume(eq(eq(cmp(addr1, addr2), const_int(-1i8)), lt(addr1, addr2)));
+    f.assume(eq(eq(cmp(addr1, addr2), const_int(1i8)), gt(addr1, addr2)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
+
+/// Comparing pointers only looks at their addresses, so dangling pointers can be compared.
+#[test]
+fn pointer_rel_dangling() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let local = f.declare_local::<i32>();
+    let ptr = f.declare_local::<*const i32>();
+    let copy = f.declare_local::<*const i32>();
+    f.storage_live(local);
+    f.storage_live(ptr);
+    f.storage_live(copy);
+    f.assign(ptr, addr_of(local, <*const i32>::get_type()));
+    f.storage_dead(local);
+    f.assign(copy, load(ptr));
+    f.assume(eq(load(ptr), load(copy)));
+    f.assume(le(load(ptr), load(copy)));
+    f.assume(lt(load(ptr), ptr_offset(load(copy), const_int(4usize), InBounds::No)));
+    f.assume(ne(load(ptr), null()));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
+
+/// Provenance is ignored when comparing pointers.
+#[test]
+fn pointer_rel_ignores_provenance() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let local = f.declare_local::<i32>();
+    let ptr = f.declare_local::<*const i32>();
+    let addr = f.declare_local::<usize>();
+    f.storage_live(local);
+    f.storage_live(ptr);
+    f.storage_live(addr);
+    f.assign(ptr, addr_of(local, <*const i32>::get_type()));
+    f.assign(addr, transmute(load(ptr), <usize>::get_type()));
+    let no_prov = transmute(load(addr), <*const i32>::get_type());
+    f.assume(eq(load(ptr), no_prov));
+    f.assume(ge(load(ptr), no_prov));
+    f.assume(eq(cmp(load(ptr), no_prov), const_int(0i8)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
@@ -0,0 +1,111 @@
+//! Copying pointers byte by byte.
+
+use crate::*;
+
+/// `MaybeUninit<u8>`, which copies a byte together with its provenance.
+fn byte_ty() -> Type {
+    union_ty(&[(size(0), <u8>::get_type())], size(1), align(1))
+}
+
+/// The byte at `offset` in `place`, at type `MaybeUninit<u8>`.
+fn byte_at(place: PlaceExpr, offset: usize) -> PlaceExpr {
+    let base = addr_of(place, <*const u8>::get_type());
+    deref(ptr_offset(base, const_int(offset), InBounds::Yes), byte_ty())
+}
+
+/// Stores a pointer to an `i32` in `src`, copies it to `dst` byte by byte,
+/// where byte `i` of `dst` comes from byte `sources[i]` of `src`,
+/// then calls `modify` and prints what `dst` points to.
+fn copy_bytes_program(
+    sources: [usize; 8],
+    modify: impl FnOnce(&mut FunctionBuilder, PlaceExpr),
+) -> Program {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<i32>();
+    let src = f.declare_local::<*const i32>();
+    let dst = f.declare_local::<*const i32>();
+    f.storage_live(x);
+    f.storage_live(src);
+    f.storage_live(dst);
+    f.assign(x, const_int(42i32));
+    f.assign(src, addr_of(x, <*const i32>::get_type()));
+    for (i, source) in sources.into_iter().enumerate() {
+        f.assign(byte_at(dst, i), load(byte_at(src, source)));
+    }
+    modify(&mut f, dst);
+    f.print(load(deref(load(dst), <i32>::get_type())));
+    f.exit();
+    let f = p.finish_function(f);
+
+    p.finish_program(f)
+}
+
+const IN_ORDER: [usize; 8] = [0, 1, 2, 3, 4, 5, 6, 7];
+
+/// A pointer copied byte by byte keeps its provenance.
+#[test]
+fn bytewise_copy() {
+    let p = copy_bytes_program(IN_ORDER, |_f, _dst| {});
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["42"], &[]);
+}
+
+/// The bytes can be copied in any order, as long as each of them ends up in its original position.
+#[test]
+fn bytewise_copy_backwards() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<i32>();
+    let src = f.declare_local::<*const i32>();
+    let dst = f.declare_local::<*const i32>();
+    f.storage_live(x);
+    f.storage_live(src);
+    f.storage_live(dst);
+    f.assign(x, const_int(42i32));
+    f.assign(src, addr_of(x, <*const i32>::get_type()));
+    for i in (0..8).rev() {
+        f.assign(byte_at(dst, i), load(byte_at(src, i)));
+    }
+    f.print(load(deref(load(dst), <i32>::get_type())));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["42"], &[]);
+}
+
+/// Bytes of a pointer that end up in the wrong position do not count,
+/// so the reassembled pointer has no provenance.
+/// We keep the lowest byte in place so that the pointer stays aligned.
+#[test]
+fn bytes_swapped() {
+    let p = copy_bytes_program([0, 2, 1, 3, 4, 5, 6, 7], |_f, _dst| {});
+    assert_ub::<BasicMem>(p, "dereferencing pointer without provenance");
+}
+
+/// Overwriting one byte of the pointer with an integer removes the provenance,
+/// even if the byte keeps its value.
+#[test]
+fn byte_overwritten() {
+    let p = copy_bytes_program(IN_ORDER, |f, dst| {
+        let last = byte_at(dst, 7);
+        f.assign(field(last, 0), load(field(last, 0)));
+    });
+    assert_ub::<BasicMem>(p, "dereferencing pointer without provenance");
+}
+
+/// A pointer reassembled from the bytes of two copies of the same pointer keeps its provenance.
+#[test]
+fn bytes_from_two_copies() {
+    let p = copy_bytes_program(IN_ORDER, |f, dst| {
+        let copy = f.declare_local::<*const i32>();
+        f.storage_live(copy);
+        f.assign(copy, load(dst));
+        for i in 4..8 {
+            f.assign(byte_at(dst, i), load(byte_at(copy, i)));
+        }
+    });
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["42"], &[]);
+}
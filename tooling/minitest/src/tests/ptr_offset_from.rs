@@ -112,3 +112,20 @@ fn inbounds_cross_alloc_same_addr() {
     let p = p.finish_program(f);
     assert_stop::<BasicMem>(p);
 }
+
+/// A distance of zero is not negative.
+#[test]
+fn nonneg_zero() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let var = f.declare_local::<u32>();
+    let var_addr = addr_of(var, <*const u32>::get_type());
+    f.storage_live(var);
+    f.assume(eq(ptr_offset_from_nonneg(var_addr, var_addr, InBounds::Yes), const_int(0isize)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
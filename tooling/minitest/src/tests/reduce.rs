@@ -0,0 +1,66 @@
+//! Reducing failing programs.
+
+use crate::*;
+
+/// A program with UB in its last statement, and lots of unrelated code before it.
+fn ub_program() -> Program {
+    let mut p = ProgramBuilder::new();
+
+    let unused = {
+        let mut f = p.declare_function();
+        f.print(const_int(1u32));
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let y = f.declare_local::<bool>();
+    let z = f.declare_local::<u32>();
+    f.storage_live(x);
+    f.storage_live(y);
+    f.assign(x, const_int(7u32));
+    f.print(load(x));
+    f.assign(y, eq(load(x), const_int(7u32)));
+    f.if_(load(y), |f| f.print(const_int(1u32)), |f| f.print(const_int(2u32)));
+    f.call_ignoreret(fn_ptr(unused), &[]);
+    f.storage_live(z);
+    f.assign(z, div(const_int(7u32), const_int(0u32)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    p.finish_program(f)
+}
+
+#[test]
+fn reduce_ub() {
+    let prog = reduce(ub_program(), has_ub::<BasicMem>("division by zero"));
+    assert_ub::<BasicMem>(prog, "division by zero");
+
+    // Only the start function remains, with a single block that makes `z` live and divides by zero.
+    assert_eq!(prog.functions.keys().count(), 1);
+    let f = prog.functions.get(prog.start).unwrap();
+    assert_eq!(f.blocks.keys().count(), 1);
+    let block = f.blocks.get(f.start).unwrap();
+    assert_eq!(block.statements.iter().count(), 2);
+    assert!(matches!(block.statements.last(), Some(Statement::Assign { .. })));
+    // `z` and the return local.
+    assert_eq!(f.locals.keys().count(), 2);
+}
+
+#[test]
+fn reduce_keeps_failing_program() {
+    // A program that cannot be reduced further stays the same.
+    // The reducer would replace an `exit()` terminator by `Return`, so we use `Return` right away.
+    let locals = [<u32>::get_type()];
+    let stmts = [storage_live(0), assign(local(0), div(const_int(1u32), const_int(0u32)))];
+    let f = function(Ret::No, 0, &locals, &[block(&stmts, Terminator::Return)]);
+    let prog = program(&[f]);
+    assert_eq!(reduce(prog, has_ub::<BasicMem>("division by zero")), prog);
+}
+
+#[test]
+#[should_panic = "the program to reduce does not fail"]
+fn reduce_requires_failure() {
+    reduce(small_program(&[], &[]), has_ub::<BasicMem>("division by zero"));
+}
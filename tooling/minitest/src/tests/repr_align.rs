@@ -0,0 +1,54 @@
+//! Tests for tuples whose alignment is raised by `repr(align)`.
+
+use crate::*;
+
+/// `#[repr(align(16))] struct(u8)`
+fn raised_align_ty() -> Type {
+    tuple_ty(&[(size(0), <u8>::get_type())], size(16), align(16))
+}
+
+#[test]
+fn raised_align_works() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local_with_ty(raised_align_ty());
+    f.assume(eq(compute_align(raised_align_ty(), unit()), const_int(16_usize)));
+    f.assume(eq(compute_size(raised_align_ty(), unit()), const_int(16_usize)));
+    f.storage_live(x);
+    // The local is allocated with the raised alignment.
+    let addr = ptr_addr(addr_of(x, <*const u8>::get_type()));
+    f.assume(eq(rem(addr, const_int(16_usize)), const_int(0_usize)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop_always::<BasicMem>(p, 16);
+}
+
+/// A field with raised alignment must be placed at an offset that respects it.
+#[test]
+fn raised_align_field() {
+    let outer_ty = tuple_ty(
+        &[(size(0), <u8>::get_type()), (size(16), raised_align_ty())],
+        size(32),
+        align(16),
+    );
+    let program = small_program(&[outer_ty], &[storage_live(0)]);
+    assert_stop::<BasicMem>(program);
+}
+
+#[test]
+fn ill_raised_align_field_unaligned() {
+    let ill_ty =
+        tuple_ty(&[(size(0), <u8>::get_type()), (size(8), raised_align_ty())], size(32), align(16));
+    let program = small_program(&[ill_ty], &[storage_live(0)]);
+    assert_ill_formed::<BasicMem>(program, "Type::Tuple: field offset not aligned");
+}
+
+#[test]
+fn ill_raised_align_size_not_multiple() {
+    let ill_ty = tuple_ty(&[(size(0), <u8>::get_type())], size(8), align(16));
+    let program = small_program(&[ill_ty], &[storage_live(0)]);
+    assert_ill_formed::<BasicMem>(program, "Type::Tuple: size not a multiple of alignment");
+}
@@ -0,0 +1,90 @@
+//! Round-trip properties of `encode` and `decode`.
+
+use miniutil::DefaultTarget;
+
+use crate::*;
+
+fn init_bytes(bytes: &[u8]) -> List<AbstractByte<<BasicMem as Memory>::Provenance>> {
+    bytes.iter().map(|&b| AbstractByte::Init(b, None)).collect()
+}
+
+#[test]
+fn random_types_roundtrip() {
+    for seed in 0..500 {
+        if let Err(err) = check_representation::<BasicMem>(seed) {
+            panic!("seed {seed}: {err}");
+        }
+    }
+}
+
+#[test]
+fn generated_types_are_varied() {
+    let types: Vec<Type> = (0..200).map(generate_type::<DefaultTarget>).collect();
+    assert!(types.iter().any(|ty| matches!(ty, Type::Union { .. })));
+    assert!(types.iter().any(|ty| matches!(ty, Type::Enum { .. })));
+    assert!(types.iter().any(|ty| matches!(ty, Type::Array { .. })));
+    assert!(types.iter().any(|ty| matches!(ty, Type::Tuple { valid_range: Some(_), .. })));
+}
+
+/// Encoding a padded tuple makes the padding uninitialized.
+#[test]
+fn padded_tuple() {
+    let ty = tuple_ty(
+        &[(offset(0), <u8>::get_type()), (offset(4), <u32>::get_type())],
+        size(8),
+        align(4),
+    );
+    let bytes = init_bytes(&[1, 0xff, 0xff, 0xff, 2, 0, 0, 0]);
+    let val = ty.decode::<BasicMem>(bytes).unwrap();
+    assert_eq!(val, Value::Tuple(list![Value::Int(Int::from(1)), Value::Int(Int::from(2))]));
+
+    let encoded = ty.encode::<BasicMem>(val);
+    assert_eq!(encoded.get(Int::from(1)), Some(AbstractByte::Uninit));
+    assert_eq!(check_value_roundtrip::<BasicMem>(ty, val), Ok(()));
+    assert_eq!(check_bytes_roundtrip::<BasicMem>(ty, bytes), Ok(()));
+}
+
+/// The bytes between the chunks of a union are not preserved.
+#[test]
+fn union_with_gap() {
+    let ty = Type::Union {
+        fields: list![(offset(0), <u32>::get_type())],
+        size: size(4),
+        align: align(4),
+        chunks: list![(offset(0), size(1)), (offset(2), size(2))],
+    };
+    let bytes = init_bytes(&[1, 2, 3, 4]);
+    let val = ty.decode::<BasicMem>(bytes).unwrap();
+    let encoded = ty.encode::<BasicMem>(val);
+    assert_eq!(encoded, list![
+        AbstractByte::Init(1, None),
+        AbstractByte::Uninit,
+        AbstractByte::Init(3, None),
+        AbstractByte::Init(4, None)
+    ]);
+    assert_eq!(check_bytes_roundtrip::<BasicMem>(ty, bytes), Ok(()));
+    assert_eq!(check_decode_monotone::<BasicMem>(ty, encoded, bytes), Ok(()));
+}
+
+/// `Option<bool>`, with `None` stored as 2.
+#[test]
+fn niche_enum() {
+    let some = enum_variant(tuple_ty(&[(offset(0), bool_ty())], size(1), align(1)), &[]);
+    let none =
+        enum_variant(tuple_ty(&[], size(1), align(1)), &[(offset(0), (IntType::U8, Int::from(2)))]);
+    let discriminator = discriminator_branch::<u8>(offset(0), discriminator_invalid(), &[
+        ((0, 2), discriminator_known(1)),
+        ((2, 3), discriminator_known(0)),
+    ]);
+    let ty = enum_ty::<u8>(&[(0, none), (1, some)], discriminator, size(1), align(1));
+
+    let none_val =
+        Value::Variant { discriminant: Int::from(0), data: GcCow::new(Value::Tuple(list![])) };
+    assert_eq!(ty.decode::<BasicMem>(init_bytes(&[2])), Some(none_val));
+    assert_eq!(ty.decode::<BasicMem>(init_bytes(&[3])), None);
+    assert_eq!(ty.decode::<BasicMem>(list![AbstractByte::Uninit]), None);
+    for byte in 0..=3 {
+        assert_eq!(check_bytes_roundtrip::<BasicMem>(ty, init_bytes(&[byte])), Ok(()));
+    }
+    assert_eq!(check_value_roundtrip::<BasicMem>(ty, none_val), Ok(()));
+}
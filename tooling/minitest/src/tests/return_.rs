@@ -53,5 +53,44 @@ fn return_intrinsic_no_next() {
     let f = function(Ret::No, 0, &locals, &[b0]);
     let p = program(&[f]);
     dump_program(p);
-    assert_ub::<BasicMem>(p, "return from an intrinsic where caller did not specify next block");
+    assert_ill_formed::<BasicMem>(
+        p,
+        "Terminator::Intrinsic: non-diverging intrinsic has no next block",
+    );
+}
+
+#[test]
+fn diverging_intrinsic_with_next() {
+    let locals = [];
+
+    let b0 = block!(Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::Exit,
+        arguments: list![],
+        ret: unit_place(),
+        next_block: Some(BbName(Name::from_internal(1))),
+    });
+    let b1 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+    let p = program(&[f]);
+    dump_program(p);
+    assert_ill_formed::<BasicMem>(p, "Terminator::Intrinsic: diverging intrinsic has a next block");
+}
+
+#[test]
+fn panic_intrinsic_with_next() {
+    let locals = [];
+
+    let b0 = block!(Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::Panic,
+        arguments: list![],
+        ret: unit_place(),
+        next_block: Some(BbName(Name::from_internal(1))),
+    });
+    let b1 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+    let p = program(&[f]);
+    dump_program(p);
+    assert_ill_formed::<BasicMem>(p, "Terminator::Intrinsic: diverging intrinsic has a next block");
 }
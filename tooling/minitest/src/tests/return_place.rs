@@ -0,0 +1,51 @@
+//! The caller's return place belongs to the callee for the duration of the call.
+
+use crate::*;
+
+/// The caller passes a raw pointer to its return place to the callee.
+/// The callee writes `1` through that pointer if `write` is set, and then returns `2`.
+fn alias_return_place(write: bool) -> Program {
+    let mut p = ProgramBuilder::new();
+
+    let mut callee = p.declare_function();
+    let ret = callee.declare_ret::<u32>();
+    let raw = callee.declare_arg::<*mut u32>();
+    if write {
+        callee.assign(deref(load(raw), <u32>::get_type()), const_int(1u32));
+    }
+    callee.assign(ret, const_int(2u32));
+    callee.return_();
+    let callee = p.finish_function(callee);
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    f.storage_live(x);
+    f.call(x, fn_ptr(callee), &[by_value(addr_of(x, <*mut u32>::get_type()))]);
+    f.print(load(x));
+    f.exit();
+    let f = p.finish_function(f);
+
+    p.finish_program(f)
+}
+
+/// Merely passing a pointer to the return place is fine.
+#[test]
+fn return_place_no_access() {
+    let p = alias_return_place(false);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["2"], &[]);
+    assert_output::<StackedBorrowMem>(p, TerminationInfo::MachineStop, &["2"], &[]);
+    assert_output::<TreeBorrowMem>(p, TerminationInfo::MachineStop, &["2"], &[]);
+}
+
+/// Writing to the return place during the call is UB in the aliasing models.
+/// Without an aliasing model, the return value simply overwrites what was written.
+#[test]
+fn return_place_write_during_call() {
+    let p = alias_return_place(true);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["2"], &[]);
+    assert_ub::<StackedBorrowMem>(p, "Stacked Borrows: removing a protected item");
+    assert_ub::<TreeBorrowMem>(
+        p,
+        "Tree Borrows: a protected pointer with Reserved permission becomes Disabled",
+    );
+}
@@ -0,0 +1,186 @@
+//! Locks used as reader-writer locks.
+
+use crate::*;
+
+/// Two readers hold the lock at the same time and read `data`, while a writer updates it.
+/// Each reader waits for the other one to arrive while holding the lock, so this would deadlock
+/// if shared acquisition blocked. The readers do not race with each other, and the writer does
+/// not race with either of them.
+#[test]
+fn concurrent_readers() {
+    let mut p = ProgramBuilder::new();
+    let lock = p.declare_global_zero_initialized::<u32>();
+    let data = p.declare_global_zero_initialized::<u32>();
+    let arrived = p.declare_global_zero_initialized::<u32>();
+
+    let reader = {
+        let mut f = p.declare_function();
+        let _arg = f.declare_arg::<*const ()>();
+        let val = f.declare_local::<u32>();
+        let old = f.declare_local::<u32>();
+        f.storage_live(val);
+        f.storage_live(old);
+        f.lock_acquire_shared(load(lock));
+        f.assign(val, load(data));
+        f.atomic_fetch(FetchBinOp::Add, old, u32_ptr(arrived), const_int(1u32));
+        f.atomic_load(old, u32_ptr(arrived));
+        f.while_(ne(load(old), const_int(2u32)), |f| f.atomic_load(old, u32_ptr(arrived)));
+        f.assign(val, load(data));
+        f.lock_release(load(lock));
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let writer = {
+        let mut f = p.declare_function();
+        let _arg = f.declare_arg::<*const ()>();
+        f.lock_acquire(load(lock));
+        f.assign(data, add(load(data), const_int(1u32)));
+        f.lock_release(load(lock));
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let mut main = p.declare_function();
+    let id_a = main.declare_local::<u32>();
+    let id_b = main.declare_local::<u32>();
+    let id_w = main.declare_local::<u32>();
+    main.storage_live(id_a);
+    main.storage_live(id_b);
+    main.storage_live(id_w);
+    main.lock_create(lock);
+    main.spawn(reader, null(), id_a);
+    main.spawn(reader, null(), id_b);
+    main.spawn(writer, null(), id_w);
+    main.join(load(id_a));
+    main.join(load(id_b));
+    main.join(load(id_w));
+    main.print(load(data));
+    main.exit();
+    let main = p.finish_function(main);
+
+    let p = p.finish_program(main);
+    for _ in 0..16 {
+        assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["1"], &[]);
+    }
+}
+
+/// Readers do not synchronize with each other, so writing under a shared lock is a data race.
+#[test]
+fn readers_writing_race() {
+    let mut p = ProgramBuilder::new();
+    let lock = p.declare_global_zero_initialized::<u32>();
+    let data = p.declare_global_zero_initialized::<u32>();
+
+    let reader = {
+        let mut f = p.declare_function();
+        let _arg = f.declare_arg::<*const ()>();
+        f.lock_acquire_shared(load(lock));
+        f.assign(data, const_int(1u32));
+        f.lock_release(load(lock));
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let mut main = p.declare_function();
+    let id = main.declare_local::<u32>();
+    main.storage_live(id);
+    main.lock_create(lock);
+    main.spawn(reader, null(), id);
+    main.lock_acquire_shared(load(lock));
+    main.assign(data, const_int(2u32));
+    main.lock_release(load(lock));
+    main.join(load(id));
+    main.exit();
+    let main = p.finish_function(main);
+
+    let p = p.finish_program(main);
+    assert!(has_data_race::<BasicMem>(p));
+}
+
+/// The lock cannot be acquired exclusively while it is shared, not even by one of the readers.
+#[test]
+fn exclusive_blocks_on_shared() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let lock = f.declare_local::<u32>();
+    f.storage_live(lock);
+    f.lock_create(lock);
+    f.lock_acquire_shared(load(lock));
+    f.lock_acquire(load(lock));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::Deadlock, &[], &[]);
+}
+
+/// The lock cannot be shared while it is held exclusively.
+#[test]
+fn shared_blocks_on_exclusive() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let lock = f.declare_local::<u32>();
+    f.storage_live(lock);
+    f.lock_create(lock);
+    f.lock_acquire(load(lock));
+    f.lock_acquire_shared(load(lock));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::Deadlock, &[], &[]);
+}
+
+#[test]
+fn shared_twice() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let lock = f.declare_local::<u32>();
+    f.storage_live(lock);
+    f.lock_create(lock);
+    f.lock_acquire_shared(load(lock));
+    f.lock_acquire_shared(load(lock));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(p, "acquiring shared lock that is already held by this thread");
+}
+
+/// Once the last reader is gone, the lock is free again.
+#[test]
+fn release_shared() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let lock = f.declare_local::<u32>();
+    f.storage_live(lock);
+    f.lock_create(lock);
+    f.lock_acquire_shared(load(lock));
+    f.lock_release(load(lock));
+    f.lock_acquire(load(lock));
+    f.lock_release(load(lock));
+    f.lock_release(load(lock));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(p, "releasing non-acquired lock");
+}
+
+#[test]
+fn acquire_shared_non_existing() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    f.lock_acquire_shared(const_int(0u32));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(p, "acquiring non-existing lock");
+}
@@ -0,0 +1,182 @@
+//! Running programs under controlled schedules.
+
+use crate::*;
+
+/// `main` prints 1 and 2 and the other thread prints 3.
+fn print_program() -> Program {
+    let mut p = ProgramBuilder::new();
+
+    let other = {
+        let mut f = p.declare_function();
+        let _arg = f.declare_arg::<*const ()>();
+        f.print(const_int(3u32));
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let mut main = p.declare_function();
+    let id = main.declare_local::<u32>();
+    main.storage_live(id);
+    main.spawn(other, null(), id);
+    main.print(const_int(1u32));
+    main.print(const_int(2u32));
+    main.join(load(id));
+    main.exit();
+    let main = p.finish_function(main);
+
+    p.finish_program(main)
+}
+
+/// Both threads do a few steps of their own, then write `data` without synchronization.
+/// If `locked`, the writes are protected by a lock.
+fn write_program(locked: bool) -> Program {
+    let mut p = ProgramBuilder::new();
+    let lock = p.declare_global_zero_initialized::<u32>();
+    let data = p.declare_global_zero_initialized::<u32>();
+
+    let busy_write = |f: &mut FunctionBuilder, val: u32| {
+        let x = f.declare_local::<u32>();
+        f.storage_live(x);
+        for i in 0..4u32 {
+            f.assign(x, const_int(i));
+        }
+        if locked {
+            f.lock_acquire(load(lock));
+        }
+        f.assign(data, const_int(val));
+        if locked {
+            f.lock_release(load(lock));
+        }
+    };
+
+    let other = {
+        let mut f = p.declare_function();
+        let _arg = f.declare_arg::<*const ()>();
+        busy_write(&mut f, 1);
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let mut main = p.declare_function();
+    let id = main.declare_local::<u32>();
+    main.storage_live(id);
+    main.lock_create(lock);
+    main.spawn(other, null(), id);
+    busy_write(&mut main, 2);
+    main.join(load(id));
+    main.exit();
+    let main = p.finish_function(main);
+
+    p.finish_program(main)
+}
+
+/// A tool cannot make a thread step that `step` would never pick.
+#[test]
+fn step_thread_not_enabled() {
+    let mut interp = Interpreter::<BasicMem>::new(print_program()).unwrap();
+    // Only `main` exists before the spawn.
+    let info = interp.step_with(|machine| machine.step_thread(ThreadId::from(1)));
+    let Some(TerminationInfo::Unsupported(msg)) = info else {
+        panic!("expected the step to be rejected, but got {info:?}")
+    };
+    assert_eq!(msg.get_internal(), "thread 1 cannot take a step, it is not enabled");
+}
+
+/// With a long enough quantum, `main` runs until it blocks on the join.
+#[test]
+fn round_robin_long_quantum() {
+    let out = run_program_scheduled::<BasicMem>(print_program(), &mut RoundRobin::new(1000), 1000)
+        .unwrap();
+    assert_eq!(out.info, TerminationInfo::MachineStop);
+    assert_eq!(out.stdout, &["1", "2", "3"]);
+}
+
+#[test]
+fn seeded_random_is_reproducible() {
+    let p = print_program();
+    for seed in 0..16 {
+        let first = run_program_scheduled::<BasicMem>(p, &mut SeededRandom::new(seed), 1000);
+        let second = run_program_scheduled::<BasicMem>(p, &mut SeededRandom::new(seed), 1000);
+        assert_eq!(first.unwrap().stdout, second.unwrap().stdout);
+    }
+}
+
+/// The exhaustive scheduler covers every order of the prints.
+#[test]
+fn exhaustive_covers_all_orders() {
+    let p = print_program();
+    let mut seen = std::collections::HashSet::new();
+    let mut scheduler = Exhaustive::new(2);
+    loop {
+        let out = run_program_scheduled::<BasicMem>(p, &mut scheduler, 1000).unwrap();
+        assert_eq!(out.info, TerminationInfo::MachineStop);
+        seen.insert(out.stdout.join(","));
+        if !scheduler.next_schedule() {
+            break;
+        }
+    }
+    let expected: std::collections::HashSet<String> =
+        ["3,1,2", "1,3,2", "1,2,3"].iter().map(|x| x.to_string()).collect();
+    assert_eq!(seen, expected);
+}
+
+/// The data race is only detected if both writes happen in consecutive steps,
+/// which random scheduling rarely does.
+#[test]
+fn race_under_some_schedule() {
+    assert_ub_under_any_schedule::<BasicMem>(write_program(false), "Data race");
+}
+
+#[test]
+fn no_race_under_any_schedule() {
+    assert_stop_under_every_schedule::<BasicMem>(write_program(true));
+}
+
+/// Two threads acquire two locks in opposite order.
+#[test]
+fn lock_order_inversion() {
+    let mut p = ProgramBuilder::new();
+    let lock_a = p.declare_global_zero_initialized::<u32>();
+    let lock_b = p.declare_global_zero_initialized::<u32>();
+
+    let other = {
+        let mut f = p.declare_function();
+        let _arg = f.declare_arg::<*const ()>();
+        f.lock_acquire(load(lock_b));
+        f.lock_acquire(load(lock_a));
+        f.lock_release(load(lock_a));
+        f.lock_release(load(lock_b));
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let mut main = p.declare_function();
+    let id = main.declare_local::<u32>();
+    main.storage_live(id);
+    main.lock_create(lock_a);
+    main.lock_create(lock_b);
+    main.spawn(other, null(), id);
+    main.lock_acquire(load(lock_a));
+    main.lock_acquire(load(lock_b));
+    main.lock_release(load(lock_b));
+    main.lock_release(load(lock_a));
+    main.join(load(id));
+    main.exit();
+    let main = p.finish_function(main);
+
+    let p = p.finish_program(main);
+    assert_deadlock_under_any_schedule::<BasicMem>(p);
+}
+
+/// A schedule that runs into the step limit is not a pass.
+#[test]
+#[should_panic = "a schedule did not terminate within"]
+fn step_limit_is_not_a_pass() {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    f.while_(const_bool(true), |_f| {});
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+    assert_deadlock_under_any_schedule::<BasicMem>(p);
+}
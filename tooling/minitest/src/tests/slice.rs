@@ -96,7 +96,7 @@ fn ill_slice_ref_unsized_elem() {
     assert_ill_formed::<BasicMem>(p, "Type::Slice: unsized element type");
 }
 
-/// Asserts that locals must be sized
+/// Asserts that locals must be sized, unless they get their size when they become live
 #[test]
 fn ill_local() {
     let mut p = ProgramBuilder::new();
@@ -104,13 +104,37 @@ fn ill_local() {
     let f = {
         let mut f = p.declare_function();
         // ill formed:
-        f.declare_local_with_ty(<[u32]>::get_type());
+        let local = f.declare_local_with_ty(<[u32]>::get_type());
+        f.storage_live(local);
         f.exit();
         p.finish_function(f)
     };
 
     let p = p.finish_program(f);
-    assert_ill_formed::<BasicMem>(p, "Function: unsized local variable");
+    assert_ill_formed::<BasicMem>(p, "Statement::StorageLive: missing metadata for unsized local");
+}
+
+/// Asserts that arguments must be sized
+#[test]
+fn ill_unsized_arg() {
+    let mut p = ProgramBuilder::new();
+
+    let _f = {
+        let mut f = p.declare_function();
+        // ill formed:
+        f.declare_arg_with_ty(<[u32]>::get_type());
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let main = {
+        let mut main = p.declare_function();
+        main.exit();
+        p.finish_function(main)
+    };
+
+    let p = p.finish_program(main);
+    assert_ill_formed::<BasicMem>(p, "Function: unsized argument or return local");
 }
 
 /// Asserts loads at unsized types are ill-formed
@@ -243,3 +243,36 @@ fn join_no_thread() {
 
     assert_ub::<BasicMem>(p, "`Join` intrinsic: join non existing thread");
 }
+
+#[test]
+fn too_many_live_threads() {
+    let mut p = ProgramBuilder::new();
+    let lock = p.declare_global_zero_initialized::<u32>();
+
+    // All spawned threads block on the lock held by the main thread, so they all stay live.
+    let blocked = {
+        let mut blocked = p.declare_function();
+        blocked.declare_arg::<*const ()>();
+        blocked.lock_acquire(load(lock));
+        blocked.return_();
+        p.finish_function(blocked)
+    };
+
+    let mut main = p.declare_function();
+    let thread_id = main.declare_local::<u32>();
+    main.storage_live(thread_id);
+    main.lock_create(lock);
+    main.lock_acquire(load(lock));
+    // Keep spawning until the target's thread limit is reached.
+    main.while_(const_bool(true), |f| {
+        f.spawn(blocked, null(), thread_id);
+    });
+    main.exit();
+    let main = p.finish_function(main);
+
+    let p = p.finish_program(main);
+    let info = TerminationInfo::ResourceExhaustion(prelude::String::from_internal(
+        "too many live threads".to_string(),
+    ));
+    assert_output::<BasicMem>(p, info, &[], &[]);
+}
@@ -0,0 +1,215 @@
+//! Classic Stacked Borrows counterexamples: programs that are fine under the basic memory model
+//! but violate the aliasing rules of Stacked Borrows.
+
+use crate::*;
+
+#[test]
+fn write_through_shared_ref() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let r = f.declare_local::<&u32>();
+    f.storage_live(x);
+    f.storage_live(r);
+    f.assign(x, const_int(0u32));
+    f.assign(r, addr_of(x, <&u32>::get_type()));
+    f.validate(r, false);
+    f.assign(deref(raw_mut(load(r)), <u32>::get_type()), const_int(1u32));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub_stacked_borrows(p, "Stacked Borrows: no item granting write access");
+}
+
+/// Creating a second mutable reference from the parent invalidates the first one.
+/// (Tree Borrows accepts this program.)
+#[test]
+fn interleaved_mut_refs() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let r1 = f.declare_local::<&mut u32>();
+    let r2 = f.declare_local::<&mut u32>();
+    f.storage_live(x);
+    f.storage_live(r1);
+    f.storage_live(r2);
+    f.assign(x, const_int(0u32));
+    f.assign(r1, addr_of(x, <&mut u32>::get_type()));
+    f.validate(r1, false);
+    f.assign(r2, addr_of(x, <&mut u32>::get_type()));
+    f.validate(r2, false);
+    f.assign(deref(load(r1), <u32>::get_type()), const_int(1u32));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub_stacked_borrows(p, "Stacked Borrows: no item granting write access");
+    assert_stop::<TreeBorrowMem>(p);
+}
+
+/// Reading through the parent invalidates a mutable reference, but not a shared one.
+#[test]
+fn read_invalidates_unique_only() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let s = f.declare_local::<&u32>();
+    let m = f.declare_local::<&mut u32>();
+    let y = f.declare_local::<u32>();
+    f.storage_live(x);
+    f.storage_live(s);
+    f.storage_live(m);
+    f.storage_live(y);
+    f.assign(x, const_int(0u32));
+    f.assign(s, addr_of(x, <&u32>::get_type()));
+    f.validate(s, false);
+    f.assign(y, load(x));
+    f.assign(y, load(deref(load(s), <u32>::get_type())));
+    f.assign(m, addr_of(x, <&mut u32>::get_type()));
+    f.validate(m, false);
+    f.assign(y, load(x));
+    f.assign(y, load(deref(load(m), <u32>::get_type())));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub_stacked_borrows(p, "Stacked Borrows: no item granting read access");
+}
+
+/// Writing through a raw pointer while a protected mutable reference to the same memory exists is UB.
+#[test]
+fn protector() {
+    let mut p = ProgramBuilder::new();
+
+    let mut callee = p.declare_function();
+    let arg = callee.declare_arg::<&mut u32>();
+    let raw = callee.declare_arg::<*mut u32>();
+    callee.validate(arg, true);
+    callee.assign(deref(load(raw), <u32>::get_type()), const_int(1u32));
+    callee.return_();
+    let callee = p.finish_function(callee);
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let r = f.declare_local::<&mut u32>();
+    f.storage_live(x);
+    f.storage_live(r);
+    f.assign(x, const_int(0u32));
+    f.assign(r, addr_of(x, <&mut u32>::get_type()));
+    f.validate(r, false);
+    f.call_ignoreret(fn_ptr(callee), &[by_value(load(r)), by_value(raw_mut(load(r)))]);
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub_stacked_borrows(p, "Stacked Borrows: removing a protected item");
+}
+
+/// Once the call has ended, the protector is gone and the write is fine.
+#[test]
+fn protector_released() {
+    let mut p = ProgramBuilder::new();
+
+    let mut callee = p.declare_function();
+    let arg = callee.declare_arg::<&mut u32>();
+    callee.validate(arg, true);
+    callee.return_();
+    let callee = p.finish_function(callee);
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let r = f.declare_local::<&mut u32>();
+    f.storage_live(x);
+    f.storage_live(r);
+    f.assign(x, const_int(0u32));
+    f.assign(r, addr_of(x, <&mut u32>::get_type()));
+    f.validate(r, false);
+    f.call_ignoreret(fn_ptr(callee), &[by_value(load(r))]);
+    f.assign(x, const_int(1u32));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<StackedBorrowMem>(p);
+}
+
+/// The equivalent of `x.push(x.len())`: a mutable reference is created (reserved) for the call,
+/// then the arguments read `x` through another reference, and then the call activates the mutable reference.
+fn two_phase_program(two_phase: bool) -> Program {
+    let mut p = ProgramBuilder::new();
+
+    let mut push = p.declare_function();
+    let arg = push.declare_arg::<&mut u32>();
+    let val = push.declare_arg::<u32>();
+    push.validate(arg, true);
+    push.assign(deref(load(arg), <u32>::get_type()), load(val));
+    push.return_();
+    let push = p.finish_function(push);
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let m = f.declare_local::<&mut u32>();
+    let s = f.declare_local::<&u32>();
+    f.storage_live(x);
+    f.storage_live(m);
+    f.storage_live(s);
+    f.assign(x, const_int(0u32));
+    f.assign(m, addr_of(x, <&mut u32>::get_type()));
+    if two_phase {
+        f.validate_two_phase(m);
+    } else {
+        f.validate(m, false);
+    }
+    f.assign(s, addr_of(x, <&u32>::get_type()));
+    f.validate(s, false);
+    f.call_ignoreret(fn_ptr(push), &[
+        by_value(load(m)),
+        by_value(load(deref(load(s), <u32>::get_type()))),
+    ]);
+    f.exit();
+    let f = p.finish_function(f);
+
+    p.finish_program(f)
+}
+
+/// Reads through other pointers do not invalidate a two-phase borrow before it gets activated.
+#[test]
+fn two_phase_borrow() {
+    let p = two_phase_program(true);
+    assert_stop::<StackedBorrowMem>(p);
+    assert_stop::<TreeBorrowMem>(p);
+}
+
+/// Without two-phase borrows, the read invalidates the mutable reference.
+#[test]
+fn two_phase_borrow_required() {
+    let p = two_phase_program(false);
+    assert_ub_stacked_borrows(p, "Stacked Borrows: no item granting write access");
+}
+
+/// Unlike reads, writes through the parent still invalidate a two-phase borrow.
+#[test]
+fn two_phase_borrow_parent_write() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let m = f.declare_local::<&mut u32>();
+    f.storage_live(x);
+    f.storage_live(m);
+    f.assign(x, const_int(0u32));
+    f.assign(m, addr_of(x, <&mut u32>::get_type()));
+    f.validate_two_phase(m);
+    f.assign(deref(load(m), <u32>::get_type()), const_int(1u32));
+    f.assign(x, const_int(2u32));
+    f.assign(deref(load(m), <u32>::get_type()), const_int(3u32));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub_stacked_borrows(p, "Stacked Borrows: no item granting write access");
+}
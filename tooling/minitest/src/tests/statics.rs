@@ -0,0 +1,163 @@
+use miniutil::DefaultTarget;
+
+use crate::*;
+
+/// Spawns a thread running `thread`, runs `main_body` concurrently, joins the thread, and then prints `result`.
+fn two_threads(
+    mut p: ProgramBuilder,
+    thread: impl FnOnce(&mut FunctionBuilder),
+    main_body: impl FnOnce(&mut FunctionBuilder),
+    result: PlaceExpr,
+) -> Program {
+    let mut t = p.declare_function();
+    t.declare_arg::<*const ()>();
+    thread(&mut t);
+    t.return_();
+    let t = p.finish_function(t);
+
+    let mut main = p.declare_function();
+    let id = main.declare_local::<u32>();
+    main.storage_live(id);
+    main.spawn(t, null(), id);
+    main_body(&mut main);
+    main.join(load(id));
+    main.print(load(result));
+    main.exit();
+    let main = p.finish_function(main);
+    p.finish_program(main)
+}
+
+#[test]
+fn static_initial_value() {
+    let mut p = ProgramBuilder::new();
+    let x = p.declare_global_int(5u32);
+    let y = p.declare_global_int(-3i16);
+
+    let mut f = p.declare_function();
+    f.print(load(x));
+    f.print(load(y));
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["5", "-3"], &[]);
+}
+
+/// Statics are mutable, like `static mut` or statics with interior mutability.
+#[test]
+fn static_mut() {
+    let mut p = ProgramBuilder::new();
+    let x = p.declare_global_int(5u32);
+
+    let mut f = p.declare_function();
+    f.assign(x, add(load(x), const_int(1u32)));
+    f.print(load(x));
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["6"], &[]);
+}
+
+#[test]
+fn static_dealloc() {
+    let mut p = ProgramBuilder::new();
+    let x = p.declare_global_int(5u32);
+
+    let mut f = p.declare_function();
+    f.deallocate(u32_ptr(x), const_int(4usize), const_int(4usize));
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(p, "deallocating Global memory with Heap deallocation operation");
+}
+
+/// Unsynchronized non-atomic writes to a static from two threads are a data race.
+#[test]
+fn static_race() {
+    let mut p = ProgramBuilder::new();
+    let x = p.declare_global_int(0u32);
+
+    let prog =
+        two_threads(p, |t| t.assign(x, const_int(1u32)), |main| main.assign(x, const_int(2u32)), x);
+    assert!(has_data_race::<BasicMem>(prog));
+}
+
+/// The initialization of a static does not race with the threads of the program.
+#[test]
+fn static_init_no_race() {
+    let mut p = ProgramBuilder::new();
+    let x = p.declare_global_int(7u32);
+    let r = p.declare_global_int(0u32);
+
+    let prog = two_threads(p, |t| t.assign(r, load(x)), |_main| {}, r);
+    for _ in 0..32 {
+        assert_output::<BasicMem>(prog, TerminationInfo::MachineStop, &["7"], &[]);
+    }
+}
+
+/// Atomic accesses to a static from two threads do not race.
+#[test]
+fn static_atomic_counter() {
+    let mut p = ProgramBuilder::new();
+    let counter = p.declare_global_int(0u32);
+    let old = p.declare_global_int(0u32);
+    let old2 = p.declare_global_int(0u32);
+
+    let prog = two_threads(
+        p,
+        |t| t.atomic_fetch(FetchBinOp::Add, old, u32_ptr(counter), const_int(1u32)),
+        |main| main.atomic_fetch(FetchBinOp::Add, old2, u32_ptr(counter), const_int(1u32)),
+        counter,
+    );
+    for _ in 0..32 {
+        assert_output::<BasicMem>(prog, TerminationInfo::MachineStop, &["2"], &[]);
+    }
+}
+
+/// Declares an immutable global holding the `u32` 7, like a promoted constant `&7`.
+fn promoted_seven(p: &mut ProgramBuilder) -> GlobalName {
+    let bytes = DefaultTarget::ENDIANNESS.encode(
+        Signedness::Unsigned,
+        Size::from_bytes_const(4),
+        Int::from(7),
+    );
+    let bytes: Vec<Option<u8>> = bytes.unwrap().iter().map(Some).collect();
+    p.declare_immutable_global(&bytes, <u32>::get_align())
+}
+
+#[test]
+fn immutable_global_read() {
+    let mut p = ProgramBuilder::new();
+    let seven = promoted_seven(&mut p);
+
+    let mut f = p.declare_function();
+    f.print(load(global_by_name::<u32>(seven)));
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["7"], &[]);
+}
+
+/// Writing through a pointer derived from a shared reference to a promoted constant is UB.
+fn write_to_promoted() -> Program {
+    let mut p = ProgramBuilder::new();
+    let seven = promoted_seven(&mut p);
+
+    let mut f = p.declare_function();
+    let r = f.declare_local::<&u32>();
+    let ptr = f.declare_local::<*mut u32>();
+    f.storage_live(r);
+    f.storage_live(ptr);
+    f.assign(r, addr_of(global_by_name::<u32>(seven), <&u32>::get_type()));
+    f.assign(ptr, transmute(load(r), <*mut u32>::get_type()));
+    f.assign(deref(load(ptr), <u32>::get_type()), const_int(8u32));
+    f.exit();
+    let f = p.finish_function(f);
+    p.finish_program(f)
+}
+
+#[test]
+fn immutable_global_write() {
+    assert_ub::<BasicMem>(write_to_promoted(), "store to immutable memory");
+    assert_ub::<TreeBorrowMem>(write_to_promoted(), "store to immutable memory");
+    assert_ub::<StackedBorrowMem>(write_to_promoted(), "store to immutable memory");
+}
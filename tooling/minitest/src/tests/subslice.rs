@@ -0,0 +1,116 @@
+//! The `ConstantIndex` and `Subslice` place projections.
+
+use crate::*;
+
+/// Declares an `[u32; 4]` local holding `[1, 2, 3, 4]` and a `&[u32]` local pointing to it.
+fn array_and_slice(f: &mut FunctionBuilder) -> (PlaceExpr, PlaceExpr) {
+    let arr = f.declare_local::<[u32; 4]>();
+    let slice = f.declare_local::<&[u32]>();
+    f.storage_live(arr);
+    f.storage_live(slice);
+    f.assign(arr, array(&[1u32, 2, 3, 4].map(const_int), <u32>::get_type()));
+    f.assign(
+        slice,
+        construct_wide_pointer(
+            addr_of(arr, <&[u32; 4]>::get_type()),
+            const_int(4_usize),
+            <&[u32]>::get_type(),
+        ),
+    );
+    (arr, deref(load(slice), <[u32]>::get_type()))
+}
+
+#[test]
+fn constant_index_works() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let (arr, slice) = array_and_slice(&mut f);
+    f.print(load(constant_index(arr, 0, false)));
+    f.print(load(constant_index(arr, 1, true)));
+    f.print(load(constant_index(slice, 1, false)));
+    f.print(load(constant_index(slice, 2, true)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["1", "4", "2", "3"], &[]);
+}
+
+#[test]
+fn constant_index_out_of_bounds() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let (_arr, slice) = array_and_slice(&mut f);
+    f.print(load(constant_index(slice, 5, true)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(p, "access to out-of-bounds index");
+}
+
+#[test]
+fn subslice_array() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let (arr, _slice) = array_and_slice(&mut f);
+    let middle = f.declare_local::<[u32; 2]>();
+    f.storage_live(middle);
+    f.assign(middle, load(subslice(arr, 1, 3)));
+    f.print(load(index(middle, const_int(0usize))));
+    f.print(load(index(middle, const_int(1usize))));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["2", "3"], &[]);
+}
+
+/// For slices, the end of the range is counted from the end.
+#[test]
+fn subslice_slice() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let (_arr, slice) = array_and_slice(&mut f);
+    let rest = subslice(slice, 1, 1);
+    f.print(get_metadata(addr_of(rest, <*const [u32]>::get_type())));
+    f.print(load(constant_index(rest, 0, false)));
+    f.print(load(constant_index(rest, 1, true)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["2", "2", "3"], &[]);
+}
+
+#[test]
+fn subslice_slice_out_of_bounds() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let (_arr, slice) = array_and_slice(&mut f);
+    f.print(get_metadata(addr_of(subslice(slice, 3, 2), <*const [u32]>::get_type())));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(p, "access to out-of-bounds subslice");
+}
+
+#[test]
+fn subslice_array_ill_formed() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let (arr, _slice) = array_and_slice(&mut f);
+    f.print(load(index(subslice(arr, 2, 5), const_int(0usize))));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ill_formed::<BasicMem>(p, "PlaceExpr::Subslice: range out of bounds");
+}
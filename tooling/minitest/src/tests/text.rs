@@ -0,0 +1,168 @@
+//! The textual program format: printing, parsing, and error reporting.
+
+use crate::*;
+
+const U8_INTTYPE: IntType =
+    IntType { signed: Signedness::Unsigned, size: Size::from_bytes_const(1) };
+
+#[track_caller]
+fn assert_roundtrip(prog: Program) {
+    let text = program_to_text(prog);
+    let parsed = program_from_text(&text).unwrap_or_else(|err| panic!("{err}\n{text}"));
+    assert_eq!(parsed, prog);
+    // Printing is deterministic.
+    assert_eq!(program_to_text(parsed), text);
+}
+
+#[test]
+fn parse_and_run() {
+    let p = program_from_text(include_str!("text_print.mr")).unwrap();
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["42"], &[]);
+}
+
+#[test]
+fn roundtrip_small_program() {
+    let locals = [<u32>::get_type(), <bool>::get_type()];
+    let b0 = block!(
+        storage_live(0),
+        storage_live(1),
+        assign(local(0), const_int(u32::MAX)),
+        assign(local(1), const_bool(true)),
+        if_(load(local(1)), 1, 2)
+    );
+    let b1 = block!(print(load(local(0)), 2));
+    let b2 = block!(storage_dead(0), storage_dead(1), exit());
+    let f = function(Ret::No, 0, &locals, &[b0, b1, b2]);
+    assert_roundtrip(program(&[f]));
+}
+
+/// Traits, vtables, and calls.
+#[test]
+fn roundtrip_trait_objects() {
+    let mut p = ProgramBuilder::new();
+
+    let mut trait_a = p.declare_trait();
+    let method_a_foo = trait_a.declare_method();
+    let trait_a = p.finish_trait(trait_a);
+    let trait_obj_a_ty = trait_object_ty(trait_a);
+
+    let impl_a_foo_for_usize = {
+        let mut f = p.declare_function();
+        let self_ = f.declare_arg::<&usize>();
+        let ret = f.declare_ret::<usize>();
+        f.assign(ret, load(deref(load(self_), <usize>::get_type())));
+        f.return_();
+        p.finish_function(f)
+    };
+
+    let mut usize_a_vtable = p.declare_vtable_for_ty(trait_a, <usize>::get_type());
+    usize_a_vtable.add_method(method_a_foo, impl_a_foo_for_usize);
+    let usize_a_vtable = p.finish_vtable(usize_a_vtable);
+
+    let main = {
+        let mut main = p.declare_function();
+        let x = main.declare_local::<usize>();
+        main.storage_live(x);
+        main.assign(x, const_int(42_usize));
+        let y = main.declare_local_with_ty(ref_ty_default_markers_for(trait_obj_a_ty));
+        main.storage_live(y);
+        main.assign(
+            y,
+            construct_wide_pointer(
+                addr_of(x, <&usize>::get_type()),
+                const_vtable(usize_a_vtable, trait_a),
+                ref_ty_default_markers_for(trait_obj_a_ty),
+            ),
+        );
+        let foo_ret = main.declare_local::<usize>();
+        main.storage_live(foo_ret);
+        main.call(foo_ret, vtable_method_lookup(get_metadata(load(y)), method_a_foo), &[by_value(
+            ptr_to_ptr(get_thin_pointer(load(y)), <&usize>::get_type()),
+        )]);
+        main.exit();
+        p.finish_function(main)
+    };
+
+    assert_roundtrip(p.finish_program(main));
+}
+
+/// Globals, enums, unions, floats, chars, and negative integers.
+#[test]
+fn roundtrip_data() {
+    let mut p = ProgramBuilder::new();
+    let counter = p.declare_global_int::<i64>(-7);
+    let uninit = p.declare_immutable_global(&[Some(0xab), None], align(2));
+
+    let enum_ty = enum_ty::<u8>(
+        &[
+            (0, enum_variant(<()>::get_type(), &[(offset(0), (U8_INTTYPE, 0.into()))])),
+            (1, enum_variant(<()>::get_type(), &[(offset(0), (U8_INTTYPE, 1.into()))])),
+        ],
+        discriminator_branch::<u8>(offset(0), discriminator_invalid(), &[
+            ((0, 1), discriminator_known(0)),
+            ((1, 2), discriminator_known(1)),
+        ]),
+        size(1),
+        align(1),
+    );
+    let union_ty = union_ty(
+        &[(offset(0), <u16>::get_type()), (offset(0), <[u8; 2]>::get_type())],
+        size(2),
+        align(2),
+    );
+
+    let mut f = p.declare_function();
+    let e = f.declare_local_with_ty(enum_ty);
+    let u = f.declare_local_with_ty(union_ty);
+    let x = f.declare_local::<f64>();
+    let c = f.declare_local::<char>();
+    let ptr = f.declare_local::<*const u8>();
+    f.storage_live(e);
+    f.assign(e, variant(1, tuple(&[], <()>::get_type()), enum_ty));
+    f.storage_live(u);
+    f.assign(field(u, 0), const_int(0xffff_u16));
+    f.storage_live(x);
+    f.assign(x, const_f64(-0.5));
+    f.storage_live(c);
+    f.assign(c, const_char('ß'));
+    f.storage_live(ptr);
+    f.assign(ptr, const_global_ptr(uninit, offset(1)));
+    f.assign(counter, add(load(counter), const_int(1_i64)));
+    f.print(load(counter));
+    f.exit();
+    let f = p.finish_function(f);
+
+    assert_roundtrip(p.finish_program(f));
+}
+
+#[test]
+fn comments_and_whitespace() {
+    let text = program_to_text(small_program(&[], &[]));
+    let text = format!(
+        "; leading comment\n{}\n   ; trailing comment\n",
+        text.replace('\n', " ; end of line\n")
+    );
+    assert_eq!(program_from_text(&text), Ok(small_program(&[], &[])));
+}
+
+#[test]
+fn parse_errors() {
+    let err = program_from_text("(Program\n  (functions ())\n").unwrap_err();
+    assert_eq!(err.to_string(), "line 1: unclosed `(`");
+
+    let err = program_from_text("(Program) extra").unwrap_err();
+    assert_eq!(err.to_string(), "line 1: unexpected `e` after the end of the program");
+
+    let err = program_from_text("(Function)").unwrap_err();
+    assert_eq!(err.to_string(), "line 1: expected `(Program ..)`, found `(Function)`");
+
+    let text =
+        program_to_text(small_program(&[], &[])).replacen("(start bb0)", "(start block0)", 1);
+    let err = program_from_text(&text).unwrap_err();
+    assert!(err.msg.contains("expected a name like `bb0`, found `block0`"), "{err}");
+    assert!(err.line > 1);
+
+    let text = program_to_text(small_program(&[], &[])).replacen("Exit", "Leave", 1);
+    let err = program_from_text(&text).unwrap_err();
+    assert!(err.msg.contains("invalid intrinsic `Leave`"), "{err}");
+}
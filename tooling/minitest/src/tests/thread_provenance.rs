@@ -0,0 +1,142 @@
+//! Sending pointers between threads: provenance is carried along with the pointer.
+
+use crate::*;
+
+/// A thread can access a local of the main thread through the spawn argument.
+#[test]
+fn pointer_via_spawn_argument() {
+    let mut p = ProgramBuilder::new();
+
+    let t = p.thread_function(|f, arg| {
+        let ptr = ptr_to_ptr(load(arg), <*const u32>::get_type());
+        f.print(load(deref(ptr, <u32>::get_type())));
+    });
+
+    let mut main = p.declare_function();
+    let x = main.declare_local::<u32>();
+    let id = main.declare_local::<u32>();
+    main.storage_live(x);
+    main.storage_live(id);
+    main.assign(x, const_int(42u32));
+    main.spawn(t, addr_of(x, <*const ()>::get_type()), id);
+    main.join(load(id));
+    main.exit();
+    let main = p.finish_function(main);
+
+    let p = p.finish_program(main);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["42"], &[]);
+}
+
+/// A pointer published with an atomic store keeps its provenance.
+#[test]
+fn pointer_via_atomic() {
+    let mut p = ProgramBuilder::new();
+    let data = p.declare_global_zero_initialized::<u32>();
+    let slot = p.declare_global_zero_initialized::<*const u32>();
+
+    let t = p.thread_function(|f, _arg| {
+        f.assign(data, const_int(5u32));
+        f.atomic_store(addr_of(slot, <*mut *const u32>::get_type()), u32_ptr(data));
+    });
+
+    let mut main = p.declare_function();
+    let id = main.declare_local::<u32>();
+    let ptr = main.declare_local::<*const u32>();
+    main.storage_live(id);
+    main.storage_live(ptr);
+    main.spawn(t, null(), id);
+    main.join(load(id));
+    main.atomic_load(ptr, addr_of(slot, <*const *const u32>::get_type()));
+    main.print(load(deref(load(ptr), <u32>::get_type())));
+    main.exit();
+    let main = p.finish_function(main);
+
+    let p = p.finish_program(main);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["5"], &[]);
+}
+
+/// Exposed provenance is shared by all threads.
+#[test]
+fn exposed_in_other_thread() {
+    let mut p = ProgramBuilder::new();
+    let data = p.declare_global_zero_initialized::<u32>();
+    let addr = p.declare_global_zero_initialized::<usize>();
+
+    let t = p.thread_function(|f, _arg| {
+        let tmp = f.declare_local::<usize>();
+        f.storage_live(tmp);
+        f.assign(data, const_int(7u32));
+        f.expose_provenance(tmp, u32_ptr(data));
+        f.atomic_store(addr_of(addr, <*mut usize>::get_type()), load(tmp));
+    });
+
+    let mut main = p.declare_function();
+    let id = main.declare_local::<u32>();
+    let ptr = main.declare_local::<*const u32>();
+    main.storage_live(id);
+    main.storage_live(ptr);
+    main.spawn(t, null(), id);
+    main.join(load(id));
+    main.with_exposed_provenance(ptr, load(addr));
+    main.print(load(deref(load(ptr), <u32>::get_type())));
+    main.exit();
+    let main = p.finish_function(main);
+
+    let p = p.finish_program(main);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["7"], &[]);
+}
+
+/// A pointer to a local of a thread that has terminated is dangling for everyone.
+#[test]
+fn pointer_to_dead_thread_local() {
+    let mut p = ProgramBuilder::new();
+    let slot = p.declare_global_zero_initialized::<*const u32>();
+
+    let t = p.thread_function(|f, _arg| {
+        let x = f.declare_local::<u32>();
+        f.storage_live(x);
+        f.assign(x, const_int(1u32));
+        f.atomic_store(addr_of(slot, <*mut *const u32>::get_type()), u32_ptr(x));
+    });
+
+    let mut main = p.declare_function();
+    let id = main.declare_local::<u32>();
+    let ptr = main.declare_local::<*const u32>();
+    main.storage_live(id);
+    main.storage_live(ptr);
+    main.spawn(t, null(), id);
+    main.join(load(id));
+    main.atomic_load(ptr, addr_of(slot, <*const *const u32>::get_type()));
+    main.print(load(deref(load(ptr), <u32>::get_type())));
+    main.exit();
+    let main = p.finish_function(main);
+
+    let p = p.finish_program(main);
+    assert_ub::<BasicMem>(p, "dereferencing pointer to dead allocation");
+}
+
+/// Publishing a pointer with a non-atomic store that races with the load is a data race.
+#[test]
+fn racy_pointer_publication() {
+    let mut p = ProgramBuilder::new();
+    let data = p.declare_global_zero_initialized::<u32>();
+    let slot = p.declare_global_zero_initialized::<*const u32>();
+
+    let t = p.thread_function(|f, _arg| {
+        f.assign(slot, u32_ptr(data));
+    });
+
+    let mut main = p.declare_function();
+    let id = main.declare_local::<u32>();
+    let ptr = main.declare_local::<*const u32>();
+    main.storage_live(id);
+    main.storage_live(ptr);
+    main.spawn(t, null(), id);
+    main.assign(ptr, load(slot));
+    main.join(load(id));
+    main.exit();
+    let main = p.finish_function(main);
+
+    let p = p.finish_program(main);
+    assert!(has_data_race::<BasicMem>(p));
+}
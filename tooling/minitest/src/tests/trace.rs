@@ -0,0 +1,121 @@
+//! The execution trace recorded when `MachineConfig::trace` is set.
+
+use crate::*;
+
+fn accesses(trace: List<TraceEvent>) -> Vec<Access> {
+    trace
+        .iter()
+        .filter_map(|event| {
+            match event {
+                TraceEvent::MemoryAccess { access, .. } => Some(access),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn intrinsics(trace: List<TraceEvent>) -> Vec<IntrinsicOp> {
+    trace
+        .iter()
+        .filter_map(|event| {
+            match event {
+                TraceEvent::Intrinsic { intrinsic, .. } => Some(intrinsic),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn trace_memory_accesses() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    f.storage_live(x);
+    f.assign(x, const_int(1u32));
+    f.print(load(x));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    let (output, trace) = run_program_with_trace::<BasicMem>(p);
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    assert_eq!(output.stdout, &["1"]);
+
+    // The store to `x` happens before the load for printing it.
+    // Then the print intrinsic stores its unit return value, which is a zero-sized access.
+    let accesses = accesses(trace);
+    assert_eq!(accesses.len(), 3);
+    assert_eq!(accesses[0].ty, AccessType::Store);
+    assert_eq!(accesses[1].ty, AccessType::Load);
+    assert_eq!(accesses[0].addr, accesses[1].addr);
+    assert_eq!(accesses[0].len, size(4));
+    assert_eq!(accesses[1].atomicity, Atomicity::None);
+    assert_eq!(accesses[2].ty, AccessType::Store);
+    assert_eq!(accesses[2].len, size(0));
+}
+
+#[test]
+fn trace_lock_operations() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let lock = f.declare_local::<u32>();
+    f.storage_live(lock);
+    f.lock_create(lock);
+    f.lock_acquire(load(lock));
+    f.lock_release(load(lock));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    let (output, trace) = run_program_with_trace::<BasicMem>(p);
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    assert_eq!(intrinsics(trace), &[
+        IntrinsicOp::Lock(IntrinsicLockOp::Create),
+        IntrinsicOp::Lock(IntrinsicLockOp::Acquire),
+        IntrinsicOp::Lock(IntrinsicLockOp::Release),
+        IntrinsicOp::Exit,
+    ]);
+}
+
+/// Joining a thread that has not finished yet requires switching to that thread.
+#[test]
+fn trace_thread_switch() {
+    fn thread() -> Function {
+        let locals = [<*const ()>::get_type()];
+        let b0 = block!(return_());
+        function(Ret::No, 1, &locals, &[b0])
+    }
+
+    let locals = [<u32>::get_type()];
+    let b0 = block!(storage_live(0), spawn(fn_ptr_internal(1), null(), local(0), 1));
+    let b1 = block!(join(load(local(0)), 2));
+    let b2 = block!(exit());
+    let f = function(Ret::No, 0, &locals, &[b0, b1, b2]);
+    let p = program(&[f, thread()]);
+
+    let (output, trace) = run_program_with_trace::<BasicMem>(p);
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    let main = ThreadId::ZERO;
+    let other = ThreadId::from(1);
+    assert!(trace.any(|event| event == TraceEvent::ThreadSwitch { from: main, to: other }));
+}
+
+/// Without `MachineConfig::trace`, nothing is recorded.
+#[test]
+fn trace_disabled() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    f.storage_live(x);
+    f.assign(x, const_int(1u32));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    let (_output, trace) = run_program_inspect::<BasicMem, _>(p, |machine| machine.trace());
+    assert_eq!(trace.unwrap().len(), 0);
+}
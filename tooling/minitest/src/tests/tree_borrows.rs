@@ -0,0 +1,73 @@
+//! Programs that are fine under the basic memory model but violate the aliasing rules of Tree Borrows.
+
+use crate::*;
+
+#[test]
+fn write_through_shared_ref() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let r = f.declare_local::<&u32>();
+    f.storage_live(x);
+    f.storage_live(r);
+    f.assign(x, const_int(0u32));
+    f.assign(r, addr_of(x, <&u32>::get_type()));
+    f.validate(r, false);
+    f.assign(deref(raw_mut(load(r)), <u32>::get_type()), const_int(1u32));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub_tree_borrows(
+        p,
+        "Tree Borrows: writing to the child of a pointer with Frozen permission",
+    );
+}
+
+#[test]
+fn read_after_foreign_write() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let r = f.declare_local::<&mut u32>();
+    let y = f.declare_local::<u32>();
+    f.storage_live(x);
+    f.storage_live(r);
+    f.storage_live(y);
+    f.assign(x, const_int(0u32));
+    f.assign(r, addr_of(x, <&mut u32>::get_type()));
+    f.validate(r, false);
+    // A write that does not go through `r` disables it.
+    f.assign(x, const_int(1u32));
+    f.assign(y, load(deref(load(r), <u32>::get_type())));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub_tree_borrows(p, "Tree Borrows: child read of a pointer with Disabled permission");
+}
+
+#[test]
+fn write_through_mut_ref() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let r = f.declare_local::<&mut u32>();
+    f.storage_live(x);
+    f.storage_live(r);
+    f.assign(x, const_int(0u32));
+    f.assign(r, addr_of(x, <&mut u32>::get_type()));
+    f.validate(r, false);
+    f.assign(deref(load(r), <u32>::get_type()), const_int(1u32));
+    // Using the parent again after the reference is fine.
+    f.assume(eq(load(x), const_int(1u32)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+    assert_stop::<TreeBorrowMem>(p);
+}
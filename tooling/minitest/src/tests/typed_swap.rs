@@ -0,0 +1,92 @@
+//! The `TypedSwapNonOverlapping` intrinsic.
+
+use crate::*;
+
+#[test]
+fn typed_swap() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let y = f.declare_local::<u32>();
+    f.storage_live(x);
+    f.storage_live(y);
+    f.assign(x, const_int(1u32));
+    f.assign(y, const_int(2u32));
+    f.typed_swap_nonoverlapping(
+        addr_of(x, <*mut u32>::get_type()),
+        addr_of(y, <*mut u32>::get_type()),
+        <u32>::get_type(),
+    );
+    f.print(load(x));
+    f.print(load(y));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["2", "1"], &[]);
+}
+
+#[test]
+fn typed_swap_overlapping() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let arr = f.declare_local::<[u32; 3]>();
+    f.storage_live(arr);
+    f.assign(arr, array(&[const_int(0u32); 3], <u32>::get_type()));
+    f.typed_swap_nonoverlapping(
+        addr_of(index(arr, const_int(0usize)), <*mut [u32; 2]>::get_type()),
+        addr_of(index(arr, const_int(1usize)), <*mut [u32; 2]>::get_type()),
+        <[u32; 2]>::get_type(),
+    );
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(p, "`TypedSwapNonOverlapping` intrinsic called on overlapping ranges");
+}
+
+/// Swapping a zero-sized value with itself is fine.
+#[test]
+fn typed_swap_zst_same_ptr() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<()>();
+    f.storage_live(x);
+    let ptr = addr_of(x, <*mut ()>::get_type());
+    f.typed_swap_nonoverlapping(ptr, ptr, <()>::get_type());
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
+
+/// Both values are loaded at the given type, so they must be valid.
+#[test]
+fn typed_swap_invalid() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u8>();
+    let y = f.declare_local::<u8>();
+    f.storage_live(x);
+    f.storage_live(y);
+    f.assign(x, const_int(2u8));
+    f.assign(y, const_int(0u8));
+    f.typed_swap_nonoverlapping(
+        addr_of(x, <*mut bool>::get_type()),
+        addr_of(y, <*mut bool>::get_type()),
+        <bool>::get_type(),
+    );
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(
+        p,
+        "load at type Bool but the data in memory violates the language invariant",
+    );
+}
@@ -0,0 +1,145 @@
+use crate::*;
+
+/// UB raised by a statement reports that statement.
+#[test]
+fn ub_report_statement() {
+    let locals = vec![<bool>::get_type(); 2];
+    let b0 = block!(storage_live(0), storage_live(1), assign(local(0), load(local(1))), exit());
+    let f = function(Ret::No, 0, &locals, &[b0]);
+    let p = program(&[f]);
+
+    let report = ub_report::<BasicMem>(p);
+    assert_eq!(
+        report.message,
+        "load at type Bool but the data in memory violates the language invariant"
+    );
+    assert_eq!(
+        report.location,
+        Some(Location {
+            func: FnName(Name::from_internal(0)),
+            block: BbName(Name::from_internal(0)),
+            stmt: Int::from(2),
+        })
+    );
+    assert_eq!(report.operation, Some(Operation::Statement(assign(local(0), load(local(1))))));
+}
+
+/// UB raised by a terminator reports that terminator, at the index after the last statement.
+#[test]
+fn ub_report_terminator() {
+    let locals = [];
+    let b0 = block!(assume(const_bool(false), 1));
+    let b1 = block!(exit());
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+    let p = program(&[f]);
+
+    let report = ub_report::<BasicMem>(p);
+    assert_eq!(report.message, "`Assume` intrinsic called on condition that is violated");
+    assert_eq!(report.location.unwrap().stmt, Int::ZERO);
+    assert!(matches!(
+        report.operation,
+        Some(Operation::Terminator(Terminator::Intrinsic { intrinsic: IntrinsicOp::Assume, .. }))
+    ));
+}
+
+/// UB in a callee reports the location in the callee.
+#[test]
+fn ub_report_callee() {
+    let mut p = ProgramBuilder::new();
+
+    let callee = {
+        let mut f = p.declare_function();
+        f.unreachable();
+        p.finish_function(f)
+    };
+
+    let main = {
+        let mut f = p.declare_function();
+        f.call_ignoreret(fn_ptr(callee), &[]);
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(main);
+    let report = ub_report::<BasicMem>(p);
+    assert_eq!(report.message, "reached unreachable code");
+    assert_eq!(report.location.unwrap().func, callee);
+    assert_eq!(report.operation, Some(Operation::Terminator(Terminator::Unreachable)));
+}
+
+/// The location names the function that was called, even if another function has the same body.
+#[test]
+fn ub_report_identical_bodies() {
+    let mut p = ProgramBuilder::new();
+
+    let mut callees = Vec::new();
+    for _ in 0..2 {
+        let mut f = p.declare_function();
+        f.unreachable();
+        callees.push(p.finish_function(f));
+    }
+
+    let main = {
+        let mut f = p.declare_function();
+        f.call_ignoreret(fn_ptr(callees[1]), &[]);
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(main);
+    let report = ub_report::<BasicMem>(p);
+    assert_eq!(report.location.unwrap().func, callees[1]);
+}
+
+/// A program without UB does not produce a report.
+#[test]
+fn ub_report_no_ub() {
+    let p = small_program(&[], &[]);
+    assert_eq!(run_program_ub_report::<BasicMem>(p), Err(TerminationInfo::MachineStop));
+}
+
+/// Declares two `u32` locals and loads a `u32` at `offset` bytes from the start of the `target`-th one.
+fn access_program(target: usize, offset: isize) -> Program {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let locals = [f.declare_local::<u32>(), f.declare_local::<u32>()];
+    let val = f.declare_local::<u32>();
+    for local in locals {
+        f.storage_live(local);
+    }
+    f.storage_live(val);
+    let ptr = addr_of(locals[target], <*const u32>::get_type());
+    let ptr = ptr_offset(ptr, const_int(offset), InBounds::No);
+    f.assign(val, load(deref(ptr, <u32>::get_type())));
+    f.exit();
+    let f = p.finish_function(f);
+
+    p.finish_program(f)
+}
+
+/// UB caused by a memory access reports the allocation, offset, and size of the access.
+#[test]
+fn ub_report_out_of_bounds_access() {
+    let report = ub_report::<BasicMem>(access_program(1, 4));
+    assert_eq!(report.message, "dereferencing pointer outside the bounds of its allocation");
+    let access = report.access.unwrap();
+    assert_eq!(access.offset, Int::from(4));
+    assert_eq!(access.size, Size::from_bytes_const(4));
+
+    // The other local was allocated right before.
+    let other = ub_report::<BasicMem>(access_program(0, -4)).access.unwrap();
+    assert_eq!(other.alloc_id + Int::ONE, access.alloc_id);
+    assert_eq!(other.offset, Int::from(-4));
+}
+
+/// UB that is not caused by a memory access does not report one.
+#[test]
+fn ub_report_no_access() {
+    let locals = [];
+    let b0 = block!(assume(const_bool(false), 1));
+    let b1 = block!(exit());
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+    let report = ub_report::<BasicMem>(program(&[f]));
+    assert_eq!(report.access, None);
+}
@@ -10,3 +10,28 @@ fn uninit_read() {
         "load at type Bool but the data in memory violates the language invariant",
     );
 }
+
+#[test]
+fn uninit_read_location() {
+    let locals = vec![<bool>::get_type(); 2];
+    // Only the second load, in block 1, reads uninitialized memory.
+    let b0 = block!(
+        storage_live(0),
+        storage_live(1),
+        assign(local(0), const_bool(true)),
+        assign(local(1), load(local(0))),
+        goto(1),
+    );
+    let b1 = block!(storage_dead(0), storage_live(0), assign(local(1), load(local(0))), exit());
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+    let p = program(&[f]);
+    assert_ub_matches::<BasicMem>(
+        p,
+        "load at type Bool but the data in memory violates the language invariant",
+        Location {
+            func: FnName(Name::from_internal(0)),
+            block: BbName(Name::from_internal(1)),
+            stmt: Int::from(2),
+        },
+    );
+}
@@ -11,3 +11,17 @@ fn reach_unreachable() {
     dump_program(p);
     assert_ub::<BasicMem>(p, "reached unreachable code");
 }
+
+/// Unreachable code is only UB when it is actually reached.
+#[test]
+fn unreachable_not_reached() {
+    let locals = [];
+
+    let b0 = block!(if_(const_bool(false), 1, 2));
+    let b1 = block!(unreachable());
+    let b2 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1, b2]);
+    let p = program(&[f]);
+    assert_stop::<BasicMem>(p);
+}
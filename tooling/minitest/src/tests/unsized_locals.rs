@@ -0,0 +1,99 @@
+//! Locals of unsized type, which get their size from the metadata given to `StorageLive`.
+
+use crate::*;
+
+#[test]
+fn unsized_local_slice() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local_with_ty(<[u32]>::get_type());
+    f.storage_live_unsized(x, const_int(3_usize));
+    for i in 0..3usize {
+        f.assign(index(x, const_int(i)), const_int(i as u32 * 10));
+    }
+    f.print(load(index(x, const_int(2_usize))));
+    f.print(get_metadata(addr_of(x, <*const [u32]>::get_type())));
+    f.print(compute_size(<[u32]>::get_type(), const_int(3_usize)));
+    f.storage_dead(x);
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["20", "3", "12"], &[]);
+}
+
+/// The size is fixed when the local becomes live, so indexing past it is UB.
+#[test]
+fn unsized_local_out_of_bounds() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local_with_ty(<[u8]>::get_type());
+    f.storage_live_unsized(x, const_int(2_usize));
+    f.assign(index(x, const_int(2_usize)), const_int(0u8));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(p, "access to out-of-bounds index");
+}
+
+#[test]
+fn unsized_local_too_large() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local_with_ty(<[u16]>::get_type());
+    f.storage_live_unsized(x, const_int(usize::MAX / 2));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(
+        p,
+        "Statement::StorageLive: total size of unsized local exceeds isize::MAX",
+    );
+}
+
+#[test]
+fn unsized_local_missing_meta() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local_with_ty(<[u8]>::get_type());
+    f.storage_live(x);
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ill_formed::<BasicMem>(p, "Statement::StorageLive: missing metadata for unsized local");
+}
+
+#[test]
+fn sized_local_with_meta() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u8>();
+    f.storage_live_unsized(x, const_int(1_usize));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ill_formed::<BasicMem>(p, "Statement::StorageLive: metadata for sized local");
+}
+
+#[test]
+fn unsized_local_wrong_meta_type() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local_with_ty(<[u8]>::get_type());
+    f.storage_live_unsized(x, const_int(1_u32));
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ill_formed::<BasicMem>(p, "Statement::StorageLive: invalid metadata type");
+}
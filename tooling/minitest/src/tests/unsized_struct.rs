@@ -148,10 +148,11 @@ fn packed_tail() {
     let usize_send_vtable = prog.finish_vtable(usize_send_vtable);
 
     // type `#packed(2) (u8, usize)`
-    let p_ty = tuple_ty(
+    let p_ty = packed_tuple_ty(
         &[(size(0), <u8>::get_type()), (size(2), <usize>::get_type())],
         size(10),
         align(2),
+        align(2),
     );
     // type `#packed(2) (u8, dyn Send)`
     let q_ty = unsized_tuple_ty(
@@ -284,32 +285,6 @@ fn ill_align_larger_than_packed() {
     assert_ill_formed::<BasicMem>(p, "TupleHeadLayout: align bigger than packed attribute");
 }
 
-/// Setting a packed attribute is only relevant for unsized tail computations, thus a sized struct must not set this.
-#[test]
-fn ill_packed_on_sized() {
-    let mut p = ProgramBuilder::new();
-
-    let ill_ty = Type::Tuple {
-        sized_fields: List::new(),
-        sized_head_layout: TupleHeadLayout {
-            end: size(0),
-            align: align(1),
-            packed_align: Some(align(1)),
-        },
-        unsized_field: GcCow::new(None),
-    };
-
-    let main = {
-        let mut f = p.declare_function();
-        f.print(compute_size(ill_ty, const_int(1_usize)));
-        f.exit();
-        p.finish_function(f)
-    };
-
-    let p = p.finish_program(main);
-    assert_ill_formed::<BasicMem>(p, "Type::Tuple: meaningless packed align for sized tuple");
-}
-
 /// The fields in the tuple head must be sized
 #[test]
 fn ill_unsized_head() {
@@ -360,7 +335,7 @@ fn ill_size_too_small() {
     let ill_ty = tuple_ty(
         &[(size(0), <u64>::get_type()), (size(8), <()>::get_type()), (size(8), <u32>::get_type())],
         size(10),
-        align(2),
+        align(8),
     );
 
     let main = {
@@ -0,0 +1,192 @@
+use crate::*;
+
+/// A function that panics, runs its cleanup block (which prints `1`), and then keeps unwinding.
+fn panicking_f() -> Function {
+    let locals = [<()>::get_type()];
+    let b0 = block!(start_unwind(Some(1)));
+    let b1 = block!(print(const_int(1u32), 2));
+    let b2 = block!(resume_unwind());
+
+    c_unwind(function(Ret::Yes, 0, &locals, &[b0, b1, b2]))
+}
+
+#[test]
+fn cleanup_and_catch() {
+    let locals = [<()>::get_type()];
+
+    let b0 = block!(storage_live(0), call_unwind(1, &[], local(0), Some(1), Some(2)));
+    let b1 = block!(unreachable());
+    let b2 = block!(print(const_int(2u32), 3));
+    let b3 = block!(stop_unwind(4));
+    let b4 = block!(print(const_int(3u32), 5));
+    let b5 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1, b2, b3, b4, b5]);
+    let p = program(&[f, panicking_f()]);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["1", "2", "3"], &[]);
+}
+
+#[test]
+fn unwind_through_caller() {
+    let locals = [<()>::get_type()];
+
+    // Function 2 calls function 1 without an unwind block, so it is unwound as well.
+    let b0 = block!(storage_live(0), call_unwind(1, &[], local(0), Some(1), None));
+    let b1 = block!(print(const_int(42u32), 2));
+    let b2 = block!(return_());
+    let middle_f = c_unwind(function(Ret::Yes, 0, &locals, &[b0, b1, b2]));
+
+    let b0 = block!(storage_live(0), call_unwind(2, &[], local(0), Some(1), Some(2)));
+    let b1 = block!(unreachable());
+    let b2 = block!(stop_unwind(3));
+    let b3 = block!(exit());
+    let f = function(Ret::No, 0, &locals, &[b0, b1, b2, b3]);
+
+    let p = program(&[f, panicking_f(), middle_f]);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["1"], &[]);
+}
+
+#[test]
+fn uncaught_panic() {
+    let locals = [<()>::get_type()];
+
+    let b0 = block!(storage_live(0), call_unwind(1, &[], local(0), Some(1), None));
+    let b1 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+    let p = program(&[f, panicking_f()]);
+    // The cleanup block of `panicking_f` still runs.
+    assert_output::<BasicMem>(p, abort_info("we panicked"), &["1"], &[]);
+}
+
+#[test]
+fn panic_while_unwinding() {
+    let locals = [<()>::get_type()];
+    let b0 = block!(start_unwind(Some(1)));
+    let b1 = block!(start_unwind(None));
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+    let p = program(&[f]);
+    assert_output::<BasicMem>(p, abort_info("panic while unwinding"), &[], &[]);
+}
+
+#[test]
+fn unwind_terminate_aborts() {
+    let locals = [<()>::get_type()];
+    let b0 = block!(start_unwind(Some(1)));
+    let b1 = block!(unwind_terminate());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+    let p = program(&[f]);
+    assert_output::<BasicMem>(
+        p,
+        abort_info("unwinding out of a function that cannot unwind"),
+        &[],
+        &[],
+    );
+}
+
+#[test]
+fn resume_unwind_not_unwinding() {
+    let locals = [<()>::get_type()];
+    let b0 = block!(resume_unwind());
+
+    let f = function(Ret::No, 0, &locals, &[b0]);
+    let p = program(&[f]);
+    assert_ub::<BasicMem>(p, "resuming unwinding in a function that is not unwinding");
+}
+
+#[test]
+fn stop_unwind_not_unwinding() {
+    let locals = [<()>::get_type()];
+    let b0 = block!(stop_unwind(1));
+    let b1 = block!(exit());
+
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+    let p = program(&[f]);
+    assert_ub::<BasicMem>(p, "stopping unwinding in a function that is not unwinding");
+}
+
+#[test]
+fn return_while_unwinding() {
+    let locals = [<()>::get_type()];
+
+    let b0 = block!(start_unwind(Some(1)));
+    let b1 = block!(return_());
+    let other_f = c_unwind(function(Ret::Yes, 0, &locals, &[b0, b1]));
+
+    let b0 = block!(storage_live(0), call_unwind(1, &[], local(0), Some(1), Some(1)));
+    let b1 = block!(exit());
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+
+    let p = program(&[f, other_f]);
+    assert_ub::<BasicMem>(p, "return from a function that is unwinding");
+}
+
+#[test]
+fn unwind_block_does_not_exist() {
+    let locals = [<()>::get_type()];
+    let b0 = block!(start_unwind(Some(1)));
+
+    let f = function(Ret::No, 0, &locals, &[b0]);
+    let p = program(&[f]);
+    assert_ill_formed::<BasicMem>(p, "Terminator::StartUnwind: unwind block does not exist");
+}
+
+/// A Rust panic must not unwind out of an `extern "C"` function.
+#[test]
+fn unwind_out_of_c() {
+    let locals = [<()>::get_type()];
+
+    // Function 2 is `extern "C"`, but does not stop the unwinding of function 1.
+    let b0 = block!(storage_live(0), call_unwind(1, &[], local(0), Some(1), None));
+    let b1 = block!(return_());
+    let c_f = function(Ret::Yes, 0, &locals, &[b0, b1]);
+
+    let b0 = block!(storage_live(0), call(2, &[], local(0), Some(1)));
+    let b1 = block!(exit());
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+
+    let p = program(&[f, panicking_f(), c_f]);
+    assert_ub::<BasicMem>(
+        p,
+        "unwinding out of a function whose calling convention does not allow unwinding",
+    );
+}
+
+/// This is what Rust generates for `extern "C"` functions: unwinding ends in `UnwindTerminate`, aborting the program.
+#[test]
+fn unwind_terminate_in_c() {
+    let locals = [<()>::get_type()];
+
+    let b0 = block!(storage_live(0), call_unwind(1, &[], local(0), Some(1), Some(2)));
+    let b1 = block!(return_());
+    let b2 = block!(unwind_terminate());
+    let c_f = function(Ret::Yes, 0, &locals, &[b0, b1, b2]);
+
+    let b0 = block!(storage_live(0), call(2, &[], local(0), Some(1)));
+    let b1 = block!(exit());
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+
+    let p = program(&[f, panicking_f(), c_f]);
+    // The cleanup block of `panicking_f` runs before the unwinding reaches `c_f`.
+    assert_output::<BasicMem>(
+        p,
+        abort_info("unwinding out of a function that cannot unwind"),
+        &["1"],
+        &[],
+    );
+}
+
+/// `extern "C"` and `extern "C-unwind"` are different calling conventions.
+#[test]
+fn c_unwind_called_as_c() {
+    let locals = [<()>::get_type()];
+
+    let b0 = block!(storage_live(0), call(1, &[], local(0), Some(1)));
+    let b1 = block!(exit());
+    let f = function(Ret::No, 0, &locals, &[b0, b1]);
+
+    let p = program(&[f, panicking_f()]);
+    assert_ub::<BasicMem>(p, "call ABI violation: calling conventions are not the same");
+}
@@ -0,0 +1,67 @@
+//! Types like `NonZeroU32` and `NonNull<T>` restrict the valid values of the integer or pointer they wrap.
+
+use crate::*;
+
+fn non_zero_u32() -> Type {
+    valid_range_ty(<u32>::get_type(), 1, u32::MAX)
+}
+
+fn non_null() -> Type {
+    valid_range_ty(<*const u8>::get_type(), 1, usize::MAX)
+}
+
+/// Transmutes `val` to `ty`, then prints the field of the result.
+fn transmute_and_print(val: ValueExpr, ty: Type) -> Program {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local_with_ty(ty);
+    f.storage_live(x);
+    f.assign(x, transmute(val, ty));
+    f.print(load(field(x, 0)));
+    f.exit();
+    let f = p.finish_function(f);
+
+    p.finish_program(f)
+}
+
+#[test]
+fn non_zero_valid() {
+    let p = transmute_and_print(const_int(42u32), non_zero_u32());
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["42"], &[]);
+}
+
+#[test]
+fn non_zero_zero() {
+    let p = transmute_and_print(const_int(0u32), non_zero_u32());
+    assert_ub::<BasicMem>(p, "transmuted value is not valid at new type");
+}
+
+#[test]
+fn non_null_null() {
+    let null = transmute(const_int(0usize), <*const u8>::get_type());
+    let p = transmute_and_print(null, non_null());
+    assert_ub::<BasicMem>(p, "transmuted value is not valid at new type");
+}
+
+/// The range `100..=-100` wraps around, so it excludes everything in between.
+#[test]
+fn wrapping_range() {
+    let ty = valid_range_ty(<i8>::get_type(), 100, -100);
+    let p = transmute_and_print(const_int(127i8), ty);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["127"], &[]);
+    let p = transmute_and_print(const_int(-128i8), ty);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["-128"], &[]);
+    let p = transmute_and_print(const_int(0i8), ty);
+    assert_ub::<BasicMem>(p, "transmuted value is not valid at new type");
+}
+
+#[test]
+fn valid_range_on_bool() {
+    let ty = valid_range_ty(<bool>::get_type(), 1, 1);
+    let p = transmute_and_print(const_bool(true), ty);
+    assert_ill_formed::<BasicMem>(
+        p,
+        "Type::Tuple: valid range on a field that is neither an integer nor a pointer",
+    );
+}
@@ -0,0 +1,64 @@
+//! `Validate` statements for the pointees of references are only executed
+//! if the machine is configured to check validity recursively behind references.
+
+use crate::*;
+
+fn validate_pointees() -> MachineConfig {
+    MachineConfig { validate_pointees: true, ..MachineConfig::DEFAULT }
+}
+
+/// Creates a `&bool` pointing to the byte `val`, validates the reference and its pointee,
+/// and then prints `1`.
+fn ref_to_byte(val: u8) -> Program {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let b = f.declare_local::<u8>();
+    let r = f.declare_local::<&bool>();
+    f.storage_live(b);
+    f.storage_live(r);
+    f.assign(b, const_int(val));
+    f.assign(r, transmute(addr_of(b, <*const u8>::get_type()), <&bool>::get_type()));
+    f.validate(r, false);
+    f.validate_pointee(deref(load(r), <bool>::get_type()));
+    f.print(const_int(1u32));
+    f.exit();
+    let f = p.finish_function(f);
+
+    p.finish_program(f)
+}
+
+#[test]
+fn validate_pointee_valid() {
+    let p = ref_to_byte(1);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["1"], &[]);
+    let out = run_program_with_config::<BasicMem>(p, validate_pointees());
+    assert_eq!(out.info, TerminationInfo::MachineStop);
+    assert_eq!(out.stdout, &["1"]);
+}
+
+/// A reference to an invalid `bool` is only UB if pointees are validated.
+#[test]
+fn validate_pointee_invalid() {
+    let p = ref_to_byte(3);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["1"], &[]);
+    let out = run_program_with_config::<BasicMem>(p, validate_pointees());
+    assert_eq!(
+        out.info,
+        ub_info("load at type Bool but the data in memory violates the language invariant")
+    );
+}
+
+/// Pointee validation never retags.
+#[test]
+fn validate_pointee_no_retag() {
+    let locals = &[<&u8>::get_type()];
+    let stmts = &[storage_live(0), Statement::Validate {
+        place: deref(load(local(0)), <u8>::get_type()),
+        fn_entry: true,
+        two_phase: false,
+        behind_reference: true,
+    }];
+    let p = small_program(locals, stmts);
+    assert_ill_formed::<BasicMem>(p, "Statement::Validate: pointee validation does not retag");
+}
@@ -0,0 +1,118 @@
+//! Which bytes the validity checks of typed loads inspect, see `miniutil::validity`.
+
+use crate::*;
+
+const U8_INTTYPE: IntType =
+    IntType { signed: Signedness::Unsigned, size: Size::from_bytes_const(1) };
+
+/// `(u8, u16)`, with a padding byte between the fields.
+fn padded_pair_ty() -> Type {
+    tuple_ty(&[(offset(0), <u8>::get_type()), (offset(2), <u16>::get_type())], size(4), align(2))
+}
+
+/// Which bytes the validity check inspects when loading `bytes` at type `ty`.
+fn checked_bytes(ty: Type, bytes: &[u8]) -> Vec<bool> {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let x = f.declare_local_with_ty(ty);
+    let y = f.declare_local_with_ty(ty);
+    f.storage_live(x);
+    f.storage_live(y);
+    let elems: Vec<_> = bytes.iter().map(|&b| const_int(b)).collect();
+    f.assign(
+        deref(addr_of(x, raw_void_ptr_ty()), array_ty(<u8>::get_type(), bytes.len())),
+        array(&elems, <u8>::get_type()),
+    );
+    f.assign(y, load(x));
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+
+    let (output, checks) = run_program_with_validity_checks::<BasicMem>(p);
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    let [check] = &checks[..] else { panic!("expected a single typed load, got {checks:?}") };
+    assert_eq!(check.ty, ty);
+    check.checked.clone()
+}
+
+#[test]
+fn checked_bytes_skip_padding() {
+    assert_eq!(checked_bytes(padded_pair_ty(), &[1, 2, 3, 4]), [true, false, true, true]);
+
+    let array = array_ty(padded_pair_ty(), 2);
+    assert_eq!(checked_bytes(array, &[0; 8]), [true, false, true, true, true, false, true, true]);
+}
+
+/// A union keeps its bytes without checking them.
+#[test]
+fn checked_bytes_union() {
+    let union = union_ty(&[(offset(0), <bool>::get_type())], size(4), align(4));
+    assert_eq!(checked_bytes(union, &[7; 4]), [false; 4]);
+}
+
+/// An enum checks its tag and the data of the variant the tag selects, but not its padding.
+#[test]
+fn checked_bytes_enum() {
+    // `enum { Empty, Full(u8) }`, with the tag stored after the data and a padding byte at the end.
+    let empty = tuple_ty(&[], size(3), align(1));
+    let full = tuple_ty(&[(offset(0), <u8>::get_type())], size(3), align(1));
+    let ty = enum_ty::<u8>(
+        &[
+            (0, enum_variant(empty, &[(offset(1), (U8_INTTYPE, 0.into()))])),
+            (1, enum_variant(full, &[(offset(1), (U8_INTTYPE, 1.into()))])),
+        ],
+        discriminator_branch::<u8>(offset(1), discriminator_invalid(), &[
+            ((0, 1), discriminator_known(0)),
+            ((1, 2), discriminator_known(1)),
+        ]),
+        size(3),
+        align(1),
+    );
+    assert_eq!(checked_bytes(ty, &[5, 0, 9]), [false, true, false]);
+    assert_eq!(checked_bytes(ty, &[5, 1, 9]), [true, true, false]);
+}
+
+#[test]
+fn validity_checks_of_run() {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    f.storage_live(x);
+    f.assign(x, const_int(7u32));
+    f.print(load(x));
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+
+    let (output, checks) = run_program_with_validity_checks::<BasicMem>(p);
+    assert_eq!(output.info, TerminationInfo::MachineStop);
+    let [check] = &checks[..] else { panic!("expected a single typed load, got {checks:?}") };
+    assert_eq!(check.ty, <u32>::get_type());
+    assert_eq!(check.checked, [true; 4]);
+
+    let report = ValidityReport::new(&checks);
+    assert_eq!(report.total(), ValidityStats { loads: 1, bytes: 4, checked_bytes: 4 });
+    assert_eq!(
+        report.to_string(),
+        "u32: 1 loads, 4 of 4 bytes checked\ntotal: 1 loads, 4 of 4 bytes checked\n"
+    );
+}
+
+/// A load whose check fails is recorded as well.
+#[test]
+fn validity_checks_failing_load() {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let b = f.declare_local::<bool>();
+    f.storage_live(b);
+    f.print(load(b));
+    f.exit();
+    let f = p.finish_function(f);
+    let p = p.finish_program(f);
+
+    let (output, checks) = run_program_with_validity_checks::<BasicMem>(p);
+    assert!(matches!(output.info, TerminationInfo::Ub(..)));
+    let [check] = &checks[..] else { panic!("expected a single typed load, got {checks:?}") };
+    assert_eq!(check.ty, <bool>::get_type());
+    assert_eq!(check.checked, [true]);
+}
@@ -0,0 +1,112 @@
+//! C-variadic functions and the `VaArg` intrinsic.
+
+use crate::*;
+
+/// Builds a program that calls a C-variadic function with the fixed argument `count: u32` and the
+/// variadic arguments `args`. The callee reads `count` variadic arguments of type `T` and prints each of them.
+fn call_variadic<T: TypeConv>(count: u32, args: &[ValueExpr]) -> Program {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    f.set_c_variadic();
+    f.declare_arg::<u32>();
+    let x = f.declare_local::<T>();
+    f.storage_live(x);
+    for _ in 0..count {
+        f.va_arg(x);
+        f.print(load(x));
+    }
+    f.return_();
+    let f = p.finish_function(f);
+
+    let mut main = p.declare_function();
+    let mut arguments = vec![by_value(const_int(count))];
+    arguments.extend(args.iter().map(|&arg| by_value(arg)));
+    main.call_ignoreret(fn_ptr(f), &arguments);
+    main.exit();
+    let main = p.finish_function(main);
+
+    p.finish_program(main)
+}
+
+#[test]
+fn variadic_args() {
+    let p = call_variadic::<i32>(2, &[const_int(20i32), const_int(-22i32)]);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["20", "-22"], &[]);
+}
+
+/// The callee does not have to read all variadic arguments.
+#[test]
+fn variadic_args_unread() {
+    let p = call_variadic::<i64>(1, &[const_int(1i64), const_int(2i64)]);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["1"], &[]);
+}
+
+#[test]
+fn variadic_args_too_few() {
+    let p = call_variadic::<i32>(2, &[const_int(1i32)]);
+    assert_ub::<BasicMem>(p, "`VaArg` intrinsic: all variadic arguments have already been read");
+}
+
+#[test]
+fn variadic_args_wrong_type() {
+    let p = call_variadic::<i32>(1, &[const_int(1i64)]);
+    assert_ub::<BasicMem>(
+        p,
+        "`VaArg` intrinsic: requested type is not compatible with the type of the argument",
+    );
+}
+
+/// Like in C, an integer may be read at the other signedness if the value fits both types.
+#[test]
+fn variadic_args_signedness() {
+    let p = call_variadic::<u32>(1, &[const_int(7i32)]);
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["7"], &[]);
+
+    let p = call_variadic::<u32>(1, &[const_int(-7i32)]);
+    assert_ub::<BasicMem>(
+        p,
+        "`VaArg` intrinsic: requested type is not compatible with the type of the argument",
+    );
+}
+
+/// Small integers are subject to C's default argument promotions, so they cannot be passed as they are.
+#[test]
+fn variadic_args_unpromoted() {
+    let p = call_variadic::<u8>(1, &[const_int(7u8)]);
+    assert_ub::<BasicMem>(p, "call ABI violation: variadic argument type is not promoted");
+}
+
+/// Only C-variadic functions can take more arguments than they declare.
+#[test]
+fn too_many_args() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    f.declare_arg::<u32>();
+    f.return_();
+    let f = p.finish_function(f);
+
+    let mut main = p.declare_function();
+    main.call_ignoreret(fn_ptr(f), &[by_value(const_int(1u32)), by_value(const_int(2i32))]);
+    main.exit();
+    let main = p.finish_function(main);
+
+    let p = p.finish_program(main);
+    assert_ub::<BasicMem>(p, "call ABI violation: number of arguments does not agree");
+}
+
+#[test]
+fn va_arg_not_variadic() {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let x = f.declare_local::<i32>();
+    f.storage_live(x);
+    f.va_arg(x);
+    f.exit();
+    let f = p.finish_function(f);
+
+    let p = p.finish_program(f);
+    assert_ill_formed::<BasicMem>(p, "IntrinsicOp::VaArg: function is not C-variadic");
+}
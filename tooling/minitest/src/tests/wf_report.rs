@@ -0,0 +1,69 @@
+//! Reporting all well-formedness violations of a program.
+
+use miniutil::DefaultTarget;
+
+use crate::*;
+
+#[track_caller]
+fn assert_wf_errors(prog: Program, expected: &[&str]) {
+    let errors: Vec<String> =
+        check_program_wf::<DefaultTarget>(prog).iter().map(WfError::to_string).collect();
+    assert_eq!(errors, expected);
+}
+
+/// A block with three violations: two statements and the terminator.
+fn many_violations() -> Program {
+    let locals = [<i32>::get_type()];
+    let b0 =
+        block!(storage_live(0), assign(local(0), const_int::<u32>(0)), storage_dead(7), goto(5));
+    let f = function(Ret::No, 0, &locals, &[b0]);
+    program(&[f])
+}
+
+#[test]
+fn well_formed() {
+    let locals = &[<i32>::get_type()];
+    let stmts = &[storage_live(0), assign(local(0), const_int::<i32>(0))];
+    assert_wf_errors(small_program(locals, stmts), &[]);
+}
+
+#[test]
+fn reports_all_violations() {
+    assert_wf_errors(many_violations(), &[
+        "f0, bb0, statement 1: Statement::Assign: destination and source type differ",
+        "f0, bb0, statement 2: Statement::StorageDead: invalid local variable",
+        "f0, bb0, terminator: Terminator::Goto: next block does not exist",
+    ]);
+}
+
+#[test]
+fn structured_violation() {
+    let errors = check_program_wf::<DefaultTarget>(many_violations());
+    let fn_name = FnName(Name::from_internal(0));
+    let bb_name = BbName(Name::from_internal(0));
+    assert_eq!(errors[1].location, WfLocation::Statement(fn_name, bb_name, Int::from(2)));
+    assert_eq!(errors[1].kind, WfKind::StatementStorageDead);
+    assert_eq!(errors[1].details, "invalid local variable");
+}
+
+#[test]
+fn ill_formed_locals_skip_blocks() {
+    // The blocks of a function are not checked when its locals are ill-formed.
+    let locals = &[array_ty(<()>::get_type(), -1)];
+    let stmts = &[storage_live(0), storage_dead(7)];
+    assert_wf_errors(small_program(locals, stmts), &[
+        "f0: Type::Array: negative amount of elements",
+    ]);
+}
+
+#[test]
+fn no_start_function() {
+    assert_wf_errors(program(&[]), &["program: Program: start function does not exist"]);
+}
+
+#[test]
+fn ill_formed_because_later_violation() {
+    // The machine only reports the first violation, `Statement::Assign`.
+    assert_ill_formed_because::<BasicMem>(many_violations(), WfKind::StatementStorageDead);
+    assert_ill_formed_because::<BasicMem>(many_violations(), WfKind::TerminatorGoto);
+}
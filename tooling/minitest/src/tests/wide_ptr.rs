@@ -385,3 +385,98 @@ fn compare_trait_obj_ptr() {
     let p = p.finish_program(f);
     assert_stop::<BasicMem>(p);
 }
+
+/// The `(usize, usize)` type that unsafe code uses to take apart wide pointers.
+fn usize_pair_ty() -> Type {
+    let usize_ty = <usize>::get_type();
+    tuple_ty(&[(size(0), usize_ty), (size(8), usize_ty)], size(16), align(8))
+}
+
+/// Asserts that transmuting a slice pointer to `(usize, usize)` yields its address and length.
+#[test]
+fn slice_ptr_to_usize_pair() {
+    let mut p = ProgramBuilder::new();
+
+    let f = {
+        let mut f = p.declare_function();
+        let arr = f.declare_local::<[u32; 3]>();
+        let pair = f.declare_local_with_ty(usize_pair_ty());
+        f.storage_live(arr);
+        f.storage_live(pair);
+        let thin = addr_of(arr, <*const u32>::get_type());
+        let wide = construct_wide_pointer(thin, const_int(3_usize), <*const [u32]>::get_type());
+        f.assign(pair, transmute(wide, usize_pair_ty()));
+        f.assume(eq(load(field(pair, 0)), transmute(thin, <usize>::get_type())));
+        f.assume(eq(load(field(pair, 1)), const_int(3_usize)));
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(f);
+    assert_stop::<BasicMem>(p);
+}
+
+/// Asserts that a slice pointer transmuted to `(usize, usize)` and back has lost its provenance.
+#[test]
+fn ub_slice_ptr_usize_pair_roundtrip_no_provenance() {
+    let mut p = ProgramBuilder::new();
+
+    let f = {
+        let mut f = p.declare_function();
+        let arr = f.declare_local::<[u32; 3]>();
+        let ptr = f.declare_local::<*const [u32]>();
+        f.storage_live(arr);
+        f.storage_live(ptr);
+        f.assign(
+            arr,
+            array(&[const_int(1_u32), const_int(2_u32), const_int(3_u32)], <u32>::get_type()),
+        );
+        let wide = construct_wide_pointer(
+            addr_of(arr, <*const u32>::get_type()),
+            const_int(3_usize),
+            <*const [u32]>::get_type(),
+        );
+        let pair = transmute(wide, usize_pair_ty());
+        f.assign(ptr, transmute(pair, <*const [u32]>::get_type()));
+        // The metadata survived the round-trip.
+        f.assume(eq(get_metadata(load(ptr)), const_int(3_usize)));
+        // But the pointer cannot be used for accesses any more.
+        f.print(load(index(deref(load(ptr), <[u32]>::get_type()), const_int(1_usize))));
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(p, "dereferencing pointer without provenance");
+}
+
+/// Asserts that a trait object pointer transmuted to `(usize, usize)` and back has an invalid vtable.
+#[test]
+fn ub_trait_object_usize_pair_roundtrip() {
+    let mut p = ProgramBuilder::new();
+
+    let trait_foo = p.declare_trait();
+    let trait_foo = p.finish_trait(trait_foo);
+    let vtable = p.declare_vtable_for_ty(trait_foo, <u8>::get_type());
+    let vtable = p.finish_vtable(vtable);
+    let dyn_ptr_ty = raw_ptr_ty(PointerMetaKind::VTablePointer(trait_foo));
+
+    let f = {
+        let mut f = p.declare_function();
+        let x = f.declare_local::<u8>();
+        let ptr = f.declare_local_with_ty(dyn_ptr_ty);
+        f.storage_live(x);
+        f.storage_live(ptr);
+        let wide = construct_wide_pointer(
+            addr_of(x, <*const u8>::get_type()),
+            const_vtable(vtable, trait_foo),
+            dyn_ptr_ty,
+        );
+        f.assign(ptr, transmute(transmute(wide, usize_pair_ty()), dyn_ptr_ty));
+        f.exit();
+        p.finish_function(f)
+    };
+
+    let p = p.finish_program(f);
+    assert_ub::<BasicMem>(p, "invalid pointer for vtable lookup");
+}
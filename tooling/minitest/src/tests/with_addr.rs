@@ -0,0 +1,59 @@
+//! Changing the address of a pointer while keeping its provenance.
+
+use crate::*;
+
+/// Stores a pointer to `arr[0]` in `ptr` and its address in `addr`,
+/// then prints what `make_ptr(ptr, addr)` points to.
+fn with_addr_program(make_ptr: impl FnOnce(ValueExpr, ValueExpr) -> ValueExpr) -> Program {
+    let mut p = ProgramBuilder::new();
+
+    let mut f = p.declare_function();
+    let arr = f.declare_local::<[u32; 2]>();
+    let ptr = f.declare_local::<*const u32>();
+    let addr = f.declare_local::<usize>();
+    f.storage_live(arr);
+    f.storage_live(ptr);
+    f.storage_live(addr);
+    f.assign(arr, array(&[const_int(1u32), const_int(2u32)], <u32>::get_type()));
+    f.assign(ptr, addr_of(index(arr, const_int(0usize)), <*const u32>::get_type()));
+    f.assign(addr, transmute(load(ptr), <usize>::get_type()));
+    f.print(load(deref(make_ptr(load(ptr), load(addr)), <u32>::get_type())));
+    f.exit();
+    let f = p.finish_function(f);
+
+    p.finish_program(f)
+}
+
+#[test]
+fn with_addr_in_bounds() {
+    let p = with_addr_program(|ptr, addr| ptr_with_addr(ptr, add(addr, const_int(4usize))));
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["2"], &[]);
+}
+
+/// The pointer can be out-of-bounds in between.
+#[test]
+fn with_addr_roundtrip() {
+    let p = with_addr_program(|ptr, addr| {
+        ptr_with_addr(ptr_with_addr(ptr, add(addr, const_int(100usize))), addr)
+    });
+    assert_output::<BasicMem>(p, TerminationInfo::MachineStop, &["1"], &[]);
+}
+
+#[test]
+fn with_addr_out_of_bounds() {
+    let p = with_addr_program(|ptr, addr| ptr_with_addr(ptr, add(addr, const_int(8usize))));
+    assert_ub::<BasicMem>(p, "dereferencing pointer outside the bounds of its allocation");
+}
+
+/// The provenance comes from the pointer, the address does not carry any.
+#[test]
+fn with_addr_without_provenance() {
+    let p = with_addr_program(|_ptr, addr| ptr_with_addr(null(), addr));
+    assert_ub::<BasicMem>(p, "dereferencing pointer without provenance");
+}
+
+#[test]
+fn with_addr_not_usize() {
+    let p = with_addr_program(|ptr, _addr| ptr_with_addr(ptr, const_int(0u32)));
+    assert_ill_formed::<BasicMem>(p, "BinOp::PtrWithAddr: invalid right type");
+}
@@ -13,6 +13,17 @@ pub fn const_bool(b: bool) -> ValueExpr {
     ValueExpr::Constant(Constant::Bool(b), Type::Bool)
 }
 
+pub fn const_char(c: char) -> ValueExpr {
+    ValueExpr::Constant(Constant::Int(u32::from(c).into()), Type::Char)
+}
+
+pub fn const_f32(f: f32) -> ValueExpr {
+    ValueExpr::Constant(Constant::Float(f.to_bits().into()), <f32>::get_type())
+}
+pub fn const_f64(f: f64) -> ValueExpr {
+    ValueExpr::Constant(Constant::Float(f.to_bits().into()), <f64>::get_type())
+}
+
 #[track_caller]
 pub fn tuple(args: &[ValueExpr], ty: Type) -> ValueExpr {
     let Type::Tuple { sized_fields, .. } = ty else {
@@ -56,6 +67,11 @@ pub fn addr_of(target: PlaceExpr, ty: Type) -> ValueExpr {
     ValueExpr::AddrOf { target: GcCow::new(target), ptr_ty }
 }
 
+/// A raw pointer to the `u32` at `target`.
+pub fn u32_ptr(target: PlaceExpr) -> ValueExpr {
+    addr_of(target, <*mut u32>::get_type())
+}
+
 /// Unary `-` on an integer.
 pub fn neg(v: ValueExpr) -> ValueExpr {
     ValueExpr::UnOp { operator: UnOp::Int(IntUnOp::Neg), operand: GcCow::new(v) }
@@ -70,6 +86,11 @@ pub fn count_ones(v: ValueExpr) -> ValueExpr {
     ValueExpr::UnOp { operator: UnOp::Int(IntUnOp::CountOnes), operand: GcCow::new(v) }
 }
 
+/// Unary `-` on a float.
+pub fn float_neg(v: ValueExpr) -> ValueExpr {
+    ValueExpr::UnOp { operator: UnOp::Float(FloatUnOp::Neg), operand: GcCow::new(v) }
+}
+
 #[track_caller]
 pub fn int_cast<T: TypeConv>(v: ValueExpr) -> ValueExpr {
     let Type::Int(t) = T::get_type() else {
@@ -86,6 +107,15 @@ pub fn compute_align(ty: Type, v: ValueExpr) -> ValueExpr {
     ValueExpr::UnOp { operator: UnOp::ComputeAlign(ty), operand: GcCow::new(v) }
 }
 
+/// The offset of the (nested) field reached by `path` in `ty`, like `offset_of!`.
+#[track_caller]
+pub fn offset_of(ty: Type, path: &[FieldProjection]) -> ValueExpr {
+    let Some(offset) = ty.field_offset(path.iter().copied().collect()) else {
+        panic!("offset_of: invalid field path");
+    };
+    const_int_typed::<usize>(offset.bytes())
+}
+
 pub fn ptr_addr(v: ValueExpr) -> ValueExpr {
     transmute(v, <usize>::get_type())
 }
@@ -163,6 +193,12 @@ pub fn div(l: ValueExpr, r: ValueExpr) -> ValueExpr {
 pub fn div_exact(l: ValueExpr, r: ValueExpr) -> ValueExpr {
     int_binop(IntBinOp::DivExact, l, r)
 }
+pub fn add_saturating(l: ValueExpr, r: ValueExpr) -> ValueExpr {
+    int_binop(IntBinOp::AddSaturating, l, r)
+}
+pub fn sub_saturating(l: ValueExpr, r: ValueExpr) -> ValueExpr {
+    int_binop(IntBinOp::SubSaturating, l, r)
+}
 pub fn rem(l: ValueExpr, r: ValueExpr) -> ValueExpr {
     int_binop(IntBinOp::Rem, l, r)
 }
@@ -188,6 +224,26 @@ pub fn bit_xor(l: ValueExpr, r: ValueExpr) -> ValueExpr {
     int_binop(IntBinOp::BitXor, l, r)
 }
 
+fn float_binop(op: FloatBinOp, l: ValueExpr, r: ValueExpr) -> ValueExpr {
+    ValueExpr::BinOp { operator: BinOp::Float(op), left: GcCow::new(l), right: GcCow::new(r) }
+}
+
+pub fn float_add(l: ValueExpr, r: ValueExpr) -> ValueExpr {
+    float_binop(FloatBinOp::Add, l, r)
+}
+pub fn float_sub(l: ValueExpr, r: ValueExpr) -> ValueExpr {
+    float_binop(FloatBinOp::Sub, l, r)
+}
+pub fn float_mul(l: ValueExpr, r: ValueExpr) -> ValueExpr {
+    float_binop(FloatBinOp::Mul, l, r)
+}
+pub fn float_div(l: ValueExpr, r: ValueExpr) -> ValueExpr {
+    float_binop(FloatBinOp::Div, l, r)
+}
+pub fn float_rem(l: ValueExpr, r: ValueExpr) -> ValueExpr {
+    float_binop(FloatBinOp::Rem, l, r)
+}
+
 fn int_overflow(op: IntBinOpWithOverflow, l: ValueExpr, r: ValueExpr) -> ValueExpr {
     ValueExpr::BinOp {
         operator: BinOp::IntWithOverflow(op),
@@ -285,6 +341,14 @@ pub fn ptr_offset_from_nonneg(l: ValueExpr, r: ValueExpr, inbounds: InBounds) ->
     }
 }
 
+pub fn ptr_with_addr(ptr: ValueExpr, addr: ValueExpr) -> ValueExpr {
+    ValueExpr::BinOp {
+        operator: BinOp::PtrWithAddr,
+        left: GcCow::new(ptr),
+        right: GcCow::new(addr),
+    }
+}
+
 pub fn local_by_name(name: LocalName) -> PlaceExpr {
     PlaceExpr::Local(name)
 }
@@ -293,10 +357,15 @@ pub fn local(x: u32) -> PlaceExpr {
     local_by_name(LocalName(Name::from_internal(x)))
 }
 
-pub fn global_by_name<T: TypeConv>(name: GlobalName) -> PlaceExpr {
-    let relocation = Relocation { name, offset: Size::ZERO };
+/// A raw pointer to the global `name` at the given byte offset.
+pub fn const_global_ptr(name: GlobalName, offset: Offset) -> ValueExpr {
+    let relocation = Relocation { name, offset };
     let ptr_type = Type::Ptr(PtrType::Raw { meta_kind: PointerMetaKind::None });
-    deref(ValueExpr::Constant(Constant::GlobalPointer(relocation), ptr_type), T::get_type())
+    ValueExpr::Constant(Constant::GlobalPointer(relocation), ptr_type)
+}
+
+pub fn global_by_name<T: TypeConv>(name: GlobalName) -> PlaceExpr {
+    deref(const_global_ptr(name, Size::ZERO), T::get_type())
 }
 
 pub fn global<T: TypeConv>(x: u32) -> PlaceExpr {
@@ -326,6 +395,18 @@ pub fn index(root: PlaceExpr, index: ValueExpr) -> PlaceExpr {
     PlaceExpr::Index { root: GcCow::new(root), index: GcCow::new(index) }
 }
 
+/// The element at position `offset`, counted from the end (with the last element at offset 1)
+/// if `from_end` is set.
+pub fn constant_index(root: PlaceExpr, offset: impl Into<Int>, from_end: bool) -> PlaceExpr {
+    PlaceExpr::ConstantIndex { root: GcCow::new(root), offset: offset.into(), from_end }
+}
+
+/// The elements `from..to` of an array, or of a slice without the first `from` and the last `to`
+/// elements.
+pub fn subslice(root: PlaceExpr, from: impl Into<Int>, to: impl Into<Int>) -> PlaceExpr {
+    PlaceExpr::Subslice { root: GcCow::new(root), from: from.into(), to: to.into() }
+}
+
 /// An enum downcast into the variant at the specified index.
 pub fn downcast(root: PlaceExpr, discriminant: impl Into<Int>) -> PlaceExpr {
     PlaceExpr::Downcast { root: GcCow::new(root), discriminant: discriminant.into() }
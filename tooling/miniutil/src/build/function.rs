@@ -82,9 +82,16 @@ pub fn function(ret: Ret, num_args: usize, locals: &[Type], bbs: &[BasicBlock])
         start,
         // For now we use the C ABI for everything since that's what `spawn` needs...
         calling_convention: CallingConvention::C,
+        c_variadic: false,
     }
 }
 
+/// Changes the calling convention of `f` to `C-unwind`, so that it may unwind into its caller.
+/// Such functions must be called with `call_unwind`.
+pub fn c_unwind(f: Function) -> Function {
+    Function { calling_convention: CallingConvention::CUnwind, ..f }
+}
+
 pub fn block(statements: &[Statement], terminator: Terminator) -> BasicBlock {
     BasicBlock { statements: statements.iter().copied().collect(), terminator }
 }
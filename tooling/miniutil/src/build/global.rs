@@ -2,12 +2,73 @@ use crate::build::*;
 
 impl ProgramBuilder {
     pub fn declare_global_zero_initialized<T: TypeConv>(&mut self) -> PlaceExpr {
-        let bytes = List::from_elem(Some(0), T::get_size().bytes());
-        let global = Global { bytes, relocations: list!(), align: <T>::get_align() };
+        let bytes = vec![Some(0); T::get_size().bytes().try_to_usize().unwrap()];
+        let name = self.declare_global(&bytes, <T>::get_align());
+        global_by_name::<T>(name)
+    }
+
+    /// Declares a global of integer type `T`, initialized to `value`.
+    pub fn declare_global_int<T: TypeConv + Into<Int>>(&mut self, value: T) -> PlaceExpr {
+        let Type::Int(int_ty) = T::get_type() else {
+            panic!("declare_global_int: not an integer type");
+        };
+        let bytes =
+            DefaultTarget::ENDIANNESS.encode(int_ty.signed, int_ty.size, value.into()).unwrap();
+        let bytes: Vec<Option<u8>> = bytes.iter().map(Some).collect();
+        let name = self.declare_global(&bytes, T::get_align());
+        global_by_name::<T>(name)
+    }
+
+    /// Declares a global of type `T` whose contents are computed by the function `init`
+    /// before the program starts. `init` must take no arguments and return a `T`.
+    /// Initializers run in the order they are declared.
+    pub fn declare_global_with_init<T: TypeConv>(&mut self, init: FnName) -> PlaceExpr {
+        let bytes = vec![None; T::get_size().bytes().try_to_usize().unwrap()];
+        let name = self.declare_global(&bytes, T::get_align());
+        self.global_inits.push((name, init));
+        global_by_name::<T>(name)
+    }
+
+    /// Declares a global allocation with the given contents and alignment.
+    /// `None` represents an uninitialized byte.
+    /// The allocation exists before execution starts; use `const_global_ptr` to obtain a pointer to it.
+    pub fn declare_global(&mut self, bytes: &[Option<u8>], align: Align) -> GlobalName {
+        self.declare_global_with_relocations(bytes, &[], align)
+    }
+
+    /// Like `declare_global`, but the global is read-only: writing to it is UB.
+    pub fn declare_immutable_global(&mut self, bytes: &[Option<u8>], align: Align) -> GlobalName {
+        self.declare_global_full(bytes, &[], align, Mutability::Immutable)
+    }
+
+    /// Like `declare_global`, but additionally stores pointers to other globals, functions, or vtables
+    /// at the given offsets. These pointers overwrite the corresponding `bytes`.
+    pub fn declare_global_with_relocations(
+        &mut self,
+        bytes: &[Option<u8>],
+        relocations: &[(Offset, RelocationTarget)],
+        align: Align,
+    ) -> GlobalName {
+        self.declare_global_full(bytes, relocations, align, Mutability::Mutable)
+    }
+
+    fn declare_global_full(
+        &mut self,
+        bytes: &[Option<u8>],
+        relocations: &[(Offset, RelocationTarget)],
+        align: Align,
+        mutbl: Mutability,
+    ) -> GlobalName {
+        let global = Global {
+            bytes: bytes.iter().cloned().collect(),
+            relocations: relocations.iter().cloned().collect(),
+            align,
+            mutbl,
+        };
         let name = GlobalName(Name::from_internal(self.next_global));
         self.next_global += 1;
         self.globals.try_insert(name, global).unwrap();
-        global_by_name::<T>(name)
+        name
     }
 }
 
@@ -15,7 +76,7 @@ impl ProgramBuilder {
 pub fn global_int<T: TypeConv>() -> Global {
     let bytes = List::from_elem(Some(0), T::get_size().bytes());
 
-    Global { bytes, relocations: list!(), align: T::get_align() }
+    Global { bytes, relocations: list!(), align: T::get_align(), mutbl: Mutability::Mutable }
 }
 
 /// Global pointer
@@ -23,5 +84,10 @@ pub fn global_ptr<T: TypeConv + ?Sized>() -> Global {
     let bytes =
         List::from_elem(Some(0), <*const T>::get_layout().expect_size("*T is `Sized`").bytes());
 
-    Global { bytes, relocations: list!(), align: <*const T>::get_align() }
+    Global {
+        bytes,
+        relocations: list!(),
+        align: <*const T>::get_align(),
+        mutbl: Mutability::Mutable,
+    }
 }
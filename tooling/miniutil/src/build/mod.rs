@@ -44,6 +44,7 @@ pub struct ProgramBuilder {
     globals: Map<GlobalName, Global>,
     vtables: Map<VTableName, VTable>,
     traits: Map<TraitName, Set<TraitMethodName>>,
+    global_inits: List<(GlobalName, FnName)>,
     next_fn: u32,
     next_global: u32,
     next_vtable: u32,
@@ -57,6 +58,7 @@ impl ProgramBuilder {
             globals: Default::default(),
             vtables: Default::default(),
             traits: Default::default(),
+            global_inits: Default::default(),
             next_fn: 0,
             next_global: 0,
             next_vtable: 0,
@@ -71,6 +73,7 @@ impl ProgramBuilder {
             globals: self.globals,
             traits: self.traits,
             vtables: self.vtables,
+            global_inits: self.global_inits,
         }
     }
 
@@ -88,6 +91,20 @@ impl ProgramBuilder {
         name
     }
 
+    /// Declares a function that can be spawned as a thread, with `body` as its code.
+    /// `body` also gets the local holding the argument passed to `spawn` (a `*const ()`).
+    #[track_caller]
+    pub fn thread_function(
+        &mut self,
+        body: impl FnOnce(&mut FunctionBuilder, PlaceExpr),
+    ) -> FnName {
+        let mut f = self.declare_function();
+        let arg = f.declare_arg::<*const ()>();
+        body(&mut f, arg);
+        f.return_();
+        self.finish_function(f)
+    }
+
     pub fn declare_vtable_for_ty(&mut self, trait_name: TraitName, ty: Type) -> VTableBuilder {
         self.declare_vtable(
             trait_name,
@@ -144,6 +161,7 @@ pub struct FunctionBuilder {
 
     start: BbName,
     ret: Option<LocalName>,
+    c_variadic: bool,
 
     cur_block: Option<CurBlock>,
 
@@ -160,6 +178,7 @@ impl FunctionBuilder {
             args: Default::default(),
             start: BbName(Name::from_internal(0)),
             ret: None,
+            c_variadic: false,
             cur_block: None,
             next_block: 0,
             next_local: 0,
@@ -213,6 +232,7 @@ impl FunctionBuilder {
             args: self.args,
             ret: self.ret.unwrap(),
             calling_convention: CallingConvention::C,
+            c_variadic: self.c_variadic,
             blocks: self.blocks,
             start: self.start,
         }
@@ -261,6 +281,12 @@ impl FunctionBuilder {
         self.args.push(name);
         local_by_name(name)
     }
+
+    /// Makes this function C-variadic: callers may pass more arguments than declared,
+    /// which the function can read with `va_arg`.
+    pub fn set_c_variadic(&mut self) {
+        self.c_variadic = true;
+    }
 }
 
 pub struct VTableBuilder {
@@ -379,6 +405,7 @@ pub fn program_with_globals(fns: &[Function], globals: &[Global]) -> Program {
         globals,
         traits: Default::default(),
         vtables: Default::default(),
+        global_inits: Default::default(),
     }
 }
 
@@ -394,3 +421,34 @@ pub fn small_program(locals: &[Type], statements: &[Statement]) -> Program {
 
     program(&[f])
 }
+
+/// Turns `prog` into a program whose start function calls `f` with the given arguments
+/// (passed by value) instead of running the original start function.
+/// If `f` returns an integer or a Boolean, the start function prints the return value
+/// before exiting, so that it can be inspected via stdout.
+pub fn program_calling(prog: Program, f: FnName, args: &[ValueExpr]) -> Program {
+    let callee = prog.functions.get(f).unwrap();
+    let ret_ty = callee.locals.get(callee.ret).unwrap();
+
+    let b0 = block!(storage_live(0), Terminator::Call {
+        callee: fn_ptr(f),
+        calling_convention: callee.calling_convention,
+        arguments: args.iter().map(|arg| by_value(*arg)).collect(),
+        ret: local(0),
+        next_block: Some(BbName(Name::from_internal(1))),
+        unwind_block: None,
+    });
+    let b1 = match ret_ty {
+        Type::Int(_) | Type::Bool => block!(print(load(local(0)), 2)),
+        _ => block!(goto(2)),
+    };
+    let b2 = block!(exit());
+    let start = function(Ret::No, 0, &[ret_ty], &[b0, b1, b2]);
+
+    let mut functions = prog.functions;
+    let name = functions.keys().map(|FnName(name)| name.get_internal() + 1).max().unwrap_or(0);
+    let name = FnName(Name::from_internal(name));
+    functions.insert(name, start);
+
+    Program { functions, start: name, ..prog }
+}
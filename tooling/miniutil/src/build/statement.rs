@@ -16,12 +16,28 @@ impl FunctionBuilder {
     }
 
     pub fn validate(&mut self, place: PlaceExpr, fn_entry: bool) {
-        self.cur_block().statements.push(Statement::Validate { place, fn_entry });
+        self.cur_block().statements.push(validate(place, fn_entry));
+    }
+
+    /// Retag the two-phase borrow in `place`.
+    pub fn validate_two_phase(&mut self, place: PlaceExpr) {
+        self.cur_block().statements.push(validate_two_phase(place));
+    }
+
+    /// Validate the pointee of a reference; only executed if the machine validates pointees.
+    pub fn validate_pointee(&mut self, place: PlaceExpr) {
+        self.cur_block().statements.push(validate_pointee(place));
     }
 
     pub fn storage_live(&mut self, local: PlaceExpr) {
         let PlaceExpr::Local(name) = local else { panic!("PlaceExpr is not a local") };
-        self.cur_block().statements.push(Statement::StorageLive(name));
+        self.cur_block().statements.push(Statement::StorageLive { local: name, meta: None });
+    }
+
+    /// Allocate an unsized local, with the size determined by the pointer metadata `meta`.
+    pub fn storage_live_unsized(&mut self, local: PlaceExpr, meta: ValueExpr) {
+        let PlaceExpr::Local(name) = local else { panic!("PlaceExpr is not a local") };
+        self.cur_block().statements.push(Statement::StorageLive { local: name, meta: Some(meta) });
     }
 
     pub fn storage_dead(&mut self, local: PlaceExpr) {
@@ -43,11 +59,19 @@ pub fn set_discriminant(destination: PlaceExpr, value: impl Into<Int>) -> Statem
 }
 
 pub fn validate(place: PlaceExpr, fn_entry: bool) -> Statement {
-    Statement::Validate { place, fn_entry }
+    Statement::Validate { place, fn_entry, two_phase: false, behind_reference: false }
+}
+
+pub fn validate_two_phase(place: PlaceExpr) -> Statement {
+    Statement::Validate { place, fn_entry: false, two_phase: true, behind_reference: false }
+}
+
+pub fn validate_pointee(place: PlaceExpr) -> Statement {
+    Statement::Validate { place, fn_entry: false, two_phase: false, behind_reference: true }
 }
 
 pub fn storage_live(x: u32) -> Statement {
-    Statement::StorageLive(LocalName(Name::from_internal(x)))
+    Statement::StorageLive { local: LocalName(Name::from_internal(x)), meta: None }
 }
 
 pub fn storage_dead(x: u32) -> Statement {
@@ -29,6 +29,27 @@ impl FunctionBuilder {
         self.finish_block(panic());
     }
 
+    /// Call the extern function `name`, which the environment provides.
+    pub fn extern_call(&mut self, ret: PlaceExpr, name: &str, args: &[ValueExpr]) {
+        let next_block = self.declare_block();
+        self.finish_block(Terminator::ExternCall {
+            name: minirust_rs::prelude::String::from_internal(name.to_string()),
+            arguments: args.iter().copied().collect(),
+            ret,
+            next_block: Some(next_block),
+        });
+        self.set_cur_block(next_block);
+    }
+
+    /// Tail-call a function: the callee replaces the current function on the stack.
+    pub fn tail_call(&mut self, f: ValueExpr, args: &[ArgumentExpr]) {
+        self.finish_block(Terminator::TailCall {
+            callee: f,
+            calling_convention: CallingConvention::C, // FIXME do not hard-code the C calling convention
+            arguments: args.iter().copied().collect(),
+        });
+    }
+
     /// Call a function that does not return.
     pub fn call_noret(&mut self, ret: PlaceExpr, f: ValueExpr, args: &[ArgumentExpr]) {
         self.finish_block(Terminator::Call {
@@ -37,6 +58,7 @@ impl FunctionBuilder {
             arguments: args.iter().copied().collect(),
             ret,
             next_block: None,
+            unwind_block: None,
         });
     }
 
@@ -49,6 +71,7 @@ impl FunctionBuilder {
             arguments: args.iter().copied().collect(),
             ret,
             next_block: Some(next_block),
+            unwind_block: None,
         });
         self.set_cur_block(next_block)
     }
@@ -62,6 +85,26 @@ impl FunctionBuilder {
             arguments: args.iter().copied().collect(),
             ret: unit_place(),
             next_block: Some(next_block),
+            unwind_block: None,
+        });
+        self.set_cur_block(next_block);
+    }
+
+    /// An inline assembly block with the given operands, see `Terminator::InlineAsm`.
+    pub fn inline_asm(
+        &mut self,
+        inputs: &[ValueExpr],
+        outputs: &[PlaceExpr],
+        reads: &[(ValueExpr, Size)],
+        clobbers: &[(ValueExpr, Size)],
+    ) {
+        let next_block = self.declare_block();
+        self.finish_block(Terminator::InlineAsm {
+            inputs: inputs.iter().copied().collect(),
+            outputs: outputs.iter().copied().collect(),
+            reads: reads.iter().copied().collect(),
+            clobbers: clobbers.iter().copied().collect(),
+            next_block: Some(next_block),
         });
         self.set_cur_block(next_block);
     }
@@ -114,15 +157,110 @@ impl FunctionBuilder {
         self.set_cur_block(next_block)
     }
 
+    /// Computes `left + right + carry`; `dest` receives the result and the outgoing carry.
+    pub fn carrying_add(
+        &mut self,
+        dest: PlaceExpr,
+        left: ValueExpr,
+        right: ValueExpr,
+        carry: ValueExpr,
+    ) {
+        let next_block = self.declare_block();
+        self.finish_block(carrying_add(dest, left, right, carry, bbname_into_u32(next_block)));
+        self.set_cur_block(next_block)
+    }
+
+    /// Computes `left - right - borrow`; `dest` receives the result and the outgoing borrow.
+    pub fn borrowing_sub(
+        &mut self,
+        dest: PlaceExpr,
+        left: ValueExpr,
+        right: ValueExpr,
+        borrow: ValueExpr,
+    ) {
+        let next_block = self.declare_block();
+        self.finish_block(borrowing_sub(dest, left, right, borrow, bbname_into_u32(next_block)));
+        self.set_cur_block(next_block)
+    }
+
+    /// Computes `left * right`; `dest` receives the low and high half of the product.
+    pub fn widening_mul(&mut self, dest: PlaceExpr, left: ValueExpr, right: ValueExpr) {
+        let next_block = self.declare_block();
+        self.finish_block(widening_mul(dest, left, right, bbname_into_u32(next_block)));
+        self.set_cur_block(next_block)
+    }
+
+    pub fn copy(&mut self, src: ValueExpr, dst: ValueExpr, count: ValueExpr, elem: Type) {
+        let next_block = self.declare_block();
+        self.finish_block(copy(src, dst, count, elem, bbname_into_u32(next_block)));
+        self.set_cur_block(next_block)
+    }
+
+    pub fn copy_nonoverlapping(
+        &mut self,
+        src: ValueExpr,
+        dst: ValueExpr,
+        count: ValueExpr,
+        elem: Type,
+    ) {
+        let next_block = self.declare_block();
+        self.finish_block(copy_nonoverlapping(src, dst, count, elem, bbname_into_u32(next_block)));
+        self.set_cur_block(next_block)
+    }
+
+    pub fn write_bytes(&mut self, dst: ValueExpr, val: ValueExpr, count: ValueExpr, elem: Type) {
+        let next_block = self.declare_block();
+        self.finish_block(write_bytes(dst, val, count, elem, bbname_into_u32(next_block)));
+        self.set_cur_block(next_block)
+    }
+
+    /// Swap the values of type `ty` behind the non-overlapping pointers `x` and `y`.
+    pub fn typed_swap_nonoverlapping(&mut self, x: ValueExpr, y: ValueExpr, ty: Type) {
+        let next_block = self.declare_block();
+        self.finish_block(typed_swap_nonoverlapping(x, y, ty, bbname_into_u32(next_block)));
+        self.set_cur_block(next_block)
+    }
+
+    pub fn freeze(&mut self, ptr: ValueExpr, ty: Type) {
+        let next_block = self.declare_block();
+        self.finish_block(freeze(ptr, ty, bbname_into_u32(next_block)));
+        self.set_cur_block(next_block)
+    }
+
+    /// Panic unless `ty` satisfies `requirement`.
+    pub fn assert_validity(&mut self, requirement: ValidityRequirement, ty: Type) {
+        let next_block = self.declare_block();
+        self.finish_block(assert_validity(requirement, ty, bbname_into_u32(next_block)));
+        self.set_cur_block(next_block)
+    }
+
     pub fn atomic_store(&mut self, ptr: ValueExpr, src: ValueExpr) {
+        self.atomic_store_ordered(ptr, src, AtomicOrdering::SeqCst)
+    }
+
+    pub fn atomic_store_ordered(
+        &mut self,
+        ptr: ValueExpr,
+        src: ValueExpr,
+        ordering: AtomicOrdering,
+    ) {
         let next_block = self.declare_block();
-        self.finish_block(atomic_store(ptr, src, bbname_into_u32(next_block)));
+        self.finish_block(atomic_store_ordered(ptr, src, ordering, bbname_into_u32(next_block)));
         self.set_cur_block(next_block)
     }
 
     pub fn atomic_load(&mut self, dest: PlaceExpr, ptr: ValueExpr) {
+        self.atomic_load_ordered(dest, ptr, AtomicOrdering::SeqCst)
+    }
+
+    pub fn atomic_load_ordered(
+        &mut self,
+        dest: PlaceExpr,
+        ptr: ValueExpr,
+        ordering: AtomicOrdering,
+    ) {
         let next_block = self.declare_block();
-        self.finish_block(atomic_load(dest, ptr, bbname_into_u32(next_block)));
+        self.finish_block(atomic_load_ordered(dest, ptr, ordering, bbname_into_u32(next_block)));
         self.set_cur_block(next_block)
     }
 
@@ -156,6 +294,25 @@ impl FunctionBuilder {
         self.set_cur_block(next_block)
     }
 
+    /// `dest` must have type `(T, bool)`, see `IntrinsicOp::AtomicCompareExchangeWeak`.
+    pub fn compare_exchange_weak(
+        &mut self,
+        dest: PlaceExpr,
+        ptr: ValueExpr,
+        current: ValueExpr,
+        next_val: ValueExpr,
+    ) {
+        let next_block = self.declare_block();
+        self.finish_block(compare_exchange_weak(
+            dest,
+            ptr,
+            current,
+            next_val,
+            bbname_into_u32(next_block),
+        ));
+        self.set_cur_block(next_block)
+    }
+
     pub fn expose_provenance(&mut self, dest: PlaceExpr, ptr: ValueExpr) {
         let next_block = self.declare_block();
         self.finish_block(expose_provenance(dest, ptr, bbname_into_u32(next_block)));
@@ -168,6 +325,12 @@ impl FunctionBuilder {
         self.set_cur_block(next_block)
     }
 
+    pub fn va_arg(&mut self, dest: PlaceExpr) {
+        let next_block = self.declare_block();
+        self.finish_block(va_arg(dest, bbname_into_u32(next_block)));
+        self.set_cur_block(next_block)
+    }
+
     pub fn lock_create(&mut self, ret: PlaceExpr) {
         let next_block = self.declare_block();
         self.finish_block(lock_create(ret, bbname_into_u32(next_block)));
@@ -180,12 +343,54 @@ impl FunctionBuilder {
         self.set_cur_block(next_block)
     }
 
+    pub fn lock_acquire_shared(&mut self, lock_id: ValueExpr) {
+        let next_block = self.declare_block();
+        self.finish_block(lock_acquire_shared(lock_id, bbname_into_u32(next_block)));
+        self.set_cur_block(next_block)
+    }
+
     pub fn lock_release(&mut self, lock_id: ValueExpr) {
         let next_block = self.declare_block();
         self.finish_block(lock_release(lock_id, bbname_into_u32(next_block)));
         self.set_cur_block(next_block)
     }
 
+    pub fn condvar_create(&mut self, ret: PlaceExpr) {
+        let next_block = self.declare_block();
+        self.finish_block(condvar_create(ret, bbname_into_u32(next_block)));
+        self.set_cur_block(next_block)
+    }
+
+    pub fn condvar_wait(&mut self, condvar_id: ValueExpr, lock_id: ValueExpr) {
+        let next_block = self.declare_block();
+        self.finish_block(condvar_wait(condvar_id, lock_id, bbname_into_u32(next_block)));
+        self.set_cur_block(next_block)
+    }
+
+    pub fn condvar_notify_one(&mut self, condvar_id: ValueExpr) {
+        let next_block = self.declare_block();
+        self.finish_block(condvar_notify_one(condvar_id, bbname_into_u32(next_block)));
+        self.set_cur_block(next_block)
+    }
+
+    pub fn condvar_notify_all(&mut self, condvar_id: ValueExpr) {
+        let next_block = self.declare_block();
+        self.finish_block(condvar_notify_all(condvar_id, bbname_into_u32(next_block)));
+        self.set_cur_block(next_block)
+    }
+
+    pub fn futex_wait(&mut self, ret: PlaceExpr, ptr: ValueExpr, expected: ValueExpr) {
+        let next_block = self.declare_block();
+        self.finish_block(futex_wait(ret, ptr, expected, bbname_into_u32(next_block)));
+        self.set_cur_block(next_block)
+    }
+
+    pub fn futex_wake(&mut self, ret: PlaceExpr, ptr: ValueExpr) {
+        let next_block = self.declare_block();
+        self.finish_block(futex_wake(ret, ptr, bbname_into_u32(next_block)));
+        self.set_cur_block(next_block)
+    }
+
     // terminators with 2 or more following blocks
     pub fn if_<F, G>(&mut self, condition: ValueExpr, then_branch: F, else_branch: G)
     where
@@ -296,6 +501,26 @@ pub fn call(f: u32, args: &[ArgumentExpr], ret: PlaceExpr, next: Option<u32>) ->
         arguments: args.iter().copied().collect(),
         ret,
         next_block: next.map(|x| BbName(Name::from_internal(x))),
+        unwind_block: None,
+    }
+}
+
+/// A `C-unwind` call, i.e. a call to a function that may unwind (see `c_unwind`).
+/// If the callee unwinds, execution continues at `unwind`, or if that is `None`, the caller unwinds as well.
+pub fn call_unwind(
+    f: u32,
+    args: &[ArgumentExpr],
+    ret: PlaceExpr,
+    next: Option<u32>,
+    unwind: Option<u32>,
+) -> Terminator {
+    Terminator::Call {
+        callee: fn_ptr_internal(f),
+        calling_convention: CallingConvention::CUnwind,
+        arguments: args.iter().copied().collect(),
+        ret,
+        next_block: next.map(|x| BbName(Name::from_internal(x))),
+        unwind_block: unwind.map(|x| BbName(Name::from_internal(x))),
     }
 }
 
@@ -366,6 +591,22 @@ pub fn return_() -> Terminator {
     Terminator::Return
 }
 
+pub fn start_unwind(unwind: Option<u32>) -> Terminator {
+    Terminator::StartUnwind(unwind.map(|x| BbName(Name::from_internal(x))))
+}
+
+pub fn resume_unwind() -> Terminator {
+    Terminator::ResumeUnwind
+}
+
+pub fn stop_unwind(next: u32) -> Terminator {
+    Terminator::StopUnwind(BbName(Name::from_internal(next)))
+}
+
+pub fn unwind_terminate() -> Terminator {
+    Terminator::UnwindTerminate
+}
+
 pub fn spawn(fn_ptr: ValueExpr, data_ptr: ValueExpr, ret: PlaceExpr, next: u32) -> Terminator {
     Terminator::Intrinsic {
         intrinsic: IntrinsicOp::Spawn,
@@ -393,9 +634,123 @@ pub fn raw_eq(ret: PlaceExpr, left_ptr: ValueExpr, right_ptr: ValueExpr, next: u
     }
 }
 
+pub fn carrying_add(
+    ret: PlaceExpr,
+    left: ValueExpr,
+    right: ValueExpr,
+    carry: ValueExpr,
+    next: u32,
+) -> Terminator {
+    Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::CarryingAdd,
+        arguments: list!(left, right, carry),
+        ret,
+        next_block: Some(BbName(Name::from_internal(next))),
+    }
+}
+
+pub fn borrowing_sub(
+    ret: PlaceExpr,
+    left: ValueExpr,
+    right: ValueExpr,
+    borrow: ValueExpr,
+    next: u32,
+) -> Terminator {
+    Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::BorrowingSub,
+        arguments: list!(left, right, borrow),
+        ret,
+        next_block: Some(BbName(Name::from_internal(next))),
+    }
+}
+
+pub fn widening_mul(ret: PlaceExpr, left: ValueExpr, right: ValueExpr, next: u32) -> Terminator {
+    Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::WideningMul,
+        arguments: list!(left, right),
+        ret,
+        next_block: Some(BbName(Name::from_internal(next))),
+    }
+}
+
+pub fn copy(src: ValueExpr, dst: ValueExpr, count: ValueExpr, elem: Type, next: u32) -> Terminator {
+    Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::Copy(elem),
+        arguments: list!(src, dst, count),
+        ret: unit_place(),
+        next_block: Some(BbName(Name::from_internal(next))),
+    }
+}
+
+pub fn copy_nonoverlapping(
+    src: ValueExpr,
+    dst: ValueExpr,
+    count: ValueExpr,
+    elem: Type,
+    next: u32,
+) -> Terminator {
+    Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::CopyNonOverlapping(elem),
+        arguments: list!(src, dst, count),
+        ret: unit_place(),
+        next_block: Some(BbName(Name::from_internal(next))),
+    }
+}
+
+pub fn write_bytes(
+    dst: ValueExpr,
+    val: ValueExpr,
+    count: ValueExpr,
+    elem: Type,
+    next: u32,
+) -> Terminator {
+    Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::WriteBytes(elem),
+        arguments: list!(dst, val, count),
+        ret: unit_place(),
+        next_block: Some(BbName(Name::from_internal(next))),
+    }
+}
+
+pub fn typed_swap_nonoverlapping(x: ValueExpr, y: ValueExpr, ty: Type, next: u32) -> Terminator {
+    Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::TypedSwapNonOverlapping(ty),
+        arguments: list!(x, y),
+        ret: unit_place(),
+        next_block: Some(BbName(Name::from_internal(next))),
+    }
+}
+
+pub fn freeze(ptr: ValueExpr, ty: Type, next: u32) -> Terminator {
+    Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::Freeze(ty),
+        arguments: list!(ptr),
+        ret: unit_place(),
+        next_block: Some(BbName(Name::from_internal(next))),
+    }
+}
+
+pub fn assert_validity(requirement: ValidityRequirement, ty: Type, next: u32) -> Terminator {
+    Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::AssertValidity(requirement, ty),
+        arguments: list!(),
+        ret: unit_place(),
+        next_block: Some(BbName(Name::from_internal(next))),
+    }
+}
+
 pub fn atomic_store(ptr: ValueExpr, src: ValueExpr, next: u32) -> Terminator {
+    atomic_store_ordered(ptr, src, AtomicOrdering::SeqCst, next)
+}
+
+pub fn atomic_store_ordered(
+    ptr: ValueExpr,
+    src: ValueExpr,
+    ordering: AtomicOrdering,
+    next: u32,
+) -> Terminator {
     Terminator::Intrinsic {
-        intrinsic: IntrinsicOp::AtomicStore,
+        intrinsic: IntrinsicOp::AtomicStore(ordering),
         arguments: list!(ptr, src),
         ret: unit_place(),
         next_block: Some(BbName(Name::from_internal(next))),
@@ -403,8 +758,17 @@ pub fn atomic_store(ptr: ValueExpr, src: ValueExpr, next: u32) -> Terminator {
 }
 
 pub fn atomic_load(dest: PlaceExpr, ptr: ValueExpr, next: u32) -> Terminator {
+    atomic_load_ordered(dest, ptr, AtomicOrdering::SeqCst, next)
+}
+
+pub fn atomic_load_ordered(
+    dest: PlaceExpr,
+    ptr: ValueExpr,
+    ordering: AtomicOrdering,
+    next: u32,
+) -> Terminator {
     Terminator::Intrinsic {
-        intrinsic: IntrinsicOp::AtomicLoad,
+        intrinsic: IntrinsicOp::AtomicLoad(ordering),
         arguments: list!(ptr),
         ret: dest,
         next_block: Some(BbName(Name::from_internal(next))),
@@ -414,6 +778,13 @@ pub fn atomic_load(dest: PlaceExpr, ptr: ValueExpr, next: u32) -> Terminator {
 pub enum FetchBinOp {
     Add,
     Sub,
+    And,
+    Or,
+    Xor,
+    Nand,
+    Min,
+    Max,
+    Swap,
 }
 
 pub fn atomic_fetch(
@@ -422,14 +793,32 @@ pub fn atomic_fetch(
     ptr: ValueExpr,
     other: ValueExpr,
     next: u32,
+) -> Terminator {
+    atomic_fetch_ordered(binop, dest, ptr, other, AtomicOrdering::SeqCst, next)
+}
+
+pub fn atomic_fetch_ordered(
+    binop: FetchBinOp,
+    dest: PlaceExpr,
+    ptr: ValueExpr,
+    other: ValueExpr,
+    ordering: AtomicOrdering,
+    next: u32,
 ) -> Terminator {
     let binop = match binop {
-        FetchBinOp::Add => IntBinOp::Add,
-        FetchBinOp::Sub => IntBinOp::Sub,
+        FetchBinOp::Add => AtomicRmwOp::IntBinOp(IntBinOp::Add),
+        FetchBinOp::Sub => AtomicRmwOp::IntBinOp(IntBinOp::Sub),
+        FetchBinOp::And => AtomicRmwOp::IntBinOp(IntBinOp::BitAnd),
+        FetchBinOp::Or => AtomicRmwOp::IntBinOp(IntBinOp::BitOr),
+        FetchBinOp::Xor => AtomicRmwOp::IntBinOp(IntBinOp::BitXor),
+        FetchBinOp::Nand => AtomicRmwOp::Nand,
+        FetchBinOp::Min => AtomicRmwOp::Min,
+        FetchBinOp::Max => AtomicRmwOp::Max,
+        FetchBinOp::Swap => AtomicRmwOp::Swap,
     };
 
     Terminator::Intrinsic {
-        intrinsic: IntrinsicOp::AtomicFetchAndOp(binop),
+        intrinsic: IntrinsicOp::AtomicFetchAndOp(binop, ordering),
         arguments: list!(ptr, other),
         ret: dest,
         next_block: Some(BbName(Name::from_internal(next))),
@@ -442,9 +831,64 @@ pub fn compare_exchange(
     current: ValueExpr,
     next_val: ValueExpr,
     next: u32,
+) -> Terminator {
+    compare_exchange_ordered(
+        dest,
+        ptr,
+        current,
+        next_val,
+        AtomicOrdering::SeqCst,
+        AtomicOrdering::SeqCst,
+        next,
+    )
+}
+
+pub fn compare_exchange_ordered(
+    dest: PlaceExpr,
+    ptr: ValueExpr,
+    current: ValueExpr,
+    next_val: ValueExpr,
+    success: AtomicOrdering,
+    failure: AtomicOrdering,
+    next: u32,
+) -> Terminator {
+    Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::AtomicCompareExchange { success, failure },
+        arguments: list!(ptr, current, next_val),
+        ret: dest,
+        next_block: Some(BbName(Name::from_internal(next))),
+    }
+}
+
+pub fn compare_exchange_weak(
+    dest: PlaceExpr,
+    ptr: ValueExpr,
+    current: ValueExpr,
+    next_val: ValueExpr,
+    next: u32,
+) -> Terminator {
+    compare_exchange_weak_ordered(
+        dest,
+        ptr,
+        current,
+        next_val,
+        AtomicOrdering::SeqCst,
+        AtomicOrdering::SeqCst,
+        next,
+    )
+}
+
+pub fn compare_exchange_weak_ordered(
+    dest: PlaceExpr,
+    ptr: ValueExpr,
+    current: ValueExpr,
+    next_val: ValueExpr,
+    success: AtomicOrdering,
+    failure: AtomicOrdering,
+    next: u32,
 ) -> Terminator {
     Terminator::Intrinsic {
-        intrinsic: IntrinsicOp::AtomicCompareExchange,
+        intrinsic: IntrinsicOp::AtomicCompareExchangeWeak { success, failure },
         arguments: list!(ptr, current, next_val),
         ret: dest,
         next_block: Some(BbName(Name::from_internal(next))),
@@ -469,6 +913,15 @@ pub fn with_exposed_provenance(dest: PlaceExpr, addr: ValueExpr, next: u32) -> T
     }
 }
 
+pub fn va_arg(dest: PlaceExpr, next: u32) -> Terminator {
+    Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::VaArg,
+        arguments: list![],
+        ret: dest,
+        next_block: Some(BbName(Name::from_internal(next))),
+    }
+}
+
 pub fn lock_create(ret: PlaceExpr, next: u32) -> Terminator {
     Terminator::Intrinsic {
         intrinsic: IntrinsicOp::Lock(IntrinsicLockOp::Create),
@@ -487,6 +940,15 @@ pub fn lock_acquire(lock_id: ValueExpr, next: u32) -> Terminator {
     }
 }
 
+pub fn lock_acquire_shared(lock_id: ValueExpr, next: u32) -> Terminator {
+    Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::Lock(IntrinsicLockOp::AcquireShared),
+        arguments: list!(lock_id),
+        ret: unit_place(),
+        next_block: Some(BbName(Name::from_internal(next))),
+    }
+}
+
 pub fn lock_release(lock_id: ValueExpr, next: u32) -> Terminator {
     Terminator::Intrinsic {
         intrinsic: IntrinsicOp::Lock(IntrinsicLockOp::Release),
@@ -495,3 +957,59 @@ pub fn lock_release(lock_id: ValueExpr, next: u32) -> Terminator {
         next_block: Some(BbName(Name::from_internal(next))),
     }
 }
+
+pub fn condvar_create(ret: PlaceExpr, next: u32) -> Terminator {
+    Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::Lock(IntrinsicLockOp::CreateCondVar),
+        arguments: list!(),
+        ret,
+        next_block: Some(BbName(Name::from_internal(next))),
+    }
+}
+
+pub fn condvar_wait(condvar_id: ValueExpr, lock_id: ValueExpr, next: u32) -> Terminator {
+    Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::Lock(IntrinsicLockOp::Wait),
+        arguments: list!(condvar_id, lock_id),
+        ret: unit_place(),
+        next_block: Some(BbName(Name::from_internal(next))),
+    }
+}
+
+pub fn condvar_notify_one(condvar_id: ValueExpr, next: u32) -> Terminator {
+    Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::Lock(IntrinsicLockOp::NotifyOne),
+        arguments: list!(condvar_id),
+        ret: unit_place(),
+        next_block: Some(BbName(Name::from_internal(next))),
+    }
+}
+
+pub fn condvar_notify_all(condvar_id: ValueExpr, next: u32) -> Terminator {
+    Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::Lock(IntrinsicLockOp::NotifyAll),
+        arguments: list!(condvar_id),
+        ret: unit_place(),
+        next_block: Some(BbName(Name::from_internal(next))),
+    }
+}
+
+/// `ret` must have type `bool`.
+pub fn futex_wait(ret: PlaceExpr, ptr: ValueExpr, expected: ValueExpr, next: u32) -> Terminator {
+    Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::FutexWait,
+        arguments: list!(ptr, expected),
+        ret,
+        next_block: Some(BbName(Name::from_internal(next))),
+    }
+}
+
+/// `ret` must have type `bool`.
+pub fn futex_wake(ret: PlaceExpr, ptr: ValueExpr, next: u32) -> Terminator {
+    Terminator::Intrinsic {
+        intrinsic: IntrinsicOp::FutexWake,
+        arguments: list!(ptr),
+        ret,
+        next_block: Some(BbName(Name::from_internal(next))),
+    }
+}
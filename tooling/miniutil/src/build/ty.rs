@@ -8,6 +8,18 @@ pub fn bool_ty() -> Type {
     Type::Bool
 }
 
+pub fn char_ty() -> Type {
+    Type::Char
+}
+
+pub fn never_ty() -> Type {
+    Type::Never
+}
+
+pub fn float_ty(size: Size) -> Type {
+    Type::Float(FloatType { size })
+}
+
 pub fn ref_ty(pointee: PointeeInfo) -> Type {
     Type::Ptr(PtrType::Ref { mutbl: Mutability::Immutable, pointee })
 }
@@ -55,6 +67,22 @@ pub fn tuple_ty(f: &[(Offset, Type)], size: Size, align: Align) -> Type {
         sized_fields: f.iter().copied().collect(),
         sized_head_layout: TupleHeadLayout { end: size, align, packed_align: None },
         unsized_field: GcCow::new(None),
+        valid_range: None,
+    }
+}
+
+/// A sized tuple with the `repr(packed(packed_align))` attribute, so its fields may be under-aligned.
+pub fn packed_tuple_ty(
+    f: &[(Offset, Type)],
+    size: Size,
+    align: Align,
+    packed_align: Align,
+) -> Type {
+    Type::Tuple {
+        sized_fields: f.iter().copied().collect(),
+        sized_head_layout: TupleHeadLayout { end: size, align, packed_align: Some(packed_align) },
+        unsized_field: GcCow::new(None),
+        valid_range: None,
     }
 }
 
@@ -69,6 +97,20 @@ pub fn unsized_tuple_ty(
         sized_fields: fs.iter().copied().collect(),
         sized_head_layout: TupleHeadLayout { end, align, packed_align },
         unsized_field: GcCow::new(Some(unsized_ty)),
+        valid_range: None,
+    }
+}
+
+/// A struct wrapping a single integer or pointer `field` whose value (or address) must lie in `start..=end`,
+/// like `NonZeroU32` or `NonNull<T>`. The range wraps around if `start > end`.
+pub fn valid_range_ty(field: Type, start: impl Into<Int>, end: impl Into<Int>) -> Type {
+    let size = field.layout::<DefaultTarget>().expect_size("valid ranges restrict sized fields");
+    let align = field.layout::<DefaultTarget>().expect_align("valid ranges restrict sized fields");
+    Type::Tuple {
+        sized_fields: list![(Offset::ZERO, field)],
+        sized_head_layout: TupleHeadLayout { end: size, align, packed_align: None },
+        unsized_field: GcCow::new(None),
+        valid_range: Some(ValidRange { start: start.into(), end: end.into() }),
     }
 }
 
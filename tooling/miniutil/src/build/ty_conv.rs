@@ -73,11 +73,35 @@ impl TypeConv for bool {
     }
 }
 
+impl TypeConv for char {
+    fn get_type() -> Type {
+        char_ty()
+    }
+}
+
+impl TypeConv for ! {
+    fn get_type() -> Type {
+        never_ty()
+    }
+}
+
+impl TypeConv for f32 {
+    fn get_type() -> Type {
+        float_ty(size(4))
+    }
+}
+
+impl TypeConv for f64 {
+    fn get_type() -> Type {
+        float_ty(size(8))
+    }
+}
+
 impl<T: TypeConv + ?Sized> TypeConv for &T {
     fn get_type() -> Type {
         ref_ty(PointeeInfo {
             layout: T::get_layout(),
-            inhabited: true,
+            inhabited: T::get_type().inhabited(),
             freeze: T::FREEZE,
             unpin: T::UNPIN,
         })
@@ -88,7 +112,7 @@ impl<T: TypeConv + ?Sized> TypeConv for &mut T {
     fn get_type() -> Type {
         ref_mut_ty(PointeeInfo {
             layout: T::get_layout(),
-            inhabited: true,
+            inhabited: T::get_type().inhabited(),
             freeze: T::FREEZE,
             unpin: T::UNPIN,
         })
@@ -0,0 +1,450 @@
+//! A conformance kit for the core memory and representation semantics of MiniRust.
+//!
+//! The kit is a list of small programs together with the verdict the spec assigns to them.
+//! Alternative implementations of MiniRust (e.g. an interpreter extracted from a formalization,
+//! or a codegen backend) can check themselves against the kit by implementing [`Evaluator`]
+//! and calling [`run_conformance`].
+//!
+//! All programs are deterministic, only use raw pointers, and assume the `x86_64` target.
+//! Verdicts are deliberately coarse: we require other implementations to detect UB,
+//! but not to report it with the same message as the spec.
+
+use std::marker::PhantomData;
+
+use crate::build::*;
+use crate::run::*;
+use crate::*;
+
+/// The part of the semantics a conformance test is about.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Category {
+    Padding,
+    Provenance,
+    Uninit,
+    Unions,
+    Enums,
+}
+
+/// How running a program ended.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    /// The program stopped normally, after printing exactly these lines to stdout.
+    Stop(Vec<String>),
+    /// The program has Undefined Behavior.
+    Ub,
+    /// The program is ill-formed.
+    IllFormed,
+    /// The program ended in some other way, e.g. it deadlocked or leaked memory.
+    /// No conformance test expects this verdict.
+    Other(String),
+}
+
+impl Verdict {
+    /// Determines the verdict from how a run of the spec interpreter ended.
+    pub fn from_output(output: Output) -> Verdict {
+        match output.info {
+            TerminationInfo::MachineStop => Verdict::Stop(output.stdout),
+            TerminationInfo::Ub(..) => Verdict::Ub,
+            TerminationInfo::IllFormed(_) => Verdict::IllFormed,
+            info => Verdict::Other(format!("{info:?}")),
+        }
+    }
+}
+
+/// A program together with the verdict it must get.
+#[derive(Clone, Debug)]
+pub struct ConformanceTest {
+    pub name: &'static str,
+    pub category: Category,
+    pub program: Program,
+    pub expected: Verdict,
+}
+
+/// A conformance test for which the evaluator reached the wrong verdict.
+#[derive(Clone, Debug)]
+pub struct Failure {
+    pub name: &'static str,
+    pub expected: Verdict,
+    pub actual: Verdict,
+}
+
+/// An implementation of MiniRust that the conformance kit can be run against.
+pub trait Evaluator {
+    /// Runs `prog` to completion and reports how it ended.
+    fn evaluate(&mut self, prog: Program) -> Verdict;
+}
+
+impl<F: FnMut(Program) -> Verdict> Evaluator for F {
+    fn evaluate(&mut self, prog: Program) -> Verdict {
+        self(prog)
+    }
+}
+
+/// The reference evaluator: the spec interpreter, using memory model `M`.
+pub struct SpecEvaluator<M: MemoryTooling>(PhantomData<M>);
+
+impl<M: MemoryTooling> Default for SpecEvaluator<M> {
+    fn default() -> Self {
+        SpecEvaluator(PhantomData)
+    }
+}
+
+impl<M: MemoryTooling> Evaluator for SpecEvaluator<M> {
+    fn evaluate(&mut self, prog: Program) -> Verdict {
+        Verdict::from_output(run_program_with_output::<M>(prog))
+    }
+}
+
+/// Runs all conformance tests against `eval`, and returns the ones that did not get the expected verdict.
+pub fn run_conformance(eval: &mut impl Evaluator) -> Vec<Failure> {
+    conformance_tests()
+        .into_iter()
+        .filter_map(|test| {
+            let actual = eval.evaluate(test.program);
+            if actual == test.expected {
+                return None;
+            }
+            Some(Failure { name: test.name, expected: test.expected, actual })
+        })
+        .collect()
+}
+
+/// All conformance tests.
+pub fn conformance_tests() -> Vec<ConformanceTest> {
+    vec![
+        test(
+            "padding_reset_by_typed_copy",
+            Category::Padding,
+            padding_reset_by_typed_copy(),
+            Verdict::Ub,
+        ),
+        test(
+            "padding_kept_by_union_copy",
+            Category::Padding,
+            padding_kept_by_union_copy(),
+            stop(&["0"]),
+        ),
+        test(
+            "ptr_copy_keeps_provenance",
+            Category::Provenance,
+            ptr_copy_keeps_provenance(),
+            stop(&["42"]),
+        ),
+        test(
+            "ptr_int_ptr_transmute_loses_provenance",
+            Category::Provenance,
+            ptr_int_ptr_transmute(),
+            Verdict::Ub,
+        ),
+        test(
+            "exposed_provenance_roundtrip",
+            Category::Provenance,
+            exposed_provenance_roundtrip(),
+            stop(&["42"]),
+        ),
+        test("out_of_bounds_access", Category::Provenance, out_of_bounds_access(), Verdict::Ub),
+        test("uninit_int_load", Category::Uninit, uninit_int_load(), Verdict::Ub),
+        test("partially_init_int_load", Category::Uninit, partially_init_int_load(), Verdict::Ub),
+        test("uninit_union_copy", Category::Uninit, uninit_union_copy(), stop(&[])),
+        test("union_type_punning", Category::Unions, union_type_punning(), stop(&["4", "1"])),
+        test(
+            "union_copy_keeps_provenance",
+            Category::Unions,
+            union_copy_keeps_provenance(),
+            stop(&["42"]),
+        ),
+        test(
+            "union_int_field_loses_provenance",
+            Category::Unions,
+            union_int_field_loses_provenance(),
+            Verdict::Ub,
+        ),
+        test(
+            "enum_discriminant_roundtrip",
+            Category::Enums,
+            enum_discriminant_roundtrip(),
+            stop(&["0", "7", "1"]),
+        ),
+        test("enum_invalid_tag", Category::Enums, enum_invalid_tag(), Verdict::Ub),
+        test(
+            "enum_unknown_variant_ill_formed",
+            Category::Enums,
+            enum_unknown_variant(),
+            Verdict::IllFormed,
+        ),
+    ]
+}
+
+fn test(
+    name: &'static str,
+    category: Category,
+    program: Program,
+    expected: Verdict,
+) -> ConformanceTest {
+    ConformanceTest { name, category, program, expected }
+}
+
+fn stop(stdout: &[&str]) -> Verdict {
+    Verdict::Stop(stdout.iter().map(|s| s.to_string()).collect())
+}
+
+/// Finishes a program consisting of just the function `f`.
+fn single_fn_program(mut p: ProgramBuilder, mut f: FunctionBuilder) -> Program {
+    f.exit();
+    let f = p.finish_function(f);
+    p.finish_program(f)
+}
+
+/// `type Pair = (u8, u16);`, with a padding byte at offset 1.
+fn pair_ty() -> Type {
+    tuple_ty(&[(offset(0), <u8>::get_type()), (offset(2), <u16>::get_type())], size(4), align(2))
+}
+
+/// `union { f0: Pair, f1: u32 }`
+fn pair_or_u32_ty() -> Type {
+    union_ty(&[(offset(0), pair_ty()), (offset(0), <u32>::get_type())], size(4), align(4))
+}
+
+/// A copy at tuple type does not preserve the padding bytes.
+fn padding_reset_by_typed_copy() -> Program {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let u = f.declare_local_with_ty(pair_or_u32_ty());
+    let pair = f.declare_local_with_ty(pair_ty());
+    let padding = f.declare_local::<u8>();
+    f.storage_live(u);
+    f.storage_live(pair);
+    f.storage_live(padding);
+    f.assign(field(u, 1), const_int(0u32));
+    f.assign(pair, load(field(u, 0)));
+    let padding_ptr =
+        ptr_offset(addr_of(pair, <*const u8>::get_type()), const_int(1usize), InBounds::Yes);
+    f.assign(padding, load(deref(padding_ptr, <u8>::get_type())));
+    single_fn_program(p, f)
+}
+
+/// A copy at union type preserves the bytes that are padding in some of its fields.
+fn padding_kept_by_union_copy() -> Program {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let u = f.declare_local_with_ty(pair_or_u32_ty());
+    let v = f.declare_local_with_ty(pair_or_u32_ty());
+    f.storage_live(u);
+    f.storage_live(v);
+    f.assign(field(u, 1), const_int(0u32));
+    f.assign(v, load(u));
+    f.print(load(field(v, 1)));
+    single_fn_program(p, f)
+}
+
+/// Declares `x: u32 = 42` and `ptr: *const u32 = &raw const x`.
+fn declare_pointee(f: &mut FunctionBuilder) -> PlaceExpr {
+    let x = f.declare_local::<u32>();
+    let ptr = f.declare_local::<*const u32>();
+    f.storage_live(x);
+    f.storage_live(ptr);
+    f.assign(x, const_int(42u32));
+    f.assign(ptr, addr_of(x, <*const u32>::get_type()));
+    ptr
+}
+
+fn deref_u32(ptr: ValueExpr) -> PlaceExpr {
+    deref(ptr, <u32>::get_type())
+}
+
+/// Copying a pointer at pointer type preserves its provenance.
+fn ptr_copy_keeps_provenance() -> Program {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let ptr = declare_pointee(&mut f);
+    let copy = f.declare_local::<*const u32>();
+    f.storage_live(copy);
+    f.assign(copy, load(ptr));
+    f.print(load(deref_u32(load(copy))));
+    single_fn_program(p, f)
+}
+
+/// Transmuting a pointer to an integer and back does not preserve its provenance.
+fn ptr_int_ptr_transmute() -> Program {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let ptr = declare_pointee(&mut f);
+    let addr = f.declare_local::<usize>();
+    f.storage_live(addr);
+    f.assign(addr, transmute(load(ptr), <usize>::get_type()));
+    f.assign(ptr, transmute(load(addr), <*const u32>::get_type()));
+    f.print(load(deref_u32(load(ptr))));
+    single_fn_program(p, f)
+}
+
+/// Exposing a pointer's provenance makes it available to integer-to-pointer casts.
+fn exposed_provenance_roundtrip() -> Program {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let ptr = declare_pointee(&mut f);
+    let addr = f.declare_local::<usize>();
+    f.storage_live(addr);
+    f.expose_provenance(addr, load(ptr));
+    f.with_exposed_provenance(ptr, load(addr));
+    f.print(load(deref_u32(load(ptr))));
+    single_fn_program(p, f)
+}
+
+/// A pointer can only access the allocation its provenance belongs to.
+fn out_of_bounds_access() -> Program {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let ptr = declare_pointee(&mut f);
+    let beyond = ptr_offset(load(ptr), const_int(4usize), InBounds::No);
+    f.print(load(deref_u32(beyond)));
+    single_fn_program(p, f)
+}
+
+/// Loading an integer from uninitialized memory is UB.
+fn uninit_int_load() -> Program {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u32>();
+    let y = f.declare_local::<u32>();
+    f.storage_live(x);
+    f.storage_live(y);
+    f.assign(y, load(x));
+    single_fn_program(p, f)
+}
+
+/// An integer must be initialized in all of its bytes.
+fn partially_init_int_load() -> Program {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let x = f.declare_local::<u16>();
+    let y = f.declare_local::<u16>();
+    f.storage_live(x);
+    f.storage_live(y);
+    f.assign(deref(addr_of(x, <*mut u8>::get_type()), <u8>::get_type()), const_int(1u8));
+    f.assign(y, load(x));
+    single_fn_program(p, f)
+}
+
+/// Unions may hold uninitialized memory.
+fn uninit_union_copy() -> Program {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let u = f.declare_local_with_ty(pair_or_u32_ty());
+    let v = f.declare_local_with_ty(pair_or_u32_ty());
+    f.storage_live(u);
+    f.storage_live(v);
+    f.assign(v, load(u));
+    single_fn_program(p, f)
+}
+
+/// Reading a different union field than the one that was written reinterprets the bytes.
+fn union_type_punning() -> Program {
+    let bytes_ty = <[u8; 4]>::get_type();
+    let union_ty =
+        union_ty(&[(offset(0), <u32>::get_type()), (offset(0), bytes_ty)], size(4), align(4));
+
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let u = f.declare_local_with_ty(union_ty);
+    f.storage_live(u);
+    f.assign(field(u, 0), const_int(0x01020304u32));
+    // `x86_64` is little-endian.
+    f.print(load(index(field(u, 1), const_int(0usize))));
+    f.print(load(index(field(u, 1), const_int(3usize))));
+    single_fn_program(p, f)
+}
+
+/// `union { f0: *const u32, f1: usize }`
+fn ptr_or_usize_ty() -> Type {
+    union_ty(
+        &[(offset(0), <*const u32>::get_type()), (offset(0), <usize>::get_type())],
+        size(8),
+        align(8),
+    )
+}
+
+/// A copy at union type preserves provenance.
+fn union_copy_keeps_provenance() -> Program {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let ptr = declare_pointee(&mut f);
+    let u = f.declare_local_with_ty(ptr_or_usize_ty());
+    let v = f.declare_local_with_ty(ptr_or_usize_ty());
+    f.storage_live(u);
+    f.storage_live(v);
+    f.assign(field(u, 0), load(ptr));
+    f.assign(v, load(u));
+    f.print(load(deref_u32(load(field(v, 0)))));
+    single_fn_program(p, f)
+}
+
+/// Loading a pointer through an integer union field strips its provenance.
+fn union_int_field_loses_provenance() -> Program {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let ptr = declare_pointee(&mut f);
+    let u = f.declare_local_with_ty(ptr_or_usize_ty());
+    f.storage_live(u);
+    f.assign(field(u, 0), load(ptr));
+    f.assign(field(u, 1), load(field(u, 1)));
+    f.print(load(deref_u32(load(field(u, 0)))));
+    single_fn_program(p, f)
+}
+
+/// The data of variant `A` of `tagged_enum_ty`.
+fn variant_a_ty() -> Type {
+    tuple_ty(&[(offset(1), <u8>::get_type())], size(2), align(1))
+}
+
+/// The data of variant `B` of `tagged_enum_ty`.
+fn variant_b_ty() -> Type {
+    tuple_ty(&[], size(2), align(1))
+}
+
+/// `enum E { A(u8), B }` with the tag in byte 0 and the data of `A` in byte 1.
+fn tagged_enum_ty() -> Type {
+    let u8_int = IntType { signed: Signedness::Unsigned, size: Size::from_bytes_const(1) };
+    let a = enum_variant(variant_a_ty(), &[(offset(0), (u8_int, 0.into()))]);
+    let b = enum_variant(variant_b_ty(), &[(offset(0), (u8_int, 1.into()))]);
+    let discriminator = discriminator_branch::<u8>(offset(0), discriminator_invalid(), &[
+        ((0, 1), discriminator_known(0)),
+        ((1, 2), discriminator_known(1)),
+    ]);
+    enum_ty::<u8>(&[(0, a), (1, b)], discriminator, size(2), align(1))
+}
+
+/// Writing a variant sets the tag, which determines the discriminant.
+fn enum_discriminant_roundtrip() -> Program {
+    let enum_ty = tagged_enum_ty();
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let e = f.declare_local_with_ty(enum_ty);
+    f.storage_live(e);
+    f.assign(e, variant(0, tuple(&[const_int(7u8)], variant_a_ty()), enum_ty));
+    f.print(get_discriminant(e));
+    f.print(load(field(downcast(e, 0), 0)));
+    f.assign(e, variant(1, tuple(&[], variant_b_ty()), enum_ty));
+    f.print(get_discriminant(e));
+    single_fn_program(p, f)
+}
+
+/// A tag that does not belong to any variant is UB.
+fn enum_invalid_tag() -> Program {
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let e = f.declare_local_with_ty(tagged_enum_ty());
+    f.storage_live(e);
+    f.assign(e, transmute(const_int(0x0002u16), tagged_enum_ty()));
+    single_fn_program(p, f)
+}
+
+/// Constructing a variant that the enum does not have is ill-formed.
+fn enum_unknown_variant() -> Program {
+    let enum_ty = tagged_enum_ty();
+    let mut p = ProgramBuilder::new();
+    let mut f = p.declare_function();
+    let e = f.declare_local_with_ty(enum_ty);
+    f.storage_live(e);
+    f.assign(e, variant(2, unit(), enum_ty));
+    single_fn_program(p, f)
+}
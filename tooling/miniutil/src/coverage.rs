@@ -0,0 +1,305 @@
+//! Coverage of the spec's rules by executed programs.
+//!
+//! A rule is either a step rule, i.e. one of the `eval_statement`, `eval_terminator`, or
+//! `eval_intrinsic` cases, or a UB check, i.e. a `throw_ub!` or `ensure_else_ub` with its message.
+//! If the `MINIRUST_COVERAGE` environment variable is set to a directory, the run functions record
+//! every step rule they execute and every UB they run into in a file in that directory.
+//! `spec_rules` finds all rules in the spec, and `Coverage` says which of them were recorded.
+//!
+//! Steps taken while running the initializers of globals are not recorded, since they happen
+//! inside `Machine::new`.
+
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use crate::run::*;
+use crate::*;
+
+/// The environment variable naming the directory to record coverage in.
+pub const COVERAGE_ENV: &str = "MINIRUST_COVERAGE";
+
+/// A rule of the spec.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Rule {
+    /// A case of a step function, named like its pattern in the spec, e.g. `Statement::Assign`.
+    Step(String),
+    /// A UB check with the given message. The message is a format string; its `{...}` placeholders match anything.
+    Ub(String),
+}
+
+impl std::fmt::Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rule::Step(name) => write!(f, "{name}"),
+            Rule::Ub(msg) => write!(f, "UB: {msg}"),
+        }
+    }
+}
+
+/// A rule together with where it is defined.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpecRule {
+    pub rule: Rule,
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+/// Finds all rules in the Markdown files in `spec_dir`, in the order of the files and lines they are defined in.
+pub fn spec_rules(spec_dir: &Path) -> io::Result<Vec<SpecRule>> {
+    let mut rules = Vec::new();
+    for file in markdown_files(spec_dir)? {
+        let code = rust_code(&fs::read_to_string(&file)?);
+        for (line, rule) in rules_in(&code) {
+            rules.push(SpecRule { rule, file: file.clone(), line });
+        }
+    }
+    Ok(rules)
+}
+
+fn markdown_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+    entries.sort();
+    let mut files = Vec::new();
+    for entry in entries {
+        if entry.is_dir() {
+            files.extend(markdown_files(&entry)?);
+        } else if entry.extension().is_some_and(|ext| ext == "md") {
+            files.push(entry);
+        }
+    }
+    Ok(files)
+}
+
+/// Blanks out everything that is not in a ```` ```rust ```` block, keeping the line numbers intact.
+fn rust_code(markdown: &str) -> String {
+    let mut in_code = false;
+    let mut code = String::new();
+    for line in markdown.lines() {
+        if line.starts_with("```") {
+            in_code = !in_code && line.trim() == "```rust";
+        } else if in_code {
+            code.push_str(line);
+        }
+        code.push('\n');
+    }
+    code
+}
+
+/// The rules in `code`, with the (1-based) line they start on.
+fn rules_in(code: &str) -> Vec<(usize, Rule)> {
+    let line_of = |pos: usize| code[..pos].matches('\n').count() + 1;
+    let mut rules = Vec::new();
+
+    for (function, prefix) in [
+        ("fn eval_statement(", "Statement::"),
+        ("fn eval_terminator(", "Terminator::"),
+        ("fn eval_intrinsic(", "IntrinsicOp::"),
+    ] {
+        for (start, _) in code.match_indices(function) {
+            // The pattern is in the parameter list; the generic declaration has none.
+            let params = &code[start..];
+            let params = &params[..params.find("->").unwrap_or(params.len())];
+            let Some(pos) = params.find(prefix) else { continue };
+            let mut name = format!("{prefix}{}", ident(&params[pos + prefix.len()..]));
+            if let Some(rest) = params[pos + name.len()..].strip_prefix("(IntrinsicLockOp::") {
+                name.push_str(&format!("(IntrinsicLockOp::{})", ident(rest)));
+            }
+            rules.push((line_of(start + pos), Rule::Step(name)));
+        }
+    }
+
+    for call in ["throw_ub!(", "throw_ub_access!(", "ensure_else_ub("] {
+        for (start, _) in code.match_indices(call) {
+            // Skip the definition of `ensure_else_ub`.
+            if code[..start].ends_with("fn ") {
+                continue;
+            }
+            let Some(quote) = code[start..].find('"') else { continue };
+            let msg = string_literal(&code[start + quote + 1..]);
+            // `throw_ub!("{}", msg)` forwards a message checked elsewhere.
+            if template_parts(&msg).iter().all(|part| part.is_empty()) {
+                continue;
+            }
+            rules.push((line_of(start), Rule::Ub(msg)));
+        }
+    }
+
+    rules.sort_by_key(|(line, _)| *line);
+    rules
+}
+
+fn ident(text: &str) -> &str {
+    let len = text.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(text.len());
+    &text[..len]
+}
+
+/// The contents of the string literal starting right after its opening quote.
+fn string_literal(text: &str) -> String {
+    let mut chars = text.chars();
+    let mut msg = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => break,
+            '\\' => msg.extend(chars.next()),
+            c => msg.push(c),
+        }
+    }
+    msg
+}
+
+/// Splits a format string at its `{...}` placeholders.
+fn template_parts(template: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}') else { break };
+        parts.push(&rest[..open]);
+        rest = &rest[open + close + 1..];
+    }
+    parts.push(rest);
+    parts
+}
+
+/// Whether `msg` is an instance of the format string `template`.
+pub fn template_matches(template: &str, msg: &str) -> bool {
+    let parts = template_parts(template);
+    let (first, rest) = parts.split_first().unwrap();
+    let Some(mut msg) = msg.strip_prefix(first) else { return false };
+    let Some((last, middle)) = rest.split_last() else { return msg.is_empty() };
+    for part in middle {
+        let Some(pos) = msg.find(part) else { return false };
+        msg = &msg[pos + part.len()..];
+    }
+    msg.ends_with(last)
+}
+
+/// The step rules that executing `op` exercises, named like `Rule::Step`.
+pub fn operation_rules(op: Operation) -> Vec<String> {
+    let variant = |debug: String| ident(&debug).to_string();
+    match op {
+        Operation::Statement(stmt) => vec![format!("Statement::{}", variant(format!("{stmt:?}")))],
+        Operation::Terminator(terminator) => {
+            let mut rules = vec![format!("Terminator::{}", variant(format!("{terminator:?}")))];
+            if let Terminator::Intrinsic { intrinsic, .. } = terminator {
+                rules.push(match intrinsic {
+                    IntrinsicOp::Lock(op) =>
+                        format!(
+                            "IntrinsicOp::Lock(IntrinsicLockOp::{})",
+                            variant(format!("{op:?}"))
+                        ),
+                    op => format!("IntrinsicOp::{}", variant(format!("{op:?}"))),
+                });
+            }
+            rules
+        }
+    }
+}
+
+/// The rules recorded in a coverage directory.
+#[derive(Clone, Debug, Default)]
+pub struct Coverage {
+    steps: HashSet<String>,
+    ub: HashSet<String>,
+}
+
+impl Coverage {
+    /// Reads everything recorded in `dir`, by all processes that recorded there.
+    pub fn read(dir: &Path) -> io::Result<Coverage> {
+        let mut coverage = Coverage::default();
+        for entry in fs::read_dir(dir)? {
+            for line in fs::read_to_string(entry?.path())?.lines() {
+                match line.strip_prefix("UB: ") {
+                    Some(msg) => coverage.ub.insert(msg.to_string()),
+                    None => coverage.steps.insert(line.to_string()),
+                };
+            }
+        }
+        Ok(coverage)
+    }
+
+    pub fn covers(&self, rule: &Rule) -> bool {
+        match rule {
+            Rule::Step(name) => self.steps.contains(name),
+            Rule::Ub(template) => self.ub.iter().any(|msg| template_matches(template, msg)),
+        }
+    }
+}
+
+struct Recorder {
+    /// The file to record in, and what was already recorded there.
+    file: Mutex<(File, HashSet<String>)>,
+}
+
+impl Recorder {
+    fn record(&self, line: String) {
+        let mut guard = self.file.lock().unwrap();
+        let (file, recorded) = &mut *guard;
+        if recorded.insert(line.clone()) {
+            writeln!(file, "{line}").expect("failed to record coverage");
+        }
+    }
+}
+
+/// The recorder, if coverage is enabled. Each process records in its own file.
+fn recorder() -> Option<&'static Recorder> {
+    static RECORDER: OnceLock<Option<Recorder>> = OnceLock::new();
+    RECORDER
+        .get_or_init(|| {
+            let dir = PathBuf::from(std::env::var_os(COVERAGE_ENV)?);
+            fs::create_dir_all(&dir).expect("failed to create the coverage directory");
+            let path = dir.join(format!("covered-{}.txt", std::process::id()));
+            let file = File::options()
+                .create(true)
+                .append(true)
+                .open(path)
+                .expect("failed to create the coverage file");
+            Some(Recorder { file: Mutex::new((file, HashSet::new())) })
+        })
+        .as_ref()
+}
+
+/// Lets `machine` take a step with `step`, and records the rules this exercises if coverage is enabled.
+/// `prog` must be the program `machine` is running.
+pub fn step<M: Memory>(
+    machine: &mut Machine<M>,
+    prog: Program,
+    step: impl FnOnce(&mut Machine<M>) -> NdResult,
+) -> Result<(), TerminationInfo> {
+    let Some(recorder) = recorder() else {
+        return step(machine).get_internal();
+    };
+
+    // We only know which thread took the step afterwards, so remember where all of them were.
+    let locations: Vec<(ThreadId, Option<Location>)> = machine
+        .enabled_threads()
+        .iter()
+        .map(|thread| {
+            let location = machine
+                .thread_location(thread)
+                .map(|(func, block, stmt)| Location { func, block, stmt });
+            (thread, location)
+        })
+        .collect();
+    let res = step(machine).get_internal();
+
+    // Other errors, like deadlocks, happen before a thread takes the step.
+    if matches!(res, Ok(()) | Err(TerminationInfo::Ub(..))) {
+        let thread = machine.active_thread_id();
+        let op = locations
+            .iter()
+            .find(|(id, _)| *id == thread)
+            .and_then(|(_, location)| location.and_then(|location| location.operation(prog)));
+        for rule in op.map(operation_rules).unwrap_or_default() {
+            recorder.record(rule);
+        }
+    }
+    if let Err(TerminationInfo::Ub(msg, _)) = res {
+        recorder.record(format!("UB: {}", msg.get_internal().replace('\n', " ")));
+    }
+    res
+}
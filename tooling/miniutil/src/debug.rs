@@ -0,0 +1,207 @@
+//! A simple interactive debugger for MiniRust programs.
+//!
+//! The debugger is driven by textual commands (see `HELP`), so it can be used from a REPL
+//! as well as from tests.
+
+use std::io::{BufRead, Write};
+
+use crate::fmt::fmt_operation;
+use crate::interp::Interpreter;
+use crate::run::Location;
+use crate::*;
+
+pub const HELP: &str = "\
+commands:
+  step [n]         execute the next n statements or terminators (default 1)
+  continue         run until a breakpoint is reached or the program terminates
+  break <fn> <bb>  stop when basic block <bb> of function <fn> is entered
+  delete           remove all breakpoints
+  where            show the statement or terminator about to be executed
+  locals           show the live locals of the current stack frame
+  mem <local> [n]  show the first n bytes of a local (default: all of them)
+  help             show this message
+  quit             leave the debugger";
+
+/// A program being debugged.
+pub struct Debugger<M: MemoryTooling> {
+    interp: Interpreter<M>,
+    /// The blocks at whose start execution should stop.
+    breakpoints: Vec<(FnName, BbName)>,
+}
+
+impl<M: MemoryTooling> Debugger<M> {
+    /// Prepares debugging `prog`, which writes its output to the given writers.
+    /// No steps are taken yet. If the program is ill-formed, returns why.
+    pub fn new(
+        prog: Program,
+        stdout: impl GcWrite,
+        stderr: impl GcWrite,
+        warnings: impl GcWrite,
+    ) -> Result<Self, TerminationInfo> {
+        let interp =
+            Interpreter::new_with_writers(prog, MachineConfig::DEFAULT, stdout, stderr, warnings)?;
+        Ok(Debugger { interp, breakpoints: Vec::new() })
+    }
+
+    /// How the program terminated, or `None` if it has not terminated yet.
+    pub fn terminated(&self) -> Option<TerminationInfo> {
+        self.interp.terminated()
+    }
+
+    /// The statement or terminator the active thread is about to execute.
+    /// After the program terminated, this is the one that caused the termination.
+    pub fn location(&self) -> Option<Location> {
+        self.interp.location()
+    }
+
+    /// Executes a single command and returns the response to show to the user.
+    /// Returns `None` if the user wants to leave the debugger.
+    pub fn command(&mut self, line: &str) -> Option<String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let response = match words.as_slice() {
+            [] => String::new(),
+            ["quit" | "q"] => return None,
+            ["help" | "h"] => HELP.to_string(),
+            ["step" | "s"] => self.step_n(1),
+            ["step" | "s", n] =>
+                match n.parse() {
+                    Ok(n) => self.step_n(n),
+                    Err(_) => format!("invalid number of steps: {n}"),
+                },
+            ["continue" | "c"] => self.continue_(),
+            ["break" | "b", func, block] =>
+                match (parse_name(func, "f"), parse_name(block, "bb")) {
+                    (Some(func), Some(block)) => {
+                        let (func, block) = (FnName(func), BbName(block));
+                        self.breakpoints.push((func, block));
+                        format!(
+                            "breakpoint at f{} bb{}",
+                            func.0.get_internal(),
+                            block.0.get_internal()
+                        )
+                    }
+                    _ => format!("invalid breakpoint: {func} {block}"),
+                },
+            ["delete" | "d"] => {
+                self.breakpoints.clear();
+                "all breakpoints removed".to_string()
+            }
+            ["where" | "w"] => self.where_(),
+            ["locals" | "l"] => self.locals(),
+            ["mem" | "m", local] => self.mem(local, None),
+            ["mem" | "m", local, n] =>
+                match n.parse() {
+                    Ok(n) => self.mem(local, Some(n)),
+                    Err(_) => format!("invalid number of bytes: {n}"),
+                },
+            _ => format!("unknown command: {line}\n{HELP}"),
+        };
+        Some(response)
+    }
+
+    /// Reads commands from `input` until `quit` or the end of the input, writing the responses to `output`.
+    pub fn repl(&mut self, input: impl BufRead, mut output: impl Write) -> std::io::Result<()> {
+        write!(output, "(minidbg) ")?;
+        output.flush()?;
+        for line in input.lines() {
+            let Some(response) = self.command(&line?) else {
+                break;
+            };
+            if !response.is_empty() {
+                writeln!(output, "{response}")?;
+            }
+            write!(output, "(minidbg) ")?;
+            output.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Takes one step. Returns `false` if the program has terminated.
+    fn step(&mut self) -> bool {
+        self.interp.step().is_none()
+    }
+
+    fn step_n(&mut self, n: u64) -> String {
+        for _ in 0..n {
+            if !self.step() {
+                break;
+            }
+        }
+        self.where_()
+    }
+
+    fn at_breakpoint(&self) -> bool {
+        self.location().is_some_and(|loc| {
+            loc.stmt == Int::ZERO && self.breakpoints.contains(&(loc.func, loc.block))
+        })
+    }
+
+    fn continue_(&mut self) -> String {
+        // Always make progress, even if we are already at a breakpoint.
+        while self.step() && !self.at_breakpoint() {}
+        self.where_()
+    }
+
+    fn where_(&self) -> String {
+        let operation =
+            self.location().and_then(|loc| Some((loc, loc.operation(self.interp.prog())?)));
+        let here = match operation {
+            Some((loc, op)) => format!("at {loc}: {}", fmt_operation(op)),
+            None => "no code is being executed".to_string(),
+        };
+        match self.interp.terminated() {
+            Some(info) => format!("program terminated: {info:?}\n{here}"),
+            None => here,
+        }
+    }
+
+    fn locals(&self) -> String {
+        let mut locals: Vec<_> = self.interp.machine().inspect_locals().iter().collect();
+        locals.sort_by_key(|local| local.name.0.get_internal());
+        let mut out = Vec::new();
+        for local in locals {
+            let name = local.name.0.get_internal();
+            match local.value {
+                Some(value) => out.push(format!("_{name} = {value:?}")),
+                None => out.push(format!("_{name} = <invalid>")),
+            }
+        }
+        if out.is_empty() { "no live locals".to_string() } else { out.join("\n") }
+    }
+
+    fn mem(&self, local: &str, len: Option<u64>) -> String {
+        let Some(name) = parse_name(local, "_") else {
+            return format!("invalid local: {local}");
+        };
+        let Some(local) =
+            self.interp.machine().inspect_locals().iter().find(|l| l.name == LocalName(name))
+        else {
+            return format!("no live local {local}");
+        };
+        let len = match len {
+            Some(len) => Size::from_bytes(len).unwrap(),
+            None => local.size,
+        };
+        match self.interp.machine().peek(local.ptr.thin_pointer, len) {
+            Ok(bytes) => {
+                let bytes: Vec<String> = bytes
+                    .iter()
+                    .map(|byte| {
+                        match byte.data() {
+                            Some(data) => format!("{data:02x}"),
+                            None => "__".to_string(),
+                        }
+                    })
+                    .collect();
+                bytes.join(" ")
+            }
+            Err(info) => format!("cannot read memory: {info:?}"),
+        }
+    }
+}
+
+/// Parses a name like `f3`, `bb3`, or `_3`, where the prefix is optional.
+fn parse_name(s: &str, prefix: &str) -> Option<Name> {
+    let n: u32 = s.strip_prefix(prefix).unwrap_or(s).parse().ok()?;
+    Some(Name::from_internal(n))
+}
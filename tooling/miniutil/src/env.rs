@@ -0,0 +1,281 @@
+//! Environments that provide the extern functions a program calls.
+
+use std::collections::HashMap;
+
+use crate::interp::Interpreter;
+use crate::mock_write::MockWrite;
+use crate::repr::gen_value;
+use crate::rng::Rng;
+use crate::run::Output;
+use crate::*;
+
+/// The environment a program runs in: it executes the program's calls to extern functions.
+pub trait Environment<M: Memory> {
+    /// Executes `call` on `machine`, returning either the return value or how the program terminated.
+    /// Returns `None` if this environment does not provide the called function.
+    fn call(
+        &mut self,
+        machine: &mut Machine<M>,
+        call: ExternCall<M>,
+    ) -> Option<Result<Value<M>, TerminationInfo>>;
+}
+
+/// The implementation of an extern function.
+pub type ExternFn<M> =
+    Box<dyn FnMut(&mut Machine<M>, ExternCall<M>) -> Result<Value<M>, TerminationInfo>>;
+
+/// An environment consisting of mock implementations that are registered by name.
+/// Implementations are called between machine steps, so they must not keep any values
+/// of the machine (such as pointers) around; the garbage collector does not know about them.
+///
+/// Calls of functions that are not registered are rejected, unless `havoc_unregistered` was called.
+pub struct MockEnv<M: Memory> {
+    fns: HashMap<String, ExternFn<M>>,
+    /// If set, the choices of the functions that are not registered.
+    havoc: Option<Rng>,
+    /// The memory accessed by each call of a function that is not registered, in the order of the calls.
+    havoc_accesses: Vec<HavocAccesses>,
+}
+
+/// The memory one call of unknown code accessed, see `MockEnv::havoc_unregistered`.
+/// Ranges are given as their start address and size in bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HavocAccesses {
+    pub name: String,
+    pub reads: Vec<(usize, usize)>,
+    pub writes: Vec<(usize, usize)>,
+}
+
+impl<M: Memory> Default for MockEnv<M> {
+    fn default() -> Self {
+        MockEnv { fns: HashMap::new(), havoc: None, havoc_accesses: Vec::new() }
+    }
+}
+
+impl<M: Memory> MockEnv<M> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `f` as the implementation of the extern function `name`.
+    pub fn register(
+        &mut self,
+        name: &str,
+        f: impl FnMut(&mut Machine<M>, ExternCall<M>) -> Result<Value<M>, TerminationInfo> + 'static,
+    ) {
+        self.fns.insert(name.to_string(), Box::new(f));
+    }
+
+    /// Treats every function that is not registered as unknown code that may do anything it has
+    /// access to, and returns an arbitrary value of the return type.
+    /// This lets tests check which assumptions a program may still make across an opaque call.
+    /// The choices are pseudo-random, reproducibly for the given seed.
+    ///
+    /// Unknown code has access to the memory behind its pointer arguments, to all allocations the
+    /// program exposed, and, transitively, to everything the pointers stored in that memory point to.
+    /// It reads all of that memory, and overwrites it with arbitrary initialized bytes, except for
+    /// memory it can only reach through shared references and memory the memory model does not let
+    /// it write to. Raw pointers, exposed provenance, and pointers found in memory give access to
+    /// their entire allocation. Functions returning a reference or `Box` are still rejected, since
+    /// no arbitrary value of those types is guaranteed to be valid.
+    pub fn havoc_unregistered(&mut self, seed: u64) {
+        self.havoc = Some(Rng::new(seed));
+    }
+
+    /// The memory accessed by each call of a function that is not registered so far.
+    pub fn havoc_accesses(&self) -> &[HavocAccesses] {
+        &self.havoc_accesses
+    }
+
+    /// Registers `write(fd, buf, count)`, writing to the stdout and stderr of the machine.
+    /// Like the real function, it returns the number of bytes written, or -1 for other file descriptors.
+    pub fn register_write(&mut self) {
+        self.register("write", |machine, call| {
+            let args: Vec<Value<M>> = call.arguments.iter().map(|(arg, _)| arg).collect();
+            let [Value::Int(fd), Value::Ptr(buf), Value::Int(count)] = args[..] else {
+                return Err(TerminationInfo::Unsupported(
+                    minirust_rs::prelude::String::from_internal(
+                        "`write` called with invalid arguments".to_string(),
+                    ),
+                ));
+            };
+            let len = Size::from_bytes(count).unwrap();
+            let mut bytes = Vec::new();
+            for byte in machine.extern_load(buf.thin_pointer, len).get_internal()? {
+                let AbstractByte::Init(byte, _) = byte else {
+                    return Err(TerminationInfo::Ub(
+                        minirust_rs::prelude::String::from_internal(
+                            "`write` of uninitialized memory".to_string(),
+                        ),
+                        None,
+                    ));
+                };
+                bytes.push(byte);
+            }
+            // Malformed UTF-8 becomes U+FFFD, the replacement character.
+            let text = String::from_utf8_lossy(&bytes).into_owned();
+            if !machine.extern_write(fd, minirust_rs::prelude::String::from_internal(text)) {
+                return Ok(Value::Int(Int::from(-1)));
+            }
+            Ok(Value::Int(count))
+        });
+    }
+}
+
+/// A range of memory unknown code can access, and whether it may write there.
+type HavocRange<M> = (ThinPointer<<M as Memory>::Provenance>, Size, bool);
+
+/// Executes `call` as unknown code, see `MockEnv::havoc_unregistered`, and records its accesses in `log`.
+/// Returns `None` if there is no arbitrary value of the return type.
+fn havoc<M: MemoryTooling>(
+    rng: &mut Rng,
+    log: &mut Vec<HavocAccesses>,
+    machine: &mut Machine<M>,
+    call: ExternCall<M>,
+) -> Option<Result<Value<M>, TerminationInfo>> {
+    if let Type::Ptr(ptr_ty) = call.ret_ty {
+        if ptr_ty.safe_pointee().is_some() {
+            return None;
+        }
+    }
+
+    // Pointers stored in memory give access to their entire allocation, and unknown code may write
+    // there only if it may write to the memory the pointer was found in.
+    let whole_allocation = |machine: &Machine<M>,
+                            provenance: M::Provenance,
+                            writable: bool|
+     -> Option<HavocRange<M>> {
+        let (start, size) = machine.allocation_of(provenance)?;
+        Some((start, size, writable))
+    };
+    let mut todo: Vec<HavocRange<M>> = Vec::new();
+    for (arg, ty) in call.arguments {
+        let (Value::Ptr(ptr), Type::Ptr(ptr_ty)) = (arg, ty) else { continue };
+        let writable = match ptr_ty {
+            PtrType::Ref { mutbl, .. } => mutbl == Mutability::Mutable,
+            PtrType::Box { .. } => true,
+            PtrType::Raw { .. } => {
+                todo.extend(
+                    ptr.thin_pointer
+                        .provenance
+                        .and_then(|prov| whole_allocation(machine, prov, true)),
+                );
+                continue;
+            }
+            PtrType::FnPtr | PtrType::VTablePtr(_) => continue,
+        };
+        let pointee = ptr_ty.safe_pointee().unwrap();
+        let LayoutStrategy::Sized(size, _) = pointee.layout else { continue };
+        todo.push((ptr.thin_pointer, size, writable));
+    }
+    for provenance in machine.exposed_provenances().iter() {
+        todo.extend(whole_allocation(machine, provenance, true));
+    }
+
+    // Read everything that is reachable, following the pointers stored in memory.
+    let mut accesses = HavocAccesses {
+        name: call.name.get_internal().to_string(),
+        reads: Vec::new(),
+        writes: Vec::new(),
+    };
+    // For each range visited so far, whether it may be written to.
+    // A range first reached read-only is visited again if it turns out to be writable.
+    let mut visited: HashMap<(ThinPointer<M::Provenance>, Size), bool> = HashMap::new();
+    // The visited ranges in the order they were first reached, to keep the writes reproducible.
+    let mut order = Vec::new();
+    while let Some((ptr, size, writable)) = todo.pop() {
+        if size.is_zero() {
+            continue;
+        }
+        let first_visit = match visited.get(&(ptr, size)) {
+            None => true,
+            Some(&old_writable) if old_writable || !writable => continue,
+            Some(_) => false,
+        };
+        visited.insert((ptr, size), writable);
+        // Unknown code only does what the memory model allows, so it skips memory it cannot read.
+        let Ok(bytes) = machine.peek(ptr, size) else { continue };
+        if first_visit {
+            if let Err(info) = machine.extern_load(ptr, size).get_internal() {
+                return Some(Err(info));
+            }
+            accesses.reads.push(range_of(ptr.addr, size));
+            order.push((ptr, size));
+        }
+        for byte in bytes {
+            todo.extend(
+                byte.provenance().and_then(|prov| whole_allocation(machine, prov, writable)),
+            );
+        }
+    }
+
+    // Then overwrite everything it may write to.
+    for (ptr, size) in order {
+        if !visited[&(ptr, size)] {
+            continue;
+        }
+        let bytes: List<_> = (Int::ZERO..size.bytes())
+            .map(|_| AbstractByte::Init(rng.next_u64() as u8, None))
+            .collect();
+        // Try the store on a copy first, to skip memory the memory model does not let us write to.
+        let [stdout, stderr, warnings] = [(); 3].map(|()| DynWrite::new(MockWrite::new()));
+        let mut probe = machine.fork(stdout, stderr, warnings);
+        if probe.extern_store(ptr, bytes).get_internal().is_err() {
+            continue;
+        }
+        if let Err(info) = machine.extern_store(ptr, bytes).get_internal() {
+            return Some(Err(info));
+        }
+        accesses.writes.push(range_of(ptr.addr, size));
+    }
+    log.push(accesses);
+
+    Some(Ok(gen_value::<M>(rng, call.ret_ty)))
+}
+
+/// The start address and size of the range of `size` bytes at `addr`.
+fn range_of(addr: Address, size: Size) -> (usize, usize) {
+    (addr.try_to_usize().unwrap(), size.bytes().try_to_usize().unwrap())
+}
+
+impl<M: MemoryTooling> Environment<M> for MockEnv<M> {
+    fn call(
+        &mut self,
+        machine: &mut Machine<M>,
+        call: ExternCall<M>,
+    ) -> Option<Result<Value<M>, TerminationInfo>> {
+        match self.fns.get_mut(&call.name.get_internal().to_string()) {
+            Some(f) => Some(f(machine, call)),
+            None => havoc(self.havoc.as_mut()?, &mut self.havoc_accesses, machine, call),
+        }
+    }
+}
+
+/// Run the program to completion in the environment `env`, capturing stdout, stderr, and warnings.
+pub fn run_program_with_env<M: MemoryTooling>(
+    prog: Program,
+    env: &mut impl Environment<M>,
+) -> Output {
+    let mut interp = match Interpreter::<M>::new(prog) {
+        Ok(interp) => interp,
+        Err(info) => return Output::without_output(info),
+    };
+    while interp.step().is_none() {
+        handle_extern_call(&mut interp, env);
+    }
+    interp.output().unwrap()
+}
+
+/// If the last step called an extern function, lets `env` execute it now.
+pub(crate) fn handle_extern_call<M: MemoryTooling>(
+    interp: &mut Interpreter<M>,
+    env: &mut impl Environment<M>,
+) {
+    interp.intervene(|machine| {
+        let Some(call) = machine.pending_extern_call() else { return Ok(()) };
+        match env.call(machine, call) {
+            None => machine.reject_extern_call(),
+            Some(ret_val) => machine.return_from_extern_call(ret_val?).get_internal(),
+        }
+    });
+}
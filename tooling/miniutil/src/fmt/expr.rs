@@ -53,6 +53,15 @@ pub(super) fn fmt_place_expr(p: PlaceExpr, comptypes: &mut Vec<CompType>) -> Fmt
             // This can be considered atomic due to the same reasoning as for PlaceExpr::Field, see above.
             FmtExpr::Atomic(format!("{root}[{index}]"))
         }
+        PlaceExpr::ConstantIndex { root, offset, from_end } => {
+            let root = fmt_place_expr(root.extract(), comptypes).to_atomic_string();
+            let sign = if from_end { "-" } else { "" };
+            FmtExpr::Atomic(format!("{root}[{sign}{offset}]"))
+        }
+        PlaceExpr::Subslice { root, from, to } => {
+            let root = fmt_place_expr(root.extract(), comptypes).to_string();
+            FmtExpr::Atomic(format!("subslice({root}, {from}, {to})"))
+        }
         PlaceExpr::Downcast { root, discriminant } => {
             let root = fmt_place_expr(root.extract(), comptypes).to_atomic_string();
             // This is not atomic as `local(1) as variant 3.0` illustrates. (Field 0 of downcast)
@@ -72,10 +81,18 @@ pub(super) fn fmt_global_name(g: GlobalName) -> String {
     format!("global({id})")
 }
 
-pub(super) fn fmt_constant(c: Constant) -> FmtExpr {
+pub(super) fn fmt_constant(c: Constant, ty: Type) -> FmtExpr {
     match c {
+        Constant::Int(code_point) if ty == Type::Char =>
+            FmtExpr::Atomic(format!("char::from_u32({code_point})")),
         Constant::Int(int) => FmtExpr::Atomic(int.to_string()),
         Constant::Bool(b) => FmtExpr::Atomic(b.to_string()),
+        Constant::Float(bits) => {
+            let Type::Float(float_ty) = ty else { panic!("float constant at non-float type") };
+            let float_ty = fmt_float_type(float_ty);
+            // Print the exact bit pattern, so that NaN payloads and signed zeros are preserved.
+            FmtExpr::Atomic(format!("{float_ty}::from_bits({bits})"))
+        }
         Constant::GlobalPointer(relocation) => fmt_relocation(relocation),
         Constant::FnPointer(fn_name) => FmtExpr::Atomic(fmt_fn_name(fn_name)),
         Constant::VTablePointer(vt_name) => FmtExpr::Atomic(fmt_vtable_name(vt_name)),
@@ -90,8 +107,15 @@ pub(super) fn fmt_constant(c: Constant) -> FmtExpr {
 
 pub(super) fn fmt_value_expr(v: ValueExpr, comptypes: &mut Vec<CompType>) -> FmtExpr {
     match v {
-        ValueExpr::Constant(c, _ty) => fmt_constant(c),
+        ValueExpr::Constant(c, ty) => fmt_constant(c, ty),
         ValueExpr::Tuple(l, t) => {
+            // Print homogeneous arrays in repeat syntax, so that huge arrays stay readable.
+            if let (Type::Array { count, .. }, Some(first)) = (t, l.iter().next()) {
+                if count > Int::ONE && l.iter().all(|x| x == first) {
+                    let elem = fmt_value_expr(first, comptypes).to_string();
+                    return FmtExpr::Atomic(format!("[{elem}; {count}]"));
+                }
+            }
             let (lparen, rparen) = match t {
                 Type::Array { .. } => ('[', ']'),
                 Type::Tuple { .. } => ('(', ')'),
@@ -145,6 +169,7 @@ pub(super) fn fmt_value_expr(v: ValueExpr, comptypes: &mut Vec<CompType>) -> Fmt
                 UnOp::Int(IntUnOp::BitNot) => FmtExpr::NonAtomic(format!("!({operand}")),
                 UnOp::Int(IntUnOp::CountOnes) =>
                     FmtExpr::NonAtomic(format!("count_ones({operand}")),
+                UnOp::Float(FloatUnOp::Neg) => FmtExpr::NonAtomic(format!("-({operand})")),
                 UnOp::Cast(CastOp::IntToInt(int_ty)) => {
                     let int_ty = fmt_int_type(int_ty);
                     FmtExpr::Atomic(format!("int2int<{int_ty}>({operand})"))
@@ -190,12 +215,28 @@ pub(super) fn fmt_value_expr(v: ValueExpr, comptypes: &mut Vec<CompType>) -> Fmt
                 SubUnchecked => return FmtExpr::Atomic(format!("SubUnchecked({l}, {r})")),
                 MulUnchecked => return FmtExpr::Atomic(format!("MulUnchecked({l}, {r})")),
                 DivExact => return FmtExpr::Atomic(format!("DivExact({l}, {r})")),
+                AddSaturating => return FmtExpr::Atomic(format!("AddSaturating({l}, {r})")),
+                SubSaturating => return FmtExpr::Atomic(format!("SubSaturating({l}, {r})")),
                 ShlUnchecked => return FmtExpr::Atomic(format!("ShlUnchecked({l}, {r})")),
                 ShrUnchecked => return FmtExpr::Atomic(format!("ShrUnchecked({l}, {r})")),
             };
 
             FmtExpr::NonAtomic(format!("{l} {int_op} {r}"))
         }
+        ValueExpr::BinOp { operator: BinOp::Float(float_op), left, right } => {
+            let l = fmt_value_expr(left.extract(), comptypes).to_atomic_string();
+            let r = fmt_value_expr(right.extract(), comptypes).to_atomic_string();
+
+            let float_op = match float_op {
+                FloatBinOp::Add => "+",
+                FloatBinOp::Sub => "-",
+                FloatBinOp::Mul => "*",
+                FloatBinOp::Div => "/",
+                FloatBinOp::Rem => "%",
+            };
+
+            FmtExpr::NonAtomic(format!("{l} {float_op} {r}"))
+        }
         ValueExpr::BinOp { operator: BinOp::IntWithOverflow(op), left, right } => {
             let l = fmt_value_expr(left.extract(), comptypes).to_atomic_string();
             let r = fmt_value_expr(right.extract(), comptypes).to_atomic_string();
@@ -242,6 +283,11 @@ pub(super) fn fmt_value_expr(v: ValueExpr, comptypes: &mut Vec<CompType>) -> Fmt
             let r = fmt_value_expr(right.extract(), comptypes).to_string();
             FmtExpr::Atomic(format!("{offset_name}({l}, {r})"))
         }
+        ValueExpr::BinOp { operator: BinOp::PtrWithAddr, left, right } => {
+            let l = fmt_value_expr(left.extract(), comptypes).to_string();
+            let r = fmt_value_expr(right.extract(), comptypes).to_string();
+            FmtExpr::Atomic(format!("with_addr({l}, {r})"))
+        }
         ValueExpr::BinOp { operator: BinOp::ConstructWidePointer(ptr_ty), left, right } => {
             let l = fmt_value_expr(left.extract(), comptypes).to_string();
             let r = fmt_value_expr(right.extract(), comptypes).to_string();
@@ -26,7 +26,11 @@ fn fmt_function(
     let fn_name = fmt_fn_name(fn_name).to_string();
 
     // Format function arguments
-    let args: Vec<String> = f.args.iter().map(|name| fmt_local_name(name).to_string()).collect();
+    let mut args: Vec<String> =
+        f.args.iter().map(|name| fmt_local_name(name).to_string()).collect();
+    if f.c_variadic {
+        args.push("...".to_string());
+    }
     let args = args.join(", ");
 
     // Format return local
@@ -82,7 +86,7 @@ fn fmt_bb(bb_name: BbName, bb: BasicBlock, start: bool, comptypes: &mut Vec<Comp
     out
 }
 
-fn fmt_statement(st: Statement, comptypes: &mut Vec<CompType>) -> String {
+pub(super) fn fmt_statement(st: Statement, comptypes: &mut Vec<CompType>) -> String {
     match st {
         Statement::Assign { destination, source } => {
             let left = fmt_place_expr(destination, comptypes).to_string();
@@ -97,18 +101,29 @@ fn fmt_statement(st: Statement, comptypes: &mut Vec<CompType>) -> String {
             let left = fmt_place_expr(destination, comptypes).to_string();
             format!("    discriminant({left}) = {value};")
         }
-        Statement::Validate { place, fn_entry } => {
+        Statement::Validate { place, fn_entry, two_phase, behind_reference } => {
             let place = fmt_place_expr(place, comptypes).to_string();
-            format!("    validate({place}, {fn_entry});")
+            if behind_reference {
+                format!("    validate_pointee({place});")
+            } else if two_phase {
+                format!("    validate_two_phase({place});")
+            } else {
+                format!("    validate({place}, {fn_entry});")
+            }
         }
         Statement::Deinit { place } => {
             let place = fmt_place_expr(place, comptypes).to_string();
             format!("    deinit({place});")
         }
-        Statement::StorageLive(local) => {
+        Statement::StorageLive { local, meta: None } => {
             let local = fmt_local_name(local).to_string();
             format!("    storage_live({local});")
         }
+        Statement::StorageLive { local, meta: Some(meta) } => {
+            let local = fmt_local_name(local).to_string();
+            let meta = fmt_value_expr(meta, comptypes).to_string();
+            format!("    storage_live({local}, meta={meta});")
+        }
         Statement::StorageDead(local) => {
             let local = fmt_local_name(local).to_string();
             format!("    storage_dead({local});")
@@ -123,30 +138,62 @@ fn fmt_call(
     args: String,
     ret: PlaceExpr,
     next_block: Option<BbName>,
+    unwind_block: Option<BbName>,
     comptypes: &mut Vec<CompType>,
 ) -> String {
     // Format return place
     let r = fmt_place_expr(ret, comptypes).to_string();
 
-    // Format next block
-    let next = match next_block {
-        Some(next_block) => {
+    // Format next and unwind block
+    let next = match (next_block, unwind_block) {
+        (Some(next_block), None) => {
             let next_str = fmt_bb_name(next_block);
             format!(" -> {next_str}")
         }
-        None => String::new(),
+        (Some(next_block), Some(unwind_block)) => {
+            let next_str = fmt_bb_name(next_block);
+            let unwind_str = fmt_bb_name(unwind_block);
+            format!(" -> [return: {next_str}, unwind: {unwind_str}]")
+        }
+        (None, Some(unwind_block)) => {
+            let unwind_str = fmt_bb_name(unwind_block);
+            format!(" -> [unwind: {unwind_str}]")
+        }
+        (None, None) => String::new(),
     };
 
     // Format calling convention
-    let conv = match conv {
-        CallingConvention::Rust => format!(""),
-        c => format!("extern \"{c:?}\""),
-    };
+    let conv = fmt_calling_convention(conv);
 
     format!("    {r} = {conv}{callee}({args}){next};")
 }
 
-fn fmt_terminator(t: Terminator, comptypes: &mut Vec<CompType>) -> String {
+fn fmt_calling_convention(conv: CallingConvention) -> &'static str {
+    match conv {
+        CallingConvention::Rust => "",
+        CallingConvention::C => "extern \"C\"",
+        CallingConvention::CUnwind => "extern \"C-unwind\"",
+    }
+}
+
+fn fmt_arguments(arguments: List<ArgumentExpr>, comptypes: &mut Vec<CompType>) -> String {
+    let args: Vec<_> = arguments
+        .iter()
+        .map(|arg| {
+            match arg {
+                ArgumentExpr::ByValue(value) => {
+                    format!("by-value({})", fmt_value_expr(value, comptypes).to_string())
+                }
+                ArgumentExpr::InPlace(place) => {
+                    format!("in-place({})", fmt_place_expr(place, comptypes).to_string())
+                }
+            }
+        })
+        .collect();
+    args.join(", ")
+}
+
+pub(super) fn fmt_terminator(t: Terminator, comptypes: &mut Vec<CompType>) -> String {
     match t {
         Terminator::Goto(bb) => {
             let bb = fmt_bb_name(bb);
@@ -169,61 +216,189 @@ fn fmt_terminator(t: Terminator, comptypes: &mut Vec<CompType>) -> String {
         Terminator::Unreachable => {
             format!("    unreachable;")
         }
-        Terminator::Call { callee, calling_convention: conv, arguments, ret, next_block } => {
+        Terminator::Call {
+            callee,
+            calling_convention: conv,
+            arguments,
+            ret,
+            next_block,
+            unwind_block,
+        } => {
             let callee = fmt_value_expr(callee, comptypes).to_atomic_string();
-            let args: Vec<_> = arguments
-                .iter()
-                .map(|arg| {
-                    match arg {
-                        ArgumentExpr::ByValue(value) => {
-                            format!("by-value({})", fmt_value_expr(value, comptypes).to_string())
-                        }
-                        ArgumentExpr::InPlace(place) => {
-                            format!("in-place({})", fmt_place_expr(place, comptypes).to_string())
-                        }
-                    }
-                })
-                .collect();
-            fmt_call(&callee, conv, args.join(", "), ret, next_block, comptypes)
+            let args = fmt_arguments(arguments, comptypes);
+            fmt_call(&callee, conv, args, ret, next_block, unwind_block, comptypes)
+        }
+        Terminator::InlineAsm { inputs, outputs, reads, clobbers, next_block } => {
+            let mut operands: Vec<String> = Vec::new();
+            for input in inputs {
+                operands.push(format!("in({})", fmt_value_expr(input, comptypes).to_string()));
+            }
+            for output in outputs {
+                operands.push(format!("out({})", fmt_place_expr(output, comptypes).to_string()));
+            }
+            for (ptr, size) in reads {
+                let ptr = fmt_value_expr(ptr, comptypes).to_string();
+                operands.push(format!("reads({ptr}, {} bytes)", size.bytes()));
+            }
+            for (ptr, size) in clobbers {
+                let ptr = fmt_value_expr(ptr, comptypes).to_string();
+                operands.push(format!("clobbers({ptr}, {} bytes)", size.bytes()));
+            }
+            let operands = operands.join(", ");
+            match next_block {
+                Some(next_block) => {
+                    let next_str = fmt_bb_name(next_block);
+                    format!("    asm!({operands}) -> {next_str};")
+                }
+                None => format!("    asm!({operands});"),
+            }
+        }
+        Terminator::ExternCall { name, arguments, ret, next_block } => {
+            let r = fmt_place_expr(ret, comptypes).to_string();
+            let name = name.get_internal();
+            let args: Vec<_> =
+                arguments.iter().map(|arg| fmt_value_expr(arg, comptypes).to_string()).collect();
+            let args = args.join(", ");
+            match next_block {
+                Some(next_block) => {
+                    let next_str = fmt_bb_name(next_block);
+                    format!("    {r} = extern {name}({args}) -> {next_str};")
+                }
+                None => format!("    {r} = extern {name}({args});"),
+            }
+        }
+        Terminator::TailCall { callee, calling_convention: conv, arguments } => {
+            let callee = fmt_value_expr(callee, comptypes).to_atomic_string();
+            let args = fmt_arguments(arguments, comptypes);
+            let conv = fmt_calling_convention(conv);
+            format!("    become {conv}{callee}({args});")
         }
         Terminator::Return => {
             format!("    return;")
         }
+        Terminator::StartUnwind(unwind_block) =>
+            match unwind_block {
+                Some(unwind_block) => {
+                    let unwind_str = fmt_bb_name(unwind_block);
+                    format!("    start_unwind -> {unwind_str};")
+                }
+                None => format!("    start_unwind;"),
+            },
+        Terminator::ResumeUnwind => {
+            format!("    resume_unwind;")
+        }
+        Terminator::StopUnwind(bb) => {
+            let bb = fmt_bb_name(bb);
+            format!("    stop_unwind -> {bb};")
+        }
+        Terminator::UnwindTerminate => {
+            format!("    unwind_terminate;")
+        }
         Terminator::Intrinsic { intrinsic, arguments, ret, next_block } => {
-            let callee = match intrinsic {
-                IntrinsicOp::Assume => "assume",
-                IntrinsicOp::Exit => "exit",
-                IntrinsicOp::Panic => "panic",
-                IntrinsicOp::PrintStdout => "print",
-                IntrinsicOp::PrintStderr => "eprint",
-                IntrinsicOp::Allocate => "allocate",
-                IntrinsicOp::Deallocate => "deallocate",
-                IntrinsicOp::Spawn => "spawn",
-                IntrinsicOp::Join => "join",
-                IntrinsicOp::RawEq => "raw_eq",
-                IntrinsicOp::AtomicStore => "atomic_store",
-                IntrinsicOp::AtomicLoad => "atomic_load",
-                IntrinsicOp::AtomicCompareExchange => "atomic_compare_exchange",
-                IntrinsicOp::AtomicFetchAndOp(binop) => fmt_fetch(binop),
-                IntrinsicOp::Lock(IntrinsicLockOp::Acquire) => "lock_acquire",
-                IntrinsicOp::Lock(IntrinsicLockOp::Create) => "lock_create",
-                IntrinsicOp::Lock(IntrinsicLockOp::Release) => "lock_release",
-                IntrinsicOp::PointerExposeProvenance => "pointer_expose_provenance",
-                IntrinsicOp::PointerWithExposedProvenance => "pointer_with_exposed_provenance",
+            let callee: String = match intrinsic {
+                IntrinsicOp::Assume => "assume".to_string(),
+                IntrinsicOp::Exit => "exit".to_string(),
+                IntrinsicOp::Panic => "panic".to_string(),
+                IntrinsicOp::PrintStdout => "print".to_string(),
+                IntrinsicOp::PrintStderr => "eprint".to_string(),
+                IntrinsicOp::Allocate => "allocate".to_string(),
+                IntrinsicOp::Deallocate => "deallocate".to_string(),
+                IntrinsicOp::Spawn => "spawn".to_string(),
+                IntrinsicOp::Join => "join".to_string(),
+                IntrinsicOp::RawEq => "raw_eq".to_string(),
+                IntrinsicOp::CarryingAdd => "carrying_add".to_string(),
+                IntrinsicOp::BorrowingSub => "borrowing_sub".to_string(),
+                IntrinsicOp::WideningMul => "widening_mul".to_string(),
+                IntrinsicOp::Copy(elem) =>
+                    format!("copy<{}>", fmt_type(elem, comptypes).to_string()),
+                IntrinsicOp::CopyNonOverlapping(elem) =>
+                    format!("copy_nonoverlapping<{}>", fmt_type(elem, comptypes).to_string()),
+                IntrinsicOp::WriteBytes(elem) =>
+                    format!("write_bytes<{}>", fmt_type(elem, comptypes).to_string()),
+                IntrinsicOp::TypedSwapNonOverlapping(ty) =>
+                    format!("typed_swap_nonoverlapping<{}>", fmt_type(ty, comptypes).to_string()),
+                IntrinsicOp::Freeze(ty) =>
+                    format!("freeze<{}>", fmt_type(ty, comptypes).to_string()),
+                IntrinsicOp::AssertValidity(requirement, ty) => {
+                    let requirement = match requirement {
+                        ValidityRequirement::Inhabited => "inhabited",
+                        ValidityRequirement::Zero => "zero_valid",
+                        ValidityRequirement::MemUninitialized => "mem_uninitialized_valid",
+                    };
+                    format!("assert_{requirement}<{}>", fmt_type(ty, comptypes).to_string())
+                }
+                IntrinsicOp::AtomicStore(ordering) =>
+                    format!("atomic_store_{}", fmt_ordering(ordering)),
+                IntrinsicOp::AtomicLoad(ordering) =>
+                    format!("atomic_load_{}", fmt_ordering(ordering)),
+                IntrinsicOp::AtomicCompareExchange { success, failure } =>
+                    format!(
+                        "atomic_compare_exchange_{}_{}",
+                        fmt_ordering(success),
+                        fmt_ordering(failure)
+                    ),
+                IntrinsicOp::AtomicCompareExchangeWeak { success, failure } =>
+                    format!(
+                        "atomic_compare_exchange_weak_{}_{}",
+                        fmt_ordering(success),
+                        fmt_ordering(failure)
+                    ),
+                IntrinsicOp::AtomicFetchAndOp(binop, ordering) =>
+                    format!("{}_{}", fmt_fetch(binop), fmt_ordering(ordering)),
+                IntrinsicOp::Lock(IntrinsicLockOp::Acquire) => "lock_acquire".to_string(),
+                IntrinsicOp::Lock(IntrinsicLockOp::AcquireShared) =>
+                    "lock_acquire_shared".to_string(),
+                IntrinsicOp::Lock(IntrinsicLockOp::Create) => "lock_create".to_string(),
+                IntrinsicOp::Lock(IntrinsicLockOp::Release) => "lock_release".to_string(),
+                IntrinsicOp::Lock(IntrinsicLockOp::CreateCondVar) => "condvar_create".to_string(),
+                IntrinsicOp::Lock(IntrinsicLockOp::Wait) => "condvar_wait".to_string(),
+                IntrinsicOp::Lock(IntrinsicLockOp::NotifyOne) => "condvar_notify_one".to_string(),
+                IntrinsicOp::Lock(IntrinsicLockOp::NotifyAll) => "condvar_notify_all".to_string(),
+                IntrinsicOp::FutexWait => "futex_wait".to_string(),
+                IntrinsicOp::FutexWake => "futex_wake".to_string(),
+                IntrinsicOp::PointerExposeProvenance => "pointer_expose_provenance".to_string(),
+                IntrinsicOp::PointerWithExposedProvenance =>
+                    "pointer_with_exposed_provenance".to_string(),
+                IntrinsicOp::VaArg => "va_arg".to_string(),
             };
             let args: Vec<_> =
                 arguments.iter().map(|arg| fmt_value_expr(arg, comptypes).to_string()).collect();
-            fmt_call(callee, CallingConvention::Rust, args.join(", "), ret, next_block, comptypes)
+            fmt_call(
+                &callee,
+                CallingConvention::Rust,
+                args.join(", "),
+                ret,
+                next_block,
+                None,
+                comptypes,
+            )
         }
     }
 }
 
-fn fmt_fetch(binop: IntBinOp) -> &'static str {
+fn fmt_fetch(op: AtomicRmwOp) -> &'static str {
     use IntBinOp as B;
-    match binop {
-        B::Add => "atomic_fetch_add",
-        B::Sub => "atomic_fetch_sub",
-        _ => "atomic_fetch_ILL_FORMED",
+    match op {
+        AtomicRmwOp::IntBinOp(B::Add) => "atomic_fetch_add",
+        AtomicRmwOp::IntBinOp(B::Sub) => "atomic_fetch_sub",
+        AtomicRmwOp::IntBinOp(B::BitAnd) => "atomic_fetch_and",
+        AtomicRmwOp::IntBinOp(B::BitOr) => "atomic_fetch_or",
+        AtomicRmwOp::IntBinOp(B::BitXor) => "atomic_fetch_xor",
+        AtomicRmwOp::IntBinOp(_) => "atomic_fetch_ILL_FORMED",
+        AtomicRmwOp::Nand => "atomic_fetch_nand",
+        AtomicRmwOp::Min => "atomic_fetch_min",
+        AtomicRmwOp::Max => "atomic_fetch_max",
+        AtomicRmwOp::Swap => "atomic_swap",
+    }
+}
+
+fn fmt_ordering(ordering: AtomicOrdering) -> &'static str {
+    match ordering {
+        AtomicOrdering::Relaxed => "relaxed",
+        AtomicOrdering::Acquire => "acquire",
+        AtomicOrdering::Release => "release",
+        AtomicOrdering::AcqRel => "acqrel",
+        AtomicOrdering::SeqCst => "seqcst",
     }
 }
 
@@ -1,6 +1,9 @@
 use super::*;
 
-pub(super) fn fmt_globals(globals: Map<GlobalName, Global>) -> String {
+pub(super) fn fmt_globals(
+    globals: Map<GlobalName, Global>,
+    global_inits: List<(GlobalName, FnName)>,
+) -> String {
     let mut out = String::new();
 
     let mut globals: Vec<(GlobalName, Global)> = globals.iter().collect();
@@ -9,7 +12,9 @@ pub(super) fn fmt_globals(globals: Map<GlobalName, Global>) -> String {
     globals.sort_by_key(|(GlobalName(name), _global)| *name);
 
     for (gname, global) in globals {
-        out += &fmt_global(gname, global);
+        let init =
+            global_inits.iter().find(|(name, _init)| *name == gname).map(|(_name, init)| init);
+        out += &fmt_global(gname, global, init);
     }
     out
 }
@@ -25,7 +30,7 @@ pub(super) fn fmt_relocation(relocation: Relocation) -> FmtExpr {
     }
 }
 
-fn fmt_global(gname: GlobalName, global: Global) -> String {
+fn fmt_global(gname: GlobalName, global: Global, init: Option<FnName>) -> String {
     let gname_str = fmt_global_name(gname);
     let bytes_str = fmt_bytes(global.bytes);
     let align = global.align.bytes();
@@ -36,9 +41,20 @@ fn fmt_global(gname: GlobalName, global: Global) -> String {
     );
     for (i, rel) in global.relocations {
         let i = i.bytes();
-        let rel_str = fmt_relocation(rel).to_string();
+        let rel_str = match rel {
+            RelocationTarget::Global(relocation) => fmt_relocation(relocation).to_string(),
+            RelocationTarget::Fn(fn_name) => fmt_fn_name(fn_name),
+            RelocationTarget::VTable(vtable_name) => fmt_vtable_name(vtable_name),
+        };
         out += &format!("  at byte {i}: {rel_str},\n");
     }
+    if global.mutbl == Mutability::Immutable {
+        out += "  immutable,\n";
+    }
+    if let Some(init) = init {
+        let init = fmt_fn_name(init);
+        out += &format!("  initialized by {init},\n");
+    }
     out += "}\n\n";
     out
 }
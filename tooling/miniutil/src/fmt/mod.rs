@@ -21,13 +21,33 @@ pub fn dump_program(prog: Program) {
     println!("{s}");
 }
 
+// Format a single statement or terminator.
+// Composite types are numbered as if this was the only thing being formatted.
+pub fn fmt_operation(op: crate::run::Operation) -> String {
+    let mut comptypes: Vec<CompType> = Vec::new();
+    let s = match op {
+        crate::run::Operation::Statement(st) => fmt_statement(st, &mut comptypes),
+        crate::run::Operation::Terminator(t) => fmt_terminator(t, &mut comptypes),
+    };
+    s.trim().to_string()
+}
+
+// Format a list of types.
+// Composite types are numbered as if these types were the only thing being formatted;
+// their definitions are returned alongside.
+pub fn fmt_types(types: &[Type]) -> (Vec<String>, String) {
+    let mut comptypes: Vec<CompType> = Vec::new();
+    let types = types.iter().map(|&t| fmt_type(t, &mut comptypes).to_string()).collect();
+    (types, fmt_comptypes(comptypes))
+}
+
 // Format a program into a string.
 pub fn fmt_program(prog: Program) -> String {
     let mut comptypes: Vec<CompType> = Vec::new();
 
     let functions_string = fmt_functions(prog, &mut comptypes);
     let comptypes_string = fmt_comptypes(comptypes);
-    let globals_string = fmt_globals(prog.globals);
+    let globals_string = fmt_globals(prog.globals, prog.global_inits);
     let traits_string = fmt_traits(prog.traits);
     let vtables_string = fmt_vtables(prog.vtables);
 
@@ -5,6 +5,9 @@ pub(super) fn fmt_type(t: Type, comptypes: &mut Vec<CompType>) -> FmtExpr {
         Type::Int(int_ty) => FmtExpr::Atomic(fmt_int_type(int_ty)),
         Type::Ptr(ptr_ty) => fmt_ptr_type(ptr_ty),
         Type::Bool => FmtExpr::Atomic(format!("bool")),
+        Type::Char => FmtExpr::Atomic(format!("char")),
+        Type::Never => FmtExpr::Atomic(format!("!")),
+        Type::Float(float_ty) => FmtExpr::Atomic(fmt_float_type(float_ty)),
         Type::Tuple { .. } | Type::Union { .. } | Type::Enum { .. } => {
             let comp_ty = CompType(t);
             let comptype_index = get_comptype_index(comp_ty, comptypes);
@@ -33,6 +36,12 @@ pub(super) fn fmt_int_type(int_ty: IntType) -> String {
     format!("{signed}{bits}")
 }
 
+pub(super) fn fmt_float_type(float_ty: FloatType) -> String {
+    let bits = float_ty.size.bits();
+
+    format!("f{bits}")
+}
+
 pub(super) fn fmt_ptr_type(ptr_ty: PtrType) -> FmtExpr {
     match ptr_ty {
         PtrType::Ref { mutbl: Mutability::Mutable, pointee } => {
@@ -170,7 +179,16 @@ fn fmt_comptype(i: CompTypeIndex, t: CompType, comptypes: &mut Vec<CompType>) ->
         _ => panic!("not a supported composite type!"),
     };
     let ct = fmt_comptype_index(i).to_string();
-    let layout = fmt_layout_strategy(t.0.layout::<DefaultTarget>());
+    let mut layout = fmt_layout_strategy(t.0.layout::<DefaultTarget>());
+    // The layout strategy of sized tuples does not mention the packed attribute.
+    if let Type::Tuple { sized_head_layout, unsized_field, valid_range, .. } = t.0 {
+        if let (Some(packed), None) = (sized_head_layout.packed_align, unsized_field.extract()) {
+            layout += &format!(", packed={}", packed.bytes());
+        }
+        if let Some(ValidRange { start, end }) = valid_range {
+            layout += &format!(", valid_range={start}..={end}");
+        }
+    }
     let mut s = format!("{keyword} {ct} ({layout}) {{\n");
     match t.0 {
         Type::Tuple { sized_fields, unsized_field, .. } => {
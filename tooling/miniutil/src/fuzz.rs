@@ -0,0 +1,457 @@
+//! A generator of random well-formed MiniRust programs, and the checks the `minifuzz` tool runs them through.
+//!
+//! Programs are built with the `build` API from a seed, so every finding can be reproduced from its seed.
+//! Generated programs always terminate and their output does not depend on scheduling or on the addresses
+//! of allocations. They may have Undefined Behavior (e.g. a division by zero), which is not a finding.
+//! A finding is one of:
+//! - a panic of the interpreter itself,
+//! - a program that the well-formedness checker and the machine disagree on, or that is ill-formed at all,
+//! - a program that ends differently when run twice.
+
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+use crate::build::*;
+use crate::rng::Rng;
+use crate::run::*;
+use crate::wf::*;
+use crate::*;
+
+/// Which features generated programs use, besides integer and boolean locals, branches, and loops.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FuzzConfig {
+    /// A raw pointer to one of the locals, and accesses through it.
+    pub pointers: bool,
+    /// A local of enum type, whose variant and data are set and read.
+    pub enums: bool,
+    /// A second thread that atomically adds to a global, like the main thread does.
+    pub concurrency: bool,
+    /// The maximal number of operations in each block of the generated program.
+    pub max_ops: usize,
+}
+
+impl Default for FuzzConfig {
+    fn default() -> Self {
+        FuzzConfig { pointers: true, enums: true, concurrency: true, max_ops: 8 }
+    }
+}
+
+/// Something that went wrong when checking a generated program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Finding {
+    /// The interpreter (or the well-formedness checker) panicked.
+    Panic(String),
+    /// The generated program is ill-formed, i.e., the generator is wrong.
+    IllFormed(String),
+    /// The well-formedness checker and the machine disagree on whether the program is ill-formed.
+    WfMismatch { checker: Option<String>, machine: Option<String> },
+    /// Two runs of the program ended differently.
+    NonDeterministic { first: String, second: String },
+}
+
+impl std::fmt::Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Finding::Panic(msg) => write!(f, "the interpreter panicked: {msg}"),
+            Finding::IllFormed(msg) => write!(f, "the generated program is ill-formed: {msg}"),
+            Finding::WfMismatch { checker, machine } =>
+                write!(
+                    f,
+                    "the well-formedness checker reports {checker:?}, but the machine reports {machine:?}"
+                ),
+            Finding::NonDeterministic { first, second } =>
+                write!(f, "two runs ended differently:\n  {first}\n  {second}"),
+        }
+    }
+}
+
+/// Generates the program for `seed` and checks it.
+pub fn fuzz_one(seed: u64, config: FuzzConfig) -> Option<Finding> {
+    check_program(generate_program(seed, config))
+}
+
+/// Checks a program that is expected to be well-formed and deterministic.
+pub fn check_program(prog: Program) -> Option<Finding> {
+    check_program_inner(prog).err()
+}
+
+fn check_program_inner(prog: Program) -> Result<(), Finding> {
+    let errors = catch(|| check_program_wf::<DefaultTarget>(prog))?;
+    let first = catch(|| run_program_with_output::<BasicMem>(prog))?;
+
+    let checker = errors.first().map(WfError::msg);
+    let machine = match &first.info {
+        TerminationInfo::IllFormed(err) => Some(err.msg().get_internal().to_string()),
+        _ => None,
+    };
+    if checker != machine {
+        return Err(Finding::WfMismatch { checker, machine });
+    }
+    if let Some(msg) = checker {
+        return Err(Finding::IllFormed(msg));
+    }
+
+    let second = catch(|| run_program_with_output::<BasicMem>(prog))?;
+    let (first, second) = (outcome(&first), outcome(&second));
+    if first != second {
+        return Err(Finding::NonDeterministic { first, second });
+    }
+    Ok(())
+}
+
+fn outcome(output: &Output) -> String {
+    format!("{:?}, stdout: {:?}", output.info, output.stdout)
+}
+
+fn catch<T>(f: impl FnOnce() -> T) -> Result<T, Finding> {
+    catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        let msg = match payload.downcast_ref::<&str>() {
+            Some(msg) => msg.to_string(),
+            None => payload.downcast_ref::<String>().cloned().unwrap_or_default(),
+        };
+        Finding::Panic(msg)
+    })
+}
+
+/// The types of the locals of generated programs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Scalar {
+    U8,
+    U32,
+    I32,
+    Bool,
+}
+
+impl Scalar {
+    const INTS: [Scalar; 3] = [Scalar::U8, Scalar::U32, Scalar::I32];
+
+    fn ty(self) -> Type {
+        match self {
+            Scalar::U8 => <u8>::get_type(),
+            Scalar::U32 => <u32>::get_type(),
+            Scalar::I32 => <i32>::get_type(),
+            Scalar::Bool => <bool>::get_type(),
+        }
+    }
+
+    fn ptr_ty(self) -> Type {
+        match self {
+            Scalar::U8 => <*mut u8>::get_type(),
+            Scalar::U32 => <*mut u32>::get_type(),
+            Scalar::I32 => <*mut i32>::get_type(),
+            Scalar::Bool => <*mut bool>::get_type(),
+        }
+    }
+}
+
+/// An operation of a generated function. Operations are generated before they are emitted,
+/// since the closures passed to `FunctionBuilder::if_` and `while_` cannot use the random number generator.
+enum Op {
+    Assign(PlaceExpr, ValueExpr),
+    Print(ValueExpr),
+    If(ValueExpr, Vec<Op>, Vec<Op>),
+    /// Runs the body `count` times, using `counter` to count the iterations.
+    Loop {
+        counter: PlaceExpr,
+        count: u32,
+        body: Vec<Op>,
+    },
+    /// Atomically adds `amount` to the shared `global`, storing the old value in `old`, which is never read.
+    AtomicAdd {
+        global: PlaceExpr,
+        old: PlaceExpr,
+        amount: u32,
+    },
+}
+
+/// The enum local: two variants, tagged by the byte at offset 0, both with a `u8` at offset 1.
+struct EnumLocal {
+    place: PlaceExpr,
+    ty: Type,
+    data_ty: Type,
+}
+
+impl EnumLocal {
+    fn new(f: &mut FunctionBuilder) -> EnumLocal {
+        let u8_int = IntType { signed: Signedness::Unsigned, size: size(1) };
+        let data_ty = tuple_ty(&[(offset(1), <u8>::get_type())], size(2), align(1));
+        let variant_of = |tag: u8| enum_variant(data_ty, &[(offset(0), (u8_int, Int::from(tag)))]);
+        let ty = enum_ty::<u8>(
+            &[(0, variant_of(0)), (1, variant_of(1))],
+            discriminator_branch::<u8>(offset(0), discriminator_invalid(), &[
+                ((0, 1), discriminator_known(0)),
+                ((1, 2), discriminator_known(1)),
+            ]),
+            size(2),
+            align(1),
+        );
+        EnumLocal { place: f.declare_local_with_ty(ty), ty, data_ty }
+    }
+
+    fn value(&self, discriminant: u8, data: ValueExpr) -> ValueExpr {
+        variant(discriminant, tuple(&[data], self.data_ty), self.ty)
+    }
+}
+
+struct Generator {
+    rng: Rng,
+    config: FuzzConfig,
+    /// The locals operations can read and write, with their types.
+    vars: Vec<(PlaceExpr, Scalar)>,
+    /// A local holding a raw pointer to one of `vars`.
+    ptr: Option<(PlaceExpr, Scalar)>,
+    enum_local: Option<EnumLocal>,
+    /// The global that both threads add to, and the local receiving the old values.
+    shared: Option<(PlaceExpr, PlaceExpr)>,
+    /// Locals that need to be made live when the function starts.
+    live: Vec<PlaceExpr>,
+}
+
+/// Generates a random well-formed program from `seed`, using the features enabled in `config`.
+pub fn generate_program(seed: u64, config: FuzzConfig) -> Program {
+    let mut rng = Rng::new(seed);
+    let mut p = ProgramBuilder::new();
+
+    // The second thread adds to the shared global a few times.
+    let worker = config.concurrency.then(|| {
+        let global = p.declare_global_zero_initialized::<u32>();
+        let count = 1 + rng.below(4) as u32;
+        let amount = 1 + rng.below(10) as u32;
+        let mut f = p.declare_function();
+        let _arg = f.declare_arg::<*const ()>();
+        let i = f.declare_local::<u32>();
+        let old = f.declare_local::<u32>();
+        f.storage_live(i);
+        f.storage_live(old);
+        f.assign(i, const_int(0u32));
+        f.while_(lt(load(i), const_int(count)), |f| {
+            f.atomic_fetch(
+                FetchBinOp::Add,
+                old,
+                addr_of(global, <*const u32>::get_type()),
+                const_int(amount),
+            );
+            f.assign(i, add(load(i), const_int(1u32)));
+        });
+        f.return_();
+        (p.finish_function(f), global)
+    });
+
+    let mut f = p.declare_function();
+    let mut g = Generator {
+        rng,
+        config,
+        vars: Vec::new(),
+        ptr: None,
+        enum_local: None,
+        shared: None,
+        live: Vec::new(),
+    };
+
+    // Declare the locals. Enums need a `u8` local to read their data into.
+    let num_vars = 2 + g.rng.below(4);
+    for i in 0..num_vars {
+        let scalar = if i == 0 && config.enums {
+            Scalar::U8
+        } else {
+            g.rng.pick(&[Scalar::U8, Scalar::U32, Scalar::I32, Scalar::Bool])
+        };
+        let place = f.declare_local_with_ty(scalar.ty());
+        g.vars.push((place, scalar));
+        g.live.push(place);
+    }
+    if config.pointers {
+        let (_, scalar) = g.vars[g.rng.below(num_vars)];
+        let place = f.declare_local_with_ty(scalar.ptr_ty());
+        g.ptr = Some((place, scalar));
+        g.live.push(place);
+    }
+    if config.enums {
+        let enum_local = EnumLocal::new(&mut f);
+        g.live.push(enum_local.place);
+        g.enum_local = Some(enum_local);
+    }
+    let thread_id = worker.map(|(_, global)| {
+        let old = f.declare_local::<u32>();
+        let thread_id = f.declare_local::<u32>();
+        g.shared = Some((global, old));
+        g.live.extend([old, thread_id]);
+        thread_id
+    });
+
+    let ops = g.gen_ops(&mut f, 2);
+
+    // Make all locals live and initialize them, then run the operations.
+    for &place in &g.live {
+        f.storage_live(place);
+    }
+    for (place, scalar) in g.vars.clone() {
+        let value = g.constant(scalar);
+        f.assign(place, value);
+    }
+    if let Some((ptr, scalar)) = g.ptr {
+        let target = g.vars.iter().find(|(_, s)| *s == scalar).unwrap().0;
+        f.assign(ptr, addr_of(target, scalar.ptr_ty()));
+    }
+    if let Some(enum_local) = &g.enum_local {
+        f.assign(enum_local.place, enum_local.value(0, const_int(0u8)));
+    }
+    if let (Some((worker, _)), Some(thread_id)) = (worker, thread_id) {
+        f.spawn(worker, null(), thread_id);
+    }
+    emit(&mut f, &ops);
+    if let (Some((global, _)), Some(thread_id)) = (g.shared, thread_id) {
+        f.join(load(thread_id));
+        f.print(load(global));
+    }
+    f.exit();
+
+    let main = p.finish_function(f);
+    p.finish_program(main)
+}
+
+impl Generator {
+    fn gen_ops(&mut self, f: &mut FunctionBuilder, depth: usize) -> Vec<Op> {
+        let count = 1 + self.rng.below(self.config.max_ops.max(1));
+        (0..count).map(|_| self.gen_op(f, depth)).collect()
+    }
+
+    fn gen_op(&mut self, f: &mut FunctionBuilder, depth: usize) -> Op {
+        loop {
+            match self.rng.below(9) {
+                0..=2 => {
+                    let (place, scalar) = self.vars[self.rng.below(self.vars.len())];
+                    return Op::Assign(place, self.expr(scalar, 2));
+                }
+                3 => {
+                    let (place, _) = self.vars[self.rng.below(self.vars.len())];
+                    return Op::Print(load(place));
+                }
+                4 if depth > 0 => {
+                    let cond = self.expr(Scalar::Bool, 2);
+                    let then_ops = self.gen_ops(f, depth - 1);
+                    let else_ops =
+                        if self.rng.percent(50) { self.gen_ops(f, depth - 1) } else { Vec::new() };
+                    return Op::If(cond, then_ops, else_ops);
+                }
+                5 if depth > 0 => {
+                    let counter = f.declare_local::<u32>();
+                    self.live.push(counter);
+                    let count = self.rng.below(4) as u32;
+                    let body = self.gen_ops(f, depth - 1);
+                    return Op::Loop { counter, count, body };
+                }
+                6 if self.ptr.is_some() => {
+                    let (ptr, scalar) = self.ptr.unwrap();
+                    let pointee = deref(load(ptr), scalar.ty());
+                    if self.rng.percent(50) {
+                        return Op::Assign(pointee, self.expr(scalar, 1));
+                    }
+                    let dests: Vec<PlaceExpr> =
+                        self.vars.iter().filter(|(_, s)| *s == scalar).map(|(p, _)| *p).collect();
+                    return Op::Assign(self.rng.pick(&dests), load(pointee));
+                }
+                7 if self.enum_local.is_some() => {
+                    let discriminant = self.rng.below(2) as u8;
+                    match self.rng.below(3) {
+                        0 => {
+                            let data = self.expr(Scalar::U8, 1);
+                            let enum_local = self.enum_local.as_ref().unwrap();
+                            return Op::Assign(
+                                enum_local.place,
+                                enum_local.value(discriminant, data),
+                            );
+                        }
+                        1 => {
+                            // Both variants have their data at the same offset, so any variant can be read.
+                            let enum_local = self.enum_local.as_ref().unwrap();
+                            let data = field(downcast(enum_local.place, discriminant), 0);
+                            return Op::Assign(self.vars[0].0, load(data));
+                        }
+                        _ => {
+                            let enum_local = self.enum_local.as_ref().unwrap();
+                            return Op::Print(get_discriminant(enum_local.place));
+                        }
+                    }
+                }
+                8 if self.shared.is_some() => {
+                    let (global, old) = self.shared.unwrap();
+                    return Op::AtomicAdd { global, old, amount: 1 + self.rng.below(10) as u32 };
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn constant(&mut self, scalar: Scalar) -> ValueExpr {
+        let interesting = self.rng.percent(50);
+        match scalar {
+            Scalar::U8 if interesting => const_int(self.rng.pick(&[0u8, 1, 2, u8::MAX])),
+            Scalar::U8 => const_int(self.rng.next_u64() as u8),
+            Scalar::U32 if interesting => const_int(self.rng.pick(&[0u32, 1, 2, u32::MAX])),
+            Scalar::U32 => const_int(self.rng.next_u64() as u32),
+            Scalar::I32 if interesting =>
+                const_int(self.rng.pick(&[0i32, 1, -1, i32::MIN, i32::MAX])),
+            Scalar::I32 => const_int(self.rng.next_u64() as i32),
+            Scalar::Bool => const_bool(self.rng.percent(50)),
+        }
+    }
+
+    /// A random expression of type `scalar`, nested at most `depth` levels deep.
+    fn expr(&mut self, scalar: Scalar, depth: usize) -> ValueExpr {
+        let vars: Vec<PlaceExpr> =
+            self.vars.iter().filter(|(_, s)| *s == scalar).map(|(p, _)| *p).collect();
+        let choice = if depth == 0 { self.rng.below(2) } else { self.rng.below(5) };
+        match (scalar, choice) {
+            (_, 0) => self.constant(scalar),
+            (_, 1) if !vars.is_empty() => load(self.rng.pick(&vars)),
+            (_, 1) => self.constant(scalar),
+            (Scalar::Bool, 2) => not(self.expr(Scalar::Bool, depth - 1)),
+            (Scalar::Bool, _) => {
+                let operand = self.rng.pick(&Scalar::INTS);
+                let l = self.expr(operand, depth - 1);
+                let r = self.expr(operand, depth - 1);
+                if self.rng.percent(50) { eq(l, r) } else { lt(l, r) }
+            }
+            (_, 2) => {
+                let from = self.rng.pick(&Scalar::INTS);
+                let operand = self.expr(from, depth - 1);
+                match scalar {
+                    Scalar::U8 => int_cast::<u8>(operand),
+                    Scalar::U32 => int_cast::<u32>(operand),
+                    _ => int_cast::<i32>(operand),
+                }
+            }
+            _ => {
+                let l = self.expr(scalar, depth - 1);
+                let r = self.expr(scalar, depth - 1);
+                let ops: [fn(ValueExpr, ValueExpr) -> ValueExpr; 7] =
+                    [add, sub, mul, bit_and, bit_xor, div, rem];
+                let op = self.rng.pick(&ops);
+                op(l, r)
+            }
+        }
+    }
+}
+
+fn emit(f: &mut FunctionBuilder, ops: &[Op]) {
+    for op in ops {
+        match op {
+            Op::Assign(place, value) => f.assign(*place, *value),
+            Op::Print(value) => f.print(*value),
+            Op::If(cond, then_ops, else_ops) =>
+                f.if_(*cond, |f| emit(f, then_ops), |f| emit(f, else_ops)),
+            Op::Loop { counter, count, body } => {
+                let counter = *counter;
+                f.assign(counter, const_int(0u32));
+                f.while_(lt(load(counter), const_int(*count)), |f| {
+                    emit(f, body);
+                    f.assign(counter, add(load(counter), const_int(1u32)));
+                });
+            }
+            Op::AtomicAdd { global, old, amount } => {
+                let ptr = addr_of(*global, <*const u32>::get_type());
+                f.atomic_fetch(FetchBinOp::Add, *old, ptr, const_int(*amount));
+            }
+        }
+    }
+}
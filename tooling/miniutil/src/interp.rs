@@ -0,0 +1,417 @@
+//! Running the machine incrementally.
+//!
+//! Tools like debuggers, schedule explorers, or fuzzers want to run a program for a while, look at
+//! the machine, and then continue. An `Interpreter` supports that by running at most a given number
+//! of steps (the "fuel") at a time, or by taking single steps chosen by the caller.
+//! All other ways of running a program in this crate are built on it, so that every run records
+//! coverage, collects garbage, and executes integer-to-pointer casts the same way.
+//! By default, such casts take their first option; see `Interpreter::set_int2ptr_lookahead` for a heuristic
+//! that tries harder to find the option the spec's `predict` would take.
+//! Either way, the option is only a guess, so if the program has UB involving a provenance a cast guessed,
+//! it stops as `Unsupported` rather than reporting UB the spec might not have.
+//! The other non-deterministic choices are made by the machine, unless an `Oracle` makes them,
+//! see `Interpreter::step_with_oracle`.
+
+use std::cell::{Cell, RefCell};
+use std::io::Write;
+use std::rc::Rc;
+
+use minirust_rs::libspecr::hidden::GcCompat;
+
+use crate::{mock_write::MockWrite, oracle::Oracle, run::*, *};
+
+/// A program being executed, which can be resumed after running out of fuel.
+pub struct Interpreter<M: MemoryTooling> {
+    /// The program `machine` is running.
+    prog: Program,
+    machine: Machine<M>,
+    /// How the program terminated, once it did.
+    terminated: Option<TerminationInfo>,
+    /// The number of steps the machine completed.
+    steps: u64,
+    /// The buffers for stdout, stderr, and warnings, unless the caller provided its own writers.
+    captured: Option<[MockWrite; 3]>,
+    /// Where the machine writes stdout, stderr, and warnings to; shared with the copies of the machine.
+    output: [SharedWrite; 3],
+    /// Whether the output of the machine is currently discarded.
+    muted: Rc<Cell<bool>>,
+    /// The number of steps the lookahead for an integer-to-pointer cast may take,
+    /// or `None` if casts just take their first option; see `set_int2ptr_lookahead`.
+    int2ptr_lookahead: Option<u64>,
+    /// The provenances integer-to-pointer casts guessed among several options, as in `Machine::int2ptr_taken`.
+    int2ptr_guesses: Vec<Option<Int>>,
+}
+
+/// The writers a `SharedWrite` can forward to.
+trait OutputWrite: Write + GcCompat {}
+impl<W: Write + GcCompat> OutputWrite for W {}
+
+/// A writer shared by all copies of a machine, which discards everything while `muted` is set.
+#[derive(Clone)]
+struct SharedWrite {
+    inner: Rc<RefCell<dyn OutputWrite>>,
+    muted: Rc<Cell<bool>>,
+}
+
+impl Write for SharedWrite {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.muted.get() { Ok(buf.len()) } else { self.inner.borrow_mut().write(buf) }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.borrow_mut().flush()
+    }
+}
+
+impl GcCompat for SharedWrite {
+    fn points_to(&self, buffer: &mut std::collections::HashSet<usize>) {
+        self.inner.borrow().points_to(buffer)
+    }
+}
+
+impl<M: MemoryTooling> Interpreter<M> {
+    /// Prepares executing `prog`, capturing everything it prints. No steps are taken yet.
+    /// If the program is ill-formed (or the machine cannot even start), returns why.
+    pub fn new(prog: Program) -> Result<Self, TerminationInfo> {
+        Self::new_with_config(prog, MachineConfig::DEFAULT)
+    }
+
+    /// Like `new`, but with the given machine configuration.
+    pub fn new_with_config(prog: Program, config: MachineConfig) -> Result<Self, TerminationInfo> {
+        let captured = [MockWrite::new(), MockWrite::new(), MockWrite::new()];
+        let [stdout, stderr, warnings] = captured.clone();
+        let mut interp = Self::new_with_writers(prog, config, stdout, stderr, warnings)?;
+        interp.captured = Some(captured);
+        Ok(interp)
+    }
+
+    /// Like `new_with_config`, but the program writes its output to the given writers instead of capturing it.
+    pub fn new_with_writers(
+        prog: Program,
+        config: MachineConfig,
+        stdout: impl GcWrite,
+        stderr: impl GcWrite,
+        warnings: impl GcWrite,
+    ) -> Result<Self, TerminationInfo> {
+        let muted = Rc::new(Cell::new(false));
+        let share =
+            |inner: Rc<RefCell<dyn OutputWrite>>| SharedWrite { inner, muted: muted.clone() };
+        let output = [
+            share(Rc::new(RefCell::new(stdout))),
+            share(Rc::new(RefCell::new(stderr))),
+            share(Rc::new(RefCell::new(warnings))),
+        ];
+        let [stdout, stderr, warnings] = output.clone().map(DynWrite::new);
+        let mut machine =
+            Machine::<M>::new_with_config(prog, config, stdout, stderr, warnings).get_internal()?;
+        // `predict` cannot be executed, so casts take their first option, unless a lookahead finds a better one.
+        machine.fix_int2ptr_choice(Some(Int::ZERO));
+        Ok(Interpreter {
+            prog,
+            machine,
+            terminated: None,
+            steps: 0,
+            captured: None,
+            output,
+            muted,
+            int2ptr_lookahead: None,
+            int2ptr_guesses: Vec::new(),
+        })
+    }
+
+    /// Makes integer-to-pointer casts with several options for their provenance look ahead up to `fuel` steps
+    /// to find an option that avoids UB, see `resolve_int2ptr`. With `None`, casts just take their first option.
+    ///
+    /// This is a heuristic: it can miss options that avoid UB. Like the first option without a lookahead,
+    /// the option it finds is a guess, so UB involving it is reported as `Unsupported`, unless every option ran into UB.
+    /// If the lookahead cannot decide within `fuel` steps, the program stops as `Unsupported` right away.
+    pub fn set_int2ptr_lookahead(&mut self, fuel: Option<u64>) {
+        self.int2ptr_lookahead = fuel;
+    }
+
+    /// Runs at most `fuel` steps.
+    /// Returns how the program terminated, or `None` if it is still running after using up all the fuel.
+    /// Once the program has terminated, this always returns the same result without taking more steps.
+    pub fn run(&mut self, fuel: u64) -> Option<TerminationInfo> {
+        for _ in 0..fuel {
+            if self.step().is_some() {
+                break;
+            }
+        }
+        self.terminated
+    }
+
+    /// Runs until the program terminates.
+    pub fn run_to_completion(&mut self) -> TerminationInfo {
+        loop {
+            if let Some(info) = self.step() {
+                return info;
+            }
+        }
+    }
+
+    /// Takes a single step, letting the machine pick the thread.
+    /// Returns how the program terminated, or `None` if it is still running.
+    pub fn step(&mut self) -> Option<TerminationInfo> {
+        self.step_with(|machine| machine.step())
+    }
+
+    /// Takes a single step with `step`, e.g. to pick the thread with `Machine::step_thread`.
+    /// Returns how the program terminated, or `None` if it is still running.
+    /// Once the program has terminated, `step` is not called anymore.
+    pub fn step_with(
+        &mut self,
+        step: impl FnOnce(&mut Machine<M>) -> NdResult,
+    ) -> Option<TerminationInfo> {
+        if self.terminated.is_none() {
+            // Keep a copy of the machine to retake the step, but only if it may need a lookahead.
+            let before = match self.int2ptr_lookahead {
+                Some(fuel) if self.may_cast_int2ptr() => Some((self.fork(&self.machine), fuel)),
+                _ => None,
+            };
+            match coverage::step(&mut self.machine, self.prog, step) {
+                Ok(()) => {
+                    let guessed = match before {
+                        Some((before, fuel)) => self.resolve_int2ptr(before, fuel),
+                        None => self.machine.int2ptr_options() > Int::ONE,
+                    };
+                    if guessed {
+                        self.record_int2ptr_guesses();
+                    }
+                    self.steps += 1;
+                    // Drops everything not reachable from `machine`.
+                    mark_and_sweep(&self.machine);
+                }
+                Err(info) => {
+                    if self.machine.int2ptr_options() > Int::ONE {
+                        self.record_int2ptr_guesses();
+                    }
+                    self.terminated = Some(self.unless_guessed(info));
+                }
+            }
+        }
+        self.terminated
+    }
+
+    /// Takes a single step, letting `oracle` make the non-deterministic choices of the machine.
+    /// Returns how the program terminated, or `None` if it is still running.
+    ///
+    /// The machine only tells us which choice comes next once it got the answers to all the choices before,
+    /// so we take the step on a copy of the machine (with its output discarded) to learn about each choice in turn,
+    /// and then take it for real with all the answers.
+    pub fn step_with_oracle(&mut self, oracle: &mut impl Oracle) -> Option<TerminationInfo> {
+        if self.terminated.is_some() {
+            return self.terminated;
+        }
+        let enabled = self.machine.enabled_threads();
+        let Some(thread) = enabled.get(oracle::ask(oracle, ChoiceKind::Thread, enabled.len()))
+        else {
+            // Without an enabled thread, `step` reports the deadlock.
+            return self.step();
+        };
+
+        self.muted.set(true);
+        let mut answers = List::new();
+        loop {
+            let mut machine = self.fork(&self.machine);
+            machine.fix_choices(Some(answers));
+            // The step may fail, but we still learn which choices it made before.
+            let _ = machine.step_thread(thread).get_internal();
+            let Some((kind, options)) = machine.choices().get(answers.len()) else {
+                break;
+            };
+            answers.push(oracle::ask(oracle, kind, options));
+        }
+        self.muted.set(false);
+
+        self.machine.fix_choices(Some(answers));
+        self.step_with(|machine| machine.step_thread(thread));
+        self.machine.fix_choices(None);
+        self.terminated
+    }
+
+    /// Remembers the provenances the casts in the step just taken guessed.
+    fn record_int2ptr_guesses(&mut self) {
+        self.int2ptr_guesses.extend(self.machine.int2ptr_taken().iter());
+    }
+
+    /// `info`, unless it is UB that may be due to a wrong guess of an integer-to-pointer cast:
+    /// UB accessing an allocation whose provenance a cast guessed,
+    /// or UB without an access (such as using a pointer without provenance) after a cast guessed no provenance.
+    /// `predict` might have picked an option that avoids this UB, so we cannot tell whether the program has UB.
+    fn unless_guessed(&self, info: TerminationInfo) -> TerminationInfo {
+        match info {
+            TerminationInfo::Ub(msg, access)
+                if self.int2ptr_guesses.contains(&access.map(|access| access.alloc_id)) =>
+            {
+                let msg = format!(
+                    "UB after guessing the provenance of an integer-to-pointer cast: {}",
+                    msg.get_internal()
+                );
+                TerminationInfo::Unsupported(minirust_rs::prelude::String::from_internal(msg))
+            }
+            info => info,
+        }
+    }
+
+    /// Copies `machine`, with the copy writing to the same output.
+    fn fork(&self, machine: &Machine<M>) -> Machine<M> {
+        let [stdout, stderr, warnings] = self.output.clone().map(DynWrite::new);
+        machine.fork(stdout, stderr, warnings)
+    }
+
+    /// Whether the next step may cast an integer to a pointer,
+    /// i.e., whether that is the next operation of some enabled thread.
+    fn may_cast_int2ptr(&self) -> bool {
+        self.machine.enabled_threads().iter().any(|thread| {
+            let Some((func, block, stmt)) = self.machine.thread_location(thread) else {
+                return false;
+            };
+            matches!(
+                Location { func, block, stmt }.operation(self.prog),
+                Some(Operation::Terminator(Terminator::Intrinsic {
+                    intrinsic: IntrinsicOp::PointerWithExposedProvenance,
+                    ..
+                }))
+            )
+        })
+    }
+
+    /// If the step just taken from `before` cast an integer to a pointer with several options for its provenance,
+    /// retakes the step with an option that avoids UB in the rest of the execution, approximating `predict`.
+    ///
+    /// The rest of the execution is not known yet, so we look ahead: for every option in turn, we run a copy
+    /// of the machine for at most `fuel` steps (with its output discarded), and take the first option with which
+    /// the program terminates without UB. If all options run into UB, we keep the first one.
+    /// This misses options that only avoid UB if a cast during the lookahead takes an option other than its first,
+    /// so it is not what the spec does. If no option terminates, but some ran out of fuel without running into UB,
+    /// we cannot tell which option to take, and stop the program as `Unsupported`.
+    ///
+    /// Returns whether the option taken is a guess, i.e., whether another option might still avoid UB.
+    fn resolve_int2ptr(&mut self, before: Machine<M>, fuel: u64) -> bool {
+        let options = self.machine.int2ptr_options();
+        if options <= Int::ONE {
+            return false;
+        }
+        let thread = self.machine.active_thread_id();
+        let retake = |choice: Int| {
+            let mut machine = self.fork(&before);
+            machine.fix_int2ptr_choice(Some(choice));
+            let result = machine.step_thread(thread).get_internal();
+            machine.fix_int2ptr_choice(Some(Int::ZERO));
+            // The step may have been taken with the answers of an oracle, but the lookahead is not.
+            machine.fix_choices(None);
+            (machine, result)
+        };
+
+        // The lookahead allocates a lot, but we must not collect garbage while the copies are running:
+        // the collector only knows about `self.machine`.
+        self.muted.set(true);
+        let mut out_of_fuel = false;
+        let good = (Int::ZERO..options).find(|&choice| {
+            let (mut machine, mut result) = retake(choice);
+            let mut fuel = fuel;
+            while result.is_ok() && fuel > 0 {
+                result = machine.step().get_internal();
+                fuel -= 1;
+            }
+            match result {
+                Ok(()) => {
+                    out_of_fuel = true;
+                    false
+                }
+                Err(TerminationInfo::Ub(..)) => false,
+                Err(_) => true,
+            }
+        });
+        self.muted.set(false);
+
+        match good {
+            Some(choice) => {
+                if choice != Int::ZERO {
+                    let (machine, result) = retake(choice);
+                    assert!(result.is_ok(), "retaking a step with another option for a cast failed");
+                    self.machine = machine;
+                }
+                true
+            }
+            None if out_of_fuel => {
+                let msg = format!(
+                    "the lookahead for an integer-to-pointer cast did not terminate within {fuel} steps"
+                );
+                self.terminated = Some(TerminationInfo::Unsupported(
+                    minirust_rs::prelude::String::from_internal(msg),
+                ));
+                true
+            }
+            // Every option runs into UB, so the program has UB no matter what `predict` picks.
+            None => false,
+        }
+    }
+
+    /// Lets `f` act on the machine between two steps, e.g. to execute a pending extern call.
+    /// If `f` returns an error, the program terminates with it.
+    /// Once the program has terminated, `f` is not called anymore.
+    pub fn intervene(
+        &mut self,
+        f: impl FnOnce(&mut Machine<M>) -> Result<(), TerminationInfo>,
+    ) -> Option<TerminationInfo> {
+        if self.terminated.is_none() {
+            if let Err(info) = f(&mut self.machine) {
+                self.terminated = Some(info);
+            }
+        }
+        self.terminated
+    }
+
+    /// How the program terminated, or `None` if it has not terminated yet.
+    pub fn terminated(&self) -> Option<TerminationInfo> {
+        self.terminated
+    }
+
+    /// The number of steps the machine completed so far.
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// The program being executed.
+    pub fn prog(&self) -> Program {
+        self.prog
+    }
+
+    /// The machine in its current state.
+    pub fn machine(&self) -> &Machine<M> {
+        &self.machine
+    }
+
+    /// The statement or terminator the active thread is about to execute.
+    /// After the program terminated, this is the one that caused the termination.
+    pub fn location(&self) -> Option<Location> {
+        self.machine.active_location().map(|(func, block, stmt)| Location { func, block, stmt })
+    }
+
+    /// Everything the program printed so far, and how it terminated.
+    /// Returns `None` if the program has not terminated yet.
+    /// If the output was not captured, `stdout`, `stderr`, and `warnings` are empty.
+    pub fn output(&self) -> Option<Output> {
+        let info = self.terminated?;
+        let Some([stdout, stderr, warnings]) = self.captured.clone() else {
+            return Some(Output::without_output(info));
+        };
+        Some(Output {
+            info,
+            stdout: stdout.into_strings(),
+            stderr: stderr.into_strings(),
+            warnings: warnings.into_strings(),
+        })
+    }
+
+    /// The lines the program printed to stdout so far.
+    /// This is empty if the output was not captured.
+    pub fn stdout(&self) -> Vec<String> {
+        match &self.captured {
+            Some([stdout, _, _]) => stdout.clone().into_strings(),
+            None => Vec::new(),
+        }
+    }
+}
@@ -18,10 +18,25 @@ pub use std::result::Result;
 pub use std::string::String;
 
 pub mod build;
+pub mod conformance;
+pub mod coverage;
+pub mod debug;
+pub mod env;
 pub mod fmt;
+pub mod fuzz;
+pub mod interp;
 pub mod mock_write;
+pub mod oracle;
+pub mod reduce;
+pub mod repr;
+pub mod rng;
 pub mod run;
+pub mod schedule;
+pub mod text;
+pub mod validity;
+pub mod wf;
 
 pub type DefaultTarget = x86_64;
 pub type BasicMem = BasicMemory<DefaultTarget>;
 pub type TreeBorrowMem = TreeBorrowsMemory<DefaultTarget>;
+pub type StackedBorrowMem = StackedBorrowsMemory<DefaultTarget>;
@@ -0,0 +1,37 @@
+//! Answering the non-deterministic choices of the machine from outside.
+//!
+//! Replaying an execution, fuzzing, and model checking all need to decide the choices the machine makes:
+//! which thread runs, which waiting thread gets a lock, whether a weak compare-exchange or a heap allocation
+//! fails spuriously, what `Freeze` puts into uninitialized bytes, and which NaN a float operation returns.
+//! An `Oracle` makes these decisions; `Interpreter::step_with_oracle` takes a step with its answers.
+//! Addresses, the store an atomic load reads from, and integer-to-pointer casts have their own hooks,
+//! see `AddrStrategy`, `Machine::fix_load_choice`, and `Interpreter::set_int2ptr_lookahead`.
+
+use crate::*;
+
+/// Answers the non-deterministic choices of the machine.
+pub trait Oracle {
+    /// Picks one of the `options` options of a choice of the given kind, see `ChoiceKind` for what they mean.
+    /// The answer must be less than `options`, which is always at least 2.
+    fn choose(&mut self, kind: ChoiceKind, options: usize) -> usize;
+}
+
+impl<F: FnMut(ChoiceKind, usize) -> usize> Oracle for F {
+    fn choose(&mut self, kind: ChoiceKind, options: usize) -> usize {
+        self(kind, options)
+    }
+}
+
+/// Asks `oracle` for a choice with `options` options, unless there is nothing to choose.
+pub(crate) fn ask(oracle: &mut impl Oracle, kind: ChoiceKind, options: Int) -> Int {
+    if options <= Int::ONE {
+        return Int::ZERO;
+    }
+    let options = options.try_to_usize().unwrap();
+    let answer = oracle.choose(kind, options);
+    assert!(
+        answer < options,
+        "the oracle picked option {answer} of a {kind:?} choice with {options} options"
+    );
+    Int::from(answer)
+}
@@ -0,0 +1,253 @@
+//! Reduces a failing program to a small program that still fails the same way.
+//!
+//! This is delta debugging: `reduce` keeps trying to remove parts of the program (statements, blocks,
+//! functions, locals, globals, and vtables) and to simplify terminators, and keeps every change after
+//! which the failure predicate still holds. Changes that make the program ill-formed are typically
+//! rejected by the predicate, so the predicate should be specific, e.g. check for one UB message.
+
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+use crate::run::*;
+use crate::*;
+
+/// Reduces `prog` for as long as some removal keeps `is_failing` true, and returns the result.
+/// `is_failing(prog)` must hold initially.
+pub fn reduce(prog: Program, mut is_failing: impl FnMut(Program) -> bool) -> Program {
+    assert!(is_failing(prog), "the program to reduce does not fail");
+    let mut reducer = Reducer { prog, is_failing: &mut is_failing };
+    // Each pass can enable further reductions in the others, so repeat until nothing changes.
+    loop {
+        let before = reducer.prog;
+        reducer.remove_functions();
+        reducer.remove_unreachable_blocks();
+        reducer.simplify_terminators();
+        reducer.merge_blocks();
+        reducer.remove_blocks();
+        reducer.remove_statements();
+        reducer.remove_locals();
+        reducer.remove_globals();
+        reducer.remove_vtables();
+        if reducer.prog == before {
+            return reducer.prog;
+        }
+    }
+}
+
+/// A predicate for `reduce`: the program makes the interpreter panic.
+/// The panic hook still runs, so callers may want to silence it while reducing.
+pub fn panics<M: MemoryTooling>(prog: Program) -> bool {
+    catch_unwind(AssertUnwindSafe(|| run_program::<M>(prog))).is_err()
+}
+
+/// A predicate for `reduce`: the program has UB with the given message.
+pub fn has_ub<M: MemoryTooling>(msg: &str) -> impl Fn(Program) -> bool {
+    let msg = minirust_rs::prelude::String::from_internal(msg.to_string());
+    move |prog| matches!(run_program::<M>(prog), TerminationInfo::Ub(res, _) if res == msg)
+}
+
+/// The blocks a terminator can continue in.
+fn successors(terminator: Terminator) -> Vec<BbName> {
+    match terminator {
+        Terminator::Goto(block) | Terminator::StopUnwind(block) => vec![block],
+        Terminator::Switch { cases, fallback, .. } => {
+            let mut blocks: Vec<BbName> = cases.values().collect();
+            blocks.push(fallback);
+            blocks
+        }
+        Terminator::Intrinsic { next_block, .. }
+        | Terminator::InlineAsm { next_block, .. }
+        | Terminator::ExternCall { next_block, .. }
+        | Terminator::StartUnwind(next_block) => next_block.into_iter().collect(),
+        Terminator::Call { next_block, unwind_block, .. } =>
+            next_block.into_iter().chain(unwind_block).collect(),
+        Terminator::Unreachable
+        | Terminator::TailCall { .. }
+        | Terminator::Return
+        | Terminator::ResumeUnwind
+        | Terminator::UnwindTerminate => vec![],
+    }
+}
+
+struct Reducer<'a> {
+    /// The smallest failing program found so far.
+    prog: Program,
+    is_failing: &'a mut dyn FnMut(Program) -> bool,
+}
+
+impl Reducer<'_> {
+    /// Switches to `candidate` if it still fails. Returns whether it did.
+    fn try_candidate(&mut self, candidate: Program) -> bool {
+        if candidate != self.prog && (self.is_failing)(candidate) {
+            self.prog = candidate;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn function(&self, fn_name: FnName) -> Function {
+        self.prog.functions.get(fn_name).unwrap()
+    }
+
+    fn with_function(&self, fn_name: FnName, func: Function) -> Program {
+        let mut prog = self.prog;
+        prog.functions.insert(fn_name, func);
+        prog
+    }
+
+    fn remove_functions(&mut self) {
+        for fn_name in self.prog.functions.keys().collect::<Vec<_>>() {
+            if fn_name == self.prog.start {
+                continue;
+            }
+            let mut prog = self.prog;
+            prog.functions.remove(fn_name);
+            self.try_candidate(prog);
+        }
+    }
+
+    /// Removing blocks that cannot be reached from the start block does not change the behavior of the program.
+    fn remove_unreachable_blocks(&mut self) {
+        for (fn_name, func) in self.prog.functions.iter().collect::<Vec<_>>() {
+            let mut reachable = vec![func.start];
+            let mut todo = vec![func.start];
+            while let Some(block) = todo.pop() {
+                let Some(block) = func.blocks.get(block) else { continue };
+                for next in successors(block.terminator) {
+                    if !reachable.contains(&next) {
+                        reachable.push(next);
+                        todo.push(next);
+                    }
+                }
+            }
+            let mut func = func;
+            func.blocks = func.blocks.iter().filter(|(name, _)| reachable.contains(name)).collect();
+            self.try_candidate(self.with_function(fn_name, func));
+        }
+    }
+
+    /// Replaces terminators by `Return` or by a jump to one of their successors.
+    fn simplify_terminators(&mut self) {
+        for fn_name in self.prog.functions.keys().collect::<Vec<_>>() {
+            for block_name in self.function(fn_name).blocks.keys().collect::<Vec<_>>() {
+                let func = self.function(fn_name);
+                let block = func.blocks.get(block_name).unwrap();
+                let mut candidates = vec![Terminator::Return];
+                candidates.extend(successors(block.terminator).into_iter().map(Terminator::Goto));
+                for terminator in candidates {
+                    if terminator == block.terminator {
+                        continue;
+                    }
+                    let mut func = func;
+                    func.blocks.insert(block_name, BasicBlock { terminator, ..block });
+                    if self.try_candidate(self.with_function(fn_name, func)) {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Appends the block a `Goto` jumps to to the block containing the `Goto`.
+    /// The block jumped to is then often unreachable and gets removed.
+    fn merge_blocks(&mut self) {
+        for fn_name in self.prog.functions.keys().collect::<Vec<_>>() {
+            for block_name in self.function(fn_name).blocks.keys().collect::<Vec<_>>() {
+                let mut func = self.function(fn_name);
+                let block = func.blocks.get(block_name).unwrap();
+                let Terminator::Goto(next_name) = block.terminator else { continue };
+                let Some(next) = func.blocks.get(next_name) else { continue };
+                if next_name == block_name {
+                    continue;
+                }
+                let statements = block.statements.iter().chain(next.statements.iter()).collect();
+                func.blocks
+                    .insert(block_name, BasicBlock { statements, terminator: next.terminator });
+                self.try_candidate(self.with_function(fn_name, func));
+            }
+        }
+    }
+
+    fn remove_blocks(&mut self) {
+        for fn_name in self.prog.functions.keys().collect::<Vec<_>>() {
+            for block_name in self.function(fn_name).blocks.keys().collect::<Vec<_>>() {
+                let mut func = self.function(fn_name);
+                if block_name == func.start {
+                    continue;
+                }
+                func.blocks.remove(block_name);
+                self.try_candidate(self.with_function(fn_name, func));
+            }
+        }
+    }
+
+    /// Removes statements in chunks, starting with the whole block and halving the chunk size,
+    /// so that long runs of irrelevant statements are removed with few runs of the predicate.
+    fn remove_statements(&mut self) {
+        for fn_name in self.prog.functions.keys().collect::<Vec<_>>() {
+            for block_name in self.function(fn_name).blocks.keys().collect::<Vec<_>>() {
+                let mut chunk = self
+                    .function(fn_name)
+                    .blocks
+                    .get(block_name)
+                    .unwrap()
+                    .statements
+                    .iter()
+                    .count();
+                while chunk > 0 {
+                    let mut start = 0;
+                    loop {
+                        let func = self.function(fn_name);
+                        let block = func.blocks.get(block_name).unwrap();
+                        let statements: Vec<Statement> = block.statements.iter().collect();
+                        if start >= statements.len() {
+                            break;
+                        }
+                        let end = (start + chunk).min(statements.len());
+                        let remaining =
+                            statements[..start].iter().chain(&statements[end..]).copied().collect();
+                        let mut func = func;
+                        func.blocks
+                            .insert(block_name, BasicBlock { statements: remaining, ..block });
+                        // After a successful removal, the next chunk starts where the removed one did.
+                        if !self.try_candidate(self.with_function(fn_name, func)) {
+                            start = end;
+                        }
+                    }
+                    chunk /= 2;
+                }
+            }
+        }
+    }
+
+    fn remove_locals(&mut self) {
+        for fn_name in self.prog.functions.keys().collect::<Vec<_>>() {
+            for local in self.function(fn_name).locals.keys().collect::<Vec<_>>() {
+                let mut func = self.function(fn_name);
+                if local == func.ret || func.args.any(|arg| arg == local) {
+                    continue;
+                }
+                func.locals.remove(local);
+                self.try_candidate(self.with_function(fn_name, func));
+            }
+        }
+    }
+
+    fn remove_globals(&mut self) {
+        for global_name in self.prog.globals.keys().collect::<Vec<_>>() {
+            let mut prog = self.prog;
+            prog.globals.remove(global_name);
+            prog.global_inits =
+                prog.global_inits.iter().filter(|(global, _)| *global != global_name).collect();
+            self.try_candidate(prog);
+        }
+    }
+
+    fn remove_vtables(&mut self) {
+        for vtable_name in self.prog.vtables.keys().collect::<Vec<_>>() {
+            let mut prog = self.prog;
+            prog.vtables.remove(vtable_name);
+            self.try_candidate(prog);
+        }
+    }
+}
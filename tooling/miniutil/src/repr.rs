@@ -0,0 +1,345 @@
+//! Property-based testing of the representation relation.
+//!
+//! The spec requires `encode` and `decode` to satisfy some
+//! [generic properties](../../../spec/lang/representation.md#generic-properties).
+//! `check_representation` generates a random type, random values and byte lists for it, and checks
+//! that these properties hold:
+//! - `encode` produces as many bytes as the type is big,
+//! - encoding a value and decoding the bytes gives back the same value,
+//! - decoding bytes and encoding the value again gives bytes that are at most as defined as the original ones,
+//! - `decode` is monotone: more defined bytes decode to a more defined value.
+//!
+//! The types include padded tuples, arrays, unions with gaps between their chunks, tagged enums, and
+//! enums that store their tag in a niche of their data. Pointers are raw and have no provenance.
+
+use crate::build::*;
+use crate::rng::Rng;
+use crate::wf::check_program_wf;
+use crate::*;
+
+/// Generates a type, values, and byte lists from `seed` and checks the round-trip properties for them.
+/// On failure, the error says which property failed and for which type, value, or bytes.
+pub fn check_representation<M: Memory>(seed: u64) -> Result<(), String> {
+    let mut rng = Rng::new(seed);
+    let ty = gen_type::<M::T>(&mut rng, 3);
+    if let Some(error) = check_program_wf::<M::T>(small_program(&[ty], &[])).first() {
+        return Err(format!("generated the ill-formed type {ty:?}: {error}"));
+    }
+
+    for _ in 0..8 {
+        let val = gen_value::<M>(&mut rng, ty);
+        check_value_roundtrip::<M>(ty, val)?;
+
+        // Mess up the encoding of a valid value, so that `decode` sometimes succeeds and sometimes does not.
+        let mut bytes: Vec<_> = ty.encode::<M>(val).iter().collect();
+        for byte in &mut bytes {
+            if rng.percent(20) {
+                *byte = gen_byte::<M>(&mut rng);
+            }
+        }
+        check_bytes_roundtrip::<M>(ty, bytes.iter().copied().collect())?;
+
+        // Uninitialize some of the bytes to get less defined bytes.
+        let less_defined: Vec<_> = bytes
+            .iter()
+            .map(|&byte| if rng.percent(20) { AbstractByte::Uninit } else { byte })
+            .collect();
+        check_decode_monotone::<M>(
+            ty,
+            less_defined.iter().copied().collect(),
+            bytes.iter().copied().collect(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Checks that `val` encodes to as many bytes as `ty` is big, and that decoding them gives back `val`.
+/// `val` must be well-formed for `ty`.
+pub fn check_value_roundtrip<M: Memory>(ty: Type, val: Value<M>) -> Result<(), String> {
+    let bytes = ty.encode::<M>(val);
+    if bytes.len() != ty.layout::<M::T>().expect_size("only sized types can be encoded").bytes() {
+        return Err(format!("encoding {val:?} at {ty:?} has the wrong size: {bytes:?}"));
+    }
+    let decoded = ty.decode::<M>(bytes);
+    if decoded != Some(val) {
+        return Err(format!(
+            "{val:?} at {ty:?} encodes to {bytes:?}, which decodes to {decoded:?}"
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that if `bytes` decode at `ty`, encoding the value again gives bytes that are at most as defined as `bytes`.
+pub fn check_bytes_roundtrip<M: Memory>(
+    ty: Type,
+    bytes: List<AbstractByte<M::Provenance>>,
+) -> Result<(), String> {
+    let Some(val) = ty.decode::<M>(bytes) else { return Ok(()) };
+    let encoded = ty.encode::<M>(val);
+    if !encoded.le_defined(bytes) {
+        return Err(format!(
+            "{bytes:?} decodes at {ty:?} to {val:?}, which encodes to the more defined {encoded:?}"
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that if `less` is at most as defined as `more`, then so is the value it decodes to at `ty`.
+pub fn check_decode_monotone<M: Memory>(
+    ty: Type,
+    less: List<AbstractByte<M::Provenance>>,
+    more: List<AbstractByte<M::Provenance>>,
+) -> Result<(), String> {
+    assert!(less.le_defined(more), "`check_decode_monotone` called with unrelated bytes");
+    let (less_val, more_val) = (ty.decode::<M>(less), ty.decode::<M>(more));
+    if !less_val.le_defined(more_val) {
+        return Err(format!(
+            "{less:?} is less defined than {more:?}, but at {ty:?} they decode to {less_val:?} and {more_val:?}"
+        ));
+    }
+    Ok(())
+}
+
+/// Generates the type `check_representation` checks for `seed`.
+pub fn generate_type<T: Target>(seed: u64) -> Type {
+    gen_type::<T>(&mut Rng::new(seed), 3)
+}
+
+fn round_up(n: u64, align: u64) -> u64 {
+    n.div_ceil(align) * align
+}
+
+fn size_and_align<T: Target>(ty: Type) -> (u64, u64) {
+    let layout = ty.layout::<T>();
+    let size = layout.expect_size("generated types are sized").bytes().try_to_usize().unwrap();
+    let align = layout.expect_align("generated types are sized").bytes().try_to_usize().unwrap();
+    (size as u64, align as u64)
+}
+
+fn gen_type<T: Target>(rng: &mut Rng, depth: usize) -> Type {
+    if depth == 0 || rng.percent(40) {
+        return gen_scalar_type::<T>(rng);
+    }
+    match rng.below(5) {
+        0 => gen_tuple_type::<T>(rng, depth),
+        1 => array_ty(gen_type::<T>(rng, depth - 1), rng.below(4) as u64),
+        2 => gen_union_type::<T>(rng, depth),
+        3 => gen_tagged_enum_type::<T>(rng, depth),
+        _ => gen_niche_enum_type::<T>(rng, depth),
+    }
+}
+
+fn gen_int_type(rng: &mut Rng) -> IntType {
+    let signed = if rng.percent(50) { Signedness::Signed } else { Signedness::Unsigned };
+    IntType { signed, size: size(rng.pick(&[1u64, 2, 4, 8, 16])) }
+}
+
+fn gen_scalar_type<T: Target>(rng: &mut Rng) -> Type {
+    match rng.below(7) {
+        0 => bool_ty(),
+        1 => Type::Int(gen_int_type(rng)),
+        2 => char_ty(),
+        3 => float_ty(size(rng.pick(&[4u64, 8]))),
+        4 => raw_void_ptr_ty(),
+        // Like `NonZero*`: everything except 0. For signed integers, the range wraps around.
+        5 => {
+            let int_ty = gen_int_type(rng);
+            let end = match int_ty.signed {
+                Signedness::Signed => Int::from(-1),
+                Signedness::Unsigned => (Int::ONE << int_ty.size.bits()) - Int::ONE,
+            };
+            valid_range_ty(Type::Int(int_ty), 1, end)
+        }
+        // Like `NonNull`.
+        _ => valid_range_ty(raw_void_ptr_ty(), 1, (Int::ONE << T::PTR_SIZE.bits()) - Int::ONE),
+    }
+}
+
+/// Lays out `fields` starting at `start`, sometimes with more padding than alignment requires.
+/// The first field is at `start` if that is suitably aligned.
+/// Returns the fields with their offsets, where the last field ends, and the alignment of the fields.
+fn layout_fields<T: Target>(
+    rng: &mut Rng,
+    start: u64,
+    fields: &[Type],
+) -> (Vec<(Offset, Type)>, u64, u64) {
+    let mut laid_out = Vec::new();
+    let mut end = start;
+    let mut max_align = 1;
+    for (i, &ty) in fields.iter().enumerate() {
+        let (field_size, field_align) = size_and_align::<T>(ty);
+        let mut field_offset = round_up(end, field_align);
+        if i > 0 && rng.percent(30) {
+            field_offset += field_align;
+        }
+        laid_out.push((offset(field_offset), ty));
+        end = field_offset + field_size;
+        max_align = max_align.max(field_align);
+    }
+    (laid_out, end, max_align)
+}
+
+fn gen_tuple_type<T: Target>(rng: &mut Rng, depth: usize) -> Type {
+    let fields: Vec<Type> = (0..rng.below(4)).map(|_| gen_type::<T>(rng, depth - 1)).collect();
+    let (fields, end, mut tuple_align) = layout_fields::<T>(rng, 0, &fields);
+    // Like `repr(align)`.
+    if rng.percent(20) {
+        tuple_align *= 2;
+    }
+    // Sometimes add trailing padding beyond what the alignment requires.
+    let extra = if rng.percent(30) { tuple_align } else { 0 };
+    tuple_ty(&fields, size(round_up(end, tuple_align) + extra), align(tuple_align))
+}
+
+fn gen_union_type<T: Target>(rng: &mut Rng, depth: usize) -> Type {
+    let fields: Vec<(Offset, Type)> =
+        (0..1 + rng.below(3)).map(|_| (Offset::ZERO, gen_type::<T>(rng, depth - 1))).collect();
+    let (end, union_align) = fields.iter().fold((0, 1), |(end, union_align), &(_, ty)| {
+        let (field_size, field_align) = size_and_align::<T>(ty);
+        (end.max(field_size), union_align.max(field_align))
+    });
+    let union_size = round_up(end, union_align);
+
+    // Chunks cover random parts of the union, with gaps between them that are not preserved.
+    let mut chunks = List::new();
+    let mut pos = 0;
+    while pos < union_size {
+        let len = 1 + rng.below((union_size - pos) as usize) as u64;
+        if rng.percent(70) {
+            chunks.push((offset(pos), size(len)));
+        }
+        pos += len;
+    }
+    Type::Union {
+        fields: fields.into_iter().collect(),
+        size: size(union_size),
+        align: align(union_align),
+        chunks,
+    }
+}
+
+/// An enum with a `u8` tag at offset 0 that says which variant it is, followed by the data of the variant.
+fn gen_tagged_enum_type<T: Target>(rng: &mut Rng, depth: usize) -> Type {
+    let variants: Vec<_> = (0..1 + rng.below(3))
+        .map(|_| {
+            let fields: Vec<Type> =
+                (0..rng.below(3)).map(|_| gen_type::<T>(rng, depth - 1)).collect();
+            layout_fields::<T>(rng, 1, &fields)
+        })
+        .collect();
+    let enum_align = variants.iter().map(|(_, _, variant_align)| *variant_align).max().unwrap();
+    let enum_size = round_up(variants.iter().map(|(_, end, _)| *end).max().unwrap(), enum_align);
+
+    let variants: Vec<(u8, Variant)> = variants
+        .into_iter()
+        .enumerate()
+        .map(|(discriminant, (fields, _, variant_align))| {
+            let ty = tuple_ty(&fields, size(enum_size), align(variant_align));
+            let tag = (IntType::U8, Int::from(discriminant));
+            (discriminant as u8, enum_variant(ty, &[(Offset::ZERO, tag)]))
+        })
+        .collect();
+    let children: Vec<((u8, u8), Discriminator)> = variants
+        .iter()
+        .map(|&(discriminant, _)| {
+            ((discriminant, discriminant + 1), discriminator_known(discriminant))
+        })
+        .collect();
+    let discriminator =
+        discriminator_branch::<u8>(Offset::ZERO, discriminator_invalid(), &children);
+    enum_ty::<u8>(&variants, discriminator, size(enum_size), align(enum_align))
+}
+
+/// An enum like `Option<(bool, ..)>`: `None` is stored as the invalid `bool` value 2.
+fn gen_niche_enum_type<T: Target>(rng: &mut Rng, depth: usize) -> Type {
+    let mut fields = vec![bool_ty()];
+    fields.extend((0..rng.below(3)).map(|_| gen_type::<T>(rng, depth - 1)));
+    let (fields, end, data_align) = layout_fields::<T>(rng, 0, &fields);
+    let enum_size = round_up(end, data_align);
+
+    let some = enum_variant(tuple_ty(&fields, size(enum_size), align(data_align)), &[]);
+    let none = enum_variant(tuple_ty(&[], size(enum_size), align(1)), &[(
+        Offset::ZERO,
+        (IntType::U8, Int::from(2)),
+    )]);
+    let discriminator = discriminator_branch::<u8>(Offset::ZERO, discriminator_invalid(), &[
+        ((0, 2), discriminator_known(1)),
+        ((2, 3), discriminator_known(0)),
+    ]);
+    enum_ty::<u8>(&[(0, none), (1, some)], discriminator, size(enum_size), align(data_align))
+}
+
+/// A random integer of the given type.
+fn gen_int(rng: &mut Rng, int_ty: IntType) -> Int {
+    let bits = (Int::from(rng.next_u64()) << Int::from(64)) + Int::from(rng.next_u64());
+    int_ty.bring_in_bounds(bits)
+}
+
+fn gen_byte<M: Memory>(rng: &mut Rng) -> AbstractByte<M::Provenance> {
+    if rng.percent(20) {
+        AbstractByte::Uninit
+    } else {
+        AbstractByte::Init(rng.next_u64() as u8, None)
+    }
+}
+
+/// The integer a `valid_range` restricts, like `Value::restricted_scalar` in the spec.
+fn restricted_scalar<M: Memory>(val: Value<M>) -> Int {
+    match val {
+        Value::Tuple(fields) =>
+            match fields.get(Int::ZERO) {
+                Some(Value::Int(i)) => i,
+                Some(Value::Ptr(ptr)) => ptr.thin_pointer.addr,
+                _ => panic!("valid range on a field that is neither an integer nor a pointer"),
+            },
+        _ => panic!("valid range on a non-tuple value"),
+    }
+}
+
+/// A random value that is well-formed for `ty`, which must be sized and not contain references or boxes.
+/// Pointers are random addresses without provenance.
+pub(crate) fn gen_value<M: Memory>(rng: &mut Rng, ty: Type) -> Value<M> {
+    match ty {
+        Type::Bool => Value::Bool(rng.percent(50)),
+        Type::Int(int_ty) => Value::Int(gen_int(rng, int_ty)),
+        Type::Char => {
+            // Skip the surrogates.
+            let code_point = rng.below(0x10_f800) as u32;
+            let code_point = if code_point < 0xd800 { code_point } else { code_point + 0x800 };
+            Value::Int(Int::from(code_point))
+        }
+        Type::Float(FloatType { size }) =>
+            Value::Float(gen_int(rng, IntType { signed: Signedness::Unsigned, size })),
+        Type::Ptr(_) => {
+            let addr = gen_int(rng, IntType::usize_ty::<M::T>());
+            Value::Ptr(ThinPointer { addr, provenance: None }.widen(None))
+        }
+        Type::Tuple { sized_fields, valid_range, .. } =>
+            loop {
+                let val = Value::Tuple(
+                    sized_fields.iter().map(|(_, ty)| gen_value::<M>(rng, ty)).collect(),
+                );
+                if valid_range.is_none_or(|range| range.contains(restricted_scalar(val))) {
+                    break val;
+                }
+            },
+        Type::Array { elem, count } =>
+            Value::Tuple((Int::ZERO..count).map(|_| gen_value::<M>(rng, elem.extract())).collect()),
+        Type::Union { chunks, .. } =>
+            Value::Union(
+                chunks
+                    .iter()
+                    .map(|(_, size)| {
+                        (Int::ZERO..size.bytes()).map(|_| gen_byte::<M>(rng)).collect()
+                    })
+                    .collect(),
+            ),
+        Type::Enum { variants, .. } => {
+            let discriminants: Vec<Int> = variants.keys().collect();
+            let discriminant = rng.pick(&discriminants);
+            let data = gen_value::<M>(rng, variants.get(discriminant).unwrap().ty);
+            Value::Variant { discriminant, data: GcCow::new(data) }
+        }
+        _ => panic!("`gen_value` called on a type `gen_type` does not generate"),
+    }
+}
@@ -1,51 +1,252 @@
-use crate::{mock_write::MockWrite, *};
+use std::sync::atomic::{self, AtomicBool};
+
+use crate::{
+    env::{Environment, handle_extern_call},
+    interp::Interpreter,
+    mock_write::MockWrite,
+    *,
+};
 
 /// Run the program and return its TerminationInfo.
-/// Stdout/stderr are just forwarded to the host.
-pub fn run_program<M: Memory>(prog: Program) -> TerminationInfo {
-    let out = std::io::stdout();
-    let err = std::io::stderr();
-
-    let res: Result<!, TerminationInfo> = run::<M>(prog, out, err);
-    match res {
-        Ok(never) => never,
-        Err(t) => t,
-    }
+/// Stdout/stderr are just forwarded to the host, warnings go to the host's stderr.
+pub fn run_program<M: MemoryTooling>(prog: Program) -> TerminationInfo {
+    run::<M>(prog, std::io::stdout(), std::io::stderr(), std::io::stderr())
 }
 
 /// Run the program and return stdout as a `Vec<String>`  or a termination info
-/// if it did not terminate correctly. Stderr is just forwarded to the host.
-pub fn get_stdout<M: Memory>(prog: Program) -> Result<Vec<String>, TerminationInfo> {
+/// if it did not terminate correctly. Stderr and warnings are just forwarded to the host's stderr.
+pub fn get_stdout<M: MemoryTooling>(prog: Program) -> Result<Vec<String>, TerminationInfo> {
     let out = MockWrite::new();
-    let err = std::io::stderr();
+    match run::<M>(prog, out.clone(), std::io::stderr(), std::io::stderr()) {
+        TerminationInfo::MachineStop => Ok(out.into_strings()),
+        info => Err(info),
+    }
+}
+
+/// How a program terminated, together with everything it printed
+/// and all warnings the machine emitted.
+#[derive(Debug)]
+pub struct Output {
+    pub info: TerminationInfo,
+    pub stdout: Vec<String>,
+    pub stderr: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl Output {
+    /// The output of a program that terminated with `info` without printing anything.
+    pub fn without_output(info: TerminationInfo) -> Output {
+        Output { info, stdout: Vec::new(), stderr: Vec::new(), warnings: Vec::new() }
+    }
+}
+
+/// Run the program to completion, capturing stdout, stderr, and warnings.
+pub fn run_program_with_output<M: MemoryTooling>(prog: Program) -> Output {
+    run_program_inspect::<M, ()>(prog, |_machine| ()).0
+}
+
+/// Run the program to completion with the given machine configuration,
+/// capturing stdout, stderr, and warnings.
+pub fn run_program_with_config<M: MemoryTooling>(prog: Program, config: MachineConfig) -> Output {
+    run_program_inspect_with_config::<M, ()>(prog, config, |_machine| ()).0
+}
+
+/// Run the program to completion with tracing enabled, capturing stdout, stderr, and warnings.
+/// Returns the output together with the recorded trace, which is empty if the program is ill-formed.
+pub fn run_program_with_trace<M: MemoryTooling>(prog: Program) -> (Output, List<TraceEvent>) {
+    let config = MachineConfig { trace: true, ..MachineConfig::DEFAULT };
+    let (output, trace) =
+        run_program_inspect_with_config::<M, _>(prog, config, |machine| machine.trace());
+    (output, trace.unwrap_or_else(List::new))
+}
+
+/// Run the program to completion, capturing stdout, stderr, and warnings,
+/// and call `inspect` on the machine in its final state.
+/// `inspect` is only called if a machine was created, i.e. the program is well-formed.
+pub fn run_program_inspect<M: MemoryTooling, R>(
+    prog: Program,
+    inspect: impl FnOnce(&Machine<M>) -> R,
+) -> (Output, Option<R>) {
+    run_program_inspect_with_config(prog, MachineConfig::DEFAULT, inspect)
+}
+
+/// Like `run_program_inspect`, but with the given machine configuration.
+pub fn run_program_inspect_with_config<M: MemoryTooling, R>(
+    prog: Program,
+    config: MachineConfig,
+    inspect: impl FnOnce(&Machine<M>) -> R,
+) -> (Output, Option<R>) {
+    match Interpreter::<M>::new_with_config(prog, config) {
+        Err(info) => (Output::without_output(info), None),
+        Ok(mut interp) => {
+            interp.run_to_completion();
+            let inspected = inspect(interp.machine());
+            (interp.output().unwrap(), Some(inspected))
+        }
+    }
+}
+
+/// A statement or terminator in a program.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Location {
+    pub func: FnName,
+    pub block: BbName,
+    /// The index of the statement in the block.
+    /// An index equal to the number of statements refers to the terminator.
+    pub stmt: Int,
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Use the same names as the pretty-printer.
+        let func = self.func.0.get_internal();
+        let block = self.block.0.get_internal();
+        write!(f, "f{func}, bb{block}, statement {}", self.stmt)
+    }
+}
+
+/// A statement or terminator, as found at a `Location`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Operation {
+    Statement(Statement),
+    Terminator(Terminator),
+}
 
-    let res = run::<M>(prog, out.clone(), err);
-    match res {
-        Ok(never) => never,
-        Err(TerminationInfo::MachineStop) => Ok(out.into_strings()),
-        Err(info) => Err(info),
+impl Location {
+    /// Returns the statement or terminator at this location in `prog`,
+    /// or `None` if the location does not exist in `prog`.
+    pub fn operation(&self, prog: Program) -> Option<Operation> {
+        let block = prog.functions.get(self.func)?.blocks.get(self.block)?;
+        if self.stmt == block.statements.len() {
+            Some(Operation::Terminator(block.terminator))
+        } else {
+            block.statements.get(self.stmt).map(Operation::Statement)
+        }
     }
 }
 
-/// Run the program to completion using the given writers for stdout/stderr.
-///
-/// We fix `BasicMemory` as a memory for now.
-fn run<M: Memory>(
+/// A structured description of the UB a program ran into,
+/// so that tools can inspect more than just the message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UbReport {
+    /// The message of the UB, as it would be shown to the user.
+    pub message: String,
+    /// The statement or terminator that raised the UB.
+    /// This is `None` if the UB occurred before any code was executed.
+    pub location: Option<Location>,
+    /// The statement or terminator at `location`.
+    pub operation: Option<Operation>,
+    /// The memory access that caused the UB, if any, e.g. the out-of-bounds load.
+    pub access: Option<UbAccess>,
+}
+
+/// Run the program and return a report of the UB it ran into.
+/// If the program terminated in any other way, that `TerminationInfo` is returned instead.
+/// Stdout/stderr are just forwarded to the host, warnings go to the host's stderr.
+pub fn run_program_ub_report<M: MemoryTooling>(prog: Program) -> Result<UbReport, TerminationInfo> {
+    let (info, location) = run_program_with_location::<M>(prog);
+    let TerminationInfo::Ub(message, access) = info else {
+        return Err(info);
+    };
+    let operation = location.and_then(|location| location.operation(prog));
+    Ok(UbReport { message: message.get_internal().to_string(), location, operation, access })
+}
+
+/// Run the program and return its TerminationInfo, together with the location
+/// the active thread was executing when the machine stopped.
+/// The location is `None` if the machine stopped before executing any code.
+/// Stdout/stderr are just forwarded to the host, warnings go to the host's stderr.
+pub fn run_program_with_location<M: MemoryTooling>(
+    prog: Program,
+) -> (TerminationInfo, Option<Location>) {
+    let mut interp = match Interpreter::<M>::new_with_writers(
+        prog,
+        MachineConfig::DEFAULT,
+        std::io::stdout(),
+        std::io::stderr(),
+        std::io::stderr(),
+    ) {
+        Ok(interp) => interp,
+        Err(info) => return (info, None),
+    };
+    let info = interp.run_to_completion();
+    (info, interp.location())
+}
+
+/// How an interruptible run ended.
+#[derive(Debug, PartialEq, Eq)]
+pub enum RunStatus {
+    /// The program terminated on its own.
+    Terminated(TerminationInfo),
+    /// The run was interrupted from the outside before the program terminated.
+    Interrupted,
+}
+
+/// The result of an interruptible run, including how far the machine got.
+#[derive(Debug)]
+pub struct InterruptibleRun {
+    pub status: RunStatus,
+    /// The number of steps the machine completed.
+    pub steps: u64,
+    /// The location the active thread was executing when the machine stopped.
+    /// For an interrupted run, this is the statement or terminator that would have been executed next.
+    pub location: Option<Location>,
+    /// The recorded trace, which is empty unless `MachineConfig::trace` is set.
+    pub trace: List<TraceEvent>,
+}
+
+/// Run the program until it terminates or `interrupt` becomes `true`, whichever happens first.
+/// `interrupt` is checked between steps, so it can be set from another thread (e.g. a Ctrl-C handler)
+/// to cancel long-running programs without losing everything they printed and where they were.
+/// The program's calls to extern functions are executed by `env`.
+pub fn run_program_interruptible<M: MemoryTooling>(
     prog: Program,
+    config: MachineConfig,
+    interrupt: &AtomicBool,
+    env: &mut impl Environment<M>,
     stdout: impl GcWrite,
     stderr: impl GcWrite,
-) -> Result<!, TerminationInfo> {
-    let res: NdResult<!> = try {
-        let mut machine = Machine::<M>::new(prog, DynWrite::new(stdout), DynWrite::new(stderr))?;
-
-        loop {
-            machine.step()?;
+    warnings: impl GcWrite,
+) -> InterruptibleRun {
+    let mut interp =
+        match Interpreter::<M>::new_with_writers(prog, config, stdout, stderr, warnings) {
+            Ok(interp) => interp,
+            Err(info) =>
+                return InterruptibleRun {
+                    status: RunStatus::Terminated(info),
+                    steps: 0,
+                    location: None,
+                    trace: List::new(),
+                },
+        };
 
-            // Drops everything not reachable from `machine`.
-            mark_and_sweep(&machine);
+    let status = loop {
+        if interrupt.load(atomic::Ordering::Relaxed) {
+            break RunStatus::Interrupted;
+        }
+        if let Some(info) = interp.step() {
+            break RunStatus::Terminated(info);
         }
+        handle_extern_call(&mut interp, env);
     };
+    InterruptibleRun {
+        status,
+        steps: interp.steps(),
+        location: interp.location(),
+        trace: interp.machine().trace(),
+    }
+}
 
-    // Extract the TerminationInfo from the `NdResult<!>`.
-    res.get_internal()
+/// Run the program to completion using the given writers for stdout/stderr and warnings.
+fn run<M: MemoryTooling>(
+    prog: Program,
+    stdout: impl GcWrite,
+    stderr: impl GcWrite,
+    warnings: impl GcWrite,
+) -> TerminationInfo {
+    match Interpreter::<M>::new_with_writers(prog, MachineConfig::DEFAULT, stdout, stderr, warnings)
+    {
+        Ok(mut interp) => interp.run_to_completion(),
+        Err(info) => info,
+    }
 }
@@ -0,0 +1,251 @@
+//! Control over the thread schedule.
+//!
+//! The spec picks the thread that takes the next step non-deterministically, which in practice
+//! means uniformly at random. Interleavings that need several unlikely choices in a row, like two
+//! racing accesses in consecutive steps, are then hardly ever explored. The schedulers here make
+//! that choice instead, and can also pick the stores that atomic loads read from. Everything else
+//! the spec leaves non-deterministic (addresses, spurious failures) is still picked at random.
+
+use crate::{interp::Interpreter, rng::Rng, run::Output, *};
+
+/// Picks the thread that takes the next step.
+pub trait Scheduler {
+    /// `enabled` lists the threads that can take a step, in increasing order. It is never empty.
+    /// The result must be one of them.
+    fn next_thread(&mut self, enabled: &[ThreadId]) -> ThreadId;
+
+    /// The store that atomic loads in the step of the thread picked last read from, see
+    /// `Machine::fix_load_choice`. By default, the machine picks it non-deterministically.
+    fn next_load_choice(&mut self) -> Option<usize> {
+        None
+    }
+
+    /// Called after every step the scheduler picked the thread for, unless the program terminated.
+    /// `load_options` is the number of stores an atomic load in that step could read from,
+    /// or 0 if there was no such load.
+    fn step_taken(&mut self, _load_options: usize) {}
+}
+
+/// Lets every enabled thread take `quantum` steps in turn.
+pub struct RoundRobin {
+    quantum: u64,
+    current: Option<ThreadId>,
+    /// The number of steps `current` has taken since it got scheduled.
+    steps: u64,
+}
+
+impl RoundRobin {
+    pub fn new(quantum: u64) -> Self {
+        assert!(quantum > 0, "the quantum of a round-robin scheduler must not be 0");
+        RoundRobin { quantum, current: None, steps: 0 }
+    }
+}
+
+impl Scheduler for RoundRobin {
+    fn next_thread(&mut self, enabled: &[ThreadId]) -> ThreadId {
+        if let Some(current) = self.current {
+            if self.steps < self.quantum && enabled.contains(&current) {
+                self.steps += 1;
+                return current;
+            }
+        }
+
+        // Switch to the next enabled thread after the current one, wrapping around.
+        let next = match self.current {
+            Some(current) => enabled.iter().copied().find(|&id| id > current).unwrap_or(enabled[0]),
+            None => enabled[0],
+        };
+        self.current = Some(next);
+        self.steps = 1;
+        next
+    }
+}
+
+/// Picks a thread uniformly at random, reproducibly for a given seed.
+pub struct SeededRandom {
+    rng: Rng,
+}
+
+impl SeededRandom {
+    pub fn new(seed: u64) -> Self {
+        SeededRandom { rng: Rng::new(seed) }
+    }
+}
+
+impl Scheduler for SeededRandom {
+    fn next_thread(&mut self, enabled: &[ThreadId]) -> ThreadId {
+        self.rng.pick(enabled)
+    }
+}
+
+/// Enumerates schedules depth-first, one run at a time: after each run, call `next_schedule`
+/// to move on to the next schedule, until it returns `false`.
+///
+/// A preemption is a switch away from a thread that could have kept running. Only schedules
+/// with at most `max_preemptions` preemptions are explored; most concurrency bugs need very few.
+/// For every atomic load, all stores it may read from are explored as well.
+/// This assumes that each step performs at most one atomic load, and that the schedule and the
+/// loads determine the run, which is not the case if the program makes other non-deterministic
+/// choices that affect which threads are enabled.
+pub struct Exhaustive {
+    max_preemptions: usize,
+    /// All choice points of the current run with more than one option, in the order they were reached.
+    trail: Vec<ChoicePoint>,
+    /// The next choice point in `trail`.
+    pos: usize,
+    /// The number of steps taken so far in the current run.
+    step: u64,
+    /// The number of preemptions so far in the current run.
+    preemptions: usize,
+    /// The thread that took the previous step.
+    last: Option<ThreadId>,
+}
+
+/// A choice of thread or of the store an atomic load reads from.
+struct ChoicePoint {
+    /// The step in which the choice was made.
+    step: u64,
+    /// The index of the option taken.
+    taken: usize,
+    options: usize,
+}
+
+impl Exhaustive {
+    pub fn new(max_preemptions: usize) -> Self {
+        Exhaustive {
+            max_preemptions,
+            trail: Vec::new(),
+            pos: 0,
+            step: 0,
+            preemptions: 0,
+            last: None,
+        }
+    }
+
+    /// Prepares the next run. Returns `false` if all schedules have been explored.
+    pub fn next_schedule(&mut self) -> bool {
+        // Choice points the last run did not reach are not part of its schedule.
+        self.trail.truncate(self.pos);
+        self.pos = 0;
+        self.step = 0;
+        self.preemptions = 0;
+        self.last = None;
+
+        // Backtrack to the last choice point that has options left, and take the next option there.
+        while let Some(point) = self.trail.pop() {
+            if point.taken + 1 < point.options {
+                self.trail.push(ChoicePoint { taken: point.taken + 1, ..point });
+                return true;
+            }
+        }
+        false
+    }
+
+    /// The option the previous run took at the next choice point, if that choice point is in the current step.
+    /// This replays the prefix of the run that is shared with the previous run.
+    fn replayed(&self) -> Option<usize> {
+        self.trail.get(self.pos).filter(|point| point.step == self.step).map(|point| point.taken)
+    }
+
+    /// Makes a choice between `options` options in the current step, and returns the index of the option taken.
+    fn choose(&mut self, options: usize) -> usize {
+        if options <= 1 {
+            return 0;
+        }
+        let replayed = self.replayed();
+        let taken = replayed.unwrap_or(0).min(options - 1);
+        let point = ChoicePoint { step: self.step, taken, options };
+        if replayed.is_some() {
+            self.trail[self.pos] = point;
+        } else {
+            // The choice points the previous run reached after this one are not part of this run.
+            self.trail.truncate(self.pos);
+            self.trail.push(point);
+        }
+        self.pos += 1;
+        taken
+    }
+}
+
+impl Scheduler for Exhaustive {
+    fn next_thread(&mut self, enabled: &[ThreadId]) -> ThreadId {
+        // Continuing with the previous thread is the first option, so it is explored first.
+        let continues = self.last.filter(|last| enabled.contains(last));
+        let mut options: Vec<ThreadId> = continues.into_iter().collect();
+        options.extend(enabled.iter().copied().filter(|&id| Some(id) != continues));
+        if continues.is_some() && self.preemptions >= self.max_preemptions {
+            options.truncate(1);
+        }
+
+        let idx = self.choose(options.len());
+        if continues.is_some() && idx > 0 {
+            self.preemptions += 1;
+        }
+        self.last = Some(options[idx]);
+        options[idx]
+    }
+
+    fn next_load_choice(&mut self) -> Option<usize> {
+        // The oldest store a load may read from is the first option, so it is explored first.
+        Some(self.replayed().unwrap_or(0))
+    }
+
+    fn step_taken(&mut self, load_options: usize) {
+        self.choose(load_options);
+        self.step += 1;
+    }
+}
+
+/// Run the program with the given scheduler for at most `max_steps` steps,
+/// capturing stdout, stderr, and warnings.
+/// Returns `None` if the program did not terminate within `max_steps` steps.
+pub fn run_program_scheduled<M: MemoryTooling>(
+    prog: Program,
+    scheduler: &mut impl Scheduler,
+    max_steps: u64,
+) -> Option<Output> {
+    let mut interp = match Interpreter::<M>::new(prog) {
+        Ok(interp) => interp,
+        Err(info) => return Some(Output::without_output(info)),
+    };
+    for _ in 0..max_steps {
+        let enabled: Vec<ThreadId> = interp.machine().enabled_threads().iter().collect();
+        // Without enabled threads, `step` reports the deadlock.
+        let terminated = interp.step_with(|machine| {
+            if enabled.is_empty() {
+                machine.step()
+            } else {
+                let thread = scheduler.next_thread(&enabled);
+                machine.fix_load_choice(scheduler.next_load_choice().map(Int::from));
+                machine.step_thread(thread)
+            }
+        });
+        if terminated.is_some() {
+            return interp.output();
+        }
+        scheduler.step_taken(interp.machine().load_options().try_to_usize().unwrap());
+    }
+    None
+}
+
+/// Run the program under every schedule with at most `max_preemptions` preemptions,
+/// and with every choice of store for its atomic loads, and call `f` with the output of each run (`None` if it took more than `max_steps` steps).
+/// The output is captured, not forwarded to the host.
+/// Stops early and returns `true` as soon as `f` returns `true`.
+pub fn explore_schedules<M: MemoryTooling>(
+    prog: Program,
+    max_preemptions: usize,
+    max_steps: u64,
+    mut f: impl FnMut(Option<Output>) -> bool,
+) -> bool {
+    let mut scheduler = Exhaustive::new(max_preemptions);
+    loop {
+        let output = run_program_scheduled::<M>(prog, &mut scheduler, max_steps);
+        if f(output) {
+            return true;
+        }
+        if !scheduler.next_schedule() {
+            return false;
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use super::*;
+
+impl Text for Int {
+    fn to_sexp(self) -> Sexp {
+        Sexp::atom(self)
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let text = s.as_atom()?;
+        if let Ok(i) = text.parse::<i128>() {
+            Ok(Int::from(i))
+        } else if let Ok(u) = text.parse::<u128>() {
+            Ok(Int::from(u))
+        } else {
+            Err(s.error(format!("expected an integer, found `{text}`")))
+        }
+    }
+}
+
+impl Text for bool {
+    fn to_sexp(self) -> Sexp {
+        Sexp::atom(self)
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        match s.as_atom()? {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            other => Err(s.error(format!("expected `true` or `false`, found `{other}`"))),
+        }
+    }
+}
+
+// Sizes and offsets are given in bytes.
+impl Text for Size {
+    fn to_sexp(self) -> Sexp {
+        self.bytes().to_sexp()
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        Size::from_bytes(Int::from_sexp(s)?).ok_or_else(|| s.error("invalid size"))
+    }
+}
+
+impl Text for Align {
+    fn to_sexp(self) -> Sexp {
+        self.bytes().to_sexp()
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        Align::from_bytes(Int::from_sexp(s)?).ok_or_else(|| s.error("invalid alignment"))
+    }
+}
+
+impl<T: Text> Text for Option<T> {
+    fn to_sexp(self) -> Sexp {
+        match self {
+            None => Sexp::atom("None"),
+            Some(x) => Sexp::node("Some", vec![x.to_sexp()]),
+        }
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        match s.as_node()? {
+            ("None", []) => Ok(None),
+            ("Some", args) => {
+                let [x] = s.args(args)?;
+                Ok(Some(T::from_sexp(x)?))
+            }
+            _ => Err(s.error(format!("expected `None` or `(Some ..)`, found `{}`", s.flat()))),
+        }
+    }
+}
+
+impl<A: Text, B: Text> Text for (A, B) {
+    fn to_sexp(self) -> Sexp {
+        Sexp::list(vec![self.0.to_sexp(), self.1.to_sexp()])
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let [a, b] = s.args(s.as_list()?)?;
+        Ok((A::from_sexp(a)?, B::from_sexp(b)?))
+    }
+}
+
+/// Implements `Text` for name types, which are written as a prefix followed by the number of the name.
+macro_rules! name_impls {
+    ($($name:ident => $prefix:literal,)*) => { $(
+        impl Text for $name {
+            fn to_sexp(self) -> Sexp {
+                Sexp::atom(format!("{}{}", $prefix, self.0.get_internal()))
+            }
+
+            fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+                let text = s.as_atom()?;
+                match text.strip_prefix($prefix).and_then(|id| id.parse().ok()) {
+                    Some(id) => Ok($name(Name::from_internal(id))),
+                    None => Err(s.error(format!("expected a name like `{}0`, found `{text}`", $prefix))),
+                }
+            }
+        }
+    )* };
+}
+
+name_impls! {
+    FnName => "f",
+    BbName => "bb",
+    LocalName => "_",
+    GlobalName => "g",
+    VTableName => "vtable",
+    TraitName => "trait",
+    TraitMethodName => "m",
+}
+
+/// Implements `Text` for enums whose variants carry no data. Each variant is written as its name.
+pub(super) macro unit_enum_impls($($ty:ident { $($variant:ident),* $(,)? })*) { $(
+    impl Text for $ty {
+        fn to_sexp(self) -> Sexp {
+            let name = match self {
+                $($ty::$variant => stringify!($variant),)*
+            };
+            Sexp::atom(name)
+        }
+
+        fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+            match s.as_atom()? {
+                $(stringify!($variant) => Ok($ty::$variant),)*
+                other => Err(s.error(format!("unknown {} `{other}`", stringify!($ty)))),
+            }
+        }
+    }
+)* }
+
+unit_enum_impls! {
+    Mutability { Mutable, Immutable }
+}
+
+/// Lists keep the order of their elements.
+pub(super) fn list_to_sexp<T: Text>(items: impl IntoIterator<Item = T>) -> Sexp {
+    Sexp::list(items.into_iter().map(Text::to_sexp).collect())
+}
+
+pub(super) fn list_from_sexp<T: Text, C: FromIterator<T>>(s: &Sexp) -> ParseResult<C> {
+    s.as_list()?.iter().map(T::from_sexp).collect()
+}
+
+/// Sets and maps are sorted, so that the output does not depend on their iteration order.
+pub(super) fn set_to_sexp<T: Text>(items: impl IntoIterator<Item = T>) -> Sexp {
+    Sexp::sorted_list(items.into_iter().map(Text::to_sexp).collect())
+}
+
+pub(super) fn set_from_sexp<T: Text, C: FromIterator<T>>(s: &Sexp) -> ParseResult<C> {
+    list_from_sexp(s)
+}
+
+pub(super) fn map_to_sexp<K: Text, V: Text>(entries: impl IntoIterator<Item = (K, V)>) -> Sexp {
+    set_to_sexp(entries)
+}
+
+/// Returns the `(key value)` pairs of a map, checking that no key appears twice.
+pub(super) fn map_entries(s: &Sexp) -> ParseResult<Vec<(&Sexp, &Sexp)>> {
+    let mut keys = std::collections::HashSet::new();
+    let mut entries = Vec::new();
+    for entry in s.as_list()? {
+        let [key, value] = entry.args(entry.as_list()?)?;
+        if !keys.insert(key.flat()) {
+            return Err(key.error(format!("duplicate key `{}`", key.flat())));
+        }
+        entries.push((key, value));
+    }
+    Ok(entries)
+}
+
+pub(super) fn map_from_sexp<K: Text, V: Text, C: FromIterator<(K, V)>>(s: &Sexp) -> ParseResult<C> {
+    map_entries(s)?.into_iter().map(|(k, v)| Ok((K::from_sexp(k)?, V::from_sexp(v)?))).collect()
+}
@@ -0,0 +1,350 @@
+use super::*;
+
+unit_enum_impls! {
+    IntUnOp { Neg, BitNot, CountOnes }
+    FloatUnOp { Neg }
+    IntBinOp {
+        Add, AddUnchecked, Sub, SubUnchecked, Mul, MulUnchecked, Div, DivExact, AddSaturating,
+        SubSaturating, Rem, Shl, ShlUnchecked, Shr, ShrUnchecked, BitAnd, BitOr, BitXor,
+    }
+    IntBinOpWithOverflow { Add, Sub, Mul }
+    FloatBinOp { Add, Sub, Mul, Div, Rem }
+    RelOp { Lt, Gt, Le, Ge, Eq, Ne, Cmp }
+}
+
+impl Text for Relocation {
+    fn to_sexp(self) -> Sexp {
+        Sexp::record("Relocation", vec![
+            ("name", self.name.to_sexp()),
+            ("offset", self.offset.to_sexp()),
+        ])
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let [name, offset] = s.as_record("Relocation", ["name", "offset"])?;
+        Ok(Relocation { name: GlobalName::from_sexp(name)?, offset: Offset::from_sexp(offset)? })
+    }
+}
+
+impl Text for Constant {
+    fn to_sexp(self) -> Sexp {
+        match self {
+            Constant::Int(int) => Sexp::node("Int", vec![int.to_sexp()]),
+            Constant::Bool(b) => Sexp::node("Bool", vec![b.to_sexp()]),
+            Constant::Float(bits) => Sexp::node("Float", vec![bits.to_sexp()]),
+            Constant::GlobalPointer(relocation) =>
+                Sexp::node("GlobalPointer", vec![relocation.to_sexp()]),
+            Constant::FnPointer(fn_name) => Sexp::node("FnPointer", vec![fn_name.to_sexp()]),
+            Constant::VTablePointer(vtable_name) =>
+                Sexp::node("VTablePointer", vec![vtable_name.to_sexp()]),
+            Constant::PointerWithoutProvenance(addr) =>
+                Sexp::node("PointerWithoutProvenance", vec![addr.to_sexp()]),
+        }
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let constant = match s.as_node()? {
+            ("Int", [int]) => Constant::Int(Int::from_sexp(int)?),
+            ("Bool", [b]) => Constant::Bool(bool::from_sexp(b)?),
+            ("Float", [bits]) => Constant::Float(Int::from_sexp(bits)?),
+            ("GlobalPointer", [relocation]) =>
+                Constant::GlobalPointer(Relocation::from_sexp(relocation)?),
+            ("FnPointer", [fn_name]) => Constant::FnPointer(FnName::from_sexp(fn_name)?),
+            ("VTablePointer", [vtable_name]) =>
+                Constant::VTablePointer(VTableName::from_sexp(vtable_name)?),
+            ("PointerWithoutProvenance", [addr]) =>
+                Constant::PointerWithoutProvenance(Address::from_sexp(addr)?),
+            _ => return Err(s.error(format!("invalid constant `{}`", s.flat()))),
+        };
+        Ok(constant)
+    }
+}
+
+impl Text for CastOp {
+    fn to_sexp(self) -> Sexp {
+        match self {
+            CastOp::IntToInt(int_ty) => Sexp::node("IntToInt", vec![int_ty.to_sexp()]),
+            CastOp::Transmute(ty) => Sexp::node("Transmute", vec![ty.to_sexp()]),
+        }
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let cast_op = match s.as_node()? {
+            ("IntToInt", [int_ty]) => CastOp::IntToInt(IntType::from_sexp(int_ty)?),
+            ("Transmute", [ty]) => CastOp::Transmute(Type::from_sexp(ty)?),
+            _ => return Err(s.error(format!("invalid cast `{}`", s.flat()))),
+        };
+        Ok(cast_op)
+    }
+}
+
+impl Text for UnOp {
+    fn to_sexp(self) -> Sexp {
+        match self {
+            UnOp::Int(op) => Sexp::node("Int", vec![op.to_sexp()]),
+            UnOp::Float(op) => Sexp::node("Float", vec![op.to_sexp()]),
+            UnOp::Cast(op) => Sexp::node("Cast", vec![op.to_sexp()]),
+            UnOp::GetThinPointer => Sexp::atom("GetThinPointer"),
+            UnOp::GetMetadata => Sexp::atom("GetMetadata"),
+            UnOp::ComputeSize(ty) => Sexp::node("ComputeSize", vec![ty.to_sexp()]),
+            UnOp::ComputeAlign(ty) => Sexp::node("ComputeAlign", vec![ty.to_sexp()]),
+            UnOp::VTableMethodLookup(method) =>
+                Sexp::node("VTableMethodLookup", vec![method.to_sexp()]),
+        }
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let op = match s.as_node()? {
+            ("Int", [op]) => UnOp::Int(IntUnOp::from_sexp(op)?),
+            ("Float", [op]) => UnOp::Float(FloatUnOp::from_sexp(op)?),
+            ("Cast", [op]) => UnOp::Cast(CastOp::from_sexp(op)?),
+            ("GetThinPointer", []) => UnOp::GetThinPointer,
+            ("GetMetadata", []) => UnOp::GetMetadata,
+            ("ComputeSize", [ty]) => UnOp::ComputeSize(Type::from_sexp(ty)?),
+            ("ComputeAlign", [ty]) => UnOp::ComputeAlign(Type::from_sexp(ty)?),
+            ("VTableMethodLookup", [method]) =>
+                UnOp::VTableMethodLookup(TraitMethodName::from_sexp(method)?),
+            _ => return Err(s.error(format!("invalid unary operator `{}`", s.flat()))),
+        };
+        Ok(op)
+    }
+}
+
+impl Text for BinOp {
+    fn to_sexp(self) -> Sexp {
+        match self {
+            BinOp::Int(op) => Sexp::node("Int", vec![op.to_sexp()]),
+            BinOp::IntWithOverflow(op) => Sexp::node("IntWithOverflow", vec![op.to_sexp()]),
+            BinOp::Float(op) => Sexp::node("Float", vec![op.to_sexp()]),
+            BinOp::Rel(op) => Sexp::node("Rel", vec![op.to_sexp()]),
+            BinOp::PtrOffset { inbounds } =>
+                Sexp::record("PtrOffset", vec![("inbounds", inbounds.to_sexp())]),
+            BinOp::PtrOffsetFrom { inbounds, nonneg } =>
+                Sexp::record("PtrOffsetFrom", vec![
+                    ("inbounds", inbounds.to_sexp()),
+                    ("nonneg", nonneg.to_sexp()),
+                ]),
+            BinOp::PtrWithAddr => Sexp::atom("PtrWithAddr"),
+            BinOp::ConstructWidePointer(ptr_ty) =>
+                Sexp::node("ConstructWidePointer", vec![ptr_ty.to_sexp()]),
+        }
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let op = match s.as_node()? {
+            ("Int", [op]) => BinOp::Int(IntBinOp::from_sexp(op)?),
+            ("IntWithOverflow", [op]) =>
+                BinOp::IntWithOverflow(IntBinOpWithOverflow::from_sexp(op)?),
+            ("Float", [op]) => BinOp::Float(FloatBinOp::from_sexp(op)?),
+            ("Rel", [op]) => BinOp::Rel(RelOp::from_sexp(op)?),
+            ("PtrOffset", args) => {
+                let [inbounds] = s.fields(args, ["inbounds"])?;
+                BinOp::PtrOffset { inbounds: bool::from_sexp(inbounds)? }
+            }
+            ("PtrOffsetFrom", args) => {
+                let [inbounds, nonneg] = s.fields(args, ["inbounds", "nonneg"])?;
+                BinOp::PtrOffsetFrom {
+                    inbounds: bool::from_sexp(inbounds)?,
+                    nonneg: bool::from_sexp(nonneg)?,
+                }
+            }
+            ("PtrWithAddr", []) => BinOp::PtrWithAddr,
+            ("ConstructWidePointer", [ptr_ty]) =>
+                BinOp::ConstructWidePointer(PtrType::from_sexp(ptr_ty)?),
+            _ => return Err(s.error(format!("invalid binary operator `{}`", s.flat()))),
+        };
+        Ok(op)
+    }
+}
+
+impl Text for ValueExpr {
+    fn to_sexp(self) -> Sexp {
+        match self {
+            ValueExpr::Constant(c, ty) => Sexp::node("Constant", vec![c.to_sexp(), ty.to_sexp()]),
+            ValueExpr::Tuple(exprs, ty) =>
+                Sexp::node("Tuple", vec![list_to_sexp(exprs), ty.to_sexp()]),
+            ValueExpr::Union { field, expr, union_ty } =>
+                Sexp::record("Union", vec![
+                    ("field", field.to_sexp()),
+                    ("expr", expr.extract().to_sexp()),
+                    ("union_ty", union_ty.to_sexp()),
+                ]),
+            ValueExpr::Variant { discriminant, data, enum_ty } =>
+                Sexp::record("Variant", vec![
+                    ("discriminant", discriminant.to_sexp()),
+                    ("data", data.extract().to_sexp()),
+                    ("enum_ty", enum_ty.to_sexp()),
+                ]),
+            ValueExpr::GetDiscriminant { place } =>
+                Sexp::record("GetDiscriminant", vec![("place", place.extract().to_sexp())]),
+            ValueExpr::Load { source } =>
+                Sexp::record("Load", vec![("source", source.extract().to_sexp())]),
+            ValueExpr::AddrOf { target, ptr_ty } =>
+                Sexp::record("AddrOf", vec![
+                    ("target", target.extract().to_sexp()),
+                    ("ptr_ty", ptr_ty.to_sexp()),
+                ]),
+            ValueExpr::UnOp { operator, operand } =>
+                Sexp::record("UnOp", vec![
+                    ("operator", operator.to_sexp()),
+                    ("operand", operand.extract().to_sexp()),
+                ]),
+            ValueExpr::BinOp { operator, left, right } =>
+                Sexp::record("BinOp", vec![
+                    ("operator", operator.to_sexp()),
+                    ("left", left.extract().to_sexp()),
+                    ("right", right.extract().to_sexp()),
+                ]),
+        }
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let expr = match s.as_node()? {
+            ("Constant", [c, ty]) =>
+                ValueExpr::Constant(Constant::from_sexp(c)?, Type::from_sexp(ty)?),
+            ("Tuple", [exprs, ty]) =>
+                ValueExpr::Tuple(list_from_sexp(exprs)?, Type::from_sexp(ty)?),
+            ("Union", args) => {
+                let [field, expr, union_ty] = s.fields(args, ["field", "expr", "union_ty"])?;
+                ValueExpr::Union {
+                    field: Int::from_sexp(field)?,
+                    expr: GcCow::new(ValueExpr::from_sexp(expr)?),
+                    union_ty: Type::from_sexp(union_ty)?,
+                }
+            }
+            ("Variant", args) => {
+                let [discriminant, data, enum_ty] =
+                    s.fields(args, ["discriminant", "data", "enum_ty"])?;
+                ValueExpr::Variant {
+                    discriminant: Int::from_sexp(discriminant)?,
+                    data: GcCow::new(ValueExpr::from_sexp(data)?),
+                    enum_ty: Type::from_sexp(enum_ty)?,
+                }
+            }
+            ("GetDiscriminant", args) => {
+                let [place] = s.fields(args, ["place"])?;
+                ValueExpr::GetDiscriminant { place: GcCow::new(PlaceExpr::from_sexp(place)?) }
+            }
+            ("Load", args) => {
+                let [source] = s.fields(args, ["source"])?;
+                ValueExpr::Load { source: GcCow::new(PlaceExpr::from_sexp(source)?) }
+            }
+            ("AddrOf", args) => {
+                let [target, ptr_ty] = s.fields(args, ["target", "ptr_ty"])?;
+                ValueExpr::AddrOf {
+                    target: GcCow::new(PlaceExpr::from_sexp(target)?),
+                    ptr_ty: PtrType::from_sexp(ptr_ty)?,
+                }
+            }
+            ("UnOp", args) => {
+                let [operator, operand] = s.fields(args, ["operator", "operand"])?;
+                ValueExpr::UnOp {
+                    operator: UnOp::from_sexp(operator)?,
+                    operand: GcCow::new(ValueExpr::from_sexp(operand)?),
+                }
+            }
+            ("BinOp", args) => {
+                let [operator, left, right] = s.fields(args, ["operator", "left", "right"])?;
+                ValueExpr::BinOp {
+                    operator: BinOp::from_sexp(operator)?,
+                    left: GcCow::new(ValueExpr::from_sexp(left)?),
+                    right: GcCow::new(ValueExpr::from_sexp(right)?),
+                }
+            }
+            _ => return Err(s.error(format!("invalid value expression `{}`", s.flat()))),
+        };
+        Ok(expr)
+    }
+}
+
+impl Text for PlaceExpr {
+    fn to_sexp(self) -> Sexp {
+        match self {
+            PlaceExpr::Local(local) => Sexp::node("Local", vec![local.to_sexp()]),
+            PlaceExpr::Deref { operand, ty } =>
+                Sexp::record("Deref", vec![
+                    ("operand", operand.extract().to_sexp()),
+                    ("ty", ty.to_sexp()),
+                ]),
+            PlaceExpr::Field { root, field } =>
+                Sexp::record("Field", vec![
+                    ("root", root.extract().to_sexp()),
+                    ("field", field.to_sexp()),
+                ]),
+            PlaceExpr::Index { root, index } =>
+                Sexp::record("Index", vec![
+                    ("root", root.extract().to_sexp()),
+                    ("index", index.extract().to_sexp()),
+                ]),
+            PlaceExpr::ConstantIndex { root, offset, from_end } =>
+                Sexp::record("ConstantIndex", vec![
+                    ("root", root.extract().to_sexp()),
+                    ("offset", offset.to_sexp()),
+                    ("from_end", from_end.to_sexp()),
+                ]),
+            PlaceExpr::Subslice { root, from, to } =>
+                Sexp::record("Subslice", vec![
+                    ("root", root.extract().to_sexp()),
+                    ("from", from.to_sexp()),
+                    ("to", to.to_sexp()),
+                ]),
+            PlaceExpr::Downcast { root, discriminant } =>
+                Sexp::record("Downcast", vec![
+                    ("root", root.extract().to_sexp()),
+                    ("discriminant", discriminant.to_sexp()),
+                ]),
+        }
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let place = match s.as_node()? {
+            ("Local", [local]) => PlaceExpr::Local(LocalName::from_sexp(local)?),
+            ("Deref", args) => {
+                let [operand, ty] = s.fields(args, ["operand", "ty"])?;
+                PlaceExpr::Deref {
+                    operand: GcCow::new(ValueExpr::from_sexp(operand)?),
+                    ty: Type::from_sexp(ty)?,
+                }
+            }
+            ("Field", args) => {
+                let [root, field] = s.fields(args, ["root", "field"])?;
+                PlaceExpr::Field {
+                    root: GcCow::new(PlaceExpr::from_sexp(root)?),
+                    field: Int::from_sexp(field)?,
+                }
+            }
+            ("Index", args) => {
+                let [root, index] = s.fields(args, ["root", "index"])?;
+                PlaceExpr::Index {
+                    root: GcCow::new(PlaceExpr::from_sexp(root)?),
+                    index: GcCow::new(ValueExpr::from_sexp(index)?),
+                }
+            }
+            ("ConstantIndex", args) => {
+                let [root, offset, from_end] = s.fields(args, ["root", "offset", "from_end"])?;
+                PlaceExpr::ConstantIndex {
+                    root: GcCow::new(PlaceExpr::from_sexp(root)?),
+                    offset: Int::from_sexp(offset)?,
+                    from_end: bool::from_sexp(from_end)?,
+                }
+            }
+            ("Subslice", args) => {
+                let [root, from, to] = s.fields(args, ["root", "from", "to"])?;
+                PlaceExpr::Subslice {
+                    root: GcCow::new(PlaceExpr::from_sexp(root)?),
+                    from: Int::from_sexp(from)?,
+                    to: Int::from_sexp(to)?,
+                }
+            }
+            ("Downcast", args) => {
+                let [root, discriminant] = s.fields(args, ["root", "discriminant"])?;
+                PlaceExpr::Downcast {
+                    root: GcCow::new(PlaceExpr::from_sexp(root)?),
+                    discriminant: Int::from_sexp(discriminant)?,
+                }
+            }
+            _ => return Err(s.error(format!("invalid place expression `{}`", s.flat()))),
+        };
+        Ok(place)
+    }
+}
@@ -0,0 +1,436 @@
+use super::*;
+
+unit_enum_impls! {
+    CallingConvention { Rust, C, CUnwind }
+    IntrinsicLockOp { Acquire, AcquireShared, Release, Create, CreateCondVar, Wait, NotifyOne, NotifyAll }
+    AtomicOrdering { Relaxed, Acquire, Release, AcqRel, SeqCst }
+    ValidityRequirement { Inhabited, Zero, MemUninitialized }
+}
+
+impl Text for Statement {
+    fn to_sexp(self) -> Sexp {
+        match self {
+            Statement::Assign { destination, source } =>
+                Sexp::record("Assign", vec![
+                    ("destination", destination.to_sexp()),
+                    ("source", source.to_sexp()),
+                ]),
+            Statement::PlaceMention(place) => Sexp::node("PlaceMention", vec![place.to_sexp()]),
+            Statement::SetDiscriminant { destination, value } =>
+                Sexp::record("SetDiscriminant", vec![
+                    ("destination", destination.to_sexp()),
+                    ("value", value.to_sexp()),
+                ]),
+            Statement::Validate { place, fn_entry, two_phase, behind_reference } =>
+                Sexp::record("Validate", vec![
+                    ("place", place.to_sexp()),
+                    ("fn_entry", fn_entry.to_sexp()),
+                    ("two_phase", two_phase.to_sexp()),
+                    ("behind_reference", behind_reference.to_sexp()),
+                ]),
+            Statement::Deinit { place } => Sexp::record("Deinit", vec![("place", place.to_sexp())]),
+            Statement::StorageLive { local, meta } =>
+                Sexp::record("StorageLive", vec![
+                    ("local", local.to_sexp()),
+                    ("meta", meta.to_sexp()),
+                ]),
+            Statement::StorageDead(local) => Sexp::node("StorageDead", vec![local.to_sexp()]),
+        }
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let st = match s.as_node()? {
+            ("Assign", args) => {
+                let [destination, source] = s.fields(args, ["destination", "source"])?;
+                Statement::Assign {
+                    destination: PlaceExpr::from_sexp(destination)?,
+                    source: ValueExpr::from_sexp(source)?,
+                }
+            }
+            ("PlaceMention", [place]) => Statement::PlaceMention(PlaceExpr::from_sexp(place)?),
+            ("SetDiscriminant", args) => {
+                let [destination, value] = s.fields(args, ["destination", "value"])?;
+                Statement::SetDiscriminant {
+                    destination: PlaceExpr::from_sexp(destination)?,
+                    value: Int::from_sexp(value)?,
+                }
+            }
+            ("Validate", args) => {
+                let [place, fn_entry, two_phase, behind_reference] =
+                    s.fields(args, ["place", "fn_entry", "two_phase", "behind_reference"])?;
+                Statement::Validate {
+                    place: PlaceExpr::from_sexp(place)?,
+                    fn_entry: bool::from_sexp(fn_entry)?,
+                    two_phase: bool::from_sexp(two_phase)?,
+                    behind_reference: bool::from_sexp(behind_reference)?,
+                }
+            }
+            ("Deinit", args) => {
+                let [place] = s.fields(args, ["place"])?;
+                Statement::Deinit { place: PlaceExpr::from_sexp(place)? }
+            }
+            ("StorageLive", args) => {
+                let [local, meta] = s.fields(args, ["local", "meta"])?;
+                Statement::StorageLive {
+                    local: LocalName::from_sexp(local)?,
+                    meta: Option::<ValueExpr>::from_sexp(meta)?,
+                }
+            }
+            ("StorageDead", [local]) => Statement::StorageDead(LocalName::from_sexp(local)?),
+            _ => return Err(s.error(format!("invalid statement `{}`", s.flat()))),
+        };
+        Ok(st)
+    }
+}
+
+impl Text for ArgumentExpr {
+    fn to_sexp(self) -> Sexp {
+        match self {
+            ArgumentExpr::ByValue(value) => Sexp::node("ByValue", vec![value.to_sexp()]),
+            ArgumentExpr::InPlace(place) => Sexp::node("InPlace", vec![place.to_sexp()]),
+        }
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let arg = match s.as_node()? {
+            ("ByValue", [value]) => ArgumentExpr::ByValue(ValueExpr::from_sexp(value)?),
+            ("InPlace", [place]) => ArgumentExpr::InPlace(PlaceExpr::from_sexp(place)?),
+            _ => return Err(s.error(format!("invalid argument `{}`", s.flat()))),
+        };
+        Ok(arg)
+    }
+}
+
+impl Text for AtomicRmwOp {
+    fn to_sexp(self) -> Sexp {
+        match self {
+            AtomicRmwOp::IntBinOp(op) => Sexp::node("IntBinOp", vec![op.to_sexp()]),
+            AtomicRmwOp::Nand => Sexp::atom("Nand"),
+            AtomicRmwOp::Min => Sexp::atom("Min"),
+            AtomicRmwOp::Max => Sexp::atom("Max"),
+            AtomicRmwOp::Swap => Sexp::atom("Swap"),
+        }
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let op = match s.as_node()? {
+            ("IntBinOp", [op]) => AtomicRmwOp::IntBinOp(IntBinOp::from_sexp(op)?),
+            ("Nand", []) => AtomicRmwOp::Nand,
+            ("Min", []) => AtomicRmwOp::Min,
+            ("Max", []) => AtomicRmwOp::Max,
+            ("Swap", []) => AtomicRmwOp::Swap,
+            _ => return Err(s.error(format!("invalid atomic operation `{}`", s.flat()))),
+        };
+        Ok(op)
+    }
+}
+
+impl Text for IntrinsicOp {
+    fn to_sexp(self) -> Sexp {
+        match self {
+            IntrinsicOp::Assume => Sexp::atom("Assume"),
+            IntrinsicOp::Exit => Sexp::atom("Exit"),
+            IntrinsicOp::Panic => Sexp::atom("Panic"),
+            IntrinsicOp::PrintStdout => Sexp::atom("PrintStdout"),
+            IntrinsicOp::PrintStderr => Sexp::atom("PrintStderr"),
+            IntrinsicOp::Allocate => Sexp::atom("Allocate"),
+            IntrinsicOp::Deallocate => Sexp::atom("Deallocate"),
+            IntrinsicOp::Spawn => Sexp::atom("Spawn"),
+            IntrinsicOp::Join => Sexp::atom("Join"),
+            IntrinsicOp::RawEq => Sexp::atom("RawEq"),
+            IntrinsicOp::CarryingAdd => Sexp::atom("CarryingAdd"),
+            IntrinsicOp::BorrowingSub => Sexp::atom("BorrowingSub"),
+            IntrinsicOp::WideningMul => Sexp::atom("WideningMul"),
+            IntrinsicOp::Copy(ty) => Sexp::node("Copy", vec![ty.to_sexp()]),
+            IntrinsicOp::CopyNonOverlapping(ty) =>
+                Sexp::node("CopyNonOverlapping", vec![ty.to_sexp()]),
+            IntrinsicOp::WriteBytes(ty) => Sexp::node("WriteBytes", vec![ty.to_sexp()]),
+            IntrinsicOp::TypedSwapNonOverlapping(ty) =>
+                Sexp::node("TypedSwapNonOverlapping", vec![ty.to_sexp()]),
+            IntrinsicOp::Freeze(ty) => Sexp::node("Freeze", vec![ty.to_sexp()]),
+            IntrinsicOp::AtomicStore(ordering) =>
+                Sexp::node("AtomicStore", vec![ordering.to_sexp()]),
+            IntrinsicOp::AtomicLoad(ordering) => Sexp::node("AtomicLoad", vec![ordering.to_sexp()]),
+            IntrinsicOp::AtomicCompareExchange { success, failure } =>
+                Sexp::record("AtomicCompareExchange", vec![
+                    ("success", success.to_sexp()),
+                    ("failure", failure.to_sexp()),
+                ]),
+            IntrinsicOp::AtomicCompareExchangeWeak { success, failure } =>
+                Sexp::record("AtomicCompareExchangeWeak", vec![
+                    ("success", success.to_sexp()),
+                    ("failure", failure.to_sexp()),
+                ]),
+            IntrinsicOp::AtomicFetchAndOp(op, ordering) =>
+                Sexp::node("AtomicFetchAndOp", vec![op.to_sexp(), ordering.to_sexp()]),
+            IntrinsicOp::Lock(op) => Sexp::node("Lock", vec![op.to_sexp()]),
+            IntrinsicOp::FutexWait => Sexp::atom("FutexWait"),
+            IntrinsicOp::FutexWake => Sexp::atom("FutexWake"),
+            IntrinsicOp::PointerExposeProvenance => Sexp::atom("PointerExposeProvenance"),
+            IntrinsicOp::PointerWithExposedProvenance => Sexp::atom("PointerWithExposedProvenance"),
+            IntrinsicOp::VaArg => Sexp::atom("VaArg"),
+            IntrinsicOp::AssertValidity(requirement, ty) =>
+                Sexp::node("AssertValidity", vec![requirement.to_sexp(), ty.to_sexp()]),
+        }
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let intrinsic = match s.as_node()? {
+            ("Assume", []) => IntrinsicOp::Assume,
+            ("Exit", []) => IntrinsicOp::Exit,
+            ("Panic", []) => IntrinsicOp::Panic,
+            ("PrintStdout", []) => IntrinsicOp::PrintStdout,
+            ("PrintStderr", []) => IntrinsicOp::PrintStderr,
+            ("Allocate", []) => IntrinsicOp::Allocate,
+            ("Deallocate", []) => IntrinsicOp::Deallocate,
+            ("Spawn", []) => IntrinsicOp::Spawn,
+            ("Join", []) => IntrinsicOp::Join,
+            ("RawEq", []) => IntrinsicOp::RawEq,
+            ("CarryingAdd", []) => IntrinsicOp::CarryingAdd,
+            ("BorrowingSub", []) => IntrinsicOp::BorrowingSub,
+            ("WideningMul", []) => IntrinsicOp::WideningMul,
+            ("Copy", [ty]) => IntrinsicOp::Copy(Type::from_sexp(ty)?),
+            ("CopyNonOverlapping", [ty]) => IntrinsicOp::CopyNonOverlapping(Type::from_sexp(ty)?),
+            ("WriteBytes", [ty]) => IntrinsicOp::WriteBytes(Type::from_sexp(ty)?),
+            ("TypedSwapNonOverlapping", [ty]) =>
+                IntrinsicOp::TypedSwapNonOverlapping(Type::from_sexp(ty)?),
+            ("Freeze", [ty]) => IntrinsicOp::Freeze(Type::from_sexp(ty)?),
+            ("AtomicStore", [ordering]) =>
+                IntrinsicOp::AtomicStore(AtomicOrdering::from_sexp(ordering)?),
+            ("AtomicLoad", [ordering]) =>
+                IntrinsicOp::AtomicLoad(AtomicOrdering::from_sexp(ordering)?),
+            ("AtomicCompareExchange", args) => {
+                let [success, failure] = s.fields(args, ["success", "failure"])?;
+                IntrinsicOp::AtomicCompareExchange {
+                    success: AtomicOrdering::from_sexp(success)?,
+                    failure: AtomicOrdering::from_sexp(failure)?,
+                }
+            }
+            ("AtomicCompareExchangeWeak", args) => {
+                let [success, failure] = s.fields(args, ["success", "failure"])?;
+                IntrinsicOp::AtomicCompareExchangeWeak {
+                    success: AtomicOrdering::from_sexp(success)?,
+                    failure: AtomicOrdering::from_sexp(failure)?,
+                }
+            }
+            ("AtomicFetchAndOp", [op, ordering]) =>
+                IntrinsicOp::AtomicFetchAndOp(
+                    AtomicRmwOp::from_sexp(op)?,
+                    AtomicOrdering::from_sexp(ordering)?,
+                ),
+            ("Lock", [op]) => IntrinsicOp::Lock(IntrinsicLockOp::from_sexp(op)?),
+            ("FutexWait", []) => IntrinsicOp::FutexWait,
+            ("FutexWake", []) => IntrinsicOp::FutexWake,
+            ("PointerExposeProvenance", []) => IntrinsicOp::PointerExposeProvenance,
+            ("PointerWithExposedProvenance", []) => IntrinsicOp::PointerWithExposedProvenance,
+            ("VaArg", []) => IntrinsicOp::VaArg,
+            ("AssertValidity", [requirement, ty]) =>
+                IntrinsicOp::AssertValidity(
+                    ValidityRequirement::from_sexp(requirement)?,
+                    Type::from_sexp(ty)?,
+                ),
+            _ => return Err(s.error(format!("invalid intrinsic `{}`", s.flat()))),
+        };
+        Ok(intrinsic)
+    }
+}
+
+impl Text for Terminator {
+    fn to_sexp(self) -> Sexp {
+        match self {
+            Terminator::Goto(bb) => Sexp::node("Goto", vec![bb.to_sexp()]),
+            Terminator::Switch { value, cases, fallback } =>
+                Sexp::record("Switch", vec![
+                    ("value", value.to_sexp()),
+                    ("cases", map_to_sexp(cases.iter())),
+                    ("fallback", fallback.to_sexp()),
+                ]),
+            Terminator::Unreachable => Sexp::atom("Unreachable"),
+            Terminator::Intrinsic { intrinsic, arguments, ret, next_block } =>
+                Sexp::record("Intrinsic", vec![
+                    ("intrinsic", intrinsic.to_sexp()),
+                    ("arguments", list_to_sexp(arguments)),
+                    ("ret", ret.to_sexp()),
+                    ("next_block", next_block.to_sexp()),
+                ]),
+            Terminator::InlineAsm { inputs, outputs, reads, clobbers, next_block } =>
+                Sexp::record("InlineAsm", vec![
+                    ("inputs", list_to_sexp(inputs)),
+                    ("outputs", list_to_sexp(outputs)),
+                    ("reads", list_to_sexp(reads)),
+                    ("clobbers", list_to_sexp(clobbers)),
+                    ("next_block", next_block.to_sexp()),
+                ]),
+            Terminator::Call {
+                callee,
+                calling_convention,
+                arguments,
+                ret,
+                next_block,
+                unwind_block,
+            } =>
+                Sexp::record("Call", vec![
+                    ("callee", callee.to_sexp()),
+                    ("calling_convention", calling_convention.to_sexp()),
+                    ("arguments", list_to_sexp(arguments)),
+                    ("ret", ret.to_sexp()),
+                    ("next_block", next_block.to_sexp()),
+                    ("unwind_block", unwind_block.to_sexp()),
+                ]),
+            Terminator::ExternCall { name, arguments, ret, next_block } =>
+                Sexp::record("ExternCall", vec![
+                    ("name", Sexp::string(name.get_internal())),
+                    ("arguments", list_to_sexp(arguments)),
+                    ("ret", ret.to_sexp()),
+                    ("next_block", next_block.to_sexp()),
+                ]),
+            Terminator::TailCall { callee, calling_convention, arguments } =>
+                Sexp::record("TailCall", vec![
+                    ("callee", callee.to_sexp()),
+                    ("calling_convention", calling_convention.to_sexp()),
+                    ("arguments", list_to_sexp(arguments)),
+                ]),
+            Terminator::Return => Sexp::atom("Return"),
+            Terminator::StartUnwind(unwind_block) =>
+                Sexp::node("StartUnwind", vec![unwind_block.to_sexp()]),
+            Terminator::ResumeUnwind => Sexp::atom("ResumeUnwind"),
+            Terminator::StopUnwind(bb) => Sexp::node("StopUnwind", vec![bb.to_sexp()]),
+            Terminator::UnwindTerminate => Sexp::atom("UnwindTerminate"),
+        }
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let terminator = match s.as_node()? {
+            ("Goto", [bb]) => Terminator::Goto(BbName::from_sexp(bb)?),
+            ("Switch", args) => {
+                let [value, cases, fallback] = s.fields(args, ["value", "cases", "fallback"])?;
+                Terminator::Switch {
+                    value: ValueExpr::from_sexp(value)?,
+                    cases: map_from_sexp(cases)?,
+                    fallback: BbName::from_sexp(fallback)?,
+                }
+            }
+            ("Unreachable", []) => Terminator::Unreachable,
+            ("Intrinsic", args) => {
+                let [intrinsic, arguments, ret, next_block] =
+                    s.fields(args, ["intrinsic", "arguments", "ret", "next_block"])?;
+                Terminator::Intrinsic {
+                    intrinsic: IntrinsicOp::from_sexp(intrinsic)?,
+                    arguments: list_from_sexp(arguments)?,
+                    ret: PlaceExpr::from_sexp(ret)?,
+                    next_block: Option::<BbName>::from_sexp(next_block)?,
+                }
+            }
+            ("InlineAsm", args) => {
+                let [inputs, outputs, reads, clobbers, next_block] =
+                    s.fields(args, ["inputs", "outputs", "reads", "clobbers", "next_block"])?;
+                Terminator::InlineAsm {
+                    inputs: list_from_sexp(inputs)?,
+                    outputs: list_from_sexp(outputs)?,
+                    reads: list_from_sexp(reads)?,
+                    clobbers: list_from_sexp(clobbers)?,
+                    next_block: Option::<BbName>::from_sexp(next_block)?,
+                }
+            }
+            ("Call", args) => {
+                let [callee, calling_convention, arguments, ret, next_block, unwind_block] = s
+                    .fields(args, [
+                        "callee",
+                        "calling_convention",
+                        "arguments",
+                        "ret",
+                        "next_block",
+                        "unwind_block",
+                    ])?;
+                Terminator::Call {
+                    callee: ValueExpr::from_sexp(callee)?,
+                    calling_convention: CallingConvention::from_sexp(calling_convention)?,
+                    arguments: list_from_sexp(arguments)?,
+                    ret: PlaceExpr::from_sexp(ret)?,
+                    next_block: Option::<BbName>::from_sexp(next_block)?,
+                    unwind_block: Option::<BbName>::from_sexp(unwind_block)?,
+                }
+            }
+            ("ExternCall", args) => {
+                let [name, arguments, ret, next_block] =
+                    s.fields(args, ["name", "arguments", "ret", "next_block"])?;
+                Terminator::ExternCall {
+                    name: minirust_rs::prelude::String::from_internal(name.as_str()?.to_string()),
+                    arguments: list_from_sexp(arguments)?,
+                    ret: PlaceExpr::from_sexp(ret)?,
+                    next_block: Option::<BbName>::from_sexp(next_block)?,
+                }
+            }
+            ("TailCall", args) => {
+                let [callee, calling_convention, arguments] =
+                    s.fields(args, ["callee", "calling_convention", "arguments"])?;
+                Terminator::TailCall {
+                    callee: ValueExpr::from_sexp(callee)?,
+                    calling_convention: CallingConvention::from_sexp(calling_convention)?,
+                    arguments: list_from_sexp(arguments)?,
+                }
+            }
+            ("Return", []) => Terminator::Return,
+            ("StartUnwind", [unwind_block]) =>
+                Terminator::StartUnwind(Option::<BbName>::from_sexp(unwind_block)?),
+            ("ResumeUnwind", []) => Terminator::ResumeUnwind,
+            ("StopUnwind", [bb]) => Terminator::StopUnwind(BbName::from_sexp(bb)?),
+            ("UnwindTerminate", []) => Terminator::UnwindTerminate,
+            _ => return Err(s.error(format!("invalid terminator `{}`", s.flat()))),
+        };
+        Ok(terminator)
+    }
+}
+
+impl Text for BasicBlock {
+    fn to_sexp(self) -> Sexp {
+        Sexp::record("BasicBlock", vec![
+            ("statements", list_to_sexp(self.statements)),
+            ("terminator", self.terminator.to_sexp()),
+        ])
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let [statements, terminator] = s.as_record("BasicBlock", ["statements", "terminator"])?;
+        Ok(BasicBlock {
+            statements: list_from_sexp(statements)?,
+            terminator: Terminator::from_sexp(terminator)?,
+        })
+    }
+}
+
+impl Text for Function {
+    fn to_sexp(self) -> Sexp {
+        Sexp::record("Function", vec![
+            ("locals", map_to_sexp(self.locals.iter())),
+            ("args", list_to_sexp(self.args)),
+            ("ret", self.ret.to_sexp()),
+            ("calling_convention", self.calling_convention.to_sexp()),
+            ("c_variadic", self.c_variadic.to_sexp()),
+            ("blocks", map_to_sexp(self.blocks.iter())),
+            ("start", self.start.to_sexp()),
+        ])
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let [locals, args, ret, calling_convention, c_variadic, blocks, start] =
+            s.as_record("Function", [
+                "locals",
+                "args",
+                "ret",
+                "calling_convention",
+                "c_variadic",
+                "blocks",
+                "start",
+            ])?;
+        Ok(Function {
+            locals: map_from_sexp(locals)?,
+            args: list_from_sexp(args)?,
+            ret: LocalName::from_sexp(ret)?,
+            calling_convention: CallingConvention::from_sexp(calling_convention)?,
+            c_variadic: bool::from_sexp(c_variadic)?,
+            blocks: map_from_sexp(blocks)?,
+            start: BbName::from_sexp(start)?,
+        })
+    }
+}
@@ -0,0 +1,204 @@
+//! A JSON encoding of the textual format, for tools that would rather not parse s-expressions.
+//!
+//! The encoding follows the s-expression tree node by node:
+//! - Lists become arrays, so `(Goto bb1)` becomes `["Goto", "bb1"]`.
+//! - Integer atoms become numbers and `true`/`false` become booleans. Integers are printed with all
+//!   their digits; consumers whose numbers are doubles should read them as big integers.
+//! - All other atoms become strings.
+//! - Quoted strings (like the names of extern functions) become `{"string": "..."}`.
+
+use super::*;
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Formats a program as JSON.
+pub fn program_to_json(prog: Program) -> String {
+    let mut out = String::new();
+    sexp_to_json(&prog.to_sexp(), &mut out);
+    out.push('\n');
+    out
+}
+
+/// Parses a program from the JSON produced by `program_to_json`.
+pub fn program_from_json(src: &str) -> ParseResult<Program> {
+    Program::from_sexp(&json_to_sexp(src)?)
+}
+
+fn is_int(text: &str) -> bool {
+    let digits = text.strip_prefix('-').unwrap_or(text);
+    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+fn push_json_string(text: &str, out: &mut String) {
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn sexp_to_json(s: &Sexp, out: &mut String) {
+    match &s.kind {
+        SexpKind::Atom(text) if is_int(text) || text == "true" || text == "false" =>
+            out.push_str(text),
+        SexpKind::Atom(text) => push_json_string(text, out),
+        SexpKind::Str(text) => {
+            out.push_str("{\"string\": ");
+            push_json_string(text, out);
+            out.push('}');
+        }
+        SexpKind::List(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push_str(", ");
+                }
+                sexp_to_json(item, out);
+            }
+            out.push(']');
+        }
+    }
+}
+
+fn json_to_sexp(src: &str) -> ParseResult<Sexp> {
+    let mut parser = JsonParser { chars: src.chars().peekable(), line: 1 };
+    let sexp = parser.parse_value()?;
+    parser.skip_whitespace();
+    if let Some(c) = parser.chars.peek().copied() {
+        return Err(parser.error(format!("unexpected `{c}` after the end of the program")));
+    }
+    Ok(sexp)
+}
+
+struct JsonParser<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+}
+
+impl JsonParser<'_> {
+    fn error(&self, msg: impl ToString) -> ParseError {
+        ParseError { line: self.line, msg: msg.to_string() }
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next();
+        if c == Some('\n') {
+            self.line += 1;
+        }
+        c
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+            self.next();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> ParseResult<()> {
+        self.skip_whitespace();
+        match self.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(self.error(format!("expected `{expected}`, found `{c}`"))),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn parse_value(&mut self) -> ParseResult<Sexp> {
+        self.skip_whitespace();
+        let line = self.line;
+        let kind = match self.chars.peek().copied() {
+            None => return Err(self.error("unexpected end of input")),
+            Some('[') => {
+                self.next();
+                let mut items = Vec::new();
+                self.skip_whitespace();
+                if self.chars.peek() == Some(&']') {
+                    self.next();
+                } else {
+                    loop {
+                        items.push(self.parse_value()?);
+                        self.skip_whitespace();
+                        match self.next() {
+                            Some(',') => {}
+                            Some(']') => break,
+                            None =>
+                                return Err(ParseError { line, msg: "unclosed `[`".to_string() }),
+                            Some(c) => return Err(self.error(format!("unexpected `{c}`"))),
+                        }
+                    }
+                }
+                SexpKind::List(items)
+            }
+            Some('{') => {
+                self.next();
+                self.skip_whitespace();
+                let key = self.parse_string()?;
+                if key != "string" {
+                    return Err(self.error(format!("unexpected key `{key}`")));
+                }
+                self.expect(':')?;
+                self.skip_whitespace();
+                let text = self.parse_string()?;
+                self.expect('}')?;
+                SexpKind::Str(text)
+            }
+            Some('"') => SexpKind::Atom(self.parse_string()?),
+            Some(c) if c == '-' || c.is_ascii_alphanumeric() => {
+                let mut text = String::new();
+                while let Some(&c) = self.chars.peek() {
+                    if !(c == '-' || c.is_ascii_alphanumeric()) {
+                        break;
+                    }
+                    text.push(c);
+                    self.next();
+                }
+                if !(is_int(&text) || text == "true" || text == "false") {
+                    return Err(self.error(format!("unexpected `{text}`")));
+                }
+                SexpKind::Atom(text)
+            }
+            Some(c) => return Err(self.error(format!("unexpected `{c}`"))),
+        };
+        Ok(Sexp { kind, line })
+    }
+
+    fn parse_string(&mut self) -> ParseResult<String> {
+        let line = self.line;
+        match self.next() {
+            Some('"') => {}
+            Some(c) => return Err(self.error(format!("expected a string, found `{c}`"))),
+            None => return Err(self.error("unexpected end of input")),
+        }
+        let mut text = String::new();
+        loop {
+            match self.next() {
+                None => return Err(ParseError { line, msg: "unclosed `\"`".to_string() }),
+                Some('"') => return Ok(text),
+                Some('\\') =>
+                    match self.next() {
+                        Some(c @ ('"' | '\\' | '/')) => text.push(c),
+                        Some('n') => text.push('\n'),
+                        Some('t') => text.push('\t'),
+                        Some('r') => text.push('\r'),
+                        Some('u') => {
+                            let hex: String = (0..4).filter_map(|_| self.next()).collect();
+                            let c = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32);
+                            match c {
+                                Some(c) => text.push(c),
+                                None => return Err(self.error("invalid escape sequence")),
+                            }
+                        }
+                        _ => return Err(self.error("invalid escape sequence")),
+                    },
+                Some(c) => text.push(c),
+            }
+        }
+    }
+}
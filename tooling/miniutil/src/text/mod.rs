@@ -0,0 +1,50 @@
+//! A textual format for MiniRust programs that can be parsed back.
+//!
+//! The output of `fmt` is meant for humans and leaves out details (e.g. the types of integer constants).
+//! This format instead describes every part of a `Program`, so `program_from_text(&program_to_text(prog))`
+//! gives back `prog`. This lets programs be stored in `.mr` files and exchanged between tools.
+//!
+//! The syntax is made of s-expressions:
+//! - Enum variants without data are atoms like `Return`, variants with data are lists like `(Goto bb1)`.
+//! - Structs and variants with named fields list their fields in order, e.g. `(Relocation (name g0) (offset 8))`.
+//! - Names are written like in `fmt`: `f0`, `bb0`, `_0`, and `g0`, `vtable0`, `trait0`, `m0`.
+//! - Integer types are written as `u8`, `i32`, ..., float types as `f32`, `f64`.
+//! - Maps are lists of `(key value)` pairs, sorted by key.
+//! - `;` starts a comment that extends to the end of the line.
+//!
+//! The same tree can also be exchanged as JSON, see `program_to_json`.
+
+use crate::*;
+
+mod sexp;
+pub use sexp::*;
+
+mod basic;
+use basic::*;
+
+mod ty;
+
+mod expr;
+
+mod function;
+
+mod program;
+
+mod json;
+pub use json::*;
+
+/// Conversion between a part of a MiniRust program and its textual representation.
+pub trait Text: Sized {
+    fn to_sexp(self) -> Sexp;
+    fn from_sexp(s: &Sexp) -> ParseResult<Self>;
+}
+
+/// Formats a program in the textual format.
+pub fn program_to_text(prog: Program) -> String {
+    format!("{}\n", prog.to_sexp())
+}
+
+/// Parses a program in the textual format.
+pub fn program_from_text(src: &str) -> ParseResult<Program> {
+    Program::from_sexp(&Sexp::parse(src)?)
+}
@@ -0,0 +1,147 @@
+use super::*;
+
+// The bytes of a global are written in hex, with `__` for uninitialized bytes.
+fn bytes_to_sexp(bytes: List<Option<u8>>) -> Sexp {
+    let bytes = bytes
+        .iter()
+        .map(|b| {
+            match b {
+                Some(b) => Sexp::atom(format!("{b:02x}")),
+                None => Sexp::atom("__"),
+            }
+        })
+        .collect();
+    Sexp::list(bytes)
+}
+
+fn bytes_from_sexp(s: &Sexp) -> ParseResult<List<Option<u8>>> {
+    s.as_list()?
+        .iter()
+        .map(|b| {
+            let text = b.as_atom()?;
+            match text {
+                "__" => Ok(None),
+                _ if text.len() == 2 =>
+                    u8::from_str_radix(text, 16)
+                        .map(Some)
+                        .map_err(|_| b.error(format!("invalid byte `{text}`"))),
+                _ => Err(b.error(format!("invalid byte `{text}`"))),
+            }
+        })
+        .collect()
+}
+
+impl Text for Global {
+    fn to_sexp(self) -> Sexp {
+        Sexp::record("Global", vec![
+            ("bytes", bytes_to_sexp(self.bytes)),
+            ("relocations", list_to_sexp(self.relocations)),
+            ("align", self.align.to_sexp()),
+            ("mutbl", self.mutbl.to_sexp()),
+        ])
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let [bytes, relocations, align, mutbl] =
+            s.as_record("Global", ["bytes", "relocations", "align", "mutbl"])?;
+        Ok(Global {
+            bytes: bytes_from_sexp(bytes)?,
+            relocations: list_from_sexp(relocations)?,
+            align: Align::from_sexp(align)?,
+            mutbl: Mutability::from_sexp(mutbl)?,
+        })
+    }
+}
+
+impl Text for RelocationTarget {
+    fn to_sexp(self) -> Sexp {
+        match self {
+            RelocationTarget::Global(relocation) =>
+                Sexp::node("Global", vec![relocation.to_sexp()]),
+            RelocationTarget::Fn(fn_name) => Sexp::node("Fn", vec![fn_name.to_sexp()]),
+            RelocationTarget::VTable(vtable_name) =>
+                Sexp::node("VTable", vec![vtable_name.to_sexp()]),
+        }
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let target = match s.as_node()? {
+            ("Global", [relocation]) =>
+                RelocationTarget::Global(Relocation::from_sexp(relocation)?),
+            ("Fn", [fn_name]) => RelocationTarget::Fn(FnName::from_sexp(fn_name)?),
+            ("VTable", [vtable_name]) =>
+                RelocationTarget::VTable(VTableName::from_sexp(vtable_name)?),
+            _ => return Err(s.error(format!("invalid relocation target `{}`", s.flat()))),
+        };
+        Ok(target)
+    }
+}
+
+impl Text for VTable {
+    fn to_sexp(self) -> Sexp {
+        Sexp::record("VTable", vec![
+            ("trait_name", self.trait_name.to_sexp()),
+            ("size", self.size.to_sexp()),
+            ("align", self.align.to_sexp()),
+            ("methods", map_to_sexp(self.methods.iter())),
+        ])
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let [trait_name, size, align, methods] =
+            s.as_record("VTable", ["trait_name", "size", "align", "methods"])?;
+        Ok(VTable {
+            trait_name: TraitName::from_sexp(trait_name)?,
+            size: Size::from_sexp(size)?,
+            align: Align::from_sexp(align)?,
+            methods: map_from_sexp(methods)?,
+        })
+    }
+}
+
+impl Text for Program {
+    fn to_sexp(self) -> Sexp {
+        // The values of `traits` are sets, which do not implement `Text` on their own.
+        let traits = self
+            .traits
+            .iter()
+            .map(|(trait_name, methods)| {
+                Sexp::list(vec![trait_name.to_sexp(), set_to_sexp(methods.iter())])
+            })
+            .collect();
+        Sexp::record("Program", vec![
+            ("functions", map_to_sexp(self.functions.iter())),
+            ("start", self.start.to_sexp()),
+            ("globals", map_to_sexp(self.globals.iter())),
+            ("traits", Sexp::sorted_list(traits)),
+            ("vtables", map_to_sexp(self.vtables.iter())),
+            ("global_inits", list_to_sexp(self.global_inits)),
+        ])
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let [functions, start, globals, traits, vtables, global_inits] =
+            s.as_record("Program", [
+                "functions",
+                "start",
+                "globals",
+                "traits",
+                "vtables",
+                "global_inits",
+            ])?;
+        let traits = map_entries(traits)?
+            .into_iter()
+            .map(|(trait_name, methods)| {
+                Ok((TraitName::from_sexp(trait_name)?, set_from_sexp(methods)?))
+            })
+            .collect::<ParseResult<_>>()?;
+        Ok(Program {
+            functions: map_from_sexp(functions)?,
+            start: FnName::from_sexp(start)?,
+            globals: map_from_sexp(globals)?,
+            traits,
+            vtables: map_from_sexp(vtables)?,
+            global_inits: list_from_sexp(global_inits)?,
+        })
+    }
+}
@@ -0,0 +1,318 @@
+use std::cmp::Ordering;
+use std::iter::Peekable;
+use std::str::Chars;
+
+// Lines longer than this are broken up when printing.
+const WIDTH: usize = 100;
+
+/// A node of the textual format: an atom like `f0` or `42`, a quoted string, or a parenthesized list.
+#[derive(Clone, Debug)]
+pub struct Sexp {
+    pub kind: SexpKind,
+    /// The line this node starts at, or 0 if it was not parsed from text.
+    pub line: usize,
+}
+
+#[derive(Clone, Debug)]
+pub enum SexpKind {
+    Atom(String),
+    Str(String),
+    List(Vec<Sexp>),
+}
+
+/// Why a text could not be turned into a program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// The line the error occurred in, or 0 if it is not known.
+    pub line: usize,
+    pub msg: String,
+}
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.msg)
+        } else {
+            write!(f, "line {}: {}", self.line, self.msg)
+        }
+    }
+}
+
+impl Sexp {
+    pub fn atom(text: impl ToString) -> Sexp {
+        Sexp { kind: SexpKind::Atom(text.to_string()), line: 0 }
+    }
+
+    pub fn string(text: impl ToString) -> Sexp {
+        Sexp { kind: SexpKind::Str(text.to_string()), line: 0 }
+    }
+
+    pub fn list(items: Vec<Sexp>) -> Sexp {
+        Sexp { kind: SexpKind::List(items), line: 0 }
+    }
+
+    /// A list whose elements are sorted, so that the output does not depend on iteration order.
+    pub fn sorted_list(mut items: Vec<Sexp>) -> Sexp {
+        items.sort_by(natural_cmp);
+        Sexp::list(items)
+    }
+
+    /// `(head args..)`, used for enum variants with data.
+    pub fn node(head: &str, args: Vec<Sexp>) -> Sexp {
+        let mut items = vec![Sexp::atom(head)];
+        items.extend(args);
+        Sexp::list(items)
+    }
+
+    /// `(head (name value)..)`, used for structs and struct-like enum variants.
+    pub fn record(head: &str, fields: Vec<(&str, Sexp)>) -> Sexp {
+        let fields =
+            fields.into_iter().map(|(name, value)| Sexp::node(name, vec![value])).collect();
+        Sexp::node(head, fields)
+    }
+
+    pub fn error(&self, msg: impl ToString) -> ParseError {
+        ParseError { line: self.line, msg: msg.to_string() }
+    }
+
+    pub fn as_atom(&self) -> ParseResult<&str> {
+        match &self.kind {
+            SexpKind::Atom(text) => Ok(text),
+            _ => Err(self.error(format!("expected an atom, found `{}`", self.flat()))),
+        }
+    }
+
+    pub fn as_str(&self) -> ParseResult<&str> {
+        match &self.kind {
+            SexpKind::Str(text) => Ok(text),
+            _ => Err(self.error(format!("expected a string, found `{}`", self.flat()))),
+        }
+    }
+
+    pub fn as_list(&self) -> ParseResult<&[Sexp]> {
+        match &self.kind {
+            SexpKind::List(items) => Ok(items),
+            _ => Err(self.error(format!("expected a list, found `{}`", self.flat()))),
+        }
+    }
+
+    /// Splits `(head args..)` into its parts. A lone atom is treated as a head without arguments.
+    pub fn as_node(&self) -> ParseResult<(&str, &[Sexp])> {
+        match &self.kind {
+            SexpKind::Atom(head) => Ok((head, &[])),
+            SexpKind::List(items) =>
+                match items.split_first() {
+                    Some((head, args)) => Ok((head.as_atom()?, args)),
+                    None => Err(self.error("expected a non-empty list")),
+                },
+            SexpKind::Str(_) => Err(self.error(format!("unexpected string `{}`", self.flat()))),
+        }
+    }
+
+    /// Checks that the node `self`, whose arguments are `args`, has exactly `N` arguments.
+    pub fn args<'a, const N: usize>(&self, args: &'a [Sexp]) -> ParseResult<&'a [Sexp; N]> {
+        args.try_into().map_err(|_| {
+            self.error(format!("expected {N} arguments, found {}: `{}`", args.len(), self.flat()))
+        })
+    }
+
+    /// Checks that the record `self`, whose arguments are `args`, has exactly the fields `names`, in this order.
+    /// Returns the values of the fields.
+    pub fn fields<'a, const N: usize>(
+        &self,
+        args: &'a [Sexp],
+        names: [&str; N],
+    ) -> ParseResult<[&'a Sexp; N]> {
+        let args: &[Sexp; N] = self.args(args)?;
+        let mut values = Vec::with_capacity(N);
+        for (arg, expected) in args.iter().zip(names) {
+            let (name, value) = arg.as_node()?;
+            if name != expected {
+                return Err(arg.error(format!("expected field `{expected}`, found `{name}`")));
+            }
+            let [value] = arg.args(value)?;
+            values.push(value);
+        }
+        Ok(values.try_into().unwrap())
+    }
+
+    /// Checks that `self` is the record `(head (name value)..)` with exactly the fields `names`, in this order.
+    /// Returns the values of the fields.
+    pub fn as_record<const N: usize>(
+        &self,
+        head: &str,
+        names: [&str; N],
+    ) -> ParseResult<[&Sexp; N]> {
+        match self.as_node()? {
+            (found, args) if found == head => self.fields(args, names),
+            _ => Err(self.error(format!("expected `({head} ..)`, found `{}`", self.flat()))),
+        }
+    }
+
+    /// Formats `self` on a single line.
+    pub fn flat(&self) -> String {
+        match &self.kind {
+            SexpKind::Atom(text) => text.clone(),
+            SexpKind::Str(text) => {
+                let text = text.replace('\\', "\\\\").replace('"', "\\\"");
+                format!("\"{text}\"")
+            }
+            SexpKind::List(items) => {
+                let items: Vec<String> = items.iter().map(Sexp::flat).collect();
+                format!("({})", items.join(" "))
+            }
+        }
+    }
+
+    // Lists that do not fit into the line are printed with one element per line, indented below their head.
+    fn pretty(&self, indent: usize, out: &mut String) {
+        let flat = self.flat();
+        match &self.kind {
+            SexpKind::List(items) if indent + flat.len() > WIDTH && !items.is_empty() => {
+                out.push('(');
+                items[0].pretty(indent + 1, out);
+                for item in &items[1..] {
+                    out.push('\n');
+                    out.push_str(&" ".repeat(indent + 2));
+                    item.pretty(indent + 2, out);
+                }
+                out.push(')');
+            }
+            _ => out.push_str(&flat),
+        }
+    }
+
+    /// Parses a single node, surrounded by whitespace and comments.
+    pub fn parse(src: &str) -> ParseResult<Sexp> {
+        let mut parser = Parser { chars: src.chars().peekable(), line: 1 };
+        let sexp = parser.parse_sexp()?;
+        parser.skip_whitespace();
+        if let Some(c) = parser.chars.peek().copied() {
+            return Err(parser.error(format!("unexpected `{c}` after the end of the program")));
+        }
+        Ok(sexp)
+    }
+}
+
+impl std::fmt::Display for Sexp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut out = String::new();
+        self.pretty(0, &mut out);
+        write!(f, "{out}")
+    }
+}
+
+// Compares atoms like `f2` and `f10` by their numeric part, so that sorted output reads naturally.
+fn natural_cmp(a: &Sexp, b: &Sexp) -> Ordering {
+    fn key(text: &str) -> (&str, Option<i128>) {
+        let split = text.find(|c: char| c.is_ascii_digit() || c == '-').unwrap_or(text.len());
+        let (prefix, number) = text.split_at(split);
+        (prefix, number.parse().ok())
+    }
+    match (&a.kind, &b.kind) {
+        (SexpKind::Atom(a), SexpKind::Atom(b)) => key(a).cmp(&key(b)).then_with(|| a.cmp(b)),
+        (SexpKind::Str(a), SexpKind::Str(b)) => a.cmp(b),
+        (SexpKind::List(a), SexpKind::List(b)) => {
+            for (a, b) in a.iter().zip(b) {
+                let ord = natural_cmp(a, b);
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+            }
+            a.len().cmp(&b.len())
+        }
+        // Atoms come first, then strings, then lists.
+        (SexpKind::Atom(_), _) => Ordering::Less,
+        (_, SexpKind::Atom(_)) => Ordering::Greater,
+        (SexpKind::Str(_), _) => Ordering::Less,
+        (_, SexpKind::Str(_)) => Ordering::Greater,
+    }
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, msg: impl ToString) -> ParseError {
+        ParseError { line: self.line, msg: msg.to_string() }
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next();
+        if c == Some('\n') {
+            self.line += 1;
+        }
+        c
+    }
+
+    // Skips whitespace and `;` comments, which extend to the end of the line.
+    fn skip_whitespace(&mut self) {
+        while let Some(&c) = self.chars.peek() {
+            if c == ';' {
+                while self.chars.peek().is_some_and(|&c| c != '\n') {
+                    self.next();
+                }
+            } else if c.is_whitespace() {
+                self.next();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn parse_sexp(&mut self) -> ParseResult<Sexp> {
+        self.skip_whitespace();
+        let line = self.line;
+        let kind = match self.next() {
+            None => return Err(self.error("unexpected end of input")),
+            Some(')') => return Err(self.error("unexpected `)`")),
+            Some('(') => {
+                let mut items = Vec::new();
+                loop {
+                    self.skip_whitespace();
+                    match self.chars.peek() {
+                        None => return Err(ParseError { line, msg: "unclosed `(`".to_string() }),
+                        Some(')') => {
+                            self.next();
+                            break;
+                        }
+                        Some(_) => items.push(self.parse_sexp()?),
+                    }
+                }
+                SexpKind::List(items)
+            }
+            Some('"') => {
+                let mut text = String::new();
+                loop {
+                    match self.next() {
+                        None => return Err(ParseError { line, msg: "unclosed `\"`".to_string() }),
+                        Some('"') => break,
+                        Some('\\') =>
+                            match self.next() {
+                                Some(c @ ('"' | '\\')) => text.push(c),
+                                _ => return Err(self.error("invalid escape sequence")),
+                            },
+                        Some(c) => text.push(c),
+                    }
+                }
+                SexpKind::Str(text)
+            }
+            Some(c) => {
+                let mut text = c.to_string();
+                while let Some(&c) = self.chars.peek() {
+                    if c.is_whitespace() || matches!(c, '(' | ')' | '"' | ';') {
+                        break;
+                    }
+                    text.push(c);
+                    self.next();
+                }
+                SexpKind::Atom(text)
+            }
+        };
+        Ok(Sexp { kind, line })
+    }
+}
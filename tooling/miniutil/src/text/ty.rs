@@ -0,0 +1,359 @@
+use super::*;
+
+// Integer types are written like `u8` or `i32`.
+impl Text for IntType {
+    fn to_sexp(self) -> Sexp {
+        let signed = match self.signed {
+            Signed => "i",
+            Unsigned => "u",
+        };
+        Sexp::atom(format!("{signed}{}", self.size.bits()))
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let text = s.as_atom()?;
+        let (signed, bits) = match text.split_at_checked(1) {
+            Some(("i", bits)) => (Signed, bits),
+            Some(("u", bits)) => (Unsigned, bits),
+            _ => return Err(s.error(format!("expected an integer type, found `{text}`"))),
+        };
+        let size = parse_bits(s, bits)?;
+        Ok(IntType { signed, size })
+    }
+}
+
+// Float types are written like `f32`.
+impl Text for FloatType {
+    fn to_sexp(self) -> Sexp {
+        Sexp::atom(format!("f{}", self.size.bits()))
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let text = s.as_atom()?;
+        match text.strip_prefix("f") {
+            Some(bits) => Ok(FloatType { size: parse_bits(s, bits)? }),
+            None => Err(s.error(format!("expected a float type, found `{text}`"))),
+        }
+    }
+}
+
+fn parse_bits(s: &Sexp, bits: &str) -> ParseResult<Size> {
+    match bits.parse::<u64>() {
+        Ok(bits) if bits % 8 == 0 => Ok(Size::from_bytes(bits / 8).unwrap()),
+        _ => Err(s.error(format!("invalid number of bits `{bits}`"))),
+    }
+}
+
+impl Text for Type {
+    fn to_sexp(self) -> Sexp {
+        match self {
+            Type::Int(int_ty) => int_ty.to_sexp(),
+            Type::Float(float_ty) => float_ty.to_sexp(),
+            Type::Bool => Sexp::atom("Bool"),
+            Type::Char => Sexp::atom("Char"),
+            Type::Never => Sexp::atom("Never"),
+            Type::Ptr(ptr_ty) => Sexp::node("Ptr", vec![ptr_ty.to_sexp()]),
+            Type::Tuple { sized_fields, sized_head_layout, unsized_field, valid_range } =>
+                Sexp::record("Tuple", vec![
+                    ("sized_fields", list_to_sexp(sized_fields)),
+                    ("sized_head_layout", sized_head_layout.to_sexp()),
+                    ("unsized_field", unsized_field.extract().to_sexp()),
+                    ("valid_range", valid_range.to_sexp()),
+                ]),
+            Type::Array { elem, count } =>
+                Sexp::record("Array", vec![
+                    ("elem", elem.extract().to_sexp()),
+                    ("count", count.to_sexp()),
+                ]),
+            Type::Slice { elem } => Sexp::record("Slice", vec![("elem", elem.extract().to_sexp())]),
+            Type::Union { fields, chunks, size, align } =>
+                Sexp::record("Union", vec![
+                    ("fields", list_to_sexp(fields)),
+                    ("chunks", list_to_sexp(chunks)),
+                    ("size", size.to_sexp()),
+                    ("align", align.to_sexp()),
+                ]),
+            Type::Enum { variants, discriminant_ty, discriminator, size, align } =>
+                Sexp::record("Enum", vec![
+                    ("variants", map_to_sexp(variants.iter())),
+                    ("discriminant_ty", discriminant_ty.to_sexp()),
+                    ("discriminator", discriminator.to_sexp()),
+                    ("size", size.to_sexp()),
+                    ("align", align.to_sexp()),
+                ]),
+            Type::TraitObject(trait_name) => Sexp::node("TraitObject", vec![trait_name.to_sexp()]),
+        }
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let ty = match s.as_node()? {
+            ("Bool", []) => Type::Bool,
+            ("Char", []) => Type::Char,
+            ("Never", []) => Type::Never,
+            ("Ptr", [ptr_ty]) => Type::Ptr(PtrType::from_sexp(ptr_ty)?),
+            ("Tuple", args) => {
+                let [sized_fields, sized_head_layout, unsized_field, valid_range] =
+                    s.fields(args, [
+                        "sized_fields",
+                        "sized_head_layout",
+                        "unsized_field",
+                        "valid_range",
+                    ])?;
+                Type::Tuple {
+                    sized_fields: list_from_sexp(sized_fields)?,
+                    sized_head_layout: TupleHeadLayout::from_sexp(sized_head_layout)?,
+                    unsized_field: GcCow::new(Option::<Type>::from_sexp(unsized_field)?),
+                    valid_range: Option::<ValidRange>::from_sexp(valid_range)?,
+                }
+            }
+            ("Array", args) => {
+                let [elem, count] = s.fields(args, ["elem", "count"])?;
+                Type::Array {
+                    elem: GcCow::new(Type::from_sexp(elem)?),
+                    count: Int::from_sexp(count)?,
+                }
+            }
+            ("Slice", args) => {
+                let [elem] = s.fields(args, ["elem"])?;
+                Type::Slice { elem: GcCow::new(Type::from_sexp(elem)?) }
+            }
+            ("Union", args) => {
+                let [fields, chunks, size, align] =
+                    s.fields(args, ["fields", "chunks", "size", "align"])?;
+                Type::Union {
+                    fields: list_from_sexp(fields)?,
+                    chunks: list_from_sexp(chunks)?,
+                    size: Size::from_sexp(size)?,
+                    align: Align::from_sexp(align)?,
+                }
+            }
+            ("Enum", args) => {
+                let [variants, discriminant_ty, discriminator, size, align] = s.fields(args, [
+                    "variants",
+                    "discriminant_ty",
+                    "discriminator",
+                    "size",
+                    "align",
+                ])?;
+                Type::Enum {
+                    variants: map_from_sexp(variants)?,
+                    discriminant_ty: IntType::from_sexp(discriminant_ty)?,
+                    discriminator: Discriminator::from_sexp(discriminator)?,
+                    size: Size::from_sexp(size)?,
+                    align: Align::from_sexp(align)?,
+                }
+            }
+            ("TraitObject", [trait_name]) => Type::TraitObject(TraitName::from_sexp(trait_name)?),
+            (head, []) if head.starts_with('f') => Type::Float(FloatType::from_sexp(s)?),
+            (_, []) => Type::Int(IntType::from_sexp(s)?),
+            _ => return Err(s.error(format!("invalid type `{}`", s.flat()))),
+        };
+        Ok(ty)
+    }
+}
+
+impl Text for ValidRange {
+    fn to_sexp(self) -> Sexp {
+        Sexp::record("ValidRange", vec![
+            ("start", self.start.to_sexp()),
+            ("end", self.end.to_sexp()),
+        ])
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let [start, end] = s.as_record("ValidRange", ["start", "end"])?;
+        Ok(ValidRange { start: Int::from_sexp(start)?, end: Int::from_sexp(end)? })
+    }
+}
+
+impl Text for Variant {
+    fn to_sexp(self) -> Sexp {
+        Sexp::record("Variant", vec![
+            ("ty", self.ty.to_sexp()),
+            ("tagger", map_to_sexp(self.tagger.iter())),
+        ])
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let [ty, tagger] = s.as_record("Variant", ["ty", "tagger"])?;
+        Ok(Variant { ty: Type::from_sexp(ty)?, tagger: map_from_sexp(tagger)? })
+    }
+}
+
+impl Text for Discriminator {
+    fn to_sexp(self) -> Sexp {
+        match self {
+            Discriminator::Known(discriminant) => Sexp::node("Known", vec![discriminant.to_sexp()]),
+            Discriminator::Invalid => Sexp::atom("Invalid"),
+            Discriminator::Branch { offset, value_type, fallback, children } =>
+                Sexp::record("Branch", vec![
+                    ("offset", offset.to_sexp()),
+                    ("value_type", value_type.to_sexp()),
+                    ("fallback", fallback.extract().to_sexp()),
+                    ("children", map_to_sexp(children.iter())),
+                ]),
+        }
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let discriminator = match s.as_node()? {
+            ("Known", [discriminant]) => Discriminator::Known(Int::from_sexp(discriminant)?),
+            ("Invalid", []) => Discriminator::Invalid,
+            ("Branch", args) => {
+                let [offset, value_type, fallback, children] =
+                    s.fields(args, ["offset", "value_type", "fallback", "children"])?;
+                Discriminator::Branch {
+                    offset: Offset::from_sexp(offset)?,
+                    value_type: IntType::from_sexp(value_type)?,
+                    fallback: GcCow::new(Discriminator::from_sexp(fallback)?),
+                    children: map_from_sexp(children)?,
+                }
+            }
+            _ => return Err(s.error(format!("invalid discriminator `{}`", s.flat()))),
+        };
+        Ok(discriminator)
+    }
+}
+
+impl Text for TupleHeadLayout {
+    fn to_sexp(self) -> Sexp {
+        Sexp::record("TupleHeadLayout", vec![
+            ("end", self.end.to_sexp()),
+            ("align", self.align.to_sexp()),
+            ("packed_align", self.packed_align.to_sexp()),
+        ])
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let [end, align, packed_align] =
+            s.as_record("TupleHeadLayout", ["end", "align", "packed_align"])?;
+        Ok(TupleHeadLayout {
+            end: Offset::from_sexp(end)?,
+            align: Align::from_sexp(align)?,
+            packed_align: Option::<Align>::from_sexp(packed_align)?,
+        })
+    }
+}
+
+impl Text for LayoutStrategy {
+    fn to_sexp(self) -> Sexp {
+        match self {
+            LayoutStrategy::Sized(size, align) =>
+                Sexp::node("Sized", vec![size.to_sexp(), align.to_sexp()]),
+            LayoutStrategy::Slice(size, align) =>
+                Sexp::node("Slice", vec![size.to_sexp(), align.to_sexp()]),
+            LayoutStrategy::TraitObject(trait_name) =>
+                Sexp::node("TraitObject", vec![trait_name.to_sexp()]),
+            LayoutStrategy::Tuple { head, tail } =>
+                Sexp::record("Tuple", vec![
+                    ("head", head.to_sexp()),
+                    ("tail", tail.extract().to_sexp()),
+                ]),
+        }
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let layout = match s.as_node()? {
+            ("Sized", [size, align]) =>
+                LayoutStrategy::Sized(Size::from_sexp(size)?, Align::from_sexp(align)?),
+            ("Slice", [size, align]) =>
+                LayoutStrategy::Slice(Size::from_sexp(size)?, Align::from_sexp(align)?),
+            ("TraitObject", [trait_name]) =>
+                LayoutStrategy::TraitObject(TraitName::from_sexp(trait_name)?),
+            ("Tuple", args) => {
+                let [head, tail] = s.fields(args, ["head", "tail"])?;
+                LayoutStrategy::Tuple {
+                    head: TupleHeadLayout::from_sexp(head)?,
+                    tail: GcCow::new(LayoutStrategy::from_sexp(tail)?),
+                }
+            }
+            _ => return Err(s.error(format!("invalid layout strategy `{}`", s.flat()))),
+        };
+        Ok(layout)
+    }
+}
+
+impl Text for PointeeInfo {
+    fn to_sexp(self) -> Sexp {
+        Sexp::record("PointeeInfo", vec![
+            ("layout", self.layout.to_sexp()),
+            ("inhabited", self.inhabited.to_sexp()),
+            ("freeze", self.freeze.to_sexp()),
+            ("unpin", self.unpin.to_sexp()),
+        ])
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let [layout, inhabited, freeze, unpin] =
+            s.as_record("PointeeInfo", ["layout", "inhabited", "freeze", "unpin"])?;
+        Ok(PointeeInfo {
+            layout: LayoutStrategy::from_sexp(layout)?,
+            inhabited: bool::from_sexp(inhabited)?,
+            freeze: bool::from_sexp(freeze)?,
+            unpin: bool::from_sexp(unpin)?,
+        })
+    }
+}
+
+impl Text for PointerMetaKind {
+    fn to_sexp(self) -> Sexp {
+        match self {
+            PointerMetaKind::None => Sexp::atom("None"),
+            PointerMetaKind::ElementCount => Sexp::atom("ElementCount"),
+            PointerMetaKind::VTablePointer(trait_name) =>
+                Sexp::node("VTablePointer", vec![trait_name.to_sexp()]),
+        }
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let meta_kind = match s.as_node()? {
+            ("None", []) => PointerMetaKind::None,
+            ("ElementCount", []) => PointerMetaKind::ElementCount,
+            ("VTablePointer", [trait_name]) =>
+                PointerMetaKind::VTablePointer(TraitName::from_sexp(trait_name)?),
+            _ => return Err(s.error(format!("invalid pointer metadata kind `{}`", s.flat()))),
+        };
+        Ok(meta_kind)
+    }
+}
+
+impl Text for PtrType {
+    fn to_sexp(self) -> Sexp {
+        match self {
+            PtrType::Ref { mutbl, pointee } =>
+                Sexp::record("Ref", vec![
+                    ("mutbl", mutbl.to_sexp()),
+                    ("pointee", pointee.to_sexp()),
+                ]),
+            PtrType::Box { pointee } => Sexp::record("Box", vec![("pointee", pointee.to_sexp())]),
+            PtrType::Raw { meta_kind } =>
+                Sexp::record("Raw", vec![("meta_kind", meta_kind.to_sexp())]),
+            PtrType::FnPtr => Sexp::atom("FnPtr"),
+            PtrType::VTablePtr(trait_name) => Sexp::node("VTablePtr", vec![trait_name.to_sexp()]),
+        }
+    }
+
+    fn from_sexp(s: &Sexp) -> ParseResult<Self> {
+        let ptr_ty = match s.as_node()? {
+            ("Ref", args) => {
+                let [mutbl, pointee] = s.fields(args, ["mutbl", "pointee"])?;
+                PtrType::Ref {
+                    mutbl: Mutability::from_sexp(mutbl)?,
+                    pointee: PointeeInfo::from_sexp(pointee)?,
+                }
+            }
+            ("Box", args) => {
+                let [pointee] = s.fields(args, ["pointee"])?;
+                PtrType::Box { pointee: PointeeInfo::from_sexp(pointee)? }
+            }
+            ("Raw", args) => {
+                let [meta_kind] = s.fields(args, ["meta_kind"])?;
+                PtrType::Raw { meta_kind: PointerMetaKind::from_sexp(meta_kind)? }
+            }
+            ("FnPtr", []) => PtrType::FnPtr,
+            ("VTablePtr", [trait_name]) => PtrType::VTablePtr(TraitName::from_sexp(trait_name)?),
+            _ => return Err(s.error(format!("invalid pointer type `{}`", s.flat()))),
+        };
+        Ok(ptr_ty)
+    }
+}
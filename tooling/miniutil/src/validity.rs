@@ -0,0 +1,130 @@
+//! Which bytes the validity checks of typed loads inspect.
+//!
+//! Every typed load decodes the loaded bytes at the type of the load, which is UB if they are not
+//! valid for that type. Not all loaded bytes take part in that check: padding is ignored, and the
+//! chunks of a union are kept as they are. So, e.g., moving data through a union or through the
+//! padding of a struct skips checks that a load at the type of the data would do.
+//!
+//! With tracing enabled, the machine records a `TraceEvent::TypedLoad` for every typed load,
+//! including the ranges of the loaded bytes its check inspected. Those depend on the loaded data:
+//! e.g. of an enum, only the fields of the variant its tag selects are checked.
+//! `validity_checks` collects these checks, and `ValidityReport` sums them up per type.
+
+use crate::fmt::fmt_types;
+use crate::run::*;
+use crate::*;
+
+/// The validity check of one typed load.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidityCheck {
+    pub thread: ThreadId,
+    pub addr: Address,
+    pub ty: Type,
+    /// For each loaded byte, whether the check inspected it.
+    pub checked: Vec<bool>,
+}
+
+/// The validity checks of the typed loads in `trace`, in the order they happened.
+pub fn validity_checks<T: Target>(trace: List<TraceEvent>) -> Vec<ValidityCheck> {
+    trace
+        .iter()
+        .filter_map(|event| {
+            match event {
+                TraceEvent::TypedLoad { thread, addr, ty, inspected } => {
+                    let size = ty.layout::<T>().expect_size("typed loads are sized");
+                    let mut checked = vec![false; bytes_of(size)];
+                    for (offset, size) in inspected.iter() {
+                        let start = bytes_of(offset);
+                        checked[start..start + bytes_of(size)].fill(true);
+                    }
+                    Some(ValidityCheck { thread, addr, ty, checked })
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn bytes_of(size: Size) -> usize {
+    size.bytes().try_to_usize().unwrap()
+}
+
+/// How many typed loads there were at one type, and how many of their bytes were checked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ValidityStats {
+    pub loads: usize,
+    pub bytes: usize,
+    pub checked_bytes: usize,
+}
+
+/// The validity checks of a run, summed up per type.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidityReport {
+    /// The types in the order they were first loaded at.
+    pub types: Vec<(Type, ValidityStats)>,
+}
+
+impl ValidityReport {
+    pub fn new(checks: &[ValidityCheck]) -> Self {
+        let mut report = ValidityReport::default();
+        for check in checks {
+            let idx = match report.types.iter().position(|(ty, _)| *ty == check.ty) {
+                Some(idx) => idx,
+                None => {
+                    report.types.push((check.ty, ValidityStats::default()));
+                    report.types.len() - 1
+                }
+            };
+            let stats = &mut report.types[idx].1;
+            stats.loads += 1;
+            stats.bytes += check.checked.len();
+            stats.checked_bytes += check.checked.iter().filter(|&&checked| checked).count();
+        }
+        report
+    }
+
+    /// The stats of all types together.
+    pub fn total(&self) -> ValidityStats {
+        let mut total = ValidityStats::default();
+        for (_, stats) in &self.types {
+            total.loads += stats.loads;
+            total.bytes += stats.bytes;
+            total.checked_bytes += stats.checked_bytes;
+        }
+        total
+    }
+}
+
+impl std::fmt::Display for ValidityReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let types: Vec<Type> = self.types.iter().map(|(ty, _)| *ty).collect();
+        let (names, comptypes) = fmt_types(&types);
+        for (name, (_, stats)) in names.iter().zip(&self.types) {
+            writeln!(
+                f,
+                "{name}: {} loads, {} of {} bytes checked",
+                stats.loads, stats.checked_bytes, stats.bytes
+            )?;
+        }
+        let total = self.total();
+        writeln!(
+            f,
+            "total: {} loads, {} of {} bytes checked",
+            total.loads, total.checked_bytes, total.bytes
+        )?;
+        write!(f, "{comptypes}")
+    }
+}
+
+/// Run the program to completion with tracing enabled, capturing stdout, stderr, and warnings.
+/// Returns the output together with the validity checks of its typed loads,
+/// which are empty if the program is ill-formed.
+pub fn run_program_with_validity_checks<M: MemoryTooling>(
+    prog: Program,
+) -> (Output, Vec<ValidityCheck>) {
+    let config = MachineConfig { trace: true, ..MachineConfig::DEFAULT };
+    let (output, checks) = run_program_inspect_with_config::<M, _>(prog, config, |machine| {
+        validity_checks::<M::T>(machine.trace())
+    });
+    (output, checks.unwrap_or_default())
+}
@@ -0,0 +1,60 @@
+//! Well-formedness checking as a standalone pass.
+//!
+//! Running a program only reports the first well-formedness violation. `check_program_wf` instead
+//! reports all of them, together with where in the program they were found.
+
+use crate::*;
+
+/// A well-formedness violation, as reported by `check_program_wf`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WfError {
+    pub location: WfLocation,
+    /// The kind of violation, i.e., the construct whose requirement was violated, e.g. `Statement::Assign`.
+    pub kind: WfKind,
+    /// What exactly is wrong, e.g. `destination and source type differ`.
+    pub details: String,
+}
+
+impl WfError {
+    /// The message the machine reports when running a program with this violation.
+    pub fn msg(&self) -> String {
+        format!("{}: {}", self.kind.name(), self.details)
+    }
+}
+
+impl std::fmt::Display for WfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", fmt_wf_location(self.location), self.msg())
+    }
+}
+
+/// Checks `prog` for well-formedness and returns all violations, in the order the machine would find them.
+/// The program is well-formed if the result is empty.
+pub fn check_program_wf<T: Target>(prog: Program) -> Vec<WfError> {
+    prog.check_wf_all::<T>()
+        .iter()
+        .map(|violation| {
+            WfError {
+                location: violation.location,
+                kind: violation.kind,
+                details: violation.details.get_internal().to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Formats a location like the pretty-printer names things, e.g. `f0, bb1, statement 2`.
+pub fn fmt_wf_location(location: WfLocation) -> String {
+    let fn_name = |f: FnName| format!("f{}", f.0.get_internal());
+    let bb_name = |bb: BbName| format!("bb{}", bb.0.get_internal());
+    match location {
+        WfLocation::Program => "program".to_string(),
+        WfLocation::VTable(name) => format!("vtable{}", name.0.get_internal()),
+        WfLocation::Function(f) => fn_name(f),
+        WfLocation::Statement(f, bb, idx) =>
+            format!("{}, {}, statement {idx}", fn_name(f), bb_name(bb)),
+        WfLocation::Terminator(f, bb) => format!("{}, {}, terminator", fn_name(f), bb_name(bb)),
+        WfLocation::Global(name) => format!("g{}", name.0.get_internal()),
+        WfLocation::GlobalInit(name) => format!("initializer of g{}", name.0.get_internal()),
+    }
+}